@@ -0,0 +1,118 @@
+//! Test double for `ShkoloApi` serving canned responses, so fetching and
+//! refreshing can be tested without the network.
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::models::*;
+use super::ShkoloApi;
+
+/// Canned responses by endpoint name; an endpoint without one fails.
+/// Every request is recorded, so tests can tell a cache hit from a fetch.
+#[derive(Default)]
+pub struct FakeApi {
+    offline: bool,
+    responses: HashMap<String, serde_json::Value>,
+    calls: RefCell<Vec<String>>,
+    slow: bool,
+    in_flight: Cell<usize>,
+    max_in_flight: Cell<usize>,
+}
+
+impl FakeApi {
+    pub fn with(mut self, endpoint: &str, response: &str) -> Self {
+        self.responses.insert(endpoint.to_string(), serde_json::from_str(response).unwrap());
+        self
+    }
+
+    /// Make an endpoint fail
+    pub fn without(mut self, endpoint: &str) -> Self {
+        self.responses.remove(endpoint);
+        self
+    }
+
+    /// A fake that is offline, like after `--offline`
+    pub fn offline() -> Self {
+        Self { offline: true, ..Default::default() }
+    }
+
+    /// Answer each request only after yielding once, so requests that are
+    /// awaited together are all in flight at the same time
+    pub fn slow(mut self) -> Self {
+        self.slow = true;
+        self
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.borrow().clone()
+    }
+
+    /// Most requests that were waiting for an answer at once
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.get()
+    }
+
+    async fn respond<T: DeserializeOwned>(&self, endpoint: String) -> Result<T> {
+        self.calls.borrow_mut().push(endpoint.clone());
+        if self.slow {
+            self.in_flight.set(self.in_flight.get() + 1);
+            self.max_in_flight.set(self.max_in_flight.get().max(self.in_flight.get()));
+            tokio::task::yield_now().await;
+            self.in_flight.set(self.in_flight.get() - 1);
+        }
+        let response = self.responses.get(&endpoint).ok_or_else(|| anyhow!("{} unavailable", endpoint))?;
+        Ok(serde_json::from_value(response.clone())?)
+    }
+}
+
+impl ShkoloApi for FakeApi {
+    fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    async fn get_pupils(&self) -> Result<PupilsResponse> {
+        self.respond("pupils".into()).await
+    }
+
+    async fn get_homework_courses(&self, _pupil_id: i64) -> Result<HomeworkCoursesResponse> {
+        self.respond("homework_courses".into()).await
+    }
+
+    async fn get_homework_list(&self, cyc_group_id: i64) -> Result<HomeworkListResponse> {
+        self.respond(format!("homework_list/{}", cyc_group_id)).await
+    }
+
+    async fn get_grades_summary(&self, _pupil_id: i64) -> Result<GradesSummaryResponse> {
+        self.respond("grades_summary".into()).await
+    }
+
+    async fn get_pupil_schedule(&self, _pupil_id: i64, date: &str) -> Result<ScheduleResponse> {
+        self.respond(format!("schedule/{}", date)).await
+    }
+
+    async fn get_pupil_events(&self, _pupil_id: i64) -> Result<EventsResponse> {
+        self.respond("events".into()).await
+    }
+
+    async fn get_absences(&self, _pupil_id: i64) -> Result<AbsencesResponse> {
+        self.respond("absences".into()).await
+    }
+
+    async fn get_feedbacks(&self, _pupil_id: i64) -> Result<FeedbacksResponse> {
+        self.respond("feedbacks".into()).await
+    }
+
+    async fn get_notifications(&self, _page: i32) -> Result<NotificationsResponse> {
+        self.respond("notifications".into()).await
+    }
+
+    async fn get_messenger_threads(&self, _folder_id: Option<i64>) -> Result<Vec<MessageThreadRaw>> {
+        self.respond("messenger_threads".into()).await
+    }
+
+    async fn get_recipients(&self) -> Result<Vec<RecipientRaw>> {
+        self.respond("recipients".into()).await
+    }
+}
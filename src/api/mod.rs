@@ -1,5 +1,7 @@
 pub mod client;
+#[cfg(test)]
+pub mod fake;
 pub mod types;
 
-pub use client::ShkoloClient;
+pub use client::{ShkoloApi, ShkoloClient};
 pub use types::*;
@@ -1,3 +1,7 @@
+use std::cell::RefCell;
+use std::future::Future;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,23 +22,165 @@ pub struct GoogleAuthRequest {
     pub id_token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub cached: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cached_at: Option<String>,
+    /// Set when served from expired cache because the API was unreachable
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+    /// When the response was produced (RFC 3339, UTC)
+    pub generated_at: String,
+    /// Time spent producing the response, in milliseconds
+    pub duration_ms: u64,
+    /// Version of the shkolo CLI that produced the response
+    pub tool_version: String,
+    /// Students whose data could not be loaded; `success` is false when there are any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<StudentError>,
+    /// How long each fetch took, with --timing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
     pub data: T,
 }
 
+/// The fetches behind a response, in the order they were made
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Timing {
+    pub fetches: Vec<FetchTiming>,
+    /// Time spent in fetches that went to the API, in milliseconds
+    pub api_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FetchTiming {
+    /// Data type, as in `cache --clear-type`
+    pub fetch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<i64>,
+    pub duration_ms: u64,
+    /// Served from the local cache rather than the API
+    pub cached: bool,
+    /// False when the fetch failed
+    pub ok: bool,
+}
+
+tokio::task_local! {
+    static FETCHES: RefCell<Vec<FetchTiming>>;
+}
+
+/// Run `future` noting the fetches it makes, for the `timing` of its responses
+pub async fn with_timing<F: Future>(future: F) -> F::Output {
+    FETCHES.scope(RefCell::new(Vec::new()), future).await
+}
+
+/// Note a fetch; does nothing outside `with_timing`
+pub fn record_fetch(fetch: FetchTiming) {
+    let _ = FETCHES.try_with(|fetches| fetches.borrow_mut().push(fetch));
+}
+
+fn recorded_timing() -> Option<Timing> {
+    FETCHES.try_with(|fetches| {
+        let fetches = fetches.borrow().clone();
+        let api_ms = fetches.iter().filter(|f| !f.cached).map(|f| f.duration_ms).sum();
+        Timing { fetches, api_ms }
+    }).ok()
+}
+
+/// A student left out of a per-student response because fetching their data failed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StudentError {
+    pub student_id: i64,
+    pub student: String,
+    pub error: String,
+}
+
 impl<T> ApiResponse<T> {
     pub fn new(data: T, cached: bool, cached_at: Option<String>) -> Self {
+        let generated_at = time::OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default();
         Self {
             success: true,
             cached,
             cached_at,
+            stale: false,
+            generated_at,
+            duration_ms: 0,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            errors: Vec::new(),
+            timing: None,
             data,
         }
     }
+
+    /// Record how long the command took since `started`, and with --timing
+    /// the fetches made so far
+    pub fn timed(mut self, started: std::time::Instant) -> Self {
+        self.duration_ms = started.elapsed().as_millis() as u64;
+        self.timing = recorded_timing();
+        self
+    }
+
+    /// Report students that were skipped, which makes the response unsuccessful
+    pub fn with_errors(mut self, errors: Vec<StudentError>) -> Self {
+        self.success = errors.is_empty();
+        self.errors = errors;
+        self
+    }
+
+    /// Mark the response as possibly stale (offline mode)
+    pub fn stale(mut self, stale: bool) -> Self {
+        self.stale = stale;
+        self
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_metadata() {
+        let started = std::time::Instant::now() - std::time::Duration::from_millis(25);
+        let response = ApiResponse::new(vec![1, 2], true, Some("5m ago".to_string())).timed(started);
+
+        assert!(response.duration_ms >= 25);
+        assert_eq!(response.tool_version, env!("CARGO_PKG_VERSION"));
+        let today = time::OffsetDateTime::now_utc().date().to_string();
+        assert!(response.generated_at.starts_with(&today[..4]));
+        assert!(response.generated_at.contains('T') && response.generated_at.ends_with('Z'));
+
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["cached"], true);
+        assert_eq!(value["cached_at"], "5m ago");
+        assert!(value.get("stale").is_none());
+        assert!(value.get("errors").is_none());
+        assert_eq!(value["data"], serde_json::json!([1, 2]));
+
+        let error = StudentError { student_id: 7, student: "Ана".to_string(), error: "API error (500)".to_string() };
+        let value = serde_json::to_value(ApiResponse::new(vec![1], false, None).with_errors(vec![error])).unwrap();
+        assert_eq!(value["success"], false);
+        assert_eq!(value["errors"][0]["student_id"], 7);
+        assert_eq!(value["data"], serde_json::json!([1]));
+    }
+
+    #[tokio::test]
+    async fn test_timing_only_with_timing() {
+        let fetch = |fetch: &str, duration_ms, cached| FetchTiming { fetch: fetch.to_string(), student_id: Some(7), duration_ms, cached, ok: true };
+        record_fetch(fetch("grades", 10, false));
+        let started = std::time::Instant::now();
+        assert!(serde_json::to_value(ApiResponse::new(1, false, None).timed(started)).unwrap().get("timing").is_none());
+
+        let response = with_timing(async {
+            record_fetch(fetch("students", 0, true));
+            record_fetch(fetch("grades", 120, false));
+            ApiResponse::new(1, false, None).timed(started)
+        }).await;
+        let timing = response.timing.unwrap();
+        assert_eq!(timing.fetches, [fetch("students", 0, true), fetch("grades", 120, false)]);
+        assert_eq!(timing.api_ms, 120);
+    }
+}
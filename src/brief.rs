@@ -0,0 +1,291 @@
+//! Plain-text digest printed by `json summary --format llm`: what an assistant
+//! needs to answer "anything to worry about today?" in as few tokens as possible.
+
+use time::Date;
+
+use crate::models::*;
+use crate::util::minutes_of_day;
+
+/// Upper bound on the whole digest, in lines
+const MAX_LINES: usize = 60;
+/// Items listed per category before the rest is summarized as "N more"
+const MAX_ITEMS: usize = 6;
+/// Homework texts and notification bodies are cut to this many characters
+const MAX_TEXT: usize = 80;
+
+/// What the digest says about one student. A category that failed to load is None.
+#[derive(Debug, Clone)]
+pub struct StudentBrief {
+    pub student: Student,
+    /// Lessons on the summary day that haven't ended yet
+    pub lessons: Option<Vec<ScheduleHour>>,
+    /// Homework due within three days, soonest first
+    pub homework: Option<Vec<(Homework, Urgency)>>,
+    /// Grade notifications from the last 7 days
+    pub new_grades: Option<Vec<Notification>>,
+    pub absences: Option<AbsenceTotals>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbsenceTotals {
+    pub total: usize,
+    pub unexcused: usize,
+    /// Unexcused with the excuse deadline ending within 48 hours
+    pub expiring: usize,
+}
+
+impl AbsenceTotals {
+    pub fn new(absences: &[Absence], today: Date, excuse_days: i64) -> Self {
+        Self {
+            total: absences.len(),
+            unexcused: absences.iter().filter(|a| !a.is_excused).count(),
+            expiring: absences.iter().filter(|a| a.excuse_deadline_soon(today, excuse_days)).count(),
+        }
+    }
+}
+
+impl StudentBrief {
+    /// `now` is the current (hour, minute) when the schedule is for today;
+    /// None keeps every lesson
+    pub fn new(
+        student: Student,
+        schedule: Option<Vec<ScheduleHour>>,
+        homework: Option<Vec<Homework>>,
+        notifications: Option<&[Notification]>,
+        absences: Option<AbsenceTotals>,
+        today: Date,
+        now: Option<(u8, u8)>,
+    ) -> Self {
+        let lessons = schedule.map(|hours| {
+            hours.into_iter()
+                .filter(|h| now.is_none_or(|now| minutes_of_day(&h.to_time) > now.0 as i32 * 60 + now.1 as i32))
+                .collect()
+        });
+
+        let homework = homework.map(|homework| {
+            let mut due: Vec<_> = homework.into_iter()
+                .filter_map(|hw| {
+                    let urgency = hw.urgency(today)?;
+                    matches!(urgency, Urgency::Today | Urgency::Tomorrow | Urgency::Soon).then_some((hw, urgency))
+                })
+                .collect();
+            due.sort_by(|(a, _), (b, _)| a.due_date_sort.cmp(&b.due_date_sort));
+            due
+        });
+
+        let first_name = student.name.split_whitespace().next().unwrap_or_default().to_string();
+        let new_grades = notifications.map(|notifications| {
+            notifications.iter()
+                .filter(|n| n.is_recent_grade_for(&first_name, today))
+                .cloned()
+                .collect()
+        });
+
+        Self { student, lessons, homework, new_grades, absences }
+    }
+}
+
+/// Render the digest for `date` (YYYY-MM-DD). Plain text without colors,
+/// at most `MAX_LINES` lines.
+pub fn render(briefs: &[StudentBrief], date: &str) -> String {
+    let mut lines = vec![format!("Shkolo summary for {}", date)];
+
+    for brief in briefs {
+        lines.push(String::new());
+        lines.push(match &brief.student.class_name {
+            Some(class) => format!("## {} ({})", brief.student.name, class),
+            None => format!("## {}", brief.student.name),
+        });
+
+        section(&mut lines, "lessons left", brief.lessons.as_deref(), |h| {
+            format!("{}. {}-{} {}", h.hour_number, h.from_time, h.to_time, h.subject)
+        });
+        section(&mut lines, "homework due within 3 days", brief.homework.as_deref(), |(hw, urgency)| {
+            let due = match urgency {
+                Urgency::Today => "today".to_string(),
+                Urgency::Tomorrow => "tomorrow".to_string(),
+                _ => hw.due_date.clone().unwrap_or_default(),
+            };
+            format!("{}: {}: {}", due, hw.subject, shorten(&hw.text))
+        });
+        section(&mut lines, "new grades this week", brief.new_grades.as_deref(), |n| {
+            format!("{}: {}", n.date.get(..10).unwrap_or(&n.date), shorten(n.body.as_deref().unwrap_or(&n.title)))
+        });
+        lines.push(match brief.absences {
+            Some(a) if a.expiring > 0 => format!("absences: {} total, {} unexcused, {} to excuse within 48 hours", a.total, a.unexcused, a.expiring),
+            Some(a) => format!("absences: {} total, {} unexcused", a.total, a.unexcused),
+            None => "absences: unavailable".to_string(),
+        });
+    }
+
+    if lines.len() > MAX_LINES {
+        let cut = lines.len() - (MAX_LINES - 1);
+        lines.truncate(MAX_LINES - 1);
+        lines.push(format!("... {} more lines truncated", cut));
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// "label: unavailable", "label: none", or "label (n):" followed by up to
+/// `MAX_ITEMS` bullet lines
+fn section<T>(lines: &mut Vec<String>, label: &str, items: Option<&[T]>, item: impl Fn(&T) -> String) {
+    let Some(items) = items else {
+        lines.push(format!("{}: unavailable", label));
+        return;
+    };
+    if items.is_empty() {
+        lines.push(format!("{}: none", label));
+        return;
+    }
+
+    lines.push(format!("{} ({}):", label, items.len()));
+    lines.extend(items.iter().take(MAX_ITEMS).map(|i| format!("- {}", item(i))));
+    if items.len() > MAX_ITEMS {
+        lines.push(format!("- ... {} more", items.len() - MAX_ITEMS));
+    }
+}
+
+/// Collapse whitespace and cut to `MAX_TEXT` characters
+fn shorten(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_TEXT {
+        text
+    } else {
+        format!("{}...", text.chars().take(MAX_TEXT - 3).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(name: &str) -> Student {
+        Student { id: 1, name: name.to_string(), class_name: Some("5А".to_string()), school_name: None }
+    }
+
+    fn lesson(hour_number: i32, from_time: &str, to_time: &str, subject: &str) -> ScheduleHour {
+        ScheduleHour {
+            hour_number,
+            from_time: from_time.to_string(),
+            to_time: to_time.to_string(),
+            subject: subject.to_string(),
+            teacher: None,
+            topic: None,
+            homework: None,
+            room: None,
+        }
+    }
+
+    fn homework(subject: &str, due: &str, text: &str) -> Homework {
+        Homework {
+            id: None,
+            subject: subject.to_string(),
+            text: text.to_string(),
+            date: "18.02.2026".to_string(),
+            due_date: Some(due.split('-').rev().collect::<Vec<_>>().join(".")),
+            date_sort: Some("2026-02-18".to_string()),
+            due_date_sort: Some(due.to_string()),
+            links: Vec::new(),
+            raw_html: None,
+        }
+    }
+
+    fn absence(is_excused: bool) -> Absence {
+        Absence {
+            id: "1".to_string(),
+            date: "16.02.2026".to_string(),
+            date_sort: "2026-02-16".to_string(),
+            hour: 1,
+            subject: "Математика".to_string(),
+            is_excused,
+            excuse_reason: None,
+            created_by: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_brief() {
+        let today = time::macros::date!(2026 - 02 - 19);
+        let notifications = vec![Notification {
+            id: None,
+            title: "Нова оценка".to_string(),
+            body: Some("Мария получи Отличен 6 по Математика".to_string()),
+            date: "2026-02-18 14:02:11".to_string(),
+            is_read: false,
+            notification_type: Some("new_grade".to_string()),
+            pupil_names: Some("Мария Иванова".to_string()),
+            target: Default::default(),
+        }];
+        let maria = StudentBrief::new(
+            student("Мария Иванова"),
+            Some(vec![
+                lesson(1, "08:00", "08:40", "Математика"),
+                lesson(2, "08:50", "09:30", "Български език и литература"),
+                lesson(3, "09:40", "10:20", "История"),
+            ]),
+            Some(vec![
+                homework("Математика", "2026-02-25", "Упр. 5"),
+                homework("История", "2026-02-20", "Прочетете урок 12"),
+                homework("БЕЛ", "2026-02-19", "Прочетете приказката „Хитър Петър“ и напишете преразказ от една страница, като обърнете внимание на героите."),
+                homework("Музика", "2026-02-18", "Песен"),
+            ]),
+            Some(&notifications),
+            Some(AbsenceTotals::new(&[absence(true), absence(false)], today, 7)),
+            today,
+            Some((9, 15)),
+        );
+        let petar = StudentBrief::new(student("Петър Иванов"), Some(Vec::new()), None, Some(&notifications), None, today, Some((9, 15)));
+
+        assert_eq!(render(&[maria, petar], "2026-02-19"), concat!(
+            "Shkolo summary for 2026-02-19\n",
+            "\n",
+            "## Мария Иванова (5А)\n",
+            "lessons left (2):\n",
+            "- 2. 08:50-09:30 Български език и литература\n",
+            "- 3. 09:40-10:20 История\n",
+            "homework due within 3 days (2):\n",
+            "- today: БЕЛ: Прочетете приказката „Хитър Петър“ и напишете преразказ от една страница, кат...\n",
+            "- tomorrow: История: Прочетете урок 12\n",
+            "new grades this week (1):\n",
+            "- 2026-02-18: Мария получи Отличен 6 по Математика\n",
+            "absences: 2 total, 1 unexcused\n",
+            "\n",
+            "## Петър Иванов (5А)\n",
+            "lessons left: none\n",
+            "homework due within 3 days: unavailable\n",
+            "new grades this week: none\n",
+            "absences: unavailable\n",
+        ));
+    }
+
+    #[test]
+    fn test_expiring_excuses_listed() {
+        let today = time::macros::date!(2026 - 02 - 19);
+        let mut old = absence(false);
+        old.date_sort = "2026-02-12".to_string();
+        let totals = AbsenceTotals::new(&[absence(true), absence(false), old], today, 7);
+        assert_eq!(totals, AbsenceTotals { total: 3, unexcused: 2, expiring: 1 });
+
+        let brief = StudentBrief::new(student("Мария Иванова"), None, None, None, Some(totals), today, None);
+        assert!(render(&[brief], "2026-02-19").contains("absences: 3 total, 2 unexcused, 1 to excuse within 48 hours\n"));
+    }
+
+    #[test]
+    fn test_brief_capped_at_max_lines() {
+        let today = time::macros::date!(2026 - 02 - 19);
+        let lessons: Vec<_> = (1..=9).map(|n| lesson(n, "08:00", "23:59", "Математика")).collect();
+        let briefs: Vec<_> = (0..8)
+            .map(|i| StudentBrief::new(student(&format!("Ученик {}", i)), Some(lessons.clone()), None, None, None, today, None))
+            .collect();
+
+        let out = render(&briefs, "2026-02-19");
+        assert_eq!(out.lines().count(), MAX_LINES);
+        assert!(out.lines().last().unwrap().ends_with("more lines truncated"));
+        assert!(out.contains("- ... 3 more"));
+        assert!(!out.contains('\x1b'));
+    }
+}
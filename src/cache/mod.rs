@@ -1,3 +1,5 @@
 pub mod store;
 
-pub use store::{CacheStore, UiConfig};
+pub use store::{age_string, schedule_ttl, CacheCounts, CacheEntry, CacheKind, CacheStats, CacheStore, Freshness, DEFAULT_TTL_SECONDS, UiConfig};
+#[cfg(test)]
+pub use store::TempCache;
@@ -1,17 +1,64 @@
 use anyhow::Result;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use time::OffsetDateTime;
 
 use crate::models::*;
-
-const DEFAULT_TTL_SECONDS: i64 = 3600; // 1 hour
+use crate::tracking::Seen;
+use crate::util;
+
+pub const DEFAULT_TTL_SECONDS: i64 = 3600; // 1 hour
+/// Overrides the cache directory; `--cache-dir` takes precedence
+pub const CACHE_DIR_ENV: &str = "SHKOLO_CACHE_DIR";
+/// Credentials and settings; these live in the config directory rather than the cache
+const CONFIG_FILES: &[&str] = &["token", "ui_config", "drafts"];
+/// Subdirectory of the cache holding other school years' data, one directory per year
+const SCHOOL_YEARS_DIR: &str = "years";
+const FUTURE_SCHEDULE_TTL_SECONDS: i64 = 900; // Substitutions get entered ahead of time
+/// Staff and parent lists rarely change, so they are kept at least this long
+const RECIPIENTS_TTL_SECONDS: i64 = 86400;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct UiConfig {
     pub students_pane_width: Option<u16>,
     pub overview_split_percent: Option<u16>,
+    pub show_weekends: Option<bool>,
+    /// "monday" or "sunday"
+    pub week_start: Option<String>,
+    /// Language code ("bg", "en", "de")
+    pub language: Option<String>,
+    /// ASCII-only drawing (--ascii)
+    pub ascii: Option<bool>,
+    /// Grades tab in timeline view
+    pub grades_timeline: Option<bool>,
+    /// Message threads list the newest message at the top
+    pub thread_newest_first: Option<bool>,
+    /// Opening a thread selects its latest message (default); false starts at the top
+    pub thread_open_at_latest: Option<bool>,
+    /// Initials badges in the students pane (default); false for names only
+    pub student_initials: Option<bool>,
+    /// Words that mark homework or events as exams, replacing the built-in list
+    pub exam_keywords: Option<Vec<String>>,
+    /// Longest gap between the clicks of a double click, in milliseconds
+    pub double_click_ms: Option<u64>,
+    /// Homework items listed on the Overview
+    pub overview_homework_count: Option<usize>,
+    /// Grades listed per subject in the Overview summary; all when unset
+    pub overview_grades_count: Option<usize>,
+    /// Days after an absence is entered that it can still be excused
+    pub absence_excuse_days: Option<i64>,
+    /// Minutes between the TUI's keep-alive requests; 0 turns them off
+    pub keep_alive_minutes: Option<u64>,
+    /// TUI key bindings by action name, e.g. "refresh": "F5"
+    pub keys: Option<BTreeMap<String, String>>,
+    /// When school ends ("HH:MM") on days without a cached schedule
+    pub default_school_day_end: Option<String>,
+    /// `json` output format without `--format` ("compact", "jsonl"...)
+    pub default_json_format: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +66,42 @@ pub struct TokenData {
     pub token: String,
     pub school_year: Option<i64>,
     pub user_data: Option<serde_json::Value>,
+    /// API host the token was issued by, when logged in with --api-url
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+impl TokenData {
+    /// Account role from the stored login response. Parent wins over pupil over
+    /// teacher, so mixed accounts keep the parent view. None when roles weren't
+    /// stored (e.g. an imported token).
+    pub fn primary_role(&self) -> Option<Role> {
+        let users = self.user_data.as_ref()?.get("users")?.as_array()?;
+        let roles: Vec<Role> = users.iter()
+            .filter_map(|u| u.get("roles")?.as_array())
+            .flatten()
+            .filter_map(|r| r.get("role_name")?.as_str())
+            .filter_map(Role::from_name)
+            .collect();
+
+        [Role::Parent, Role::Pupil, Role::Teacher]
+            .into_iter()
+            .find(|role| roles.contains(role))
+    }
+}
+
+/// A file in the store, as listed by `cache --json`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheEntry {
+    /// File name without `.json`, e.g. "grades_42"
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+    /// When the data was saved (Unix seconds); None for the token and settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<i64>,
+    /// Older than the TTL, so the next lookup asks the API
+    pub expired: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,36 +125,207 @@ impl<T> CachedData<T> {
     }
 
     pub fn age_string(&self) -> String {
-        let now = OffsetDateTime::now_utc().unix_timestamp();
-        let seconds = now - self.cached_at;
-
-        if seconds < 60 {
-            format!("{}s ago", seconds)
-        } else if seconds < 3600 {
-            format!("{}m ago", seconds / 60)
-        } else if seconds < 86400 {
-            format!("{}h ago", seconds / 3600)
-        } else {
-            format!("{}d ago", seconds / 86400)
+        age_string(self.cached_at)
+    }
+}
+
+/// English age of a cache timestamp ("12m ago"), as used in JSON output
+pub fn age_string(cached_at: i64) -> String {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let seconds = now - cached_at;
+
+    if seconds < 60 {
+        format!("{}s ago", seconds)
+    } else if seconds < 3600 {
+        format!("{}m ago", seconds / 60)
+    } else if seconds < 86400 {
+        format!("{}h ago", seconds / 3600)
+    } else {
+        format!("{}d ago", seconds / 86400)
+    }
+}
+
+/// How old cached data is compared with its TTL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Fresh,
+    /// Past the TTL, but by less than another TTL
+    Expired,
+    /// More than twice the TTL old
+    Stale,
+}
+
+impl Freshness {
+    /// `ttl_seconds` of None means the data never expires
+    pub fn of(cached_at: i64, now: i64, ttl_seconds: Option<i64>) -> Self {
+        let age = now - cached_at;
+        match ttl_seconds {
+            Some(ttl) if age > ttl.saturating_mul(2) => Freshness::Stale,
+            Some(ttl) if age > ttl => Freshness::Expired,
+            _ => Freshness::Fresh,
         }
     }
 }
 
+/// TTL for a schedule date: past schedules never expire and future ones expire
+/// sooner than `ttl_seconds`. Both dates are YYYY-MM-DD so they compare as strings.
+pub fn schedule_ttl(ttl_seconds: i64, date: &str, today: &str) -> Option<i64> {
+    match date.cmp(today) {
+        std::cmp::Ordering::Less => None,
+        std::cmp::Ordering::Equal => Some(ttl_seconds),
+        std::cmp::Ordering::Greater => Some(ttl_seconds.min(FUTURE_SCHEDULE_TTL_SECONDS)),
+    }
+}
+
+/// Data types tracked by the cache statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    Students,
+    Homework,
+    Grades,
+    Schedule,
+    Events,
+    Notifications,
+    Absences,
+    Messages,
+    Feedbacks,
+    Recipients,
+}
+
+impl CacheKind {
+    pub const ALL: [CacheKind; 10] = [
+        CacheKind::Students,
+        CacheKind::Homework,
+        CacheKind::Grades,
+        CacheKind::Schedule,
+        CacheKind::Events,
+        CacheKind::Notifications,
+        CacheKind::Absences,
+        CacheKind::Messages,
+        CacheKind::Feedbacks,
+        CacheKind::Recipients,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CacheKind::Students => "students",
+            CacheKind::Homework => "homework",
+            CacheKind::Grades => "grades",
+            CacheKind::Schedule => "schedule",
+            CacheKind::Events => "events",
+            CacheKind::Notifications => "notifications",
+            CacheKind::Absences => "absences",
+            CacheKind::Messages => "messages",
+            CacheKind::Feedbacks => "feedbacks",
+            CacheKind::Recipients => "recipients",
+        }
+    }
+}
+
+impl std::str::FromStr for CacheKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        CacheKind::ALL.iter()
+            .find(|kind| kind.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = CacheKind::ALL.iter().map(|k| k.name()).collect();
+                format!("unknown cache type '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Snapshot of the lookup counters for one data type.
+/// A hit is a fresh entry, an expiry is an entry past its TTL, a miss is no usable entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheCounts {
+    pub hits: u64,
+    pub misses: u64,
+    pub expired: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    expired: AtomicU64,
+}
+
+/// Session counters for cache lookups, shared between clones of a `CacheStore`
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    counters: [Counters; CacheKind::ALL.len()],
+}
+
+impl CacheStats {
+    fn counters(&self, kind: CacheKind) -> &Counters {
+        &self.counters[kind as usize]
+    }
+
+    pub fn get(&self, kind: CacheKind) -> CacheCounts {
+        let c = self.counters(kind);
+        CacheCounts {
+            hits: c.hits.load(Ordering::Relaxed),
+            misses: c.misses.load(Ordering::Relaxed),
+            expired: c.expired.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<(CacheKind, CacheCounts)> {
+        CacheKind::ALL.iter().map(|&kind| (kind, self.get(kind))).collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CacheStore {
     cache_dir: PathBuf,
+    /// Token, settings and drafts; the same as `cache_dir` unless using the XDG layout
+    config_dir: PathBuf,
     ttl_seconds: i64,
+    stats: Arc<CacheStats>,
 }
 
 impl CacheStore {
-    pub fn new(ttl_seconds: Option<i64>) -> Result<Self> {
+    /// Open the store in `cache_dir` (from `--cache-dir`), then `SHKOLO_CACHE_DIR`;
+    /// either holds everything. Otherwise Linux uses the XDG cache and config
+    /// directories, moving data over from `~/.shkolo/cache` on first run, and
+    /// other systems keep `~/.shkolo/cache`.
+    pub fn new(cache_dir: Option<PathBuf>, ttl_seconds: Option<i64>) -> Result<Self> {
+        let var = |name| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        if let Some(dir) = cache_dir.or_else(|| var(CACHE_DIR_ENV)) {
+            return Self::with_dir(dir, ttl_seconds);
+        }
+
         let home = dirs_home();
-        let cache_dir = home.join(".shkolo").join("cache");
+        let legacy = home.join(".shkolo").join("cache");
+        if cfg!(target_os = "linux") {
+            let (cache, config) = xdg_dirs(&home, var("XDG_CACHE_HOME"), var("XDG_CONFIG_HOME"));
+            // Anything going wrong here leaves the old directory to use as before
+            if let Ok(store) = Self::with_dirs(cache, config, ttl_seconds) {
+                if store.migrate_from(&legacy).is_ok() {
+                    return Ok(store);
+                }
+            }
+        }
+        Self::with_dir(legacy, ttl_seconds)
+    }
+
+    /// Use a specific directory for everything (created if missing)
+    pub fn with_dir(cache_dir: PathBuf, ttl_seconds: Option<i64>) -> Result<Self> {
+        Self::with_dirs(cache_dir.clone(), cache_dir, ttl_seconds)
+    }
+
+    /// Separate cache and config directories (created if missing)
+    pub fn with_dirs(cache_dir: PathBuf, config_dir: PathBuf, ttl_seconds: Option<i64>) -> Result<Self> {
         fs::create_dir_all(&cache_dir)?;
+        fs::create_dir_all(&config_dir)?;
 
         Ok(Self {
             cache_dir,
+            config_dir,
             ttl_seconds: ttl_seconds.unwrap_or(DEFAULT_TTL_SECONDS),
+            stats: Arc::new(CacheStats::default()),
         })
     }
 
@@ -79,12 +333,106 @@ impl CacheStore {
         &self.cache_dir
     }
 
+    pub fn config_dir(&self) -> &PathBuf {
+        &self.config_dir
+    }
+
+    /// Store for another school year's data (`json --school-year`), kept in its own
+    /// directory so it never replaces this year's. Token and settings are shared.
+    pub fn for_school_year(&self, year: i64) -> Result<Self> {
+        let dir = self.cache_dir.join(SCHOOL_YEARS_DIR).join(year.to_string());
+        let store = Self::with_dirs(dir, self.config_dir.clone(), Some(self.ttl_seconds))?;
+        Ok(Self { stats: self.stats.clone(), ..store })
+    }
+
+    /// Move the files of an old single-directory store into this one, unless
+    /// this one already has a token or settings. Files are copied first, so a
+    /// failure leaves the old directory complete. Returns the number moved.
+    fn migrate_from(&self, legacy: &Path) -> Result<usize> {
+        let already_set_up = CONFIG_FILES.iter().any(|name| self.file_path(name).exists());
+        if !legacy.is_dir() || legacy == self.cache_dir || already_set_up {
+            return Ok(0);
+        }
+
+        let mut moved = Vec::new();
+        for entry in fs::read_dir(legacy)? {
+            let from = entry?.path();
+            let Some(stem) = from.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if !from.is_file() || from.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let to = self.file_path(stem);
+            // fs::copy keeps the permission bits, so the token stays 0600
+            if let Err(e) = fs::copy(&from, &to) {
+                let copies: Vec<PathBuf> = moved.into_iter().map(|(_, to)| to).collect();
+                let _ = Self::remove_files(&copies);
+                return Err(e.into());
+            }
+            moved.push((from, to));
+        }
+
+        for (from, _) in &moved {
+            let _ = fs::remove_file(from);
+        }
+        // Only removed when nothing else was left behind
+        let _ = fs::remove_dir(legacy);
+        if let Some(parent) = legacy.parent() {
+            let _ = fs::remove_dir(parent);
+        }
+        Ok(moved.len())
+    }
+
     pub fn ttl(&self) -> i64 {
         self.ttl_seconds
     }
 
+    /// The same store, with entries older than `seconds` expired as well (`json --max-age`)
+    pub fn with_max_age(mut self, seconds: i64) -> Self {
+        self.ttl_seconds = self.ttl_seconds.min(seconds);
+        self
+    }
+
+    /// Hit/miss/expiry counts per data type for this session
+    pub fn stats(&self) -> Vec<(CacheKind, CacheCounts)> {
+        self.stats.snapshot()
+    }
+
+    /// Shared handle to the counters, for displaying live stats elsewhere
+    pub fn stats_handle(&self) -> Arc<CacheStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// Unwrap a loaded entry into (data, age, expired, cached_at), recording the lookup
+    fn lookup<T>(&self, kind: CacheKind, loaded: Result<CachedData<T>>) -> Option<(T, String, bool, i64)> {
+        self.lookup_with_ttl(kind, loaded, Some(self.ttl_seconds))
+    }
+
+    /// Like `lookup`, with a specific TTL (None never expires)
+    fn lookup_with_ttl<T>(&self, kind: CacheKind, loaded: Result<CachedData<T>>, ttl_seconds: Option<i64>) -> Option<(T, String, bool, i64)> {
+        let counters = self.stats.counters(kind);
+        match loaded {
+            Ok(cached) => {
+                let expired = ttl_seconds.is_some_and(|ttl| cached.is_expired(ttl));
+                if expired {
+                    counters.expired.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    counters.hits.fetch_add(1, Ordering::Relaxed);
+                }
+                let age = cached.age_string();
+                Some((cached.data, age, expired, cached.cached_at))
+            }
+            Err(_) => {
+                counters.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
     fn file_path(&self, name: &str) -> PathBuf {
-        self.cache_dir.join(format!("{}.json", name))
+        let dir = if CONFIG_FILES.contains(&name) { &self.config_dir } else { &self.cache_dir };
+        dir.join(format!("{}.json", name))
     }
 
     fn read_file<T: DeserializeOwned>(&self, name: &str) -> Result<T> {
@@ -115,11 +463,12 @@ impl CacheStore {
         self.read_file("token")
     }
 
-    pub fn save_token(&self, token: &str, school_year: Option<i64>, user_data: Option<serde_json::Value>) -> Result<()> {
+    pub fn save_token(&self, token: &str, school_year: Option<i64>, user_data: Option<serde_json::Value>, api_url: Option<&str>) -> Result<()> {
         let data = TokenData {
             token: token.to_string(),
             school_year,
             user_data,
+            api_url: api_url.map(str::to_string),
         };
         self.write_file("token", &data)
     }
@@ -143,15 +492,8 @@ impl CacheStore {
         self.write_file("students", &cached)
     }
 
-    pub fn get_students(&self) -> Option<(Vec<Student>, String, bool)> {
-        match self.load_students() {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_students(&self) -> Option<(Vec<Student>, String, bool, i64)> {
+        self.lookup(CacheKind::Students, self.load_students())
     }
 
     // Homework cache (per student)
@@ -165,15 +507,8 @@ impl CacheStore {
         self.write_file(&format!("homework_{}", student_id), &cached)
     }
 
-    pub fn get_homework(&self, student_id: i64) -> Option<(Vec<Homework>, String, bool)> {
-        match self.load_homework(student_id) {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_homework(&self, student_id: i64) -> Option<(Vec<Homework>, String, bool, i64)> {
+        self.lookup(CacheKind::Homework, self.load_homework(student_id))
     }
 
     // Grades cache (per student)
@@ -187,15 +522,8 @@ impl CacheStore {
         self.write_file(&format!("grades_{}", student_id), &cached)
     }
 
-    pub fn get_grades(&self, student_id: i64) -> Option<(Vec<Grade>, String, bool)> {
-        match self.load_grades(student_id) {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_grades(&self, student_id: i64) -> Option<(Vec<Grade>, String, bool, i64)> {
+        self.lookup(CacheKind::Grades, self.load_grades(student_id))
     }
 
     // Schedule cache (per student, per date)
@@ -209,15 +537,14 @@ impl CacheStore {
         self.write_file(&format!("schedule_{}_{}", student_id, date), &cached)
     }
 
-    pub fn get_schedule(&self, student_id: i64, date: &str) -> Option<(Vec<ScheduleHour>, String, bool)> {
-        match self.load_schedule(student_id, date) {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    /// Past schedules never expire and future ones expire sooner than the usual TTL
+    pub fn get_schedule(&self, student_id: i64, date: &str) -> Option<(Vec<ScheduleHour>, String, bool, i64)> {
+        self.get_schedule_as_of(student_id, date, &util::today_local())
+    }
+
+    fn get_schedule_as_of(&self, student_id: i64, date: &str, today: &str) -> Option<(Vec<ScheduleHour>, String, bool, i64)> {
+        let ttl = schedule_ttl(self.ttl_seconds, date, today);
+        self.lookup_with_ttl(CacheKind::Schedule, self.load_schedule(student_id, date), ttl)
     }
 
     // Events cache (per student)
@@ -231,15 +558,8 @@ impl CacheStore {
         self.write_file(&format!("events_{}", student_id), &cached)
     }
 
-    pub fn get_events(&self, student_id: i64) -> Option<(Vec<Event>, String, bool)> {
-        match self.load_events(student_id) {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_events(&self, student_id: i64) -> Option<(Vec<Event>, String, bool, i64)> {
+        self.lookup(CacheKind::Events, self.load_events(student_id))
     }
 
     // Notifications cache (global, not per student)
@@ -253,15 +573,8 @@ impl CacheStore {
         self.write_file("notifications", &cached)
     }
 
-    pub fn get_notifications(&self) -> Option<(Vec<Notification>, String, bool)> {
-        match self.load_notifications() {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_notifications(&self) -> Option<(Vec<Notification>, String, bool, i64)> {
+        self.lookup(CacheKind::Notifications, self.load_notifications())
     }
 
     // Absences cache (per student)
@@ -275,37 +588,24 @@ impl CacheStore {
         self.write_file(&format!("absences_{}", student_id), &cached)
     }
 
-    pub fn get_absences(&self, student_id: i64) -> Option<(Vec<Absence>, String, bool)> {
-        match self.load_absences(student_id) {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_absences(&self, student_id: i64) -> Option<(Vec<Absence>, String, bool, i64)> {
+        self.lookup(CacheKind::Absences, self.load_absences(student_id))
     }
 
     // Messages cache (global, not per student)
 
-    pub fn load_messages(&self) -> Result<CachedData<Vec<MessageThread>>> {
-        self.read_file("messages")
+    /// Threads of a messenger folder; None is the default list (the inbox)
+    pub fn load_messages(&self, folder_id: Option<i64>) -> Result<CachedData<Vec<MessageThread>>> {
+        self.read_file(&messages_file(folder_id))
     }
 
-    pub fn save_messages(&self, messages: &[MessageThread]) -> Result<()> {
+    pub fn save_messages(&self, folder_id: Option<i64>, messages: &[MessageThread]) -> Result<()> {
         let cached = CachedData::new(messages.to_vec());
-        self.write_file("messages", &cached)
+        self.write_file(&messages_file(folder_id), &cached)
     }
 
-    pub fn get_messages(&self) -> Option<(Vec<MessageThread>, String, bool)> {
-        match self.load_messages() {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_messages(&self, folder_id: Option<i64>) -> Option<(Vec<MessageThread>, String, bool, i64)> {
+        self.lookup(CacheKind::Messages, self.load_messages(folder_id))
     }
 
     // Feedbacks cache (per student)
@@ -319,48 +619,142 @@ impl CacheStore {
         self.write_file(&format!("feedbacks_{}", student_id), &cached)
     }
 
-    pub fn get_feedbacks(&self, student_id: i64) -> Option<(Vec<Feedback>, String, bool)> {
-        match self.load_feedbacks(student_id) {
-            Ok(cached) => {
-                let expired = cached.is_expired(self.ttl_seconds);
-                let age = cached.age_string();
-                Some((cached.data, age, expired))
-            }
-            Err(_) => None,
-        }
+    pub fn get_feedbacks(&self, student_id: i64) -> Option<(Vec<Feedback>, String, bool, i64)> {
+        self.lookup(CacheKind::Feedbacks, self.load_feedbacks(student_id))
+    }
+
+    // Message recipients (global)
+
+    pub fn load_recipients(&self) -> Result<CachedData<Vec<Recipient>>> {
+        self.read_file("recipients")
+    }
+
+    pub fn save_recipients(&self, recipients: &[Recipient]) -> Result<()> {
+        let cached = CachedData::new(recipients.to_vec());
+        self.write_file("recipients", &cached)
+    }
+
+    pub fn get_recipients(&self) -> Option<(Vec<Recipient>, String, bool, i64)> {
+        self.lookup_with_ttl(CacheKind::Recipients, self.load_recipients(), Some(self.recipients_ttl()))
+    }
+
+    fn recipients_ttl(&self) -> i64 {
+        self.ttl_seconds.max(RECIPIENTS_TTL_SECONDS)
     }
 
     // Cache management
 
-    pub fn clear(&self) -> Result<()> {
-        if self.cache_dir.exists() {
-            for entry in fs::read_dir(&self.cache_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |e| e == "json") {
-                    // Don't delete token file on regular clear
-                    if path.file_stem().map_or(false, |s| s != "token") {
-                        fs::remove_file(path)?;
-                    }
+    /// JSON files in the cache directory, its other school years and the config
+    /// directory whose name (without extension) matches `keep`, sorted by path
+    fn json_files(&self, keep: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut dirs = vec![self.cache_dir.clone()];
+        if let Ok(years) = fs::read_dir(self.cache_dir.join(SCHOOL_YEARS_DIR)) {
+            dirs.extend(years.filter_map(|entry| entry.ok()).map(|entry| entry.path()));
+        }
+        for dir in dirs.iter().filter(|d| d.is_dir()) {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if !path.is_file() || path.extension().is_none_or(|e| e != "json") {
+                    continue;
+                }
+                if path.file_stem().and_then(|s| s.to_str()).is_some_and(&keep) {
+                    files.push(path);
                 }
             }
         }
-        Ok(())
+        // Only the config files from the config directory: for another school
+        // year's store, that is where this year's cache lives
+        if self.config_dir != self.cache_dir {
+            let config = CONFIG_FILES.iter().filter(|name| keep(name)).map(|name| self.file_path(name));
+            files.extend(config.filter(|path| path.is_file()));
+        }
+        files.sort();
+        Ok(files)
     }
 
-    pub fn clear_all(&self) -> Result<()> {
-        if self.cache_dir.exists() {
-            for entry in fs::read_dir(&self.cache_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |e| e == "json") {
-                    fs::remove_file(path)?;
-                }
+    /// Every file in the store with how old its data is, for `cache --json`
+    pub fn list_entries(&self) -> Result<Vec<CacheEntry>> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let today = util::today_local();
+        let mut entries = Vec::new();
+        for path in self.json_files(|_| true)? {
+            let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+            let bytes = fs::metadata(&path)?.len();
+            // Settings and the token have no timestamp
+            let cached_at = fs::read_to_string(&path).ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .and_then(|value| value.get("cached_at")?.as_i64());
+            let ttl = match name.strip_prefix("schedule_").and_then(|rest| rest.split_once('_')) {
+                Some((_, date)) => schedule_ttl(self.ttl_seconds, date, &today),
+                None if name == "recipients" => Some(self.recipients_ttl()),
+                None => Some(self.ttl_seconds),
+            };
+            let expired = cached_at.zip(ttl).is_some_and(|(at, ttl)| now - at > ttl);
+            entries.push(CacheEntry { name, path: path.display().to_string(), bytes, cached_at, expired });
+        }
+        Ok(entries)
+    }
+
+    /// Files `cache --clear` would remove, or `--clear-all` with `include_token`.
+    /// Unsent drafts aren't cache, so only `--clear-all` removes them.
+    pub fn clear_files(&self, include_token: bool) -> Result<Vec<PathBuf>> {
+        self.json_files(|stem| include_token || !matches!(stem, "token" | "drafts"))
+    }
+
+    /// Files `clear_type` would remove
+    pub fn type_files(&self, kind: CacheKind, student_id: Option<i64>) -> Result<Vec<PathBuf>> {
+        let name = kind.name();
+        let per_student = !matches!(kind, CacheKind::Students | CacheKind::Notifications | CacheKind::Messages | CacheKind::Recipients);
+        if student_id.is_some() && !per_student {
+            anyhow::bail!("{} are not cached per student", name);
+        }
+
+        // Files are "<type>", "<type>_<student>" or "schedule_<student>_<date>";
+        // messages also have "messages_<folder>"
+        self.json_files(|stem| match (per_student, student_id) {
+            (false, _) if kind == CacheKind::Messages => stem == name || stem.starts_with("messages_"),
+            (false, _) => stem == name,
+            (true, None) => stem.starts_with(&format!("{}_", name)),
+            (true, Some(id)) => {
+                let prefix = format!("{}_{}", name, id);
+                stem == prefix || stem.starts_with(&format!("{}_", prefix))
             }
+        })
+    }
+
+    pub fn remove_files(files: &[PathBuf]) -> Result<()> {
+        for path in files {
+            fs::remove_file(path)?;
         }
         Ok(())
     }
 
+    /// Remove cached files of one data type, optionally only for one student.
+    /// Returns the number of files removed.
+    pub fn clear_type(&self, kind: CacheKind, student_id: Option<i64>) -> Result<usize> {
+        let files = self.type_files(kind, student_id)?;
+        Self::remove_files(&files)?;
+        Ok(files.len())
+    }
+
+    /// Record the API language of cached data. When it differs from what the cache
+    /// was filled with, the cached API data is dropped, since it holds names and
+    /// labels in the old language. Returns whether anything was invalidated.
+    pub fn set_data_language(&self, code: &str) -> Result<bool> {
+        // Caches from before the language was recorded were always fetched in Bulgarian
+        let previous = self.read_file::<String>("data_language").unwrap_or_else(|_| "bg".to_string());
+        if previous == code {
+            return Ok(false);
+        }
+
+        for kind in CacheKind::ALL {
+            self.clear_type(kind, None)?;
+        }
+        self.write_file("data_language", &code)?;
+        Ok(true)
+    }
+
     // UI configuration (persistent settings)
 
     pub fn load_ui_config(&self) -> UiConfig {
@@ -370,12 +764,47 @@ impl CacheStore {
     pub fn save_ui_config(&self, config: &UiConfig) -> Result<()> {
         self.write_file("ui_config", config)
     }
+
+    // Unsent messages put aside in the TUI
+
+    pub fn load_drafts(&self) -> Drafts {
+        self.read_file::<Drafts>("drafts").unwrap_or_default()
+    }
+
+    pub fn save_drafts(&self, drafts: &Drafts) -> Result<()> {
+        self.write_file("drafts", drafts)
+    }
+
+    // Grades and homework already seen, for "new since last visit"
+
+    pub fn load_seen(&self) -> Seen {
+        self.read_file::<Seen>("seen").unwrap_or_default()
+    }
+
+    pub fn save_seen(&self, seen: &Seen) -> Result<()> {
+        self.write_file("seen", seen)
+    }
+}
+
+fn messages_file(folder_id: Option<i64>) -> String {
+    match folder_id {
+        Some(id) => format!("messages_{}", id),
+        None => "messages".to_string(),
+    }
 }
 
 fn dirs_home() -> PathBuf {
     dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
 }
 
+/// (cache, config) directories: `$XDG_CACHE_HOME/shkolo` and `$XDG_CONFIG_HOME/shkolo`,
+/// defaulting to `~/.cache` and `~/.config`. Relative values are ignored, as the
+/// XDG spec asks.
+fn xdg_dirs(home: &Path, xdg_cache_home: Option<PathBuf>, xdg_config_home: Option<PathBuf>) -> (PathBuf, PathBuf) {
+    let base = |xdg: Option<PathBuf>, default: &str| xdg.filter(|p| p.is_absolute()).unwrap_or_else(|| home.join(default));
+    (base(xdg_cache_home, ".cache").join("shkolo"), base(xdg_config_home, ".config").join("shkolo"))
+}
+
 // Add dirs crate functions since we're using directories
 mod dirs {
     use std::path::PathBuf;
@@ -386,3 +815,288 @@ mod dirs {
             .map(PathBuf::from)
     }
 }
+
+/// A store in a fresh temporary directory, removed again when this is dropped
+#[cfg(test)]
+pub struct TempCache(CacheStore);
+
+#[cfg(test)]
+impl TempCache {
+    pub fn new(ttl_seconds: Option<i64>) -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let dir = std::env::temp_dir().join(format!("shkolo-test-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)));
+        let _ = fs::remove_dir_all(&dir);
+        Self(CacheStore::with_dir(dir, ttl_seconds).unwrap())
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TempCache {
+    type Target = CacheStore;
+
+    fn deref(&self) -> &CacheStore {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempCache {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(self.0.cache_dir());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_count_hits_misses_and_expiries() {
+        let cache = TempCache::new(None);
+        let shared = cache.clone();
+
+        assert!(cache.get_grades(1).is_none());
+        cache.save_grades(1, &[]).unwrap();
+        assert!(shared.get_grades(1).is_some());
+
+        let expiring = CacheStore::with_dir(cache.cache_dir().clone(), Some(-1)).unwrap();
+        assert!(expiring.get_grades(1).is_some_and(|(_, _, expired, _)| expired));
+
+        let grades = cache.stats_handle().get(CacheKind::Grades);
+        assert_eq!(grades, CacheCounts { hits: 1, misses: 1, expired: 0 });
+        assert_eq!(expiring.stats_handle().get(CacheKind::Grades).expired, 1);
+        assert_eq!(cache.stats_handle().get(CacheKind::Homework), CacheCounts::default());
+    }
+
+    #[test]
+    fn test_schedule_expiry_depends_on_date() {
+        let cache = TempCache::new(Some(3600));
+        let today = "2026-02-19";
+
+        // Entries cached two hours ago
+        let two_hours_ago = OffsetDateTime::now_utc().unix_timestamp() - 2 * 3600;
+        for date in ["2026-02-18", "2026-02-19", "2026-02-20"] {
+            let cached = CachedData { data: Vec::<ScheduleHour>::new(), cached_at: two_hours_ago };
+            cache.write_file(&format!("schedule_1_{}", date), &cached).unwrap();
+        }
+
+        let expired = |date: &str| cache.get_schedule_as_of(1, date, today).map(|(_, _, e, _)| e);
+        assert_eq!(expired("2026-02-18"), Some(false)); // Yesterday never expires
+        assert_eq!(expired("2026-02-19"), Some(true)); // Today uses the normal TTL
+        assert_eq!(expired("2026-02-20"), Some(true));
+        assert_eq!(expired("2026-02-21"), None);
+
+        // Tomorrow expires sooner than today
+        let twenty_minutes_ago = OffsetDateTime::now_utc().unix_timestamp() - 20 * 60;
+        for date in ["2026-02-19", "2026-02-20"] {
+            let cached = CachedData { data: Vec::<ScheduleHour>::new(), cached_at: twenty_minutes_ago };
+            cache.write_file(&format!("schedule_1_{}", date), &cached).unwrap();
+        }
+        assert_eq!(expired("2026-02-19"), Some(false));
+        assert_eq!(expired("2026-02-20"), Some(true));
+    }
+
+    #[test]
+    fn test_freshness_thresholds() {
+        assert_eq!(Freshness::of(0, 3600, Some(3600)), Freshness::Fresh);
+        assert_eq!(Freshness::of(0, 3601, Some(3600)), Freshness::Expired);
+        assert_eq!(Freshness::of(0, 7201, Some(3600)), Freshness::Stale);
+        assert_eq!(Freshness::of(0, 1_000_000, None), Freshness::Fresh);
+
+        assert_eq!(schedule_ttl(3600, "2025-01-09", "2025-01-10"), None);
+        assert_eq!(schedule_ttl(3600, "2025-01-10", "2025-01-10"), Some(3600));
+        assert_eq!(schedule_ttl(3600, "2025-01-11", "2025-01-10"), Some(FUTURE_SCHEDULE_TTL_SECONDS));
+    }
+
+    #[test]
+    fn test_clear_files_lists_without_removing() {
+        let cache = TempCache::new(None);
+
+        cache.save_token("token", None, None, None).unwrap();
+        cache.save_grades(1, &[]).unwrap();
+        cache.save_notifications(&[]).unwrap();
+        fs::write(cache.cache_dir().join("notes.txt"), "not ours").unwrap();
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect()
+        };
+        assert_eq!(names(cache.clear_files(false).unwrap()), ["grades_1.json", "notifications.json"]);
+        assert_eq!(names(cache.clear_files(true).unwrap()), ["grades_1.json", "notifications.json", "token.json"]);
+        assert_eq!(names(cache.type_files(CacheKind::Grades, Some(1)).unwrap()), ["grades_1.json"]);
+        // Listing never deletes
+        assert!(cache.load_token().is_ok() && cache.get_grades(1).is_some());
+
+        let entries = cache.list_entries().unwrap();
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["grades_1", "notifications", "token"]);
+        assert!(entries[0].cached_at.is_some() && !entries[0].expired && entries[0].bytes > 0);
+        assert_eq!(entries[2].cached_at, None);
+    }
+
+    #[test]
+    fn test_drafts_round_trip_and_survive_clear() {
+        let cache = TempCache::new(None);
+        assert_eq!(cache.load_drafts(), Drafts::default());
+
+        let mut drafts = Drafts::default();
+        drafts.set(Draft { thread_id: None, subject: "Отсъствие".into(), body: "Здравейте,".into(), recipients: vec![7, 9] });
+        drafts.set(Draft { thread_id: Some(42), body: "Благодаря".into(), ..Default::default() });
+        cache.save_drafts(&drafts).unwrap();
+        cache.save_grades(1, &[]).unwrap();
+
+        let loaded = cache.load_drafts();
+        assert_eq!(loaded, drafts);
+        assert_eq!(loaded.get(None).map(|d| d.recipients.clone()), Some(vec![7, 9]));
+        assert_eq!(loaded.get(Some(42)).map(|d| d.body.as_str()), Some("Благодаря"));
+        assert_eq!(loaded.get(Some(1)), None);
+
+        // `cache --clear` keeps them; only --clear-all removes them
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect()
+        };
+        assert_eq!(names(cache.clear_files(false).unwrap()), ["grades_1.json"]);
+        assert_eq!(names(cache.clear_files(true).unwrap()), ["drafts.json", "grades_1.json"]);
+    }
+
+    #[test]
+    fn test_clear_type_only_removes_matching_files() {
+        let cache = TempCache::new(None);
+
+        cache.save_token("token", None, None, None).unwrap();
+        cache.save_grades(1, &[]).unwrap();
+        cache.save_grades(12, &[]).unwrap();
+        cache.save_schedule(1, "2026-02-19", &[]).unwrap();
+        cache.save_schedule(1, "2026-02-20", &[]).unwrap();
+        cache.save_schedule(12, "2026-02-19", &[]).unwrap();
+        cache.save_notifications(&[]).unwrap();
+
+        // Student 1 must not match student 12
+        assert_eq!(cache.clear_type(CacheKind::Schedule, Some(1)).unwrap(), 2);
+        assert!(cache.get_schedule(12, "2026-02-19").is_some());
+
+        assert_eq!(cache.clear_type(CacheKind::Grades, None).unwrap(), 2);
+        assert!(cache.get_grades(1).is_none() && cache.get_grades(12).is_none());
+
+        assert!(cache.clear_type(CacheKind::Notifications, Some(1)).is_err());
+        assert_eq!(cache.clear_type(CacheKind::Notifications, None).unwrap(), 1);
+        assert_eq!(cache.clear_type(CacheKind::Homework, None).unwrap(), 0);
+        assert!(cache.load_token().is_ok());
+
+        assert_eq!("grades".parse::<CacheKind>(), Ok(CacheKind::Grades));
+        assert!("token".parse::<CacheKind>().is_err());
+    }
+
+    #[test]
+    fn test_school_year_store_is_kept_apart() {
+        let cache = TempCache::new(None);
+        cache.save_token("token", Some(25), None, None).unwrap();
+        cache.save_grades(1, &[]).unwrap();
+
+        let last_year = cache.for_school_year(24).unwrap();
+        assert_eq!(last_year.load_token().unwrap().school_year, Some(25));
+        assert!(last_year.get_grades(1).is_none());
+        last_year.save_grades(2, &[]).unwrap();
+        assert!(cache.get_grades(2).is_none());
+
+        // A language change there leaves this year's data alone
+        assert!(last_year.set_data_language("en").unwrap());
+        assert!(cache.get_grades(1).is_some() && last_year.get_grades(2).is_none());
+
+        // `cache --clear` covers every year
+        last_year.save_grades(2, &[]).unwrap();
+        let names: Vec<_> = cache.clear_files(false).unwrap().into_iter()
+            .map(|p| p.strip_prefix(cache.cache_dir()).unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["grades_1.json", "years/24/data_language.json", "years/24/grades_2.json"]);
+    }
+
+    #[test]
+    fn test_language_change_drops_cached_data() {
+        let cache = TempCache::new(None);
+
+        cache.save_token("token", None, None, None).unwrap();
+        cache.save_grades(1, &[]).unwrap();
+        cache.save_ui_config(&UiConfig::default()).unwrap();
+
+        // Existing caches were fetched in Bulgarian
+        assert!(!cache.set_data_language("bg").unwrap());
+        assert!(cache.get_grades(1).is_some());
+
+        assert!(cache.set_data_language("en").unwrap());
+        assert!(cache.get_grades(1).is_none());
+        assert!(cache.load_token().is_ok());
+        assert!(cache.file_path("ui_config").exists());
+        assert!(!cache.set_data_language("en").unwrap());
+    }
+
+    #[test]
+    fn test_xdg_dirs() {
+        let home = PathBuf::from("/home/maria");
+        assert_eq!(xdg_dirs(&home, None, None), (home.join(".cache/shkolo"), home.join(".config/shkolo")));
+        assert_eq!(
+            xdg_dirs(&home, Some("/var/cache".into()), Some("/etc/xdg".into())),
+            (PathBuf::from("/var/cache/shkolo"), PathBuf::from("/etc/xdg/shkolo")),
+        );
+        assert_eq!(xdg_dirs(&home, Some("cache".into()), None).0, home.join(".cache/shkolo"));
+    }
+
+    #[test]
+    fn test_migrate_from_legacy_dir() {
+        let temp = TempCache::new(None);
+        let root = temp.cache_dir();
+        let legacy = CacheStore::with_dir(root.join(".shkolo/cache"), None).unwrap();
+        legacy.save_token("token", None, None, None).unwrap();
+        legacy.save_ui_config(&UiConfig { ascii: Some(true), ..Default::default() }).unwrap();
+        legacy.save_grades(1, &[]).unwrap();
+
+        let store = CacheStore::with_dirs(root.join("cache/shkolo"), root.join("config/shkolo"), None).unwrap();
+        assert_eq!(store.migrate_from(legacy.cache_dir()).unwrap(), 3);
+        assert!(store.config_dir().join("token.json").exists());
+        assert!(store.cache_dir().join("grades_1.json").exists());
+        assert_eq!(store.load_token().unwrap().token, "token");
+        assert_eq!(store.load_ui_config().ascii, Some(true));
+        assert!(store.get_grades(1).is_some());
+        // The emptied ~/.shkolo is gone
+        assert!(!root.join(".shkolo").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(store.config_dir().join("token.json")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Once set up, a reappearing old directory is left alone
+        let stale = CacheStore::with_dir(root.join(".shkolo/cache"), None).unwrap();
+        stale.save_token("old", None, None, None).unwrap();
+        assert_eq!(store.migrate_from(stale.cache_dir()).unwrap(), 0);
+        assert_eq!(store.load_token().unwrap().token, "token");
+    }
+
+    #[test]
+    fn test_primary_role() {
+        let token = |user_data: serde_json::Value| TokenData {
+            token: "token".into(),
+            school_year: None,
+            user_data: Some(user_data),
+            api_url: None,
+        };
+
+        let teacher = token(serde_json::json!({
+            "users": [{ "names": "Иван", "roles": [{ "role_id": 3, "role_name": "Учител" }] }],
+        }));
+        assert_eq!(teacher.primary_role(), Some(Role::Teacher));
+
+        // A teacher who is also a parent keeps the parent view
+        let both = token(serde_json::json!({
+            "users": [
+                { "roles": [{ "role_name": "Учител" }] },
+                { "roles": [{ "role_name": "Родител" }] },
+            ],
+        }));
+        assert_eq!(both.primary_role(), Some(Role::Parent));
+
+        // Imported tokens don't carry roles
+        assert_eq!(token(serde_json::json!({ "names": "Иван" })).primary_role(), None);
+    }
+}
@@ -0,0 +1,264 @@
+//! Plain text out of the HTML fragments some teachers paste from Word:
+//! tags are dropped, line-breaking ones become newlines, entities are
+//! decoded and link targets are kept aside so they stay reachable.
+
+/// Text ready for display plus the targets of the links it contained
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sanitized {
+    pub text: String,
+    pub links: Vec<String>,
+}
+
+/// Whether `input` contains something that looks like a tag. Plain text
+/// with a stray `<` (e.g. "x < 5") is left alone.
+pub fn has_tags(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    bytes.windows(2).any(|w| w[0] == b'<' && (w[1].is_ascii_alphabetic() || w[1] == b'/' || w[1] == b'!'))
+}
+
+/// Strip tags, turn `<br>` and the ends of block elements into line breaks,
+/// decode entities and collapse whitespace. Text without tags keeps its own
+/// line breaks and only gets its entities decoded.
+pub fn sanitize(input: &str) -> Sanitized {
+    if !has_tags(input) {
+        return Sanitized { text: tidy(&decode_entities(input), false), links: Vec::new() };
+    }
+
+    let mut text = String::new();
+    let mut links = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find('<') {
+        // Line breaks in the source are just whitespace
+        text.push_str(&rest[..start].replace(['\r', '\n'], " "));
+        let after = &rest[start + 1..];
+        let opens_tag = after.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!');
+        if !opens_tag {
+            text.push('<');
+            rest = after;
+            continue;
+        }
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = after.find('>') else {
+            // An unterminated tag swallows the rest, like a browser would
+            rest = "";
+            break;
+        };
+        let tag = &after[..end];
+        rest = &after[end + 1..];
+
+        let name = tag_name(tag);
+        match name.as_str() {
+            "br" | "/p" | "/div" | "/li" | "/tr" | "/h1" | "/h2" | "/h3" | "/h4" | "/h5" | "/h6" => text.push('\n'),
+            // A paragraph starts on a line of its own even when the one
+            // before it was never closed
+            "p" | "div" => start_line(&mut text),
+            "li" => {
+                start_line(&mut text);
+                text.push_str("- ");
+            }
+            "td" | "th" => text.push(' '),
+            "a" => {
+                if let Some(href) = attribute(tag, "href").map(|h| decode_entities(&h)) {
+                    let href = href.trim().to_string();
+                    if !href.is_empty() && !href.starts_with('#') && !href.starts_with("javascript:") && !links.contains(&href) {
+                        links.push(href);
+                    }
+                }
+            }
+            // Their content isn't text
+            "style" | "script" => {
+                let close = format!("</{}", name);
+                rest = find_ignore_case(rest, &close)
+                    .map_or("", |i| rest[i..].find('>').map_or("", |e| &rest[i + e + 1..]));
+            }
+            _ => {}
+        }
+    }
+    text.push_str(&rest.replace(['\r', '\n'], " "));
+
+    Sanitized { text: tidy(&decode_entities(&text), true), links }
+}
+
+fn start_line(text: &mut String) {
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+/// Lowercase tag name, with a leading '/' for closing tags
+fn tag_name(tag: &str) -> String {
+    let closing = tag.starts_with('/');
+    let name: String = tag.trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if closing { format!("/{}", name) } else { name }
+}
+
+/// Value of `name="..."`, `name='...'` or `name=...` in a tag's source
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(name) {
+        let at = from + found;
+        from = at + name.len();
+        // Must be a whole attribute name, not the tail of another one
+        if !lower[..at].ends_with(|c: char| c.is_whitespace()) {
+            continue;
+        }
+        let value = tag[from..].trim_start();
+        let Some(value) = value.strip_prefix('=') else { continue };
+        let value = value.trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default().to_string(),
+            _ => value.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default().to_string(),
+        });
+    }
+    None
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(&needle.to_ascii_lowercase())
+}
+
+/// Named entities that turn up in pasted text, and numeric ones in either
+/// base. Anything unrecognised is kept as written.
+pub fn decode_entities(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..].find(';')
+            .filter(|&end| end > 0 && end <= 10)
+            .and_then(|end| entity(&rest[1..=end]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+    Some(match name {
+        "nbsp" => ' ',
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "hellip" => '…',
+        "ndash" => '–',
+        "mdash" => '—',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bdquo" => '„',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "bull" => '•',
+        "deg" => '°',
+        "euro" => '€',
+        "copy" => '©',
+        "shy" => '\u{ad}',
+        _ => return None,
+    })
+}
+
+/// Trim line ends and drop soft hyphens; with `collapse`, runs of spaces
+/// shrink to one and runs of blank lines to a single empty line, as HTML
+/// whitespace doesn't carry meaning
+fn tidy(text: &str, collapse: bool) -> String {
+    let text = text.replace('\u{ad}', "").replace("\r\n", "\n");
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = if collapse {
+            line.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            line.trim_end().to_string()
+        };
+        if collapse && line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n").trim_start_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_paste() {
+        let input = "<p class=\"MsoNormal\"><span style=\"font-size:12.0pt\">Упражнение&nbsp;3,&nbsp;стр.&nbsp;45</span></p>\r\n\
+                     <p class=\"MsoNormal\">&nbsp;</p>\r\n<p>Прочетете   <b>„Под игото“</b><br>глава&nbsp;1&ndash;3</p>";
+        let s = sanitize(input);
+        // The empty paragraph stays as a blank line
+        assert_eq!(s.text, "Упражнение 3, стр. 45\n\nПрочетете „Под игото“\nглава 1–3");
+        assert!(s.links.is_empty());
+    }
+
+    #[test]
+    fn test_cyrillic_numeric_entities() {
+        assert_eq!(sanitize("&#1044;&#1086;&#1084;&#1072;&#1096;&#1085;&#1086;").text, "Домашно");
+        assert_eq!(sanitize("<div>&#x417;&#x430;&#x434;&#x430;&#x447;&#x430; 5</div>").text, "Задача 5");
+        assert_eq!(decode_entities("&laquo;Бай Ганьо&raquo; &amp; &quot;Чичовци&quot;"), "«Бай Ганьо» & \"Чичовци\"");
+    }
+
+    #[test]
+    fn test_links_are_extracted() {
+        let input = "<p>Вижте <a href=\"https://example.com/a?x=1&amp;y=2\" target=\"_blank\">тук</a> и \
+                     <A HREF='https://example.com/b'>тук</A>, <a href=\"#top\">горе</a></p>\
+                     <p><a href=\"https://example.com/a?x=1&amp;y=2\">пак</a></p>";
+        let s = sanitize(input);
+        assert_eq!(s.text, "Вижте тук и тук, горе\nпак");
+        assert_eq!(s.links, vec!["https://example.com/a?x=1&y=2", "https://example.com/b"]);
+    }
+
+    #[test]
+    fn test_lists_comments_and_styles() {
+        let input = "<!--[if gte mso 9]><xml>junk</xml><![endif]--><style>p { color: red }</style>\
+                     <ul><li>Задача 1</li><li>Задача 2</li></ul>";
+        assert_eq!(sanitize(input).text, "- Задача 1\n- Задача 2");
+    }
+
+    #[test]
+    fn test_plain_text_is_kept() {
+        // No tags: line breaks and stray brackets stay as the teacher wrote them
+        assert_eq!(sanitize("Задача 1\n\n  Задача 2 (x < 5)").text, "Задача 1\n\n  Задача 2 (x < 5)");
+        assert_eq!(sanitize("A & B; C &unknown; D").text, "A & B; C &unknown; D");
+        assert_eq!(sanitize("<p>x < 5 &amp;&amp; y > 2</p>").text, "x < 5 && y > 2");
+        assert!(!has_tags("1 <2"));
+    }
+
+    #[test]
+    fn test_unclosed_paragraphs_and_breaks() {
+        assert_eq!(sanitize("Задача 1<p>Задача 2<p>Задача 3").text, "Задача 1\nЗадача 2\nЗадача 3");
+        assert_eq!(sanitize("Ред 1<br/>Ред 2<BR>Ред 3<br />").text, "Ред 1\nРед 2\nРед 3");
+        assert_eq!(sanitize("<div><p>Вътре</p></div>").text, "Вътре");
+    }
+}
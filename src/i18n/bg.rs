@@ -0,0 +1,303 @@
+//! Bulgarian strings
+
+use super::MsgKey::{self, *};
+
+pub(super) static MESSAGES: &[(MsgKey, &str)] = &[
+    // App title
+    (AppTitle, "Школо"),
+
+    // Tab names
+    (Overview, "Преглед"),
+    (Homework, "Домашни"),
+    (Grades, "Оценки"),
+    (Schedule, "Програма"),
+    (Notifications, "Известия"),
+    (Absences, "Отсъствия"),
+    (Feedbacks, "Отзиви"),
+    (Messages, "Съобщения"),
+    (Settings, "Настройки"),
+
+    // Feedbacks
+    (NoFeedbacks, "Няма отзиви"),
+    (LoadFailed, "неуспешно зареждане"),
+    (ToRetry, "за опит"),
+    (ForDetails, "за подробности"),
+    (NoMatchingFeedbacks, "Няма отзиви, отговарящи на филтъра"),
+    (Positive, "положителни"),
+    (Negative, "отрицателни"),
+
+    // Messages
+    (NoMessages, "Няма съобщения"),
+    (Participants, "участници"),
+    (ThreadParticipants, "Участници: "),
+    (You, "вие"),
+    (Today, "днес"),
+    (Yesterday, "вчера"),
+
+    // Absences
+    (NoAbsences, "Няма отсъствия"),
+    (Excused, "извинено"),
+    (Unexcused, "неизвинено"),
+    (ExpiringUnexcused, "неизвинени с изтичащ срок"),
+    (ExcuseDeadlineSoon, "излиза срокът за извиняване"),
+    (HourLabel, "час"),
+
+    // Section titles
+    (Students, "Ученици"),
+    (TodaySchedule, "Днешна програма"),
+    (RecentHomework, "Скорошни домашни"),
+    (UpcomingExams, "Предстоящи контролни"),
+    (GradesSummary, "Обобщение оценки"),
+    (TotalGrades, "Общо оценки"),
+
+    // Status messages
+    (Loading, "Зареждане..."),
+    (LoadingBase, "Зареждане"),
+    (AutoRefreshing, "Автоматично обновяване..."),
+    (NoHomework, "Няма домашни"),
+    (NoGrades, "Няма оценки"),
+    (NoSchedule, "Няма часове за днес"),
+    (NoNotifications, "Няма известия"),
+    (NoStudent, "Не е избран ученик"),
+
+    // Labels
+    (PastDue, "Минал"),
+    (NewItem, "НОВО"),
+    (DueToday, "(днес)"),
+    (DueTomorrow, "(утре)"),
+    (Term1, "Срок 1"),
+    (Term2, "Срок 2"),
+    (GradesTimelineTitle, "хронологично"),
+    (GradesReportTitle, "отсъствия и успех"),
+    (ReportSubject, "Предмет"),
+    (ReportAverage, "Среден"),
+    (ReportGrades, "Оценки"),
+    (ReportExcused, "Изв."),
+    (ReportUnexcused, "Неизв."),
+    (FinalGrade, "Срочна"),
+    (Annual, "Годишна"),
+    (Weighted, "претеглен"),
+    (Teacher, "Учител"),
+    (Topic, "Тема"),
+    (Unread, "непрочетени"),
+    (NewMarker, "[НОВО] "),
+
+    // Help text
+    (HelpRefresh, "[R] Обнови"),
+    (HelpQuit, "[Q] Изход"),
+    (HelpHelp, "[?] Помощ"),
+
+    // Status bar
+    (LastRefresh, "Последно:"),
+    (LoadingData, "Зареждане данни..."),
+    (LoadingThread, "Зареждане нишка..."),
+    (LoadingRecipients, "Зареждане получатели..."),
+    (LoadingMessages, "Зареждане на съобщенията..."),
+    (ErrorPrefix, "Грешка:"),
+    (FailedLoadThread, "Грешка при зареждане на нишка:"),
+    (FailedLoadRecipients, "Неуспешно зареждане на получателите:"),
+    (RecipientsRetry, "Няма получатели — [r] опитай отново"),
+    (Loaded, "Заредено"),
+    (Sending, "Изпращане..."),
+    (SendingMessage, "Изпращане на съобщение..."),
+    (MessageSent, "Съобщението е изпратено!"),
+    (SentReloadFailed, "Изпратено, но презареждане се провали:"),
+    (SendFailed, "Грешка при изпращане:"),
+    (LogoutError, "Грешка при изход:"),
+    (LoggedOut, "Излязохте. Рестартирайте за нов вход."),
+
+    // Settings/Account
+    (Account, "Акаунт"),
+    (LoggedInAs, "Вписан като"),
+    (Logout, "Изход от акаунт"),
+    (Login, "Вход"),
+    (LoginPassword, "Вход с парола"),
+    (LoginGoogle, "Вход с Google"),
+    (ImportToken, "Импорт на токен"),
+    (ImportTokenDesc, "Импортира токен от iOS приложението Shkolo на този Mac"),
+    (RunInTerminal, "Изпълнете в терминал"),
+    (LoginRequired, "Трябва да влезете"),
+    (LoginRequiredHint, "Изберете начин за вход в Настройки; данните се зареждат веднага щом влезете."),
+    (LoggedInLoading, "Влязохте, зареждане на данните..."),
+
+    // Keybinding descriptions
+    (KeyShowHelp, "Покажи/скрий помощ"),
+    (KeyQuit, "Изход"),
+    (KeyCancelInput, "Отказ"),
+    (KeySubmit, "Изпрати/напред"),
+    (KeyDeleteChar, "Изтрий символ"),
+    (KeyMoveCursor, "Курсор ляво/дясно"),
+    (KeyJumpStartEnd, "В начало/края"),
+    (KeyMoveToBody, "Към текст"),
+    (KeyReply, "Отговор"),
+    (KeyComposeToParticipants, "Ново съобщение до участниците"),
+    (KeyScroll, "Превърти"),
+    (KeyCancelCompose, "Отказ"),
+    (KeyNavigate, "Навигация"),
+    (KeyToggleRecipient, "Избери/премахни"),
+    (KeyStartSubject, "Напиши тема"),
+    (KeyReloadRecipients, "Зареди получателите наново"),
+    (KeySwitchTabs, "Смени раздел"),
+    (KeyToggleFocus, "Смени фокус"),
+    (KeyNavigateScroll, "Навигация / Превърти"),
+    (KeyQuickSelectTab, "Бърз избор раздел"),
+    (KeyCountPrefix, "Брой: 5j слиза 5 реда, 10G отива на 10-ия"),
+    (KeyRefresh, "Обнови"),
+    (KeyForceRefresh, "Обнови всичко"),
+    (KeyToggleLang, "Смени език (БГ/EN/DE)"),
+    (KeySwitchStudent, "Избор на ученик по име"),
+    (KeyShowLoadError, "Грешка при зареждане на таба"),
+    (KeyResizePane, "Преоразмери панел"),
+    (KeyGoBack, "Назад"),
+    (KeyGoForward, "Напред"),
+    (KeyResizeSplit, "Преоразмери разделител"),
+    (KeyPrevDay, "Предишен ден"),
+    (KeyNextDay, "Следващ ден"),
+    (KeyPrevWeek, "Предишна седмица"),
+    (KeyNextWeek, "Следваща седмица"),
+    (KeyGoToday, "Днес"),
+    (KeyEnterDate, "Въведи дата"),
+    (KeyGoToTab, "Към свързан раздел"),
+    (KeyExpand, "Разгъни/свий"),
+    (KeyFilterPositive, "Само положителни"),
+    (KeyFilterNegative, "Само отрицателни"),
+    (KeyFilterAll, "Всички"),
+    (KeyFilterSubject, "Филтър по предмет"),
+    (KeyGradesTimeline, "По предмети / хронологично"),
+    (KeyGradesReport, "Отсъствия и успех по предмети"),
+    (KeyOpenThread, "Отвори"),
+    (KeyCompose, "Ново съобщение"),
+    (KeyResumeDraft, "Продължи черновата"),
+    (KeyNextFolder, "Следваща папка"),
+    (KeyboardShortcuts, "Клавишни комбинации"),
+    (PressAnyKey, "Натисни клавиш"),
+    (HelpSearchHint, "/ - търсене"),
+
+    // Context descriptions for help overlay
+    (CtxReplying, "Отговор"),
+    (CtxComposingSubject, "Тема"),
+    (CtxComposingBody, "Съобщение"),
+    (CtxThreadView, "Преглед нишка"),
+    (CtxSelectRecipients, "Избор получатели"),
+    (CtxDateEntry, "Въвеждане на дата"),
+    (CtxSubjectFilter, "Филтър по предмет"),
+    (CtxStudentSwitch, "Избор на ученик"),
+
+    // Offline mode
+    (Offline, "ОФЛАЙН"),
+    (OfflineDataFrom, "данни от"),
+    (AgeUnknown, "неизвестно"),
+    (OfflineCached, "Няма връзка - показани са кеширани данни"),
+    (OfflineUnavailable, "Няма връзка - действието изисква мрежа"),
+
+    // Account roles
+    (TeacherUnsupported, "Учителските профили все още не се поддържат - влезте с профил на родител или ученик"),
+    (SessionExpired, "Сесията е изтекла - влезте отново с shkolo login"),
+    (IgnoredKeyBindings, "Пренебрегнати клавиши от ui_config.json:"),
+    (InvalidSchoolDayEnd, "Пренебрегнат default_school_day_end от ui_config.json:"),
+
+    // Partial refresh failures
+    (IncompleteData, "Непълни данни:"),
+    (LoadFailedDetails, "Грешка при зареждане:"),
+    (NoLoadError, "Данните в този таб са заредени без грешка"),
+
+    // Pane titles and hints
+    (TodayMarker, " (днес)"),
+    (ScheduleNavHint, " [p/n]-ден [t]-днес [d]-дата"),
+    (Total, "Общо"),
+    (MessagesHint, "[Enter]-отвори [c]-ново"),
+    (ThreadHint, "[r]-отговор [Esc]-назад"),
+    (ReplyTitle, " Отговор (Enter - изпрати, Esc - отказ) "),
+    (RecipientsTitleActive, " Получатели (Space-избери, Tab-напред) "),
+    (RecipientsTitle, " Получатели (Shift+Tab-назад) "),
+    (SubjectTitleActive, " Тема (Tab-напред, Shift+Tab-назад) "),
+    (SubjectTitle, " Тема "),
+    (BodyTitleActive, " Съобщение (Enter-изпрати, Shift+Tab-назад) "),
+    (BodyTitle, " Съобщение "),
+    (NotificationsHint, "[Enter]-отвори"),
+    (NotificationExpandHint, "… [Space]-покажи всичко"),
+    (LanguageName, "Български"),
+    (AutoRefreshLabel, "Автоматично обновяване: "),
+    (ErrorTitle, " Грешка [натисни клавиш за затваряне] "),
+    (DiscardTitle, " Неизпратено съобщение "),
+    (DiscardUnsent, "Да се изтрие ли неизпратеното съобщение?"),
+    (DiscardHint, "y/Enter: изтрий · s: чернова · n/Esc: продължи писането"),
+    (DraftSaved, "Черновата е запазена"),
+    (EmptyMessage, "Съобщението е празно"),
+    (EmptySubject, "Темата е празна"),
+    (DraftMarker, "(чернова)"),
+    (DraftResumeHint, "[d]-продължи"),
+    (Inbox, "Входящи"),
+    (FolderHint, "[f]-папка"),
+
+    // Status bar messages
+    (Refreshing, "Обновяване..."),
+    (RefreshingAll, "Пълно обновяване..."),
+    (Refreshed, "Обновено"),
+
+    // Parameterized labels
+    (PaneWidthLabel, "Ширина на панела"),
+    (GradeChangedIn, "Променена оценка по"),
+    (GradeRemovedIn, "Изтрита оценка по"),
+    (OverviewSplitLabel, "Разделител на прегледа"),
+
+    // Overview alerts line
+    (AlertHomeworkDue, "за днес"),
+    (AlertNewGrades, "нови оценки"),
+    (ThisWeek, "Тази седмица:"),
+    (AbsencesCount, "отсъствия"),
+
+    // Schedule weekdays
+    (Monday, "понеделник"),
+    (Tuesday, "вторник"),
+    (Wednesday, "сряда"),
+    (Thursday, "четвъртък"),
+    (Friday, "петък"),
+    (Saturday, "събота"),
+    (Sunday, "неделя"),
+    (DayOff, "Почивен ден"),
+    (ShowWeekends, "Показване на почивните дни: "),
+    (WeekStartLabel, "Седмицата започва в: "),
+    (ThreadOrderLabel, "Подредба в нишките: "),
+    (OldestFirst, "най-старите първи"),
+    (NewestFirst, "най-новите първи"),
+    (AsciiSymbols, "ASCII символи (за стари терминали): "),
+    (OverviewHomeworkCount, "Домашни в Преглед: "),
+    (On, "Вкл."),
+    (Off, "Изкл."),
+
+    // Schedule date entry prompt
+    (DateEntryTitle, " Дата ГГГГ-ММ-ДД (Enter - отиди, Esc - отказ) "),
+    (DateEntryInvalid, " Невалидна дата, очаква се ГГГГ-ММ-ДД "),
+    (SubjectFilterTitle, " Предмет (Enter - филтрирай, празно - всички, Esc - отказ) "),
+    (StudentSwitchTitle, " Ученик (Enter - избери, ↑/↓ - избор, Esc - отказ) "),
+    (NoMatches, "Няма съвпадения"),
+
+    // `shkolo report`
+    (PeriodReportTitle, "Отчет от Школо"),
+    (PeriodWeek, "Седмица"),
+    (PeriodHomeworkAssigned, "Зададени домашни"),
+    (PeriodHomeworkDue, "Домашни със срок"),
+    (PeriodDue, "срок"),
+    (PeriodUnexcused, "неизвинени"),
+    (PeriodPositive, "положителен"),
+    (PeriodNegative, "отрицателен"),
+    (PeriodDaysWithoutLessons, "Учебни дни без часове"),
+    (PeriodNotLoaded, "Не можа да се зареди."),
+    (PeriodNone, "Няма."),
+    (PeriodNoStudents, "Няма ученици."),
+
+    // Month names
+    (January, "януари"),
+    (February, "февруари"),
+    (March, "март"),
+    (April, "април"),
+    (May, "май"),
+    (June, "юни"),
+    (July, "юли"),
+    (August, "август"),
+    (September, "септември"),
+    (October, "октомври"),
+    (November, "ноември"),
+    (December, "декември"),
+];
@@ -0,0 +1,303 @@
+//! German strings
+
+use super::MsgKey::{self, *};
+
+pub(super) static MESSAGES: &[(MsgKey, &str)] = &[
+    // App title
+    (AppTitle, "Shkolo"),
+
+    // Tab names
+    (Overview, "Übersicht"),
+    (Homework, "Hausaufgaben"),
+    (Grades, "Noten"),
+    (Schedule, "Stundenplan"),
+    (Notifications, "Mitteilungen"),
+    (Absences, "Fehlzeiten"),
+    (Feedbacks, "Bewertungen"),
+    (Messages, "Nachrichten"),
+    (Settings, "Einstellungen"),
+
+    // Feedbacks
+    (NoFeedbacks, "Keine Bewertungen"),
+    (LoadFailed, "Laden fehlgeschlagen"),
+    (ToRetry, "zum Wiederholen"),
+    (ForDetails, "für Details"),
+    (NoMatchingFeedbacks, "Keine Bewertungen entsprechen dem Filter"),
+    (Positive, "positiv"),
+    (Negative, "negativ"),
+
+    // Messages
+    (NoMessages, "Keine Nachrichten"),
+    (Participants, "Teilnehmer"),
+    (ThreadParticipants, "Teilnehmer: "),
+    (You, "Sie"),
+    (Today, "heute"),
+    (Yesterday, "gestern"),
+
+    // Absences
+    (NoAbsences, "Keine Fehlzeiten"),
+    (Excused, "entschuldigt"),
+    (Unexcused, "unentschuldigt"),
+    (ExpiringUnexcused, "unentschuldigt mit ablaufender Frist"),
+    (ExcuseDeadlineSoon, "die Entschuldigungsfrist läuft ab"),
+    (HourLabel, "Stunde"),
+
+    // Section titles
+    (Students, "Schüler"),
+    (TodaySchedule, "Heutiger Stundenplan"),
+    (RecentHomework, "Aktuelle Hausaufgaben"),
+    (UpcomingExams, "Anstehende Prüfungen"),
+    (GradesSummary, "Notenübersicht"),
+    (TotalGrades, "Noten gesamt"),
+
+    // Status messages
+    (Loading, "Wird geladen..."),
+    (LoadingBase, "Wird geladen"),
+    (AutoRefreshing, "Automatische Aktualisierung..."),
+    (NoHomework, "Keine Hausaufgaben"),
+    (NoGrades, "Keine Noten"),
+    (NoSchedule, "Keine Stunden geplant"),
+    (NoNotifications, "Keine Mitteilungen"),
+    (NoStudent, "Kein Schüler ausgewählt"),
+
+    // Labels
+    (PastDue, "Vorbei"),
+    (NewItem, "NEU"),
+    (DueToday, "(heute)"),
+    (DueTomorrow, "(morgen)"),
+    (Term1, "Halbjahr 1"),
+    (Term2, "Halbjahr 2"),
+    (GradesTimelineTitle, "chronologisch"),
+    (GradesReportTitle, "Fehlzeiten und Noten"),
+    (ReportSubject, "Fach"),
+    (ReportAverage, "Schnitt"),
+    (ReportGrades, "Noten"),
+    (ReportExcused, "Ent."),
+    (ReportUnexcused, "Unent."),
+    (FinalGrade, "Halbjahresnote"),
+    (Annual, "Jahresnote"),
+    (Weighted, "gewichtet"),
+    (Teacher, "Lehrer"),
+    (Topic, "Thema"),
+    (Unread, "ungelesen"),
+    (NewMarker, "[NEU] "),
+
+    // Help text
+    (HelpRefresh, "[R] Aktualisieren"),
+    (HelpQuit, "[Q] Beenden"),
+    (HelpHelp, "[?] Hilfe"),
+
+    // Status bar
+    (LastRefresh, "Zuletzt:"),
+    (LoadingData, "Daten werden geladen..."),
+    (LoadingThread, "Unterhaltung wird geladen..."),
+    (LoadingRecipients, "Empfänger werden geladen..."),
+    (LoadingMessages, "Nachrichten werden geladen..."),
+    (ErrorPrefix, "Fehler:"),
+    (FailedLoadThread, "Unterhaltung konnte nicht geladen werden:"),
+    (FailedLoadRecipients, "Empfänger konnten nicht geladen werden:"),
+    (RecipientsRetry, "Keine Empfänger — [r] erneut versuchen"),
+    (Loaded, "Geladen"),
+    (Sending, "Wird gesendet..."),
+    (SendingMessage, "Nachricht wird gesendet..."),
+    (MessageSent, "Nachricht gesendet!"),
+    (SentReloadFailed, "Gesendet, aber Neuladen fehlgeschlagen:"),
+    (SendFailed, "Senden fehlgeschlagen:"),
+    (LogoutError, "Fehler beim Abmelden:"),
+    (LoggedOut, "Abgemeldet. Zum erneuten Anmelden neu starten."),
+
+    // Settings/Account
+    (Account, "Konto"),
+    (LoggedInAs, "Angemeldet als"),
+    (Logout, "Abmelden"),
+    (Login, "Anmelden"),
+    (LoginPassword, "Mit Passwort anmelden"),
+    (LoginGoogle, "Mit Google anmelden"),
+    (ImportToken, "Token importieren"),
+    (ImportTokenDesc, "Importiert das Token der Shkolo-iOS-App auf diesem Mac"),
+    (RunInTerminal, "Im Terminal ausführen"),
+    (LoginRequired, "Sie müssen sich anmelden"),
+    (LoginRequiredHint, "Wählen Sie unter Einstellungen eine Anmeldung; die Daten laden, sobald Sie angemeldet sind."),
+    (LoggedInLoading, "Angemeldet, Daten werden geladen..."),
+
+    // Keybinding descriptions
+    (KeyShowHelp, "Hilfe ein-/ausblenden"),
+    (KeyQuit, "Beenden"),
+    (KeyCancelInput, "Eingabe abbrechen"),
+    (KeySubmit, "Senden/nächstes Feld"),
+    (KeyDeleteChar, "Zeichen löschen"),
+    (KeyMoveCursor, "Cursor bewegen"),
+    (KeyJumpStartEnd, "Zum Anfang/Ende"),
+    (KeyMoveToBody, "Zum Nachrichtentext"),
+    (KeyReply, "Auf Unterhaltung antworten"),
+    (KeyComposeToParticipants, "Neue Nachricht an die Teilnehmer"),
+    (KeyScroll, "Scrollen"),
+    (KeyCancelCompose, "Verfassen abbrechen"),
+    (KeyNavigate, "Navigieren"),
+    (KeyToggleRecipient, "Empfänger aus-/abwählen"),
+    (KeyStartSubject, "Betreff schreiben"),
+    (KeyReloadRecipients, "Empfänger neu laden"),
+    (KeySwitchTabs, "Reiter wechseln"),
+    (KeyToggleFocus, "Fokus wechseln (Schüler/Inhalt)"),
+    (KeyNavigateScroll, "Navigieren / Scrollen"),
+    (KeyQuickSelectTab, "Reiter direkt wählen"),
+    (KeyCountPrefix, "Anzahl: 5j geht 5 Zeilen, 10G springt zum 10."),
+    (KeyRefresh, "Daten aktualisieren"),
+    (KeyForceRefresh, "Alles neu laden"),
+    (KeyToggleLang, "Sprache wechseln (BG/EN/DE)"),
+    (KeySwitchStudent, "Schüler nach Name suchen"),
+    (KeyShowLoadError, "Warum der Tab nicht geladen wurde"),
+    (KeyResizePane, "Schülerbereich anpassen"),
+    (KeyGoBack, "Zurück"),
+    (KeyGoForward, "Vorwärts"),
+    (KeyResizeSplit, "Aufteilung anpassen"),
+    (KeyPrevDay, "Vorheriger Tag"),
+    (KeyNextDay, "Nächster Tag"),
+    (KeyPrevWeek, "Vorherige Woche"),
+    (KeyNextWeek, "Nächste Woche"),
+    (KeyGoToday, "Zu heute"),
+    (KeyEnterDate, "Datum eingeben"),
+    (KeyGoToTab, "Zum zugehörigen Reiter"),
+    (KeyExpand, "Auf-/zuklappen"),
+    (KeyFilterPositive, "Nur positive"),
+    (KeyFilterNegative, "Nur negative"),
+    (KeyFilterAll, "Alle"),
+    (KeyFilterSubject, "Nach Fach filtern"),
+    (KeyGradesTimeline, "Nach Fach / chronologisch"),
+    (KeyGradesReport, "Fehlzeiten und Noten pro Fach"),
+    (KeyOpenThread, "Unterhaltung öffnen"),
+    (KeyCompose, "Neue Nachricht verfassen"),
+    (KeyResumeDraft, "Gespeicherten Entwurf fortsetzen"),
+    (KeyNextFolder, "Nächster Ordner"),
+    (KeyboardShortcuts, "Tastenkürzel"),
+    (PressAnyKey, "Beliebige Taste drücken"),
+    (HelpSearchHint, "/ - suchen"),
+
+    // Context descriptions for help overlay
+    (CtxReplying, "Antworten"),
+    (CtxComposingSubject, "Betreff verfassen"),
+    (CtxComposingBody, "Nachricht verfassen"),
+    (CtxThreadView, "Unterhaltung"),
+    (CtxSelectRecipients, "Empfänger auswählen"),
+    (CtxDateEntry, "Datumseingabe"),
+    (CtxSubjectFilter, "Fachfilter"),
+    (CtxStudentSwitch, "Schüler wechseln"),
+
+    // Offline mode
+    (Offline, "OFFLINE"),
+    (OfflineDataFrom, "Daten von"),
+    (AgeUnknown, "unbekannt"),
+    (OfflineCached, "Offline - zwischengespeicherte Daten werden angezeigt"),
+    (OfflineUnavailable, "Offline - diese Aktion benötigt das Netzwerk"),
+
+    // Account roles
+    (TeacherUnsupported, "Lehrerkonten werden noch nicht unterstützt - bitte mit einem Eltern- oder Schülerkonto anmelden"),
+    (SessionExpired, "Sitzung abgelaufen - mit shkolo login erneut anmelden"),
+    (IgnoredKeyBindings, "Ignorierte Tastenbelegungen aus ui_config.json:"),
+    (InvalidSchoolDayEnd, "default_school_day_end aus ui_config.json ignoriert:"),
+
+    // Partial refresh failures
+    (IncompleteData, "Unvollständige Daten:"),
+    (LoadFailedDetails, "Ladefehler:"),
+    (NoLoadError, "Dieser Tab wurde ohne Fehler geladen"),
+
+    // Pane titles and hints
+    (TodayMarker, " (heute)"),
+    (ScheduleNavHint, " [p/n]-Tag [t]-heute [d]-Datum"),
+    (Total, "Gesamt"),
+    (MessagesHint, "[Enter]-öffnen [c]-neu"),
+    (ThreadHint, "[r]-antworten [Esc]-zurück"),
+    (ReplyTitle, " Antwort (Enter - senden, Esc - abbrechen) "),
+    (RecipientsTitleActive, " Empfänger (Leertaste-wählen, Tab-weiter) "),
+    (RecipientsTitle, " Empfänger (Shift+Tab-zurück) "),
+    (SubjectTitleActive, " Betreff (Tab-weiter, Shift+Tab-zurück) "),
+    (SubjectTitle, " Betreff "),
+    (BodyTitleActive, " Nachricht (Enter-senden, Shift+Tab-zurück) "),
+    (BodyTitle, " Nachricht "),
+    (NotificationsHint, "[Enter]-öffnen"),
+    (NotificationExpandHint, "… [Leertaste]-aufklappen"),
+    (LanguageName, "Deutsch"),
+    (AutoRefreshLabel, "Automatisch aktualisieren: "),
+    (ErrorTitle, " Fehler [beliebige Taste zum Schließen] "),
+    (DiscardTitle, " Nicht gesendete Nachricht "),
+    (DiscardUnsent, "Nicht gesendete Nachricht verwerfen?"),
+    (DiscardHint, "y/Enter: verwerfen · s: Entwurf · n/Esc: weiter schreiben"),
+    (DraftSaved, "Entwurf gespeichert"),
+    (EmptyMessage, "Die Nachricht ist leer"),
+    (EmptySubject, "Der Betreff ist leer"),
+    (DraftMarker, "(Entwurf)"),
+    (DraftResumeHint, "[d]-fortsetzen"),
+    (Inbox, "Posteingang"),
+    (FolderHint, "[f]-Ordner"),
+
+    // Status bar messages
+    (Refreshing, "Wird aktualisiert..."),
+    (RefreshingAll, "Alles wird aktualisiert..."),
+    (Refreshed, "Aktualisiert"),
+
+    // Parameterized labels
+    (PaneWidthLabel, "Breite des Schülerbereichs"),
+    (GradeChangedIn, "Note geändert in"),
+    (GradeRemovedIn, "Note gelöscht in"),
+    (OverviewSplitLabel, "Aufteilung der Übersicht"),
+
+    // Overview alerts line
+    (AlertHomeworkDue, "heute fällig"),
+    (AlertNewGrades, "neue Noten"),
+    (ThisWeek, "Diese Woche:"),
+    (AbsencesCount, "Fehlstunden"),
+
+    // Schedule weekdays
+    (Monday, "Montag"),
+    (Tuesday, "Dienstag"),
+    (Wednesday, "Mittwoch"),
+    (Thursday, "Donnerstag"),
+    (Friday, "Freitag"),
+    (Saturday, "Samstag"),
+    (Sunday, "Sonntag"),
+    (DayOff, "Freier Tag"),
+    (ShowWeekends, "Wochenenden anzeigen: "),
+    (WeekStartLabel, "Woche beginnt am: "),
+    (ThreadOrderLabel, "Reihenfolge im Verlauf: "),
+    (OldestFirst, "älteste zuerst"),
+    (NewestFirst, "neueste zuerst"),
+    (AsciiSymbols, "ASCII-Symbole (für alte Terminals): "),
+    (OverviewHomeworkCount, "Hausaufgaben in der Übersicht: "),
+    (On, "Ein"),
+    (Off, "Aus"),
+
+    // Schedule date entry prompt
+    (DateEntryTitle, " Datum JJJJ-MM-TT (Enter - los, Esc - abbrechen) "),
+    (DateEntryInvalid, " Ungültiges Datum, erwartet JJJJ-MM-TT "),
+    (SubjectFilterTitle, " Fach (Enter - filtern, leer - alle, Esc - abbrechen) "),
+    (StudentSwitchTitle, " Schüler (Enter - wählen, ↑/↓ - auswählen, Esc - abbrechen) "),
+    (NoMatches, "Keine Treffer"),
+
+    // `shkolo report`
+    (PeriodReportTitle, "Shkolo-Bericht"),
+    (PeriodWeek, "Woche"),
+    (PeriodHomeworkAssigned, "Aufgegebene Hausaufgaben"),
+    (PeriodHomeworkDue, "Fällige Hausaufgaben"),
+    (PeriodDue, "fällig"),
+    (PeriodUnexcused, "unentschuldigt"),
+    (PeriodPositive, "positiv"),
+    (PeriodNegative, "negativ"),
+    (PeriodDaysWithoutLessons, "Schultage ohne Unterricht"),
+    (PeriodNotLoaded, "Konnte nicht geladen werden."),
+    (PeriodNone, "Keine."),
+    (PeriodNoStudents, "Keine Schüler."),
+
+    // Month names
+    (January, "Januar"),
+    (February, "Februar"),
+    (March, "März"),
+    (April, "April"),
+    (May, "Mai"),
+    (June, "Juni"),
+    (July, "Juli"),
+    (August, "August"),
+    (September, "September"),
+    (October, "Oktober"),
+    (November, "November"),
+    (December, "Dezember"),
+];
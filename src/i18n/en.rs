@@ -0,0 +1,303 @@
+//! English strings
+
+use super::MsgKey::{self, *};
+
+pub(super) static MESSAGES: &[(MsgKey, &str)] = &[
+    // App title
+    (AppTitle, "Shkolo"),
+
+    // Tab names
+    (Overview, "Overview"),
+    (Homework, "Homework"),
+    (Grades, "Grades"),
+    (Schedule, "Schedule"),
+    (Notifications, "Notifications"),
+    (Absences, "Absences"),
+    (Feedbacks, "Feedbacks"),
+    (Messages, "Messages"),
+    (Settings, "Settings"),
+
+    // Feedbacks
+    (NoFeedbacks, "No feedbacks"),
+    (LoadFailed, "failed to load"),
+    (ToRetry, "to retry"),
+    (ForDetails, "for details"),
+    (NoMatchingFeedbacks, "No feedbacks match the filter"),
+    (Positive, "positive"),
+    (Negative, "negative"),
+
+    // Messages
+    (NoMessages, "No messages"),
+    (Participants, "participants"),
+    (ThreadParticipants, "Participants: "),
+    (You, "you"),
+    (Today, "today"),
+    (Yesterday, "yesterday"),
+
+    // Absences
+    (NoAbsences, "No absences"),
+    (Excused, "excused"),
+    (Unexcused, "unexcused"),
+    (ExpiringUnexcused, "unexcused with the excuse deadline running out"),
+    (ExcuseDeadlineSoon, "the excuse deadline is running out"),
+    (HourLabel, "hour"),
+
+    // Section titles
+    (Students, "Students"),
+    (TodaySchedule, "Today's Schedule"),
+    (RecentHomework, "Recent Homework"),
+    (UpcomingExams, "Upcoming exams"),
+    (GradesSummary, "Grades Summary"),
+    (TotalGrades, "Total grades"),
+
+    // Status messages
+    (Loading, "Loading..."),
+    (LoadingBase, "Loading"),
+    (AutoRefreshing, "Auto-refreshing..."),
+    (NoHomework, "No homework found"),
+    (NoGrades, "No grades found"),
+    (NoSchedule, "No classes scheduled"),
+    (NoNotifications, "No notifications"),
+    (NoStudent, "No student selected"),
+
+    // Labels
+    (PastDue, "Past"),
+    (NewItem, "NEW"),
+    (DueToday, "(today)"),
+    (DueTomorrow, "(tomorrow)"),
+    (Term1, "Term 1"),
+    (Term2, "Term 2"),
+    (GradesTimelineTitle, "timeline"),
+    (GradesReportTitle, "absences and grades"),
+    (ReportSubject, "Subject"),
+    (ReportAverage, "Average"),
+    (ReportGrades, "Grades"),
+    (ReportExcused, "Exc."),
+    (ReportUnexcused, "Unexc."),
+    (FinalGrade, "Final"),
+    (Annual, "Annual"),
+    (Weighted, "weighted"),
+    (Teacher, "Teacher"),
+    (Topic, "Topic"),
+    (Unread, "unread"),
+    (NewMarker, "[NEW] "),
+
+    // Help text
+    (HelpRefresh, "[R]efresh"),
+    (HelpQuit, "[Q]uit"),
+    (HelpHelp, "[?]Help"),
+
+    // Status bar
+    (LastRefresh, "Last:"),
+    (LoadingData, "Loading data..."),
+    (LoadingThread, "Loading thread..."),
+    (LoadingRecipients, "Loading recipients..."),
+    (LoadingMessages, "Loading messages..."),
+    (ErrorPrefix, "Error:"),
+    (FailedLoadThread, "Failed to load thread:"),
+    (FailedLoadRecipients, "Failed to load recipients:"),
+    (RecipientsRetry, "No recipients — [r] to retry"),
+    (Loaded, "Loaded"),
+    (Sending, "Sending..."),
+    (SendingMessage, "Sending message..."),
+    (MessageSent, "Message sent!"),
+    (SentReloadFailed, "Sent, but reload failed:"),
+    (SendFailed, "Send failed:"),
+    (LogoutError, "Logout error:"),
+    (LoggedOut, "Logged out. Restart to log in again."),
+
+    // Settings/Account
+    (Account, "Account"),
+    (LoggedInAs, "Logged in as"),
+    (Logout, "Logout"),
+    (Login, "Login"),
+    (LoginPassword, "Login with password"),
+    (LoginGoogle, "Login with Google"),
+    (ImportToken, "Import token"),
+    (ImportTokenDesc, "Import token from the Shkolo iOS app on this Mac"),
+    (RunInTerminal, "Run in a terminal"),
+    (LoginRequired, "You need to log in"),
+    (LoginRequiredHint, "Pick a login option in Settings; the data loads as soon as you are logged in."),
+    (LoggedInLoading, "Logged in, loading data..."),
+
+    // Keybinding descriptions
+    (KeyShowHelp, "Show/hide help"),
+    (KeyQuit, "Quit"),
+    (KeyCancelInput, "Cancel input"),
+    (KeySubmit, "Submit/next field"),
+    (KeyDeleteChar, "Delete character"),
+    (KeyMoveCursor, "Move cursor"),
+    (KeyJumpStartEnd, "Jump to start/end"),
+    (KeyMoveToBody, "Move to message body"),
+    (KeyReply, "Reply to thread"),
+    (KeyComposeToParticipants, "New message to the participants"),
+    (KeyScroll, "Scroll"),
+    (KeyCancelCompose, "Cancel compose"),
+    (KeyNavigate, "Navigate"),
+    (KeyToggleRecipient, "Toggle recipient"),
+    (KeyStartSubject, "Start writing subject"),
+    (KeyReloadRecipients, "Reload recipients"),
+    (KeySwitchTabs, "Switch tabs"),
+    (KeyToggleFocus, "Toggle focus (students/content)"),
+    (KeyNavigateScroll, "Navigate / Scroll"),
+    (KeyQuickSelectTab, "Quick select tab"),
+    (KeyCountPrefix, "Count: 5j moves 5 rows, 10G goes to the 10th"),
+    (KeyRefresh, "Refresh data"),
+    (KeyForceRefresh, "Force refresh all"),
+    (KeyToggleLang, "Switch language (BG/EN/DE)"),
+    (KeySwitchStudent, "Find student by name"),
+    (KeyShowLoadError, "Why the tab failed to load"),
+    (KeyResizePane, "Resize students pane"),
+    (KeyGoBack, "Go back"),
+    (KeyGoForward, "Go forward"),
+    (KeyResizeSplit, "Resize split"),
+    (KeyPrevDay, "Previous day"),
+    (KeyNextDay, "Next day"),
+    (KeyPrevWeek, "Previous week"),
+    (KeyNextWeek, "Next week"),
+    (KeyGoToday, "Go to today"),
+    (KeyEnterDate, "Enter date"),
+    (KeyGoToTab, "Go to related tab"),
+    (KeyExpand, "Expand/collapse"),
+    (KeyFilterPositive, "Positive only"),
+    (KeyFilterNegative, "Negative only"),
+    (KeyFilterAll, "All"),
+    (KeyFilterSubject, "Filter by subject"),
+    (KeyGradesTimeline, "By subject / timeline"),
+    (KeyGradesReport, "Absences and grades per subject"),
+    (KeyOpenThread, "Open thread"),
+    (KeyCompose, "Compose new message"),
+    (KeyResumeDraft, "Resume the saved draft"),
+    (KeyNextFolder, "Next folder"),
+    (KeyboardShortcuts, "Keyboard Shortcuts"),
+    (PressAnyKey, "Press any key"),
+    (HelpSearchHint, "/ - search"),
+
+    // Context descriptions for help overlay
+    (CtxReplying, "Replying"),
+    (CtxComposingSubject, "Composing Subject"),
+    (CtxComposingBody, "Composing Message"),
+    (CtxThreadView, "Thread View"),
+    (CtxSelectRecipients, "Select Recipients"),
+    (CtxDateEntry, "Date Entry"),
+    (CtxSubjectFilter, "Subject Filter"),
+    (CtxStudentSwitch, "Switch Student"),
+
+    // Offline mode
+    (Offline, "OFFLINE"),
+    (OfflineDataFrom, "data from"),
+    (AgeUnknown, "unknown"),
+    (OfflineCached, "Offline - showing cached data"),
+    (OfflineUnavailable, "Offline - this action needs the network"),
+
+    // Account roles
+    (TeacherUnsupported, "Teacher accounts are not yet supported - log in with a parent or student account"),
+    (SessionExpired, "Session expired - log in again with shkolo login"),
+    (IgnoredKeyBindings, "Ignored key bindings in ui_config.json:"),
+    (InvalidSchoolDayEnd, "Ignored default_school_day_end in ui_config.json:"),
+
+    // Partial refresh failures
+    (IncompleteData, "Incomplete data:"),
+    (LoadFailedDetails, "Load error:"),
+    (NoLoadError, "This tab loaded without errors"),
+
+    // Pane titles and hints
+    (TodayMarker, " (today)"),
+    (ScheduleNavHint, " [p/n]-day [t]-today [d]-date"),
+    (Total, "Total"),
+    (MessagesHint, "[Enter]-open [c]-new"),
+    (ThreadHint, "[r]-reply [Esc]-back"),
+    (ReplyTitle, " Reply (Enter - send, Esc - cancel) "),
+    (RecipientsTitleActive, " Recipients (Space-select, Tab-next) "),
+    (RecipientsTitle, " Recipients (Shift+Tab-back) "),
+    (SubjectTitleActive, " Subject (Tab-next, Shift+Tab-back) "),
+    (SubjectTitle, " Subject "),
+    (BodyTitleActive, " Message (Enter-send, Shift+Tab-back) "),
+    (BodyTitle, " Message "),
+    (NotificationsHint, "[Enter]-open"),
+    (NotificationExpandHint, "… [Space]-expand"),
+    (LanguageName, "English"),
+    (AutoRefreshLabel, "Auto-refresh: "),
+    (ErrorTitle, " Error [Press any key to dismiss] "),
+    (DiscardTitle, " Unsent message "),
+    (DiscardUnsent, "Discard unsent message?"),
+    (DiscardHint, "y/Enter: discard · s: save draft · n/Esc: keep editing"),
+    (DraftSaved, "Draft saved"),
+    (EmptyMessage, "The message is empty"),
+    (EmptySubject, "The subject is empty"),
+    (DraftMarker, "(draft)"),
+    (DraftResumeHint, "[d]-resume"),
+    (Inbox, "Inbox"),
+    (FolderHint, "[f]-folder"),
+
+    // Status bar messages
+    (Refreshing, "Refreshing..."),
+    (RefreshingAll, "Refreshing all..."),
+    (Refreshed, "Refreshed"),
+
+    // Parameterized labels
+    (PaneWidthLabel, "Pane width"),
+    (GradeChangedIn, "Grade changed in"),
+    (GradeRemovedIn, "Grade removed in"),
+    (OverviewSplitLabel, "Overview split"),
+
+    // Overview alerts line
+    (AlertHomeworkDue, "due today"),
+    (AlertNewGrades, "new grades"),
+    (ThisWeek, "This week:"),
+    (AbsencesCount, "absences"),
+
+    // Schedule weekdays
+    (Monday, "Monday"),
+    (Tuesday, "Tuesday"),
+    (Wednesday, "Wednesday"),
+    (Thursday, "Thursday"),
+    (Friday, "Friday"),
+    (Saturday, "Saturday"),
+    (Sunday, "Sunday"),
+    (DayOff, "Day off"),
+    (ShowWeekends, "Show weekends: "),
+    (WeekStartLabel, "Week starts on: "),
+    (ThreadOrderLabel, "Thread order: "),
+    (OldestFirst, "oldest first"),
+    (NewestFirst, "newest first"),
+    (AsciiSymbols, "ASCII symbols (for old terminals): "),
+    (OverviewHomeworkCount, "Homework on Overview: "),
+    (On, "On"),
+    (Off, "Off"),
+
+    // Schedule date entry prompt
+    (DateEntryTitle, " Date YYYY-MM-DD (Enter - go, Esc - cancel) "),
+    (DateEntryInvalid, " Invalid date, expected YYYY-MM-DD "),
+    (SubjectFilterTitle, " Subject (Enter - filter, empty - all, Esc - cancel) "),
+    (StudentSwitchTitle, " Student (Enter - select, ↑/↓ - choose, Esc - cancel) "),
+    (NoMatches, "No matches"),
+
+    // `shkolo report`
+    (PeriodReportTitle, "Shkolo report"),
+    (PeriodWeek, "Week"),
+    (PeriodHomeworkAssigned, "Homework assigned"),
+    (PeriodHomeworkDue, "Homework due"),
+    (PeriodDue, "due"),
+    (PeriodUnexcused, "unexcused"),
+    (PeriodPositive, "positive"),
+    (PeriodNegative, "negative"),
+    (PeriodDaysWithoutLessons, "School days without lessons"),
+    (PeriodNotLoaded, "Could not be loaded."),
+    (PeriodNone, "None."),
+    (PeriodNoStudents, "No students."),
+
+    // Month names
+    (January, "January"),
+    (February, "February"),
+    (March, "March"),
+    (April, "April"),
+    (May, "May"),
+    (June, "June"),
+    (July, "July"),
+    (August, "August"),
+    (September, "September"),
+    (October, "October"),
+    (November, "November"),
+    (December, "December"),
+];
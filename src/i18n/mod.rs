@@ -0,0 +1,645 @@
+//! Simple internationalization module
+//! Default language is Bulgarian (bg). Strings are looked up by `MsgKey` in
+//! per-language tables; adding a language means adding one table file.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use time::Date;
+
+use crate::util;
+
+mod bg;
+mod de;
+mod en;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    Bg,  // Bulgarian is the default
+    En,
+    De,
+}
+
+impl Lang {
+    /// Every language in Settings cycling order
+    pub const ALL: [Lang; 3] = [Lang::Bg, Lang::En, Lang::De];
+
+    /// Short code used in the config file
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Bg => "bg",
+            Lang::En => "en",
+            Lang::De => "de",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lang| lang.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Language from `SHKOLO_LANG`, falling back to the `LC_ALL`/`LANG` locale
+    pub fn from_env() -> Option<Self> {
+        if let Some(lang) = std::env::var("SHKOLO_LANG").ok().and_then(|v| Self::from_code(&v)) {
+            return Some(lang);
+        }
+        ["LC_ALL", "LANG"].iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+    }
+
+    /// Language of a POSIX locale such as "de_DE.UTF-8"
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        locale.split(['_', '.', '@', '-']).next().and_then(Self::from_code)
+    }
+
+    /// Value of the API's `language` header. The API only speaks Bulgarian and
+    /// English, so other languages get English data.
+    pub fn api_code(self) -> &'static str {
+        match self {
+            Lang::Bg => "bg",
+            Lang::En | Lang::De => "en",
+        }
+    }
+
+    /// The language after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&lang| lang == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    fn messages(self) -> &'static [(MsgKey, &'static str)] {
+        match self {
+            Lang::Bg => bg::MESSAGES,
+            Lang::En => en::MESSAGES,
+            Lang::De => de::MESSAGES,
+        }
+    }
+}
+
+impl std::str::FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Lang::from_code(s).ok_or_else(|| {
+            let codes: Vec<&str> = Lang::ALL.iter().map(|l| l.code()).collect();
+            format!("unknown language '{}' (expected one of: {})", s, codes.join(", "))
+        })
+    }
+}
+
+/// Translation strings
+pub struct T;
+
+/// Declares `MsgKey` and a `T::name(lang)` wrapper for every key
+macro_rules! messages {
+    ($($(#[$meta:meta])* $name:ident => $key:ident,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum MsgKey {
+            $($key,)*
+        }
+
+        impl MsgKey {
+            #[allow(dead_code)] // used by the completeness test
+            pub const ALL: &'static [MsgKey] = &[$(MsgKey::$key,)*];
+        }
+
+        impl T {
+            $(
+                $(#[$meta])*
+                pub fn $name(lang: Lang) -> &'static str {
+                    T::get(lang, MsgKey::$key)
+                }
+            )*
+        }
+    };
+}
+
+messages! {
+    // App title
+    app_title => AppTitle,
+
+    // Tab names
+    overview => Overview,
+    homework => Homework,
+    grades => Grades,
+    schedule => Schedule,
+    notifications => Notifications,
+    absences => Absences,
+    feedbacks => Feedbacks,
+    messages => Messages,
+    settings => Settings,
+
+    // Feedbacks
+    no_feedbacks => NoFeedbacks,
+    load_failed => LoadFailed,
+    to_retry => ToRetry,
+    for_details => ForDetails,
+    no_matching_feedbacks => NoMatchingFeedbacks,
+    positive => Positive,
+    negative => Negative,
+
+    // Messages
+    no_messages => NoMessages,
+    participants => Participants,
+    thread_participants => ThreadParticipants,
+    you => You,
+    today => Today,
+    yesterday => Yesterday,
+
+    // Absences
+    no_absences => NoAbsences,
+    excused => Excused,
+    unexcused => Unexcused,
+    expiring_unexcused => ExpiringUnexcused,
+    excuse_deadline_soon => ExcuseDeadlineSoon,
+    hour_label => HourLabel,
+
+    // Section titles
+    students => Students,
+    today_schedule => TodaySchedule,
+    recent_homework => RecentHomework,
+    upcoming_exams => UpcomingExams,
+    grades_summary => GradesSummary,
+    total_grades => TotalGrades,
+
+    // Status messages
+    loading => Loading,
+    loading_base => LoadingBase,
+    auto_refreshing => AutoRefreshing,
+    no_homework => NoHomework,
+    no_grades => NoGrades,
+    no_schedule => NoSchedule,
+    no_notifications => NoNotifications,
+    no_student => NoStudent,
+
+    // Labels
+    past_due => PastDue,
+    new_item => NewItem,
+    due_today => DueToday,
+    due_tomorrow => DueTomorrow,
+    term1 => Term1,
+    term2 => Term2,
+    grades_timeline_title => GradesTimelineTitle,
+    grades_report_title => GradesReportTitle,
+    report_subject => ReportSubject,
+    report_average => ReportAverage,
+    report_grades => ReportGrades,
+    report_excused => ReportExcused,
+    report_unexcused => ReportUnexcused,
+    final_grade => FinalGrade,
+    annual => Annual,
+    weighted => Weighted,
+    teacher => Teacher,
+    topic => Topic,
+    unread => Unread,
+    new_marker => NewMarker,
+
+    // Help text
+    help_refresh => HelpRefresh,
+    help_quit => HelpQuit,
+    help_help => HelpHelp,
+
+    // Status bar
+    last_refresh => LastRefresh,
+    loading_data => LoadingData,
+    loading_thread => LoadingThread,
+    loading_recipients => LoadingRecipients,
+    loading_messages => LoadingMessages,
+    error_prefix => ErrorPrefix,
+    failed_load_thread => FailedLoadThread,
+    failed_load_recipients => FailedLoadRecipients,
+    recipients_retry => RecipientsRetry,
+    loaded => Loaded,
+    sending => Sending,
+    sending_message => SendingMessage,
+    message_sent => MessageSent,
+    sent_reload_failed => SentReloadFailed,
+    send_failed => SendFailed,
+    logout_error => LogoutError,
+    logged_out => LoggedOut,
+
+    // Settings/Account
+    account => Account,
+    logged_in_as => LoggedInAs,
+    logout => Logout,
+    login => Login,
+    login_password => LoginPassword,
+    login_google => LoginGoogle,
+    import_token => ImportToken,
+    import_token_desc => ImportTokenDesc,
+    run_in_terminal => RunInTerminal,
+    login_required => LoginRequired,
+    login_required_hint => LoginRequiredHint,
+    logged_in_loading => LoggedInLoading,
+
+    // Keybinding descriptions
+    key_show_help => KeyShowHelp,
+    key_quit => KeyQuit,
+    key_cancel_input => KeyCancelInput,
+    key_submit => KeySubmit,
+    key_delete_char => KeyDeleteChar,
+    key_move_cursor => KeyMoveCursor,
+    key_jump_start_end => KeyJumpStartEnd,
+    key_move_to_body => KeyMoveToBody,
+    key_reply => KeyReply,
+    key_compose_to_participants => KeyComposeToParticipants,
+    key_scroll => KeyScroll,
+    key_cancel_compose => KeyCancelCompose,
+    key_navigate => KeyNavigate,
+    key_toggle_recipient => KeyToggleRecipient,
+    key_start_subject => KeyStartSubject,
+    key_reload_recipients => KeyReloadRecipients,
+    key_switch_tabs => KeySwitchTabs,
+    key_toggle_focus => KeyToggleFocus,
+    key_navigate_scroll => KeyNavigateScroll,
+    key_quick_select_tab => KeyQuickSelectTab,
+    key_count_prefix => KeyCountPrefix,
+    key_refresh => KeyRefresh,
+    key_force_refresh => KeyForceRefresh,
+    key_toggle_lang => KeyToggleLang,
+    key_switch_student => KeySwitchStudent,
+    key_show_load_error => KeyShowLoadError,
+    key_resize_pane => KeyResizePane,
+    key_go_back => KeyGoBack,
+    key_go_forward => KeyGoForward,
+    key_resize_split => KeyResizeSplit,
+    key_prev_day => KeyPrevDay,
+    key_next_day => KeyNextDay,
+    key_prev_week => KeyPrevWeek,
+    key_next_week => KeyNextWeek,
+    key_go_today => KeyGoToday,
+    key_enter_date => KeyEnterDate,
+    key_go_to_tab => KeyGoToTab,
+    key_expand => KeyExpand,
+    key_filter_positive => KeyFilterPositive,
+    key_filter_negative => KeyFilterNegative,
+    key_filter_all => KeyFilterAll,
+    key_filter_subject => KeyFilterSubject,
+    key_grades_timeline => KeyGradesTimeline,
+    key_grades_report => KeyGradesReport,
+    key_open_thread => KeyOpenThread,
+    key_compose => KeyCompose,
+    key_resume_draft => KeyResumeDraft,
+    key_next_folder => KeyNextFolder,
+    keyboard_shortcuts => KeyboardShortcuts,
+    press_any_key => PressAnyKey,
+    help_search_hint => HelpSearchHint,
+
+    // Context descriptions for help overlay
+    ctx_replying => CtxReplying,
+    ctx_composing_subject => CtxComposingSubject,
+    ctx_composing_body => CtxComposingBody,
+    ctx_thread_view => CtxThreadView,
+    ctx_select_recipients => CtxSelectRecipients,
+    ctx_date_entry => CtxDateEntry,
+    ctx_subject_filter => CtxSubjectFilter,
+    ctx_student_switch => CtxStudentSwitch,
+
+    // Offline mode
+    offline => Offline,
+    offline_data_from => OfflineDataFrom,
+    age_unknown => AgeUnknown,
+    offline_cached => OfflineCached,
+    offline_unavailable => OfflineUnavailable,
+
+    // Account roles
+    teacher_unsupported => TeacherUnsupported,
+    session_expired => SessionExpired,
+    ignored_key_bindings => IgnoredKeyBindings,
+    invalid_school_day_end => InvalidSchoolDayEnd,
+
+    // Partial refresh failures
+    incomplete_data => IncompleteData,
+    load_failed_details => LoadFailedDetails,
+    no_load_error => NoLoadError,
+
+    // Pane titles and hints
+    today_marker => TodayMarker,
+    schedule_nav_hint => ScheduleNavHint,
+    total => Total,
+    messages_hint => MessagesHint,
+    thread_hint => ThreadHint,
+    reply_title => ReplyTitle,
+    recipients_title_active => RecipientsTitleActive,
+    recipients_title => RecipientsTitle,
+    subject_title_active => SubjectTitleActive,
+    subject_title => SubjectTitle,
+    body_title_active => BodyTitleActive,
+    body_title => BodyTitle,
+    notifications_hint => NotificationsHint,
+    notification_expand_hint => NotificationExpandHint,
+    language_name => LanguageName,
+    auto_refresh_label => AutoRefreshLabel,
+    error_title => ErrorTitle,
+    discard_title => DiscardTitle,
+    discard_unsent => DiscardUnsent,
+    discard_hint => DiscardHint,
+    draft_saved => DraftSaved,
+    empty_message => EmptyMessage,
+    empty_subject => EmptySubject,
+    draft_marker => DraftMarker,
+    draft_resume_hint => DraftResumeHint,
+    inbox => Inbox,
+    folder_hint => FolderHint,
+
+    // Status bar messages
+    refreshing => Refreshing,
+    refreshing_all => RefreshingAll,
+    refreshed => Refreshed,
+
+    // Parameterized labels
+    pane_width_label => PaneWidthLabel,
+    grade_changed_in => GradeChangedIn,
+    grade_removed_in => GradeRemovedIn,
+    overview_split_label => OverviewSplitLabel,
+
+    // Overview alerts line
+    alert_homework_due => AlertHomeworkDue,
+    alert_new_grades => AlertNewGrades,
+    this_week => ThisWeek,
+    absences_count => AbsencesCount,
+
+    // Schedule weekdays, looked up through `T::weekday` rather than by name
+    #[allow(dead_code)] monday => Monday,
+    #[allow(dead_code)] tuesday => Tuesday,
+    #[allow(dead_code)] wednesday => Wednesday,
+    #[allow(dead_code)] thursday => Thursday,
+    #[allow(dead_code)] friday => Friday,
+    #[allow(dead_code)] saturday => Saturday,
+    #[allow(dead_code)] sunday => Sunday,
+    day_off => DayOff,
+    show_weekends => ShowWeekends,
+    week_start_label => WeekStartLabel,
+    thread_order_label => ThreadOrderLabel,
+    oldest_first => OldestFirst,
+    newest_first => NewestFirst,
+    ascii_symbols => AsciiSymbols,
+    overview_homework_count => OverviewHomeworkCount,
+    on => On,
+    off => Off,
+
+    // Schedule date entry prompt
+    date_entry_title => DateEntryTitle,
+    date_entry_invalid => DateEntryInvalid,
+    subject_filter_title => SubjectFilterTitle,
+    student_switch_title => StudentSwitchTitle,
+    no_matches => NoMatches,
+
+    // `shkolo report`
+    period_report_title => PeriodReportTitle,
+    period_week => PeriodWeek,
+    period_homework_assigned => PeriodHomeworkAssigned,
+    period_homework_due => PeriodHomeworkDue,
+    period_due => PeriodDue,
+    period_unexcused => PeriodUnexcused,
+    period_positive => PeriodPositive,
+    period_negative => PeriodNegative,
+    period_days_without_lessons => PeriodDaysWithoutLessons,
+    period_not_loaded => PeriodNotLoaded,
+    period_none => PeriodNone,
+    period_no_students => PeriodNoStudents,
+
+    // Month names, looked up through `T::month` rather than by name
+    #[allow(dead_code)] january => January,
+    #[allow(dead_code)] february => February,
+    #[allow(dead_code)] march => March,
+    #[allow(dead_code)] april => April,
+    #[allow(dead_code)] may => May,
+    #[allow(dead_code)] june => June,
+    #[allow(dead_code)] july => July,
+    #[allow(dead_code)] august => August,
+    #[allow(dead_code)] september => September,
+    #[allow(dead_code)] october => October,
+    #[allow(dead_code)] november => November,
+    #[allow(dead_code)] december => December,
+}
+
+impl T {
+    /// Look up a string, falling back to English when a table lacks the key
+    pub fn get(lang: Lang, key: MsgKey) -> &'static str {
+        static TABLES: OnceLock<Vec<HashMap<MsgKey, &'static str>>> = OnceLock::new();
+        let tables = TABLES.get_or_init(|| {
+            Lang::ALL.iter().map(|lang| lang.messages().iter().copied().collect()).collect()
+        });
+        let table = |lang: Lang| &tables[lang as usize];
+        table(lang).get(&key)
+            .or_else(|| table(Lang::En).get(&key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The setting label stays multilingual so it can be found in any language
+    pub fn language_label() -> &'static str {
+        "Език / Language / Sprache: "
+    }
+    pub fn pane_width(lang: Lang, width: u16) -> String {
+        format!("{}: {}", T::pane_width_label(lang), width)
+    }
+    /// "Grade changed in Математика: 3 → 5", or "Grade removed in ..." without a new value
+    pub fn grade_change(lang: Lang, subject: &str, old: &str, new: Option<&str>) -> String {
+        match new {
+            Some(new) => format!("{} {}: {} → {}", T::grade_changed_in(lang), subject, old, new),
+            None => format!("{} {}: {}", T::grade_removed_in(lang), subject, old),
+        }
+    }
+    pub fn overview_split(lang: Lang, percent: u16) -> String {
+        format!("{}: {}%", T::overview_split_label(lang), percent)
+    }
+    pub fn weekday(lang: Lang, day: time::Weekday) -> &'static str {
+        use time::Weekday::*;
+        let key = match day {
+            Monday => MsgKey::Monday,
+            Tuesday => MsgKey::Tuesday,
+            Wednesday => MsgKey::Wednesday,
+            Thursday => MsgKey::Thursday,
+            Friday => MsgKey::Friday,
+            Saturday => MsgKey::Saturday,
+            Sunday => MsgKey::Sunday,
+        };
+        T::get(lang, key)
+    }
+    pub fn month(lang: Lang, month: time::Month) -> &'static str {
+        use time::Month::*;
+        let key = match month {
+            January => MsgKey::January,
+            February => MsgKey::February,
+            March => MsgKey::March,
+            April => MsgKey::April,
+            May => MsgKey::May,
+            June => MsgKey::June,
+            July => MsgKey::July,
+            August => MsgKey::August,
+            September => MsgKey::September,
+            October => MsgKey::October,
+            November => MsgKey::November,
+            December => MsgKey::December,
+        };
+        T::get(lang, key)
+    }
+}
+
+/// Localized age of a Unix timestamp relative to now ("преди 12 мин" / "12m ago")
+pub fn format_age(timestamp: i64, lang: Lang) -> String {
+    format_age_at(timestamp, time::OffsetDateTime::now_utc().unix_timestamp(), lang)
+}
+
+/// API timestamp in Sofia time relative to `today`: "днес 14:32", "вчера 09:10",
+/// "19.02 08:00", with the year when it's not this year's. Unreadable
+/// timestamps are shown as they are.
+pub fn format_relative_datetime(raw: &str, today: Date, lang: Lang) -> String {
+    let Some(datetime) = util::parse_sofia_datetime(raw) else {
+        return raw.to_string();
+    };
+    let (date, time) = (datetime.date(), format!("{:02}:{:02}", datetime.hour(), datetime.minute()));
+    if date == today {
+        format!("{} {}", T::today(lang), time)
+    } else if date.next_day() == Some(today) {
+        format!("{} {}", T::yesterday(lang), time)
+    } else if date.year() == today.year() {
+        format!("{:02}.{:02} {}", date.day(), date.month() as u8, time)
+    } else {
+        format!("{:02}.{:02}.{} {}", date.day(), date.month() as u8, date.year(), time)
+    }
+}
+
+fn format_age_at(timestamp: i64, now: i64, lang: Lang) -> String {
+    let seconds = (now - timestamp).max(0);
+    let (value, unit) = if seconds < 60 {
+        (seconds, 0)
+    } else if seconds < 3600 {
+        (seconds / 60, 1)
+    } else if seconds < 86400 {
+        (seconds / 3600, 2)
+    } else {
+        (seconds / 86400, 3)
+    };
+
+    match lang {
+        Lang::Bg => {
+            let unit = ["сек", "мин", "ч", if value == 1 { "ден" } else { "дни" }][unit];
+            format!("преди {} {}", value, unit)
+        }
+        Lang::En => format!("{}{} ago", value, ["s", "m", "h", "d"][unit]),
+        Lang::De => {
+            let unit = ["Sek.", "Min.", "Std.", if value == 1 { "Tag" } else { "Tagen" }][unit];
+            format!("vor {} {}", value, unit)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_relative_datetime() {
+        let today = time::macros::date!(2026 - 02 - 19);
+        let cases = [
+            ("2026-02-19 14:32:05", "днес 14:32", "today 14:32"),
+            ("2026-02-18 09:10:00", "вчера 09:10", "yesterday 09:10"),
+            ("2026-02-17T06:00:00.000000Z", "17.02 08:00", "17.02 08:00"),
+            ("19.02.2025 08:00", "19.02.2025 08:00", "19.02.2025 08:00"),
+            // Just after midnight in Sofia, still the previous day in UTC
+            ("2026-02-18T22:15:00Z", "днес 00:15", "today 00:15"),
+            ("", "", ""),
+            ("неизвестно", "неизвестно", "неизвестно"),
+        ];
+        for (raw, bg, en) in cases {
+            assert_eq!(format_relative_datetime(raw, today, Lang::Bg), bg);
+            assert_eq!(format_relative_datetime(raw, today, Lang::En), en);
+        }
+        assert_eq!(format_relative_datetime("2026-02-18 09:10", today, Lang::De), "gestern 09:10");
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = 1_771_500_000;
+        let cases = [
+            (12, "преди 12 сек", "12s ago"),
+            (12 * 60, "преди 12 мин", "12m ago"),
+            (3 * 3600 + 59, "преди 3 ч", "3h ago"),
+            (86400, "преди 1 ден", "1d ago"),
+            (5 * 86400, "преди 5 дни", "5d ago"),
+        ];
+        for (elapsed, bg, en) in cases {
+            assert_eq!(format_age_at(now - elapsed, now, Lang::Bg), bg);
+            assert_eq!(format_age_at(now - elapsed, now, Lang::En), en);
+        }
+
+        assert_eq!(format_age_at(now - 5 * 86400, now, Lang::De), "vor 5 Tagen");
+
+        // Clock skew never produces negative ages
+        assert_eq!(format_age_at(now + 30, now, Lang::En), "0s ago");
+    }
+
+    #[test]
+    fn test_every_key_translated_in_every_language() {
+        for lang in Lang::ALL {
+            let table = lang.messages();
+            for key in MsgKey::ALL {
+                let count = table.iter().filter(|(k, _)| k == key).count();
+                assert_eq!(count, 1, "{:?} has {} entries for {:?}", lang, count, key);
+            }
+            assert_eq!(table.len(), MsgKey::ALL.len(), "{:?} has extra entries", lang);
+        }
+    }
+
+    #[test]
+    fn test_lang_cycle_and_codes() {
+        assert_eq!(Lang::Bg.next(), Lang::En);
+        assert_eq!(Lang::En.next(), Lang::De);
+        assert_eq!(Lang::De.next(), Lang::Bg);
+        for lang in Lang::ALL {
+            assert_eq!(Lang::from_code(lang.code()), Some(lang));
+        }
+        assert_eq!(Lang::from_code("DE"), Some(Lang::De));
+        assert_eq!(Lang::from_code("fr"), None);
+        assert_eq!(Lang::from_locale("de_DE.UTF-8"), Some(Lang::De));
+        assert_eq!(Lang::from_locale("bg_BG"), Some(Lang::Bg));
+        assert_eq!(Lang::from_locale("C.UTF-8"), None);
+        assert_eq!(Lang::De.api_code(), "en");
+        assert_eq!(T::weekday(Lang::De, time::Weekday::Friday), "Freitag");
+    }
+
+    #[test]
+    fn test_no_cyrillic_outside_i18n() {
+        // UI strings belong in T; Cyrillic literals elsewhere mean a string bypassed it
+        let sources = [
+            ("tui/ui.rs", include_str!("../tui/ui.rs")),
+            ("tui/handlers.rs", include_str!("../tui/handlers.rs")),
+        ];
+        for (name, source) in sources {
+            let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+            for (i, line) in code.lines().enumerate() {
+                let line = line.split("//").next().unwrap_or_default();
+                assert!(
+                    !line.chars().any(|c| ('\u{0400}'..='\u{04FF}').contains(&c)),
+                    "{}:{} has a hardcoded Cyrillic string: {}", name, i + 1, line.trim()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pane_strings_are_translated() {
+        let fns: [fn(Lang) -> &'static str; 20] = [
+            T::today_marker, T::schedule_nav_hint, T::total, T::messages_hint, T::thread_hint,
+            T::reply_title, T::recipients_title_active, T::recipients_title, T::subject_title_active,
+            T::subject_title, T::body_title_active, T::body_title, T::notifications_hint,
+            T::language_name, T::auto_refresh_label, T::error_title, T::refreshing,
+            T::refreshing_all, T::refreshed, T::teacher_unsupported,
+        ];
+        for f in fns {
+            let (bg, en) = (f(Lang::Bg), f(Lang::En));
+            assert!(!bg.trim().is_empty() && !en.trim().is_empty());
+            assert_ne!(bg, en);
+        }
+
+        assert_eq!(T::pane_width(Lang::En, 30), "Pane width: 30");
+        assert_eq!(T::pane_width(Lang::Bg, 30), "Ширина на панела: 30");
+        assert_eq!(T::overview_split(Lang::En, 40), "Overview split: 40%");
+        assert_eq!(T::overview_split(Lang::Bg, 40), "Разделител на прегледа: 40%");
+    }
+}
@@ -1,21 +1,37 @@
 mod api;
+mod brief;
 mod cache;
+mod html;
 mod i18n;
+mod metrics;
 mod models;
+mod output;
+mod period_report;
+mod serve;
+mod service;
+mod style;
+mod table;
+mod tracking;
 mod tui;
+mod util;
 
 use anyhow::{anyhow, Result};
 use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{Event, EventStream, MouseEventKind, EnableMouseCapture, DisableMouseCapture},
+    event::{
+        Event, EventStream, MouseEventKind, EnableMouseCapture, DisableMouseCapture,
+        EnableBracketedPaste, DisableBracketedPaste, EnableFocusChange, DisableFocusChange,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::StreamExt;
 use ratatui::prelude::*;
-use std::io::{self, Write};
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
-use time::OffsetDateTime;
 use std::pin::Pin;
 use std::future::Future;
 
@@ -23,7 +39,8 @@ use api::ShkoloClient;
 use cache::CacheStore;
 use i18n::T;
 use models::*;
-use tui::{App, draw, handle_key, handlers::Action, app::{ClickResult, StudentData}};
+use output::{MaxAgePolicy, OutputFormat};
+use tui::{App, draw, handle_key, handlers::{Action, activate_setting}, app::{ClickResult, StudentData, OVERVIEW_HOMEWORK_COUNTS}};
 
 /// Result of a background refresh operation
 enum BackgroundResult {
@@ -32,6 +49,8 @@ enum BackgroundResult {
         students: Vec<StudentData>,
         notifications: Vec<Notification>,
         messages: Vec<MessageThread>,
+        /// Partial failures (data was still returned, but is incomplete)
+        errors: Vec<String>,
     },
     /// Schedule-only refresh completed
     ScheduleRefresh {
@@ -62,6 +81,45 @@ struct Cli {
     /// Cache TTL in seconds (default: 3600)
     #[arg(long, global = true)]
     cache_ttl: Option<i64>,
+
+    /// Directory for the cache, token and settings (default: SHKOLO_CACHE_DIR; then on
+    /// Linux $XDG_CACHE_HOME/shkolo and $XDG_CONFIG_HOME/shkolo; elsewhere ~/.shkolo/cache)
+    #[arg(long, global = true, value_name = "PATH")]
+    cache_dir: Option<std::path::PathBuf>,
+
+    /// Offline mode: serve cached data (even if expired) without network access
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Language: bg, en or de (default: SHKOLO_LANG, then the LC_ALL/LANG locale)
+    #[arg(long, global = true)]
+    lang: Option<i18n::Lang>,
+
+    /// API server to use (default: SHKOLO_API_URL or SHKOLO_API_BASE, then the server used at login)
+    #[arg(long, global = true, value_name = "URL")]
+    api_url: Option<String>,
+
+    /// Allow an http:// API URL, e.g. a local mock server
+    #[arg(long, global = true)]
+    insecure_http: bool,
+
+    /// Draw the TUI with ASCII only, for consoles that garble box-drawing characters and emoji
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Log API requests (URL, User-Agent) and response statuses to stderr, and save
+    /// a response that can't be decoded to last_decode_error.json in the cache
+    /// directory; ignored by the TUI
+    #[arg(long, global = true)]
+    debug_http: bool,
+
+    /// Plain text output without ANSI colors, the same as `--color never`
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// ANSI colors in text output: auto (only on a terminal without NO_COLOR), always or never
+    #[arg(long, global = true, value_enum, default_value_t = style::ColorChoice::Auto)]
+    color: style::ColorChoice,
 }
 
 #[derive(Subcommand)]
@@ -71,16 +129,154 @@ enum Commands {
         #[command(subcommand)]
         command: JsonCommands,
 
-        /// Output format: pretty or compact
-        #[arg(long, default_value = "pretty")]
-        format: String,
+        /// Output format: pretty, compact, jsonl (one line per entry), llm
+        /// (plain-text digest, summary only) or table (students, grades and schedule).
+        /// Defaults to $SHKOLO_JSON_FORMAT, then default_json_format in the config, then pretty
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Another school year's data instead of the current one's, by year id (the
+        /// current id is the school_year printed by `login --print-token`)
+        #[arg(long, value_name = "ID")]
+        school_year: Option<i64>,
+
+        /// Add a `timing` object to the response: how long each fetch took and
+        /// whether it came from the cache
+        #[arg(long)]
+        timing: bool,
+
+        /// Cached data older than this many seconds is fetched again, or reported
+        /// (see --max-age-policy)
+        #[arg(long, value_name = "SECONDS")]
+        max_age: Option<i64>,
+
+        /// What to do about data older than --max-age
+        #[arg(long, value_enum, default_value_t = MaxAgePolicy::Refetch, requires = "max_age")]
+        max_age_policy: MaxAgePolicy,
+
+        /// Keep homework text that came as HTML in a `raw_html` field, next to
+        /// the cleaned-up `text`
+        #[arg(long)]
+        raw: bool,
     },
 
     /// Launch interactive TUI
     Tui,
 
+    /// Draw one TUI tab from cached data into a text file, for bug reports
+    Snapshot {
+        #[arg(long, value_enum, default_value_t = tui::app::Tab::Overview)]
+        tab: tui::app::Tab,
+
+        /// Student name or index (default: the first)
+        #[arg(long)]
+        student: Option<String>,
+
+        /// File to write; stdout without it
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+
+        /// Keep colors and styles as ANSI escape sequences
+        #[arg(long)]
+        ansi: bool,
+
+        /// Screen size in columns
+        #[arg(long, default_value_t = 120, value_parser = clap::value_parser!(u16).range(20..=500))]
+        width: u16,
+
+        /// Screen size in rows
+        #[arg(long, default_value_t = 40, value_parser = clap::value_parser!(u16).range(10..=200))]
+        height: u16,
+    },
+
+    /// Day-by-day plan for the coming days: homework by due date, events and
+    /// tests, with "—" on free days
+    Agenda {
+        /// Student name or index (optional, defaults to all)
+        student: Option<String>,
+
+        /// How many days to cover, today included
+        #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(u32).range(1..=60))]
+        days: u32,
+
+        /// Print the agenda as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// What happened this week or month: grades, homework, absences, feedbacks
+    /// and days without lessons, as Markdown or a standalone HTML page
+    Report {
+        /// Student name or index (optional, defaults to all)
+        #[arg(long)]
+        student: Option<String>,
+
+        #[arg(long, value_enum, default_value_t = period_report::Period::Week)]
+        period: period_report::Period,
+
+        #[arg(long, value_enum, default_value_t = period_report::ReportFormat::Md)]
+        format: period_report::ReportFormat,
+    },
+
+    /// Print notifications; with --follow, keep printing new ones as they arrive
+    Notifications {
+        /// Keep polling and print only notifications that weren't there before (Ctrl+C to stop)
+        #[arg(long, short)]
+        follow: bool,
+
+        /// One JSON object per line instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Seconds between polls with --follow; never less than --cache-ttl, or 60 without it
+        #[arg(long, value_name = "SECONDS", default_value_t = 300, requires = "follow")]
+        interval: u64,
+
+        /// With --follow, print API and cache statistics as a JSON line on stderr this often
+        #[arg(long, value_name = "SECONDS", requires = "follow", value_parser = clap::value_parser!(u64).range(1..))]
+        stats_interval: Option<u64>,
+    },
+
+    /// Serve the `json` data over a local read-only HTTP API:
+    /// GET /students, /homework/<student>, /grades/<student>,
+    /// /schedule/<student>?date=DATE and /summary?date=DATE
+    /// (without <student>: every student; a student is an index or part of a name)
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = serve::DEFAULT_PORT)]
+        port: u16,
+
+        /// Address to listen on; only loopback addresses unless --allow-remote is given
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: std::net::IpAddr,
+
+        /// Allow binding to an address other machines can reach (needs --token)
+        #[arg(long)]
+        allow_remote: bool,
+
+        /// Require clients to send `Authorization: Bearer <TOKEN>`
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Export a student's absences per subject and term as CSV
+    /// (subject, excused and unexcused in each term, total)
+    ExportAbsencesCsv {
+        /// Student: 1-based index or part of the name
+        #[arg(long)]
+        student: String,
+
+        /// File to write; stdout when omitted
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+
     /// Import token from iOS Shkolo app
-    ImportToken,
+    ImportToken {
+        /// Save the token without checking it against the API (e.g. while offline)
+        #[arg(long)]
+        no_verify: bool,
+    },
 
     /// Login with credentials
     Login {
@@ -91,6 +287,15 @@ enum Commands {
         /// Password
         #[arg(short, long)]
         password: Option<String>,
+
+        /// Print the token and school year as JSON instead of saving them, for other tools.
+        /// The token gives full access to the account: keep it out of logs and shared places
+        #[arg(long)]
+        print_token: bool,
+
+        /// Don't warn on stderr that the token is being printed
+        #[arg(short, long, requires = "print_token")]
+        quiet: bool,
     },
 
     /// Login with Google
@@ -116,9 +321,48 @@ enum Commands {
         #[arg(long)]
         clear_all: bool,
 
+        /// Clear only one data type (homework, grades, schedule, ...)
+        #[arg(long, value_name = "TYPE")]
+        clear_type: Option<cache::CacheKind>,
+
+        /// With --clear-type, only clear this student's files
+        #[arg(long, value_name = "ID", requires = "clear_type")]
+        student: Option<i64>,
+
         /// Force refresh all data
         #[arg(long)]
         refresh: bool,
+
+        /// Show cache location, TTL and cached entries
+        #[arg(long)]
+        info: bool,
+
+        /// With --info, also show hit/miss/expiry counts per data type
+        #[arg(long, short, requires = "info")]
+        verbose: bool,
+
+        /// List the files --clear/--clear-all/--clear-type would remove, without deleting
+        #[arg(long, conflicts_with = "refresh")]
+        dry_run: bool,
+
+        /// Don't ask for confirmation before --clear-all (required when not on a terminal)
+        #[arg(long, short)]
+        yes: bool,
+
+        /// Print what was done as one JSON object: the location, TTL and
+        /// cached files, or how many files were cleared
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MessagesCommands {
+    /// List the threads of one folder
+    List {
+        /// Folder name, slug (e.g. inbox, sent) or id (default: the inbox)
+        #[arg(long, value_name = "NAME|ID")]
+        folder: Option<String>,
     },
 }
 
@@ -131,12 +375,40 @@ enum JsonCommands {
     Homework {
         /// Student name or index (optional, defaults to all)
         student: Option<String>,
+
+        /// Only subjects whose name contains this (case-insensitive)
+        #[arg(long)]
+        subject: Option<String>,
+
+        /// Only homework due before this day (YYYY-MM-DD or DD.MM.YYYY)
+        #[arg(long, value_parser = util::parse_date_arg)]
+        due_before: Option<time::Date>,
+
+        /// Only homework due after this day (YYYY-MM-DD or DD.MM.YYYY)
+        #[arg(long, value_parser = util::parse_date_arg)]
+        due_after: Option<time::Date>,
+
+        /// Only homework still to be done: due later, or today before the last lesson ends
+        #[arg(long)]
+        pending: bool,
     },
 
     /// Get grades
     Grades {
         /// Student name or index (optional, defaults to all)
         student: Option<String>,
+
+        /// Only subjects whose name contains this (case-insensitive)
+        #[arg(long)]
+        subject: Option<String>,
+
+        /// Only grades of one term, or the final grades with "annual"
+        #[arg(long, value_enum)]
+        term: Option<models::GradeTerm>,
+
+        /// Leave out subjects with fewer grades than this
+        #[arg(long, default_value_t = 0)]
+        min_count: usize,
     },
 
     /// Get schedule
@@ -149,7 +421,33 @@ enum JsonCommands {
     },
 
     /// Get summary for all students
-    Summary,
+    Summary {
+        /// Schedule date in YYYY-MM-DD format (defaults to today)
+        #[arg(long)]
+        date: Option<String>,
+    },
+
+    /// Get everything for one or all students in one response
+    All {
+        /// Student name or index (optional, defaults to all)
+        student: Option<String>,
+
+        /// Schedule date in YYYY-MM-DD format (defaults to today)
+        #[arg(long)]
+        date: Option<String>,
+    },
+
+    /// Get upcoming tests found in homework and events
+    Exams {
+        /// Student name or index (optional, defaults to all)
+        student: Option<String>,
+    },
+
+    /// Get average grade and absences per subject, riskiest first
+    Report {
+        /// Student name or index (optional, defaults to all)
+        student: Option<String>,
+    },
 
     /// Get absences
     Absences {
@@ -166,8 +464,12 @@ enum JsonCommands {
     /// Get notifications
     Notifications,
 
-    /// Get messages
-    Messages,
+    /// Get messages: folders, inbox threads and whether sending is allowed
+    #[command(args_conflicts_with_subcommands = true)]
+    Messages {
+        #[command(subcommand)]
+        command: Option<MessagesCommands>,
+    },
 
     /// Get a specific message thread (for debugging)
     Thread {
@@ -180,6 +482,12 @@ enum JsonCommands {
         /// Student name or index (optional, defaults to first)
         student: Option<String>,
     },
+
+    /// Print the JSON Schema of a command's output
+    Schema {
+        #[arg(value_enum)]
+        command: output::SchemaKind,
+    },
 }
 
 #[tokio::main]
@@ -190,250 +498,495 @@ async fn main() -> Result<()> {
     let ttl = cli.cache_ttl
         .or_else(|| std::env::var("SHKOLO_CACHE_TTL").ok().and_then(|v| v.parse().ok()));
 
-    let cache = CacheStore::new(ttl)?;
+    let cache = CacheStore::new(cli.cache_dir.clone(), ttl)?;
+
+    // Logins remember the override with the token, so later commands and the TUI use the same host
+    let api_url = api::client::api_url_override(cli.api_url.as_deref(), cli.insecure_http)?;
+    let api_url = api_url.as_deref();
+
+    // stderr output would tear through the TUI's alternate screen
+    let debug_http = cli.debug_http && !matches!(cli.command, Commands::Tui);
+    api::client::set_debug_http(debug_http);
+    api::client::set_decode_dump_dir(debug_http.then(|| cache.cache_dir().clone()));
+    style::init(if cli.no_color { style::ColorChoice::Never } else { cli.color });
+
+    // Network-only commands can't do anything useful offline
+    if cli.offline {
+        match &cli.command {
+            Commands::Login { .. } | Commands::LoginGoogle { .. } => {
+                return Err(anyhow!("Cannot log in while in offline mode"));
+            }
+            Commands::Cache { refresh: true, .. } => {
+                return Err(anyhow!("Cannot refresh the cache while in offline mode"));
+            }
+            Commands::Notifications { follow: true, .. } => {
+                return Err(anyhow!("Cannot follow notifications while in offline mode"));
+            }
+            _ => {}
+        }
+    }
 
     match cli.command {
-        Commands::Json { command, format } => {
-            run_json_command(command, &cache, cli.refresh, cli.no_cache, &format).await
+        // Schemas are static, so they don't need a login
+        Commands::Json { command: JsonCommands::Schema { command }, format, .. } => {
+            output_json(&output::schema(command), json_format(format, &cache)?)
+        }
+        Commands::Json { command, format, school_year, timing, max_age, max_age_policy, raw } => {
+            let format = json_format(format, &cache)?;
+            let (client, lang) = command_client(&cache, api_url, cli.offline, cli.lang)?;
+            let (client, cache) = school_year_scope(client, &cache, school_year)?;
+            let force = cli.refresh || cli.no_cache;
+            let Some(max_age) = max_age else {
+                let run = run_json_command(command, &client, &cache, force, lang, format, raw);
+                return if timing { api::with_timing(run).await } else { run.await };
+            };
+            let run = run_json_max_age(command, &client, &cache, force, lang, format, raw, max_age, max_age_policy);
+            let too_old = if timing { api::with_timing(run).await } else { run.await }?;
+            if too_old && max_age_policy == MaxAgePolicy::Fail {
+                io::stdout().flush()?;
+                std::process::exit(MAX_AGE_EXIT_CODE);
+            }
+            Ok(())
+        }
+        Commands::Tui => run_tui(&cache, api_url, cli.offline, cli.lang, cli.ascii).await,
+        Commands::Snapshot { tab, student, out, ansi, width, height } => {
+            let app = snapshot_app(&cache, cli.lang, cli.ascii, tab, student.as_deref()).await?;
+            let text = tui::snapshot::render(&app, width, height, ansi);
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, text).map_err(|e| anyhow!("Could not write {}: {}", path.display(), e))?;
+                    eprintln!("Wrote the {} tab to {}", tab.name(i18n::Lang::En), path.display());
+                }
+                None => print!("{}", text),
+            }
+            Ok(())
         }
-        Commands::Tui => run_tui(&cache).await,
-        Commands::ImportToken => import_token(&cache),
-        Commands::Login { username, password } => login(&cache, username, password).await,
-        Commands::LoginGoogle { token } => login_google(&cache, token).await,
-        Commands::Logout => logout(&cache).await,
-        Commands::Status => show_status(&cache),
-        Commands::Cache { clear, clear_all, refresh } => {
-            cache_command(&cache, clear, clear_all, refresh).await
+        Commands::Agenda { student, days, json } => {
+            let (client, _) = command_client(&cache, api_url, cli.offline, cli.lang)?;
+            print_agenda(&client, &cache, student.as_deref(), days, json, cli.refresh || cli.no_cache).await
         }
+        Commands::Report { student, period, format } => {
+            let (client, lang) = command_client(&cache, api_url, cli.offline, cli.lang)?;
+            print_period_report(&client, &cache, student.as_deref(), period, format, lang, cli.refresh || cli.no_cache).await
+        }
+        Commands::Notifications { follow, json, interval, stats_interval } => {
+            let (client, _) = command_client(&cache, api_url, cli.offline, cli.lang)?;
+            if follow {
+                if let Some(seconds) = stats_interval {
+                    print_stats(client.metrics(), cache.stats_handle(), Duration::from_secs(seconds));
+                }
+                follow_notifications(&client, &cache, json, follow_interval(interval, cli.cache_ttl)).await
+            } else {
+                let (notifications, _) = service::fetch_notifications(&client, &cache, cli.refresh || cli.no_cache).await?;
+                print_notifications(&fresh_notifications(&mut HashSet::new(), notifications), json)
+            }
+        }
+        Commands::Serve { port, bind, allow_remote, token } => {
+            serve::check_bind(bind, allow_remote, token.as_deref())?;
+            let (client, _) = command_client(&cache, api_url, cli.offline, cli.lang)?;
+            let metrics = client.metrics();
+            serve::Server::new(client, cache.clone(), token)?.with_metrics(metrics).run(std::net::SocketAddr::new(bind, port)).await
+        }
+        Commands::ExportAbsencesCsv { student, out } => {
+            let (client, _) = command_client(&cache, api_url, cli.offline, cli.lang)?;
+            export_absences_csv(&client, &cache, &student, out.as_deref(), cli.refresh || cli.no_cache).await
+        }
+        Commands::ImportToken { no_verify } => import_token(&cache, api_url, no_verify).await,
+        Commands::Login { username, password, print_token, quiet } => {
+            login(&cache, api_url, username, password, print_token, quiet).await
+        }
+        Commands::LoginGoogle { token } => login_google(&cache, api_url, token).await,
+        Commands::Logout => logout(&cache, api_url).await,
+        Commands::Status => show_status(&cache, api_url),
+        Commands::Cache { clear, clear_all, clear_type, student, refresh, info, verbose, dry_run, yes, json } => {
+            let clearing = clear || clear_all || clear_type.is_some();
+            let mut report = CacheReport::new(&cache);
+            if clearing {
+                match clear_cache_files(&cache, clear, clear_all, clear_type, student, dry_run, yes, json)? {
+                    Some(files) if dry_run => report.would_remove = Some(files.iter().map(|p| p.display().to_string()).collect()),
+                    Some(files) => report.cleared = Some(files.len()),
+                    None => {
+                        report.aborted = true;
+                        return if json { output_json(&report, OutputFormat::Pretty) } else { Ok(()) };
+                    }
+                }
+            }
+            cache_command(&cache, api_url, clearing, refresh, info, verbose, json.then_some(report)).await
+        }
+    }
+}
+
+/// `json --max-age --max-age-policy fail` when some data was older than that
+const MAX_AGE_EXIT_CODE: i32 = 6;
+
+/// A `json` command with --max-age. Data older than `max_age` seconds is
+/// refetched under the `refetch` policy; whatever is still that old gets a
+/// warning on stderr. Returns whether there was any.
+#[allow(clippy::too_many_arguments)]
+async fn run_json_max_age(
+    command: JsonCommands,
+    client: &ShkoloClient,
+    cache: &CacheStore,
+    force: bool,
+    lang: i18n::Lang,
+    format: OutputFormat,
+    raw: bool,
+    max_age: i64,
+    policy: MaxAgePolicy,
+) -> Result<bool> {
+    let cache = match policy {
+        MaxAgePolicy::Refetch => cache.clone().with_max_age(max_age),
+        MaxAgePolicy::Warn | MaxAgePolicy::Fail => cache.clone(),
+    };
+    let (result, oldest) = service::with_cache_age(run_json_command(command, client, &cache, force, lang, format, raw)).await;
+    result?;
+
+    let age = oldest.map(|cached_at| util::now_local().unix_timestamp() - cached_at);
+    match age.filter(|&age| age > max_age) {
+        Some(age) => {
+            eprintln!("{}", style::yellow(&format!("Warning: some of the data is {}s old, more than --max-age {}s", age, max_age)));
+            Ok(true)
+        }
+        None => Ok(false),
     }
 }
 
+/// `raw` keeps the homework texts as the API sent them (see `Homework::raw_html`)
+#[allow(clippy::too_many_arguments)]
 async fn run_json_command(
     command: JsonCommands,
+    client: &ShkoloClient,
     cache: &CacheStore,
-    force_refresh: bool,
-    no_cache: bool,
-    format: &str,
+    force: bool,
+    lang: i18n::Lang,
+    format: OutputFormat,
+    raw: bool,
 ) -> Result<()> {
-    let client = get_authenticated_client(cache)?;
+    let started = std::time::Instant::now();
+    client.set_language(lang);
+    cache.set_data_language(lang.api_code())?;
+
+    if format == OutputFormat::Llm && !matches!(command, JsonCommands::Summary { .. }) {
+        return Err(anyhow!("--format llm is only supported by `json summary`"));
+    }
+    if format == OutputFormat::Table && !matches!(command, JsonCommands::Students | JsonCommands::Grades { .. } | JsonCommands::Schedule { .. }) {
+        return Err(anyhow!("--format table is only supported by `json students`, `json grades` and `json schedule`"));
+    }
 
     match command {
+        JsonCommands::Schema { .. } => unreachable!("handled before authentication"),
         JsonCommands::Students => {
-            let (students, cached, cached_at) = get_students(&client, cache, force_refresh || no_cache).await?;
-            output_json(&api::ApiResponse::new(students, cached && !no_cache, cached_at), format)?;
+            let (students, info) = service::fetch_students(client, cache, force).await?;
+            if format == OutputFormat::Table {
+                print!("{}", table::students(&students, style::enabled()));
+                return Ok(());
+            }
+            output_students(api::ApiResponse::new(students, info.cached, info.cached_at.map(cache::age_string)), Vec::new(), client, started, format)?;
+        }
+        JsonCommands::Homework { student, subject, due_before, due_after, pending } => {
+            let (students, _) = service::fetch_students(client, cache, force).await?;
+            let selected = select_students(&students, student.as_deref());
+            let filter = models::HomeworkFilter { subject, due_before, due_after, pending };
+            let (mut all_homework, failures) = service::homework_entries(client, cache, &selected, &filter, &exam_keywords(cache), service::school_day_end(cache)?, force).await;
+            if !raw {
+                output::strip_raw_html(all_homework.iter_mut().flat_map(|e| e.homework.iter_mut().map(|h| &mut h.homework)));
+            }
+
+            let state = output::CacheState::merge(all_homework.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(all_homework, state.cached, state.cached_at), failures, client, started, format)?;
         }
-        JsonCommands::Homework { student } => {
-            let (students, _, _) = get_students(&client, cache, force_refresh || no_cache).await?;
+        JsonCommands::Grades { student, subject, term, min_count } => {
+            let (students, _) = service::fetch_students(client, cache, force).await?;
             let selected = select_students(&students, student.as_deref());
+            let filter = models::GradeFilter { subject, term, min_count };
+            let (all_grades, failures) = service::grades_entries(client, cache, &selected, &filter, force).await;
 
-            let mut all_homework = Vec::new();
-            let mut any_cached = false;
-            let mut oldest_cache: Option<String> = None;
+            if format == OutputFormat::Table {
+                return output_table(table::grades(&all_grades, style::enabled()), all_grades.is_empty(), failures);
+            }
+            let state = output::CacheState::merge(all_grades.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(all_grades, state.cached, state.cached_at), failures, client, started, format)?;
+        }
+        JsonCommands::Schedule { student, date } => {
+            let date = date.unwrap_or_else(util::today_local);
+            let (students, _) = service::fetch_students(client, cache, force).await?;
+            let selected = select_students(&students, student.as_deref());
+            let (all_schedules, failures) = service::schedule_entries(client, cache, &selected, &date, force).await;
 
-            for s in selected {
-                let (homework, cached, cached_at) = get_homework(&client, cache, s.id, force_refresh || no_cache).await?;
-                if cached {
-                    any_cached = true;
-                    if oldest_cache.is_none() {
-                        oldest_cache = cached_at;
-                    }
-                }
-                all_homework.push(serde_json::json!({
-                    "student": s,
-                    "homework": homework,
-                }));
+            if format == OutputFormat::Table {
+                return output_table(table::schedule(&all_schedules, style::enabled()), all_schedules.is_empty(), failures);
+            }
+            let state = output::CacheState::merge(all_schedules.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(all_schedules, state.cached, state.cached_at), failures, client, started, format)?;
+        }
+        JsonCommands::Summary { date } if format == OutputFormat::Llm => {
+            // Categories that fail to load are reported as unavailable instead of failing the digest
+            let date = date.unwrap_or_else(util::today_local);
+            let now = util::now_local();
+            let time_now = (date == util::date_string(now)).then(|| (now.hour(), now.minute()));
+            let excuse_days = excuse_days(cache);
+            let (students, _) = service::fetch_students(client, cache, force).await?;
+            let notifications = service::fetch_notifications(client, cache, force).await.ok().map(|(n, _)| n);
+
+            let mut briefs = Vec::new();
+            for s in &students {
+                let schedule = service::fetch_schedule(client, cache, s.id, &date, force).await.ok().map(|(d, _)| d);
+                let homework = service::fetch_homework(client, cache, s.id, force).await.ok().map(|(d, _, _)| d);
+                let absences = service::fetch_absences(client, cache, s.id, force).await.ok()
+                    .map(|(d, _)| brief::AbsenceTotals::new(&d, now.date(), excuse_days));
+                briefs.push(brief::StudentBrief::new(
+                    s.clone(),
+                    schedule,
+                    homework,
+                    notifications.as_deref(),
+                    absences,
+                    now.date(),
+                    time_now,
+                ));
             }
 
-            output_json(&api::ApiResponse::new(all_homework, any_cached && !no_cache, oldest_cache), format)?;
+            print!("{}", brief::render(&briefs, &date));
         }
-        JsonCommands::Grades { student } => {
-            let (students, _, _) = get_students(&client, cache, force_refresh || no_cache).await?;
-            let selected = select_students(&students, student.as_deref());
+        JsonCommands::Summary { date } => {
+            let date = date.unwrap_or_else(util::today_local);
+            let (students, _) = service::fetch_students(client, cache, force).await?;
+            let (mut summaries, failures) = service::summary_entries(client, cache, &students, &date, excuse_days(cache), force).await;
+            if !raw {
+                output::strip_raw_html(summaries.iter_mut().flat_map(|e| e.recent_homework.iter_mut()));
+            }
 
-            let mut all_grades = Vec::new();
-            let mut any_cached = false;
-            let mut oldest_cache: Option<String> = None;
+            let state = output::CacheState::merge(summaries.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(summaries, state.cached, state.cached_at), failures, client, started, format)?;
+        }
+        JsonCommands::All { student, date } => {
+            let date = date.unwrap_or_else(util::today_local);
+            let now = util::now_local();
+            let today = now.date();
+            let keywords = exam_keywords(cache);
+            let day_end = service::school_day_end(cache)?;
+            let (students, _) = service::fetch_students(client, cache, force).await?;
+            let selected = select_students(&students, student.as_deref());
 
+            let mut entries = Vec::new();
+            let mut failures = Vec::new();
             for s in selected {
-                let (grades, cached, cached_at) = get_grades(&client, cache, s.id, force_refresh || no_cache).await?;
-                if cached {
-                    any_cached = true;
-                    if oldest_cache.is_none() {
-                        oldest_cache = cached_at;
+                // A category that fails is left empty with its status; the
+                // student only fails when nothing loaded
+                let mut status = output::CategoryStatus::default();
+                let mut parts = Vec::new();
+                let (homework, errors) = match service::fetch_homework(client, cache, s.id, force).await {
+                    Ok((mut homework, info, errors)) => {
+                        if !raw {
+                            output::strip_raw_html(&mut homework);
+                        }
+                        status.homework = load_status(&homework, info, client);
+                        parts.push(output::CacheState::from(info));
+                        (homework, errors)
+                    }
+                    Err(e) => {
+                        status.homework = output::LoadStatus::failed(&e, false);
+                        (Vec::new(), Vec::new())
                     }
+                };
+                let grades = category(service::fetch_grades(client, cache, s.id, force).await, client, &mut status.grades, &mut parts);
+                let schedule = category(service::fetch_schedule(client, cache, s.id, &date, force).await, client, &mut status.schedule, &mut parts);
+                let absences = category(service::fetch_absences(client, cache, s.id, force).await, client, &mut status.absences, &mut parts);
+                let feedbacks = category(service::fetch_feedbacks(client, cache, s.id, force).await, client, &mut status.feedbacks, &mut parts);
+                let events = category(service::fetch_events(client, cache, s.id, force).await, client, &mut status.events, &mut parts);
+                if let Some(error) = status.all_failed() {
+                    failures.push(service::student_error(s, anyhow!(error.to_string())));
+                    continue;
                 }
-                all_grades.push(serde_json::json!({
-                    "student": s,
-                    "grades": grades,
-                }));
+
+                // The schedule only tells when school ends today if it is today's
+                let today_schedule = if date == today.to_string() { schedule.as_slice() } else { &[] };
+                let school_day_over = models::school_day_over(today_schedule, (now.hour(), now.minute()), day_end);
+                let homework = homework.into_iter().map(|hw| output::HomeworkEntry::new(hw, today, school_day_over, &keywords)).collect();
+                entries.push(output::StudentAll::new(
+                    s.clone(),
+                    output::CacheState::merge(&parts),
+                    homework,
+                    grades,
+                    date.clone(),
+                    schedule,
+                    absences,
+                    feedbacks,
+                    events,
+                    status,
+                    errors,
+                ));
             }
 
-            output_json(&api::ApiResponse::new(all_grades, any_cached && !no_cache, oldest_cache), format)?;
+            let state = output::CacheState::merge(entries.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(entries, state.cached, state.cached_at), failures, client, started, format)?;
         }
-        JsonCommands::Schedule { student, date } => {
-            let date = date.unwrap_or_else(|| get_today_date());
-            let (students, _, _) = get_students(&client, cache, force_refresh || no_cache).await?;
+        JsonCommands::Exams { student } => {
+            let today = util::now_local().date();
+            let keywords = exam_keywords(cache);
+            let (students, _) = service::fetch_students(client, cache, force).await?;
             let selected = select_students(&students, student.as_deref());
 
-            let mut all_schedules = Vec::new();
-            let mut any_cached = false;
-            let mut oldest_cache: Option<String> = None;
-
+            let mut all_exams = Vec::new();
+            let mut failures = Vec::new();
             for s in selected {
-                let (schedule, cached, cached_at) = get_schedule(&client, cache, s.id, &date, force_refresh || no_cache).await?;
-                if cached {
-                    any_cached = true;
-                    if oldest_cache.is_none() {
-                        oldest_cache = cached_at;
-                    }
+                let entry = async {
+                    let (homework, homework_info, _) = service::fetch_homework(client, cache, s.id, force).await?;
+                    let (events, events_info) = service::fetch_events(client, cache, s.id, force).await?;
+                    let parts = [
+                        output::CacheState::from(homework_info),
+                        output::CacheState::from(events_info),
+                    ];
+                    Ok::<_, anyhow::Error>(output::StudentExams {
+                        student: s.clone(),
+                        cache: output::CacheState::merge(&parts),
+                        exams: models::upcoming_exams(&homework, &events, &keywords, today),
+                    })
+                }.await;
+                match entry {
+                    Ok(entry) => all_exams.push(entry),
+                    Err(e) => failures.push(service::student_error(s, e)),
                 }
-                all_schedules.push(serde_json::json!({
-                    "student": s,
-                    "date": date,
-                    "schedule": schedule,
-                }));
             }
 
-            output_json(&api::ApiResponse::new(all_schedules, any_cached && !no_cache, oldest_cache), format)?;
+            let state = output::CacheState::merge(all_exams.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(all_exams, state.cached, state.cached_at), failures, client, started, format)?;
         }
-        JsonCommands::Summary => {
-            let date = get_today_date();
-            let (students, students_cached, _) = get_students(&client, cache, force_refresh || no_cache).await?;
-
-            let mut summaries = Vec::new();
+        JsonCommands::Report { student } => {
+            let (students, _) = service::fetch_students(client, cache, force).await?;
+            let selected = select_students(&students, student.as_deref());
 
-            for s in &students {
-                let (homework, _, _) = get_homework(&client, cache, s.id, force_refresh || no_cache).await?;
-                let (grades, _, _) = get_grades(&client, cache, s.id, force_refresh || no_cache).await?;
-                let (schedule, _, _) = get_schedule(&client, cache, s.id, &date, force_refresh || no_cache).await?;
-
-                // Get recent homework (last 5)
-                let recent_homework: Vec<_> = homework.into_iter().take(5).collect();
-
-                summaries.push(serde_json::json!({
-                    "student": s,
-                    "today_schedule": schedule,
-                    "recent_homework": recent_homework,
-                    "grades_count": grades.len(),
-                }));
+            let mut reports = Vec::new();
+            let mut failures = Vec::new();
+            for s in selected {
+                let entry = async {
+                    let (grades, grades_info) = service::fetch_grades(client, cache, s.id, force).await?;
+                    let (absences, absences_info) = service::fetch_absences(client, cache, s.id, force).await?;
+                    let parts = [
+                        output::CacheState::from(grades_info),
+                        output::CacheState::from(absences_info),
+                    ];
+                    Ok::<_, anyhow::Error>(output::StudentReport {
+                        student: s.clone(),
+                        cache: output::CacheState::merge(&parts),
+                        subjects: models::subject_report(&grades, &absences),
+                    })
+                }.await;
+                match entry {
+                    Ok(entry) => reports.push(entry),
+                    Err(e) => failures.push(service::student_error(s, e)),
+                }
             }
 
-            output_json(&api::ApiResponse::new(summaries, students_cached && !no_cache, None), format)?;
+            let state = output::CacheState::merge(reports.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(reports, state.cached, state.cached_at), failures, client, started, format)?;
         }
         JsonCommands::Absences { student } => {
-            let (students, _, _) = get_students(&client, cache, force_refresh || no_cache).await?;
+            let (students, _) = service::fetch_students(client, cache, force).await?;
             let selected = select_students(&students, student.as_deref());
+            let today = util::now_local().date();
+            let excuse_days = excuse_days(cache);
 
             let mut all_absences = Vec::new();
-            let mut any_cached = false;
-            let mut oldest_cache: Option<String> = None;
-
+            let mut failures = Vec::new();
             for s in selected {
-                let (absences, cached, cached_at) = get_absences(&client, cache, s.id, force_refresh || no_cache).await?;
-                if cached {
-                    any_cached = true;
-                    if oldest_cache.is_none() {
-                        oldest_cache = cached_at;
-                    }
-                }
-                all_absences.push(serde_json::json!({
-                    "student": s,
-                    "absences": absences,
-                    "total": absences.len(),
-                    "excused": absences.iter().filter(|a| a.is_excused).count(),
-                    "unexcused": absences.iter().filter(|a| !a.is_excused).count(),
-                }));
+                let (absences, info) = match service::fetch_absences(client, cache, s.id, force).await {
+                    Ok(result) => result,
+                    Err(e) => { failures.push(service::student_error(s, e)); continue; }
+                };
+                let cache = output::CacheState::from(info);
+                all_absences.push(output::StudentAbsences::new(s.clone(), cache, absences, today, excuse_days));
             }
 
-            output_json(&api::ApiResponse::new(all_absences, any_cached && !no_cache, oldest_cache), format)?;
+            let state = output::CacheState::merge(all_absences.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(all_absences, state.cached, state.cached_at), failures, client, started, format)?;
         }
         JsonCommands::Feedbacks { student } => {
-            let (students, _, _) = get_students(&client, cache, force_refresh || no_cache).await?;
+            let (students, _) = service::fetch_students(client, cache, force).await?;
             let selected = select_students(&students, student.as_deref());
 
             let mut all_feedbacks = Vec::new();
-            let mut any_cached = false;
-            let mut oldest_cache: Option<String> = None;
-
+            let mut failures = Vec::new();
             for s in selected {
-                let (feedbacks, cached, cached_at) = get_feedbacks(&client, cache, s.id, force_refresh || no_cache).await?;
-                if cached {
-                    any_cached = true;
-                    if oldest_cache.is_none() {
-                        oldest_cache = cached_at;
-                    }
-                }
-                all_feedbacks.push(serde_json::json!({
-                    "student": s,
-                    "feedbacks": feedbacks,
-                    "total": feedbacks.len(),
-                    "positive": feedbacks.iter().filter(|f| f.is_positive).count(),
-                    "negative": feedbacks.iter().filter(|f| !f.is_positive).count(),
-                }));
+                let (feedbacks, info) = match service::fetch_feedbacks(client, cache, s.id, force).await {
+                    Ok(result) => result,
+                    Err(e) => { failures.push(service::student_error(s, e)); continue; }
+                };
+                let cache = output::CacheState::from(info);
+                all_feedbacks.push(output::StudentFeedbacks::new(s.clone(), cache, feedbacks));
             }
 
-            output_json(&api::ApiResponse::new(all_feedbacks, any_cached && !no_cache, oldest_cache), format)?;
+            let state = output::CacheState::merge(all_feedbacks.iter().map(|e| &e.cache));
+            output_students(api::ApiResponse::new(all_feedbacks, state.cached, state.cached_at), failures, client, started, format)?;
         }
         JsonCommands::Notifications => {
-            let (notifications, cached, cached_at) = get_notifications(&client, cache, force_refresh || no_cache).await?;
+            let (notifications, info) = service::fetch_notifications(client, cache, force).await?;
 
-            let unread = notifications.iter().filter(|n| !n.is_read).count();
-            output_json(&api::ApiResponse::new(serde_json::json!({
-                "notifications": notifications,
-                "total": notifications.len(),
-                "unread": unread,
-            }), cached && !no_cache, cached_at), format)?;
+            output_json(&api::ApiResponse::new(output::NotificationList::new(notifications), info.cached, info.cached_at.map(cache::age_string)).stale(client.is_offline()).timed(started), format)?;
+        }
+        JsonCommands::Messages { command: Some(MessagesCommands::List { folder }) } => {
+            let folders = client.get_messenger_folders().await;
+            let folder = match (&folder, &folders) {
+                (None, Ok(folders)) => folders.iter().find(|f| f.is_inbox()).cloned(),
+                (None, Err(_)) => None,
+                (Some(query), Ok(folders)) => Some(folders.iter().find(|f| f.matches(query)).cloned().ok_or_else(|| {
+                    let names: Vec<_> = folders.iter().map(|f| f.name.as_str()).collect();
+                    anyhow!("No folder '{}' (available: {})", query, names.join(", "))
+                })?),
+                (Some(_), Err(e)) => return Err(anyhow!("Could not load messenger folders: {}", e)),
+            };
+
+            let folder_id = folder.as_ref().and_then(MessageFolder::thread_filter);
+            let (threads, info) = service::fetch_messages(client, cache, folder_id, force).await?;
+            output_json(&api::ApiResponse::new(output::MessageList::new(folder, threads), info.cached, info.cached_at.map(cache::age_string)).stale(client.is_offline()).timed(started), format)?;
         }
-        JsonCommands::Messages => {
+        JsonCommands::Messages { command: None } => {
             // Use the correct messenger API
-            let mut results = serde_json::json!({});
+            let mut results = output::MessagesOverview::default();
 
             // Get folders
             match client.get_messenger_folders().await {
-                Ok(data) => results["folders"] = serde_json::to_value(data)?,
-                Err(e) => results["folders_error"] = serde_json::json!(e.to_string()),
+                Ok(data) => results.folders = Some(data),
+                Err(e) => results.folders_error = Some(e.to_string()),
             }
 
             // Get threads (inbox)
             match client.get_messenger_threads(None).await {
-                Ok(data) => results["threads"] = serde_json::to_value(data)?,
-                Err(e) => results["threads_error"] = serde_json::json!(e.to_string()),
+                Ok(data) => results.threads = Some(data),
+                Err(e) => results.threads_error = Some(e.to_string()),
             }
 
             // Check if can send
             match client.can_send_messages().await {
-                Ok(data) => results["can_send"] = serde_json::json!(data),
-                Err(e) => results["can_send_error"] = serde_json::json!(e.to_string()),
+                Ok(data) => results.can_send = Some(data),
+                Err(e) => results.can_send_error = Some(e.to_string()),
             }
 
-            output_json(&api::ApiResponse::new(results, false, None), format)?;
+            output_json(&api::ApiResponse::new(results, false, None).timed(started), format)?;
         }
         JsonCommands::Thread { thread_id } => {
             // Get raw thread data for debugging
             match client.get_thread_raw(thread_id).await {
-                Ok(data) => output_json(&api::ApiResponse::new(data, false, None), format)?,
-                Err(e) => output_json(&api::ApiResponse::new(serde_json::json!({
-                    "error": e.to_string(),
-                    "thread_id": thread_id,
-                }), false, None), format)?,
+                Ok(data) => output_json(&api::ApiResponse::new(data, false, None).timed(started), format)?,
+                Err(e) => output_json(&api::ApiResponse::new(output::DebugError {
+                    thread_id: Some(thread_id),
+                    ..output::DebugError::new(e)
+                }, false, None).stale(client.is_offline()).timed(started), format)?,
             }
         }
         JsonCommands::FeedbacksRaw { student } => {
-            let (students, _, _) = get_students(&client, cache, force_refresh || no_cache).await?;
+            let (students, _) = service::fetch_students(client, cache, force).await?;
             let selected = select_students(&students, student.as_deref());
 
             if let Some(s) = selected.first() {
                 match client.get_feedbacks_raw(s.id).await {
-                    Ok(data) => output_json(&api::ApiResponse::new(data, false, None), format)?,
-                    Err(e) => output_json(&api::ApiResponse::new(serde_json::json!({
-                        "error": e.to_string(),
-                        "student_id": s.id,
-                    }), false, None), format)?,
+                    Ok(data) => output_json(&api::ApiResponse::new(data, false, None).timed(started), format)?,
+                    Err(e) => output_json(&api::ApiResponse::new(output::DebugError {
+                        student_id: Some(s.id),
+                        ..output::DebugError::new(e)
+                    }, false, None).stale(client.is_offline()).timed(started), format)?,
                 }
             } else {
-                output_json(&api::ApiResponse::new(serde_json::json!({
-                    "error": "No students found",
-                }), false, None), format)?;
+                output_json(&api::ApiResponse::new(output::DebugError::new("No students found"), false, None).stale(client.is_offline()).timed(started), format)?;
             }
         }
     }
@@ -441,25 +994,98 @@ async fn run_json_command(
     Ok(())
 }
 
-async fn run_tui(cache: &CacheStore) -> Result<()> {
-    let client = get_authenticated_client(cache)?;
+/// `lang` is the --lang flag; without it the saved setting wins over the environment.
+/// `ascii` (--ascii) forces ASCII drawing on; otherwise the saved setting applies.
+async fn run_tui(cache: &CacheStore, api_url: Option<&str>, offline: bool, lang: Option<i18n::Lang>, ascii: bool) -> Result<()> {
+    // Without a token the TUI still opens, on the Settings tab with the login options
+    let client = signed_in_client(cache, api_url, offline);
+
+    // Restore the terminal before the panic message is printed, so a crash
+    // doesn't leave the shell in raw mode on the alternate screen
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+
+    // SIGINT outside raw mode (e.g. `kill -INT`) would otherwise skip the teardown
+    let signal_task = tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            restore_terminal();
+            std::process::exit(130);
+        }
+    });
 
     // Setup terminal with mouse support
+    let result = match setup_terminal() {
+        Ok(mut terminal) => run_tui_loop(&mut terminal, client, cache, api_url, offline, lang, ascii).await,
+        Err(e) => Err(e),
+    };
+
+    // Always restore, whether the loop finished normally or bailed out with an error
+    restore_terminal();
+    signal_task.abort();
+    let _ = std::panic::take_hook();
+
+    result
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste, EnableFocusChange)?;
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    Ok(Terminal::new(backend)?)
+}
 
-    // Create app
-    let mut app = App::new();
+/// Leave raw mode, mouse capture and the alternate screen. Errors are ignored
+/// since this also runs from the panic hook and signal handler.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), DisableMouseCapture, DisableBracketedPaste, DisableFocusChange, LeaveAlternateScreen, crossterm::cursor::Show);
+}
+
+/// Fetch the messages of a just-opened thread, shared by Enter and mouse clicks.
+/// Redraws first so the loading status shows during the request.
+async fn load_thread(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    client: &ShkoloClient,
+    thread_id: i64,
+) -> Result<()> {
+    app.loading = true;
+    app.set_status(T::loading_thread(app.lang));
+    terminal.draw(|f| draw(f, app))?;
+
+    match client.get_thread(thread_id).await {
+        Ok(thread) => {
+            app.set_thread(thread, app.thread_open_at_latest);
+            app.clear_status();
+        }
+        Err(e) => {
+            app.set_error(format!("{}\n{}", T::failed_load_thread(app.lang), e));
+            app.close_thread();
+        }
+    }
+    app.loading = false;
+    Ok(())
+}
 
-    // Load user name from token cache
+/// A client for the saved token, if there is one yet
+fn signed_in_client(cache: &CacheStore, api_url: Option<&str>, offline: bool) -> Option<ShkoloClient> {
+    let client = get_authenticated_client(cache, api_url).ok()?;
+    client.set_offline(offline);
+    Some(client)
+}
+
+/// Account name and id from the saved token
+fn load_user(app: &mut App, cache: &CacheStore) {
     if let Ok(token_data) = cache.load_token() {
         if let Some(data) = token_data.user_data {
             // Try "names" field first (from import)
             if let Some(names) = data.get("names").and_then(|v: &serde_json::Value| v.as_str()) {
                 app.user_name = Some(names.to_string());
+                app.user_id = data.get("id").and_then(|v| v.as_i64());
             }
             // Try "users" array (from login)
             else if let Some(users) = data.get("users").and_then(|v: &serde_json::Value| v.as_array()) {
@@ -467,10 +1093,42 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
                     if let Some(names) = first.get("names").and_then(|v: &serde_json::Value| v.as_str()) {
                         app.user_name = Some(names.to_string());
                     }
+                    app.user_id = first.get("id").and_then(|v| v.as_i64());
                 }
             }
         }
     }
+}
+
+/// The TUI as it would open offline on `tab`, with `selector`'s data on it
+async fn snapshot_app(cache: &CacheStore, lang: Option<i18n::Lang>, ascii: bool, tab: tui::app::Tab, selector: Option<&str>) -> Result<App> {
+    let mut app = configured_app(cache, true, lang, ascii)?;
+    app.seen = cache.load_seen();
+    app.load_from_cache(cache).await;
+    app.track_new();
+    if let Some(selector) = selector {
+        let students: Vec<Student> = app.students.iter().map(|s| s.student.clone()).collect();
+        let student = service::find_students(&students, Some(selector))
+            .and_then(|found| found.first().map(|s| s.id))
+            .ok_or_else(|| anyhow!("No student matches \"{}\"", selector))?;
+        if let Some(index) = app.students.iter().position(|s| s.student.id == student) {
+            app.select_student(index);
+        }
+    }
+    if app.students.is_empty() && tab != tui::app::Tab::Settings {
+        eprintln!("Nothing cached yet; run `shkolo tui` or a `shkolo json` command first");
+    }
+    app.current_tab = tab;
+    Ok(app)
+}
+
+/// A new App set up from ui_config.json and the flags, before any data is loaded
+fn configured_app(cache: &CacheStore, offline: bool, lang: Option<i18n::Lang>, ascii: bool) -> Result<App> {
+    let mut app = App::new();
+    app.offline = offline;
+    app.cache_stats = Some(cache.stats_handle());
+    app.cache_ttl = cache.ttl();
+    load_user(&mut app, cache);
 
     // Load UI configuration (pane sizes, etc.)
     let ui_config = cache.load_ui_config();
@@ -480,52 +1138,145 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
     if let Some(percent) = ui_config.overview_split_percent {
         app.overview_split_percent = percent;
     }
+    if let Some(show) = ui_config.show_weekends {
+        app.show_weekends = show;
+    }
+    if let Some(week_start) = ui_config.week_start.as_deref().and_then(util::WeekStart::from_name) {
+        app.week_start = week_start;
+    }
+    app.ascii = ascii || ui_config.ascii.unwrap_or(false);
+    app.grades_timeline = ui_config.grades_timeline.unwrap_or(false);
+    app.thread_newest_first = ui_config.thread_newest_first.unwrap_or(false);
+    app.thread_open_at_latest = ui_config.thread_open_at_latest.unwrap_or(true);
+    app.student_initials = ui_config.student_initials.unwrap_or(true);
+    app.exam_keywords = exam_keywords(cache);
+    app.absence_excuse_days = excuse_days(cache);
+    if let Some(count) = ui_config.overview_homework_count {
+        app.overview_homework_count = count.clamp(*OVERVIEW_HOMEWORK_COUNTS.start(), *OVERVIEW_HOMEWORK_COUNTS.end());
+    }
+    app.overview_grades_count = ui_config.overview_grades_count.filter(|&count| count > 0);
+    app.drafts = cache.load_drafts();
+    if let Some(ms) = ui_config.double_click_ms {
+        app.double_click_interval = std::time::Duration::from_millis(ms);
+    }
+    if let Some(minutes) = ui_config.keep_alive_minutes {
+        app.keep_alive_interval = (minutes > 0).then(|| std::time::Duration::from_secs(minutes * 60));
+    }
+    app.lang = lang
+        .or_else(|| ui_config.language.as_deref().and_then(i18n::Lang::from_code))
+        .or_else(i18n::Lang::from_env)
+        .unwrap_or_default();
+    cache.set_data_language(app.lang.api_code())?;
+    let (keymap, ignored_keys) = tui::keymap::KeyMap::from_config(&ui_config.keys.unwrap_or_default());
+    app.keymap = keymap;
+    if !ignored_keys.is_empty() {
+        app.set_error(format!("{} {}", T::ignored_key_bindings(app.lang), ignored_keys.join("; ")));
+    }
+    match ui_config.default_school_day_end.as_deref().map(models::parse_school_day_end) {
+        Some(Ok(end)) => app.school_day_end = end,
+        Some(Err(e)) => app.set_error(format!("{} {}", T::invalid_school_day_end(app.lang), e)),
+        None => {}
+    }
 
-    // Load cached data first
-    app.load_from_cache(cache).await;
+    Ok(app)
+}
 
-    // If no cached data, refresh
-    if app.students.is_empty() {
-        // Show loading state
-        app.loading = true;
-        app.set_status(T::loading_data(app.lang));
-        terminal.draw(|f| draw(f, &app))?;
+async fn run_tui_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut client: Option<ShkoloClient>,
+    cache: &CacheStore,
+    api_url: Option<&str>,
+    offline: bool,
+    lang: Option<i18n::Lang>,
+    ascii: bool,
+) -> Result<()> {
+    let mut app = configured_app(cache, offline, lang, ascii)?;
+    if let Some(client) = &client {
+        client.set_language(app.lang);
+    }
+    app.seen = cache.load_seen();
+    if let Some(client) = &client {
+        // Load cached data first
+        app.load_from_cache(cache).await;
+        app.track_new();
+
+        // Teacher accounts have no students to show; say so instead of an empty pane
+        if app.students.is_empty() && service::unsupported_role_error(cache).is_some() {
+            app.set_error(T::teacher_unsupported(app.lang));
+        }
 
-        if let Err(e) = app.refresh_data(&client, cache, false).await {
-            app.set_status(format!("{} {}", T::error_prefix(app.lang), e));
+        // If no cached data, refresh
+        if app.students.is_empty() && !client.is_offline() {
+            // Show loading state
+            app.loading = true;
+            app.set_status(T::loading_data(app.lang));
+            terminal.draw(|f| draw(f, &app))?;
+
+            // Only the first student blocks startup; the background refresh below loads the rest
+            if let Err(e) = app.refresh_data(client, cache, false, true).await {
+                app.set_status(format!("{} {}", T::error_prefix(app.lang), e));
+            }
+            app.track_new();
         }
+    } else {
+        app.require_login();
     }
 
     // Main loop - async event handling with background refresh
     let mut last_time_update = std::time::Instant::now();
     let mut last_auto_refresh = std::time::Instant::now();
+    let mut last_keep_alive = std::time::Instant::now();
+    // Signed out: when the token file was last looked for; None looks right away
+    let mut last_login_check: Option<std::time::Instant> = None;
     let mut event_stream = EventStream::new();
 
     // Type alias for background task
     type BackgroundTask = Pin<Box<dyn Future<Output = Result<BackgroundResult>> + Send>>;
+    // Keep-alive requests run on their own cadence, next to refreshes
+    let mut keep_alive_task: Option<Pin<Box<dyn Future<Output = Result<()>> + Send>>> = None;
+    // Fire-and-forget schedule prefetch for the days around the viewed one
+    let mut prefetch_task: Option<tokio::task::JoinHandle<()>> = None;
 
     // Initial refresh on startup
-    app.loading = true;
-    app.set_status(T::loading(app.lang));
-    let mut background_task: Option<BackgroundTask> = {
+    let mut background_task: Option<BackgroundTask> = None;
+    if let Some(client) = &client {
+        app.loading = true;
+        app.set_status(T::loading(app.lang));
         let client_clone = client.clone();
         let cache_clone = cache.clone();
         let student_ids: Vec<i64> = app.students.iter().map(|s| s.student.id).collect();
-        Some(Box::pin(async move {
+        background_task = Some(Box::pin(async move {
             refresh_data_background(&client_clone, &cache_clone, false, student_ids).await
-        }))
-    };
+        }));
+    }
 
     loop {
-        // Update time periodically for schedule highlighting (once per minute is enough)
-        if last_time_update.elapsed() >= Duration::from_secs(60) {
-            app.update_time();
-            last_time_update = std::time::Instant::now();
+        // Logging in happens in a terminal (the Settings rows say how); pick up the new token
+        if client.is_none() && last_login_check.is_none_or(|checked| checked.elapsed() >= Duration::from_secs(2)) {
+            last_login_check = Some(std::time::Instant::now());
+            if let Some(new_client) = signed_in_client(cache, api_url, offline) {
+                new_client.set_language(app.lang);
+                load_user(&mut app, cache);
+                app.logged_in();
+                let client_clone = new_client.clone();
+                let cache_clone = cache.clone();
+                background_task = Some(Box::pin(async move {
+                    refresh_data_background(&client_clone, &cache_clone, false, Vec::new()).await
+                }));
+                last_auto_refresh = std::time::Instant::now();
+                client = Some(new_client);
+            }
+        }
+
+        // Update time periodically for schedule highlighting (once per minute is enough)
+        if !app.background_paused() && last_time_update.elapsed() >= Duration::from_secs(60) {
+            app.update_time();
+            last_time_update = std::time::Instant::now();
         }
 
-        // Auto-refresh check
-        if background_task.is_none() {
-            if let Some(interval_minutes) = app.auto_refresh_interval.minutes() {
+        // Auto-refresh check, skipped while the window is in the background
+        if background_task.is_none() && !app.background_paused() {
+            if let (Some(interval_minutes), Some(client)) = (app.auto_refresh_interval.minutes(), &client) {
                 let interval_secs = interval_minutes * 60;
                 if last_auto_refresh.elapsed() >= Duration::from_secs(interval_secs) {
                     app.loading = true;
@@ -541,11 +1292,23 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
             }
         }
 
+        if keep_alive_task.is_none() && app.keep_alive_due(last_keep_alive.elapsed()) {
+            if let Some(client) = &client {
+                let client_clone = client.clone();
+                keep_alive_task = Some(Box::pin(async move { client_clone.keep_alive().await }));
+                last_keep_alive = std::time::Instant::now();
+            }
+        }
+
         // Tick for loading animation
         if app.loading {
             app.tick();
         }
 
+        // A count nothing followed turns back into a tab number
+        app.expire_count(std::time::Instant::now());
+
+        app.terminal_width = terminal.size()?.width;
         terminal.draw(|f| draw(f, &app))?;
 
         // Use tokio::select! to handle events and background tasks concurrently
@@ -565,18 +1328,33 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
             } => {
                 background_task = None;
                 app.loading = false;
+                app.offline = client.as_ref().is_some_and(ShkoloClient::is_offline);
 
                 if let Some(Ok(bg_result)) = result {
                     match bg_result {
-                        BackgroundResult::DataRefresh { students, notifications, messages } => {
+                        BackgroundResult::DataRefresh { students, notifications, messages, errors } => {
+                            let grade_notice = app.grade_change_notice(&students);
                             app.students = students;
-                            app.notifications = notifications;
-                            app.messages = messages;
-                            app.set_status("Refreshed");
+                            app.track_new();
+                            app.set_notifications(notifications);
+                            // The refresh fetches the default list; another folder keeps its threads
+                            if app.selected_folder.is_none() {
+                                app.messages = messages;
+                            }
+                            if !errors.is_empty() {
+                                app.set_status(format!("{} {}", T::incomplete_data(app.lang), errors.join("; ")));
+                            } else if let Some(notice) = grade_notice {
+                                app.set_status(notice);
+                            } else if app.offline {
+                                app.set_status(T::offline_cached(app.lang));
+                            } else {
+                                app.set_status(T::refreshed(app.lang));
+                            }
                         }
                         BackgroundResult::ScheduleRefresh { student_id, date, schedule } => {
                             // Update schedule for the specific student
                             if let Some(student_data) = app.students.iter_mut().find(|s| s.student.id == student_id) {
+                                student_data.schedule_status = output::LoadStatus::loaded(schedule.is_empty());
                                 student_data.schedule = schedule;
                             }
                             app.set_status(format!("{} {}", T::loaded(app.lang), date));
@@ -587,188 +1365,41 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
                 }
             }
 
+            // An expired session needs a new login; other failures wait for the next ping
+            result = async {
+                match &mut keep_alive_task {
+                    Some(task) => task.await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                keep_alive_task = None;
+                if let Err(e) = result {
+                    if api::client::is_session_expired(&e) {
+                        app.set_error(T::session_expired(app.lang));
+                    }
+                }
+            }
+
             // Tick for animation
             _ = tick_delay => {}
 
             // Handle terminal events
             maybe_event = event_stream.next() => {
                 if let Some(Ok(event)) = maybe_event {
-                    match event {
-                        Event::Key(key) => {
-                            let action = handle_key(&mut app, key);
-                            match action {
-                                Action::Refresh if background_task.is_none() => {
-                                    app.loading = true;
-                                    app.set_status("Refreshing...");
-                                    let client_clone = client.clone();
-                                    let cache_clone = cache.clone();
-                                    let student_ids: Vec<i64> = app.students.iter().map(|s| s.student.id).collect();
-                                    background_task = Some(Box::pin(async move {
-                                        refresh_data_background(&client_clone, &cache_clone, false, student_ids).await
-                                    }));
-                                    last_auto_refresh = std::time::Instant::now();
-                                }
-                                Action::RefreshAll if background_task.is_none() => {
-                                    app.loading = true;
-                                    app.set_status("Refreshing all...");
-                                    let client_clone = client.clone();
-                                    let cache_clone = cache.clone();
-                                    let student_ids: Vec<i64> = app.students.iter().map(|s| s.student.id).collect();
-                                    background_task = Some(Box::pin(async move {
-                                        refresh_data_background(&client_clone, &cache_clone, true, student_ids).await
-                                    }));
-                                    last_auto_refresh = std::time::Instant::now();
-                                }
-                                Action::RefreshSchedule if background_task.is_none() => {
-                                    app.loading = true;
-                                    let schedule_date = app.schedule_date.clone();
-                                    let student_id = app.current_student().map(|s| s.student.id);
-                                    app.set_status(format!("{} {}...", T::loading_base(app.lang), schedule_date));
-                                    if let Some(sid) = student_id {
-                                        let client_clone = client.clone();
-                                        let cache_clone = cache.clone();
-                                        background_task = Some(Box::pin(async move {
-                                            refresh_schedule_background(&client_clone, &cache_clone, sid, &schedule_date).await
-                                        }));
-                                    }
-                                }
-                                Action::Logout => {
-                                    // Clear token and exit
-                                    if let Err(e) = cache.clear_token() {
-                                        app.set_status(format!("{} {}", T::logout_error(app.lang), e));
-                                    } else {
-                                        app.set_status(T::logged_out(app.lang));
-                                        app.user_name = None;
-                                        // Exit after logout
-                                        app.quit();
-                                    }
-                                }
-                                Action::OpenThread(thread_id) => {
-                                    // Load thread messages
-                                    app.loading = true;
-                                    app.set_status(T::loading_thread(app.lang));
-                                    terminal.draw(|f| draw(f, &app))?;
-
-                                    match client.get_thread_messages(thread_id).await {
-                                        Ok(messages) => {
-                                            app.thread_messages = messages;
-                                            app.loading = false;
-                                            app.clear_status();
-                                        }
-                                        Err(e) => {
-                                            app.set_error(format!("{}\n{}", T::failed_load_thread(app.lang), e));
-                                            app.loading = false;
-                                            app.close_thread();
-                                        }
-                                    }
-                                }
-                                Action::CloseThread => {
-                                    // Already handled in app.close_thread()
-                                }
-                                Action::SendReply(message) => {
-                                    if let Some(thread_id) = app.selected_thread_id {
-                                        app.loading = true;
-                                        app.set_status(T::sending(app.lang));
-                                        terminal.draw(|f| draw(f, &app))?;
-
-                                        match client.reply_to_thread(thread_id, &message).await {
-                                            Ok(_) => {
-                                                // Reload thread messages
-                                                match client.get_thread_messages(thread_id).await {
-                                                    Ok(messages) => {
-                                                        app.thread_messages = messages;
-                                                        app.set_status(T::message_sent(app.lang));
-                                                    }
-                                                    Err(e) => {
-                                                        app.set_status(format!("{} {}", T::sent_reload_failed(app.lang), e));
-                                                    }
-                                                }
-                                            }
-                                            Err(e) => {
-                                                app.set_status(format!("{} {}", T::send_failed(app.lang), e));
-                                            }
-                                        }
-                                        app.loading = false;
-                                    }
-                                }
-                                Action::StartCompose => {
-                                    // Fetch recipients
-                                    app.loading = true;
-                                    app.set_status(T::loading_recipients(app.lang));
-                                    terminal.draw(|f| draw(f, &app))?;
-
-                                    match client.get_recipients().await {
-                                        Ok(recipients) => {
-                                            app.recipients = recipients;
-                                            app.loading = false;
-                                            app.clear_status();
-                                        }
-                                        Err(e) => {
-                                            app.set_status(format!("{} {}", T::error_prefix(app.lang), e));
-                                            app.loading = false;
-                                            app.cancel_compose();
-                                        }
-                                    }
-                                }
-                                Action::SendCompose { subject, body, recipients } => {
-                                    app.loading = true;
-                                    app.set_status(T::sending_message(app.lang));
-                                    terminal.draw(|f| draw(f, &app))?;
-
-                                    match client.create_thread(&recipients, &subject, &body).await {
-                                        Ok(_) => {
-                                            app.set_status(T::message_sent(app.lang));
-                                            // Refresh messages list
-                                            if let Ok(messages) = app.fetch_messages(&client).await {
-                                                app.messages = messages;
-                                            }
-                                        }
-                                        Err(e) => {
-                                            app.set_status(format!("{} {}", T::send_failed(app.lang), e));
-                                        }
-                                    }
-                                    app.loading = false;
-                                }
-                                Action::NavigateBack | Action::NavigateForward => {
-                                    // Navigation was handled in the handler, but we may need
-                                    // to reload thread messages if we navigated to a thread view
-                                    if app.message_view == tui::app::MessageView::Thread {
-                                        if let Some(thread_id) = app.selected_thread_id {
-                                            app.loading = true;
-                                            app.set_status(T::loading_thread(app.lang));
-                                            terminal.draw(|f| draw(f, &app))?;
-
-                                            match client.get_thread_messages(thread_id).await {
-                                                Ok(messages) => {
-                                                    app.thread_messages = messages;
-                                                    app.loading = false;
-                                                    app.clear_status();
-                                                }
-                                                Err(e) => {
-                                                    app.set_error(format!("{}\n{}", T::failed_load_thread(app.lang), e));
-                                                    app.loading = false;
-                                                    app.close_thread();
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                Action::None => {}
-                                // These are handled by guards above (when background_task.is_none())
-                                // If we get here, a background task is already running
-                                Action::Refresh | Action::RefreshAll | Action::RefreshSchedule => {
-                                    // Already refreshing, ignore
-                                }
-                            }
-                        }
+                    let action = match event {
+                        Event::Key(key) => handle_key(&mut app, key),
                         Event::Mouse(mouse) => {
+                            let mut action = Action::None;
                             match mouse.kind {
                                 // Mouse scroll wheel
-                                MouseEventKind::ScrollUp => {
-                                    app.scroll_up();
-                                }
-                                MouseEventKind::ScrollDown => {
-                                    app.scroll_down();
+                                MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                                    // Same layout as the click handling below
+                                    let content_bottom = terminal.size()?.height.saturating_sub(3);
+                                    if mouse.row >= 3 && mouse.row < content_bottom {
+                                        let down = mouse.kind == MouseEventKind::ScrollDown;
+                                        let content_height = content_bottom.saturating_sub(3);
+                                        app.scroll_at(down, mouse.row, 3, mouse.column, app.effective_students_width(), content_height);
+                                    }
                                 }
                                 // Mouse click / drag start
                                 MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
@@ -797,37 +1428,38 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
                                                 ClickResult::ActivateNotification(index) => {
                                                     app.activate_notification_at(index);
                                                 }
-                                                ClickResult::ActivateMessage(index) => {
-                                                    if let Some(thread_id) = app.open_thread_at(index) {
-                                                        // Load thread messages
-                                                        app.set_status(T::loading_thread(app.lang));
-                                                        match client.get_thread_messages(thread_id).await {
-                                                            Ok(messages) => {
-                                                                app.thread_messages = messages;
-                                                                app.clear_status();
-                                                            }
-                                                            Err(e) => {
-                                                                app.set_error(format!("{}\n{}", T::failed_load_thread(app.lang), e));
-                                                                app.close_thread();
-                                                            }
+                                                ClickResult::ActivateMessage(index) => match &client {
+                                                    Some(client) if client.is_offline() => {
+                                                        app.set_status(T::offline_unavailable(app.lang));
+                                                    }
+                                                    Some(client) => {
+                                                        if let Some(thread_id) = app.open_thread_at(index) {
+                                                            load_thread(terminal, &mut app, client, thread_id).await?;
                                                         }
                                                     }
+                                                    None => {}
+                                                },
+                                                ClickResult::Setting(entry) => {
+                                                    action = activate_setting(&mut app, entry);
                                                 }
-                                                ClickResult::ItemSelected(_) | ClickResult::StudentSelected | ClickResult::None => {}
+                                                ClickResult::Folder(folder) => {
+                                                    if app.select_folder(folder) {
+                                                        action = Action::SwitchFolder;
+                                                    }
+                                                }
+                                                ClickResult::Select(_) | ClickResult::StudentSelected | ClickResult::None => {}
                                             }
                                         }
                                     }
                                 }
                                 // Drag for split resizing
-                                MouseEventKind::Drag(crossterm::event::MouseButton::Left) => {
-                                    if app.is_dragging() {
-                                        let terminal_size = terminal.size()?;
-                                        let header_height = 3u16;
-                                        let footer_height = 3u16;
-                                        let content_height = terminal_size.height.saturating_sub(header_height + footer_height);
-                                        let content_area = (0, header_height, terminal_size.width, content_height);
-                                        app.update_drag(mouse.row, mouse.column, content_area);
-                                    }
+                                MouseEventKind::Drag(crossterm::event::MouseButton::Left) if app.is_dragging() => {
+                                    let terminal_size = terminal.size()?;
+                                    let header_height = 3u16;
+                                    let footer_height = 3u16;
+                                    let content_height = terminal_size.height.saturating_sub(header_height + footer_height);
+                                    let content_area = (0, header_height, terminal_size.width, content_height);
+                                    app.update_drag(mouse.row, mouse.column, content_area);
                                 }
                                 // End drag on mouse up
                                 MouseEventKind::Up(crossterm::event::MouseButton::Left) => {
@@ -835,13 +1467,291 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
                                 }
                                 _ => {}
                             }
+                            action
+                        }
+                        Event::Paste(text) => {
+                            app.input_paste(&text);
+                            Action::None
+                        }
+                        Event::FocusLost => {
+                            app.set_window_focused(false);
+                            Action::None
+                        }
+                        Event::FocusGained => {
+                            app.set_window_focused(true);
+                            last_time_update = std::time::Instant::now();
+                            Action::None
+                        }
+                        _ => Action::None,
+                    };
+                    let action = match action {
+                        // Signed out there is nothing to act with; a refresh looks for a token right away
+                        Action::Refresh | Action::RefreshAll if client.is_none() => {
+                            last_login_check = None;
+                            Action::None
+                        }
+                        action if client.is_none() && !matches!(action, Action::None | Action::ChangeLanguage | Action::NavigateBack | Action::NavigateForward) => {
+                            // Undo what the key started, like the offline guards below
+                            match action {
+                                Action::OpenThread(_) => {
+                                    app.go_back();
+                                }
+                                Action::StartCompose => app.cancel_compose(),
+                                _ => {}
+                            }
+                            app.set_status(T::login_required(app.lang));
+                            Action::None
+                        }
+                        Action::ChangeLanguage => {
+                            if let Some(client) = &client {
+                                client.set_language(app.lang);
+                            }
+                            // Cached names and labels are in the old language
+                            match cache.set_data_language(app.lang.api_code()) {
+                                Ok(true) => Action::RefreshAll,
+                                Ok(false) => Action::None,
+                                Err(e) => {
+                                    app.set_status(format!("{} {}", T::error_prefix(app.lang), e));
+                                    Action::None
+                                }
+                            }
+                        }
+                        action => action,
+                    };
+                    if let Some(client) = &client {
+                        match action {
+                            Action::Refresh if background_task.is_none() => {
+                                // Retry the network unless offline mode was forced
+                                client.set_offline(offline);
+                                app.loading = true;
+                                app.set_status(T::refreshing(app.lang));
+                                let client_clone = client.clone();
+                                let cache_clone = cache.clone();
+                                let student_ids: Vec<i64> = app.students.iter().map(|s| s.student.id).collect();
+                                background_task = Some(Box::pin(async move {
+                                    refresh_data_background(&client_clone, &cache_clone, false, student_ids).await
+                                }));
+                                last_auto_refresh = std::time::Instant::now();
+                            }
+                            Action::RefreshAll if background_task.is_none() => {
+                                // Retry the network unless offline mode was forced
+                                client.set_offline(offline);
+                                app.loading = true;
+                                app.set_status(T::refreshing_all(app.lang));
+                                let client_clone = client.clone();
+                                let cache_clone = cache.clone();
+                                let student_ids: Vec<i64> = app.students.iter().map(|s| s.student.id).collect();
+                                background_task = Some(Box::pin(async move {
+                                    refresh_data_background(&client_clone, &cache_clone, true, student_ids).await
+                                }));
+                                last_auto_refresh = std::time::Instant::now();
+                            }
+                            Action::RefreshSchedule if background_task.is_none() => {
+                                client.set_offline(offline);
+                                app.loading = true;
+                                let schedule_date = app.schedule_date.clone();
+                                let student_id = app.current_student().map(|s| s.student.id);
+                                app.set_status(format!("{} {}...", T::loading_base(app.lang), schedule_date));
+                                if let Some(sid) = student_id {
+                                    let client_clone = client.clone();
+                                    let cache_clone = cache.clone();
+                                    background_task = Some(Box::pin(async move {
+                                        refresh_schedule_background(&client_clone, &cache_clone, sid, &schedule_date).await
+                                    }));
+
+                                    // Warm the cache for the neighbouring days so `n`/`p` don't stall.
+                                    // Only the latest day's prefetch is kept, so holding a key doesn't pile up requests.
+                                    if let Some(task) = prefetch_task.take() {
+                                        task.abort();
+                                    }
+                                    if !client.is_offline() {
+                                        let client_clone = client.clone();
+                                        let cache_clone = cache.clone();
+                                        let dates = app.adjacent_schedule_days();
+                                        prefetch_task = Some(tokio::spawn(async move {
+                                            service::prefetch_schedules(&client_clone, &cache_clone, sid, &dates).await
+                                        }));
+                                    }
+                                }
+                            }
+                            Action::Logout => {
+                                // Clear token and exit
+                                if let Err(e) = cache.clear_token() {
+                                    app.set_status(format!("{} {}", T::logout_error(app.lang), e));
+                                } else {
+                                    app.set_status(T::logged_out(app.lang));
+                                    app.user_name = None;
+                                    // Exit after logout
+                                    app.quit();
+                                }
+                            }
+                            // Messaging needs the network - refuse cleanly and keep what the user typed
+                            Action::OpenThread(_) if client.is_offline() => {
+                                app.go_back();
+                                app.set_status(T::offline_unavailable(app.lang));
+                            }
+                            Action::SendReply(message) if client.is_offline() => {
+                                app.start_reply();
+                                app.input_cursor = message.chars().count();
+                                app.input_buffer = message;
+                                app.set_status(T::offline_unavailable(app.lang));
+                            }
+                            Action::StartCompose if client.is_offline() => {
+                                app.cancel_compose();
+                                app.set_status(T::offline_unavailable(app.lang));
+                            }
+                            Action::SendCompose { subject, body, recipients } if client.is_offline() => {
+                                app.start_compose();
+                                app.compose_subject = subject;
+                                app.selected_recipients = recipients;
+                                app.input_cursor = body.chars().count();
+                                app.input_buffer = body;
+                                app.input_mode = tui::app::InputMode::ComposeBody;
+                                app.set_status(T::offline_unavailable(app.lang));
+                            }
+                            Action::OpenThread(thread_id) => {
+                                load_thread(terminal, &mut app, client, thread_id).await?;
+                            }
+                            Action::CloseThread => {
+                                // Already handled in app.close_thread()
+                            }
+                            Action::SendReply(message) => {
+                                if let Some(thread_id) = app.selected_thread_id {
+                                    app.loading = true;
+                                    app.set_status(T::sending(app.lang));
+                                    terminal.draw(|f| draw(f, &app))?;
+
+                                    match client.reply_to_thread(thread_id, &message).await {
+                                        Ok(_) => {
+                                            app.remove_draft(Some(thread_id));
+                                            // Reload thread messages
+                                            match client.get_thread(thread_id).await {
+                                                Ok(thread) => {
+                                                    // Show the reply just sent
+                                                    app.set_thread(thread, true);
+                                                    app.set_status(T::message_sent(app.lang));
+                                                }
+                                                Err(e) => {
+                                                    app.set_status(format!("{} {}", T::sent_reload_failed(app.lang), e));
+                                                }
+                                            }
+                                        }
+                                        Err(e) => {
+                                            // Keep the text to retry with 'r'
+                                            app.drafts.set(Draft { thread_id: Some(thread_id), body: message, ..Default::default() });
+                                            app.drafts_changed = true;
+                                            app.set_status(format!("{} {}", T::send_failed(app.lang), e));
+                                        }
+                                    }
+                                    app.loading = false;
+                                }
+                            }
+                            Action::SwitchFolder => {
+                                app.loading = true;
+                                app.set_status(T::loading_messages(app.lang));
+                                terminal.draw(|f| draw(f, &app))?;
+
+                                match service::fetch_messages(client, cache, app.selected_folder, false).await {
+                                    Ok((messages, info)) => {
+                                        app.messages = messages;
+                                        app.messages_age = Some(info.as_of());
+                                        app.clear_status();
+                                    }
+                                    Err(e) => app.set_status(format!("{} {}", T::error_prefix(app.lang), e)),
+                                }
+                                app.loading = false;
+                            }
+                            Action::StartCompose | Action::ReloadRecipients => {
+                                let force = matches!(action, Action::ReloadRecipients);
+                                app.loading = true;
+                                app.set_status(T::loading_recipients(app.lang));
+                                terminal.draw(|f| draw(f, &app))?;
+                                app.load_recipients(client, cache, force).await;
+                            }
+                            Action::SendCompose { subject, body, recipients } => {
+                                app.loading = true;
+                                app.set_status(T::sending_message(app.lang));
+                                terminal.draw(|f| draw(f, &app))?;
+
+                                match client.create_thread(&recipients, &subject, &body).await {
+                                    Ok(_) => {
+                                        app.remove_draft(None);
+                                        app.set_status(T::message_sent(app.lang));
+                                        // Refresh messages list
+                                        if let Ok((messages, info)) = service::fetch_messages(client, cache, app.selected_folder, true).await {
+                                            app.messages = messages;
+                                            app.messages_age = Some(info.as_of());
+                                        }
+                                    }
+                                    Err(e) => {
+                                        // Keep the message to resume with 'd'
+                                        app.drafts.set(Draft { thread_id: None, subject, body, recipients });
+                                        app.drafts_changed = true;
+                                        app.set_status(format!("{} {}", T::send_failed(app.lang), e));
+                                    }
+                                }
+                                app.loading = false;
+                            }
+                            Action::NavigateBack | Action::NavigateForward => {
+                                // Navigation was handled in the handler, but we may need
+                                // to reload thread messages if we navigated to a thread view
+                                if app.message_view == tui::app::MessageView::Thread && client.is_offline() {
+                                    app.close_thread();
+                                    app.set_status(T::offline_unavailable(app.lang));
+                                } else if app.message_view == tui::app::MessageView::Thread {
+                                    if let Some(thread_id) = app.selected_thread_id {
+                                        app.loading = true;
+                                        app.set_status(T::loading_thread(app.lang));
+                                        terminal.draw(|f| draw(f, &app))?;
+
+                                        match client.get_thread(thread_id).await {
+                                            Ok(thread) => {
+                                                app.set_thread(thread, app.thread_open_at_latest);
+                                                app.loading = false;
+                                                app.clear_status();
+                                            }
+                                            Err(e) => {
+                                                app.set_error(format!("{}\n{}", T::failed_load_thread(app.lang), e));
+                                                app.loading = false;
+                                                app.close_thread();
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            // Mapped to a refresh before this match
+                            Action::None | Action::ChangeLanguage => {}
+                            // These are handled by guards above (when background_task.is_none())
+                            // If we get here, a background task is already running
+                            Action::Refresh | Action::RefreshAll | Action::RefreshSchedule => {
+                                // Already refreshing, ignore
+                            }
                         }
-                        _ => {}
                     }
                 }
             }
         } // end tokio::select!
 
+        // Folders are only needed for the Messages tab, so wait until it's opened
+        let messages_tab = app.current_tab == tui::app::Tab::Messages && !app.folders_requested;
+        if let Some(client) = client.as_ref().filter(|client| messages_tab && !client.is_offline()) {
+            app.folders_requested = true;
+            if let Ok(folders) = client.get_messenger_folders().await {
+                app.message_folders = folders;
+            }
+        }
+
+        if app.drafts_changed {
+            let _ = cache.save_drafts(&app.drafts);
+            app.drafts_changed = false;
+        }
+
+        app.update_seen(std::time::Instant::now());
+        if app.seen_changed {
+            let _ = cache.save_seen(&app.seen);
+            app.seen_changed = false;
+        }
+
         if !app.running {
             break;
         }
@@ -851,17 +1761,22 @@ async fn run_tui(cache: &CacheStore) -> Result<()> {
     let ui_config = cache::UiConfig {
         students_pane_width: Some(app.students_pane_width),
         overview_split_percent: Some(app.overview_split_percent),
+        show_weekends: Some(app.show_weekends),
+        week_start: Some(app.week_start.name().to_string()),
+        language: Some(app.lang.code().to_string()),
+        ascii: Some(app.ascii),
+        grades_timeline: Some(app.grades_timeline),
+        thread_newest_first: Some(app.thread_newest_first),
+        overview_homework_count: Some(app.overview_homework_count),
+        // Only edited by hand; keep what's there
+        ..cache.load_ui_config()
     };
     let _ = cache.save_ui_config(&ui_config);
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), DisableMouseCapture, LeaveAlternateScreen)?;
-
     Ok(())
 }
 
-fn import_token(cache: &CacheStore) -> Result<()> {
+async fn import_token(cache: &CacheStore, api_url: Option<&str>, no_verify: bool) -> Result<()> {
     let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
     let ios_path = home.join(IOS_APP_STORAGE);
 
@@ -890,23 +1805,61 @@ fn import_token(cache: &CacheStore) -> Result<()> {
     cache.save_token(token, None, Some(serde_json::json!({
         "names": user_name,
         "id": user_id,
-    })))?;
+    })), api_url)?;
 
-    println!("Token imported successfully!");
+    println!("{}", style::green("Token imported successfully!"));
     println!("User: {}", user_name);
     if let Some(id) = user_id {
         println!("User ID: {}", id);
     }
 
-    Ok(())
+    if no_verify {
+        return Ok(());
+    }
+    verify_imported_token(cache, api_url).await
+}
+
+/// One authenticated request with a just-imported token, so a stale one fails
+/// now rather than on the next command. Only an expired token is an error.
+async fn verify_imported_token(cache: &CacheStore, api_url: Option<&str>) -> Result<()> {
+    let client = get_authenticated_client(cache, api_url)?;
+    match client.get_pupils().await {
+        Ok(_) => {
+            println!("Token verified.");
+            Ok(())
+        }
+        Err(e) if api::client::is_session_expired(&e) => Err(anyhow!(
+            "The imported token has expired. Log in again in the Shkolo iOS app, then run 'shkolo import-token' again."
+        )),
+        Err(e) => {
+            eprintln!("{}", style::yellow(&format!("Warning: could not verify the token: {}", e)));
+            Ok(())
+        }
+    }
 }
 
-async fn login(cache: &CacheStore, username: Option<String>, password: Option<String>) -> Result<()> {
+async fn login(
+    cache: &CacheStore,
+    api_url: Option<&str>,
+    username: Option<String>,
+    password: Option<String>,
+    print_token: bool,
+    quiet: bool,
+) -> Result<()> {
+    // With --print-token stdout carries only the JSON, so prompts go to stderr
+    let prompt = |text: &str| -> io::Result<()> {
+        if print_token {
+            eprint!("{}", text);
+            io::stderr().flush()
+        } else {
+            print!("{}", text);
+            io::stdout().flush()
+        }
+    };
     let username = match username {
         Some(u) => u,
         None => {
-            print!("Username: ");
-            io::stdout().flush()?;
+            prompt("Username: ")?;
             let mut input = String::new();
             io::stdin().read_line(&mut input)?;
             input.trim().to_string()
@@ -916,24 +1869,33 @@ async fn login(cache: &CacheStore, username: Option<String>, password: Option<St
     let password = match password {
         Some(p) => p,
         None => {
-            print!("Password: ");
-            io::stdout().flush()?;
+            prompt("Password: ")?;
             rpassword::read_password()?
         }
     };
 
-    let mut client = ShkoloClient::new();
+    let mut client = new_client(api_url);
     let response = client.login(&username, &password).await?;
 
+    if print_token {
+        if !quiet {
+            eprintln!("{}", style::yellow("Warning: the token below gives full access to the account and was not saved. Keep it secret."));
+        }
+        let token = client.token().ok_or_else(|| anyhow!("The login response had no token"))?;
+        println!("{}", token_json(token, client.school_year()));
+        return Ok(());
+    }
+
     // Save token
     let user_data = serde_json::to_value(&response)?;
     cache.save_token(
         client.token().unwrap(),
         client.school_year(),
         Some(user_data.clone()),
+        api_url,
     )?;
 
-    println!("Logged in successfully!");
+    println!("{}", style::green("Logged in successfully!"));
 
     if let Some(users) = response.users {
         for user in users {
@@ -954,11 +1916,16 @@ async fn login(cache: &CacheStore, username: Option<String>, password: Option<St
     Ok(())
 }
 
-async fn login_google(cache: &CacheStore, token: Option<String>) -> Result<()> {
+/// What `login --print-token` prints for other programs to reuse
+fn token_json(token: &str, school_year: Option<i64>) -> serde_json::Value {
+    serde_json::json!({ "token": token, "school_year": school_year })
+}
+
+async fn login_google(cache: &CacheStore, api_url: Option<&str>, token: Option<String>) -> Result<()> {
     let id_token = match token {
         Some(t) => t,
         None => {
-            println!("Google OAuth Login");
+            println!("{}", style::bold("Google OAuth Login"));
             println!("==================");
             println!();
             println!("To login with Google, you need to obtain an ID token.");
@@ -982,7 +1949,7 @@ async fn login_google(cache: &CacheStore, token: Option<String>) -> Result<()> {
         return Err(anyhow!("No token provided"));
     }
 
-    let mut client = ShkoloClient::new();
+    let mut client = new_client(api_url);
     let response = client.login_google(&id_token).await?;
 
     // Save token
@@ -991,29 +1958,147 @@ async fn login_google(cache: &CacheStore, token: Option<String>) -> Result<()> {
         client.token().unwrap(),
         client.school_year(),
         Some(user_data),
+        api_url,
     )?;
 
-    println!("Logged in with Google successfully!");
+    println!("{}", style::green("Logged in with Google successfully!"));
 
     Ok(())
 }
 
-async fn logout(cache: &CacheStore) -> Result<()> {
-    if let Ok(token_data) = cache.load_token() {
-        let mut client = ShkoloClient::with_token(token_data.token, token_data.school_year);
+async fn logout(cache: &CacheStore, api_url: Option<&str>) -> Result<()> {
+    if let Ok(mut client) = get_authenticated_client(cache, api_url) {
         let _ = client.logout().await;
     }
 
     cache.clear_token()?;
-    println!("Logged out successfully!");
+    println!("{}", style::green("Logged out successfully!"));
 
     Ok(())
 }
 
-fn show_status(cache: &CacheStore) -> Result<()> {
+/// Where the store lives; the config directory only when it's a separate one
+fn print_dirs(cache: &CacheStore) {
+    println!("Cache directory: {}", cache.cache_dir().display());
+    if cache.config_dir() != cache.cache_dir() {
+        println!("Config directory: {}", cache.config_dir().display());
+    }
+}
+
+/// Shortest time between polls of `notifications --follow` without --cache-ttl
+const MIN_FOLLOW_INTERVAL_SECS: u64 = 60;
+
+/// Poll interval for `notifications --follow`: `--interval`, but never more
+/// often than `--cache-ttl` (or `MIN_FOLLOW_INTERVAL_SECS`) allows
+fn follow_interval(interval: u64, cache_ttl: Option<i64>) -> Duration {
+    let floor = cache_ttl.map_or(MIN_FOLLOW_INTERVAL_SECS, |ttl| ttl.max(1) as u64);
+    Duration::from_secs(interval.max(floor))
+}
+
+/// Identity of a notification for --follow: its id, else its time and title
+fn notification_key(notification: &Notification) -> String {
+    match &notification.id {
+        Some(id) => id.clone(),
+        None => format!("{}|{}", notification.date, notification.title),
+    }
+}
+
+/// The notifications not in `seen`, oldest first; they are added to it
+fn fresh_notifications(seen: &mut HashSet<String>, notifications: Vec<Notification>) -> Vec<Notification> {
+    let mut fresh: Vec<Notification> = notifications.into_iter()
+        .filter(|n| seen.insert(notification_key(n)))
+        .collect();
+    fresh.sort_by(|a, b| a.date.cmp(&b.date));
+    fresh
+}
+
+/// "2026-02-17 12:30  Мария Иванова: Нова оценка - Отличен 6"
+fn notification_line(notification: &Notification) -> String {
+    let date = notification.date.get(..16).unwrap_or(&notification.date);
+    let mut line = match &notification.pupil_names {
+        Some(names) => format!("{}  {}: {}", date, names, style::bold(&notification.title)),
+        None => format!("{}  {}", date, style::bold(&notification.title)),
+    };
+    if let Some(body) = notification.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        line.push_str(" - ");
+        line.push_str(&body.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+    line
+}
+
+fn print_notifications(notifications: &[Notification], json: bool) -> Result<()> {
+    for notification in notifications {
+        if json {
+            println!("{}", serde_json::to_string(notification)?);
+        } else {
+            println!("{}", notification_line(notification));
+        }
+    }
+    Ok(())
+}
+
+/// `notifications --follow`: the notifications there at the start only fill
+/// the seen set; after that every poll prints the ones that arrived since.
+/// Network errors are reported and retried at the next poll; an expired
+/// session ends the command.
+async fn follow_notifications(client: &ShkoloClient, cache: &CacheStore, json: bool, interval: Duration) -> Result<()> {
+    let mut seen = HashSet::new();
+    let mut first = true;
+    let metrics = client.metrics();
+    loop {
+        let started = std::time::Instant::now();
+        let response = tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            response = client.get_notifications(1) => response,
+        };
+        metrics.record_refresh(started.elapsed(), response.is_ok());
+        match response {
+            Ok(response) => {
+                let notifications = response.notifications();
+                let _ = cache.save_notifications(&notifications);
+                let fresh = fresh_notifications(&mut seen, notifications);
+                if first {
+                    eprintln!("Waiting for new notifications, checking every {}s (Ctrl+C to stop)", interval.as_secs());
+                    first = false;
+                } else {
+                    print_notifications(&fresh, json)?;
+                }
+            }
+            Err(e) if api::client::is_session_expired(&e) => return Err(e),
+            Err(e) => {
+                eprintln!("{}", style::yellow(&e.to_string()));
+                // A dropped connection marks the client offline; try again next time
+                client.set_offline(false);
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
+/// Every `interval`, print the metrics as one JSON line on stderr, in the background
+fn print_stats(metrics: Arc<metrics::Metrics>, cache: Arc<cache::CacheStats>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        // The first tick is immediate, with nothing counted yet
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            if let Ok(line) = serde_json::to_string(&metrics.snapshot(&cache)) {
+                eprintln!("{}", line);
+            }
+        }
+    });
+}
+
+fn show_status(cache: &CacheStore, api_url: Option<&str>) -> Result<()> {
+    let client = get_authenticated_client(cache, api_url).unwrap_or_else(|_| new_client(api_url));
     match cache.load_token() {
         Ok(token_data) => {
-            println!("Status: Authenticated");
+            println!("Status: {}", style::green("Authenticated"));
 
             if let Some(user_data) = token_data.user_data {
                 if let Some(name) = user_data.get("names").and_then(|v| v.as_str()) {
@@ -1032,12 +2117,17 @@ fn show_status(cache: &CacheStore) -> Result<()> {
                 println!("School Year ID: {}", year);
             }
 
+            println!("API: {}", client.base_url());
+            println!("User-Agent: {}", client.user_agent());
+
             println!();
-            println!("Cache directory: {}", cache.cache_dir().display());
+            print_dirs(cache);
             println!("Cache TTL: {} seconds", cache.ttl());
         }
         Err(_) => {
-            println!("Status: Not authenticated");
+            println!("Status: {}", style::yellow("Not authenticated"));
+            println!("API: {}", client.base_url());
+            println!("User-Agent: {}", client.user_agent());
             println!();
             println!("Run 'shkolo login' or 'shkolo import-token' to authenticate");
         }
@@ -1046,376 +2136,486 @@ fn show_status(cache: &CacheStore) -> Result<()> {
     Ok(())
 }
 
-async fn cache_command(cache: &CacheStore, clear: bool, clear_all: bool, refresh: bool) -> Result<()> {
-    if clear_all {
-        cache.clear_all()?;
-        println!("All cache cleared (including token)");
-    } else if clear {
-        cache.clear()?;
-        println!("Cache cleared (token preserved)");
+/// Remove the files --clear, --clear-all and --clear-type select. Returns them
+/// (only listed with --dry-run), or None when the confirmation was declined.
+/// With `json` nothing is printed on stdout.
+#[allow(clippy::too_many_arguments)]
+fn clear_cache_files(
+    cache: &CacheStore,
+    clear: bool,
+    clear_all: bool,
+    clear_type: Option<cache::CacheKind>,
+    student: Option<i64>,
+    dry_run: bool,
+    yes: bool,
+    json: bool,
+) -> Result<Option<Vec<PathBuf>>> {
+    let mut files = Vec::new();
+    if let Some(kind) = clear_type {
+        files.extend(cache.type_files(kind, student)?);
     }
+    if clear || clear_all {
+        files.extend(cache.clear_files(clear_all)?);
+    }
+    files.sort();
+    files.dedup();
 
-    if refresh {
-        let client = get_authenticated_client(cache)?;
+    if !json {
+        if files.is_empty() {
+            println!("No cache files to remove");
+            return Ok(Some(files));
+        }
+        println!("{}", if dry_run { "Would remove:" } else { "Files to remove:" });
+        for path in &files {
+            println!("  {}", path.display());
+        }
+    }
+    if dry_run || files.is_empty() {
+        return Ok(Some(files));
+    }
 
-        println!("Refreshing all data...");
+    // --clear-all logs you out, so don't let it happen by accident
+    if clear_all && !yes {
+        if !io::stdin().is_terminal() {
+            return Err(anyhow!("--clear-all also removes the login token; pass --yes to confirm"));
+        }
+        // Keep stdout for the JSON
+        let question = format!("Remove {} file(s), including the login token? [y/N] ", files.len());
+        if json {
+            eprint!("{}", question);
+        } else {
+            print!("{}", question);
+            io::stdout().flush()?;
+        }
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            eprintln!("{}", style::yellow("Aborted"));
+            return Ok(None);
+        }
+    }
 
-        // Get students
-        let pupils_response = client.get_pupils().await?;
-        let mut students = Vec::new();
+    CacheStore::remove_files(&files)?;
+    if json {
+        // Reported by the caller
+    } else if clear_all {
+        println!("{}", style::green("All cache cleared (including token)"));
+    } else if clear {
+        println!("{}", style::green("Cache cleared (token preserved)"));
+    } else if let Some(kind) = clear_type {
+        println!("Removed {} cached {} file(s)", files.len(), kind.name());
+    }
+    Ok(Some(files))
+}
 
-        if let Some(child_pupils) = pupils_response.child_pupils {
-            for (id, pupil) in child_pupils {
-                students.push(Student::from_child_pupil(&id, &pupil));
-            }
+/// `cache --json`: what the command did, as one object
+#[derive(Debug, serde::Serialize)]
+struct CacheReport {
+    cache_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config_dir: Option<String>,
+    ttl: i64,
+    /// Files removed by --clear, --clear-all or --clear-type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cleared: Option<usize>,
+    /// With --dry-run, the files a clear would remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    would_remove: Option<Vec<String>>,
+    /// The --clear-all confirmation was declined; nothing was removed
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    aborted: bool,
+    /// Students refreshed by --refresh
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refreshed: Option<usize>,
+    /// What --refresh failed to fetch
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+    /// Cached files, with --info or when nothing else was asked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<cache::CacheEntry>>,
+}
+
+impl CacheReport {
+    fn new(cache: &CacheStore) -> Self {
+        Self {
+            cache_dir: cache.cache_dir().display().to_string(),
+            config_dir: (cache.config_dir() != cache.cache_dir()).then(|| cache.config_dir().display().to_string()),
+            ttl: cache.ttl(),
+            cleared: None,
+            would_remove: None,
+            aborted: false,
+            refreshed: None,
+            errors: Vec::new(),
+            files: None,
         }
+    }
+}
 
-        cache.save_students(&students)?;
-        println!("  Refreshed {} students", students.len());
+/// The rest of `cache`, after any clearing. With a `report` (--json) the
+/// outcome is printed as JSON at the end instead of as text.
+#[allow(clippy::too_many_arguments)]
+async fn cache_command(cache: &CacheStore, api_url: Option<&str>, cleared: bool, refresh: bool, info: bool, verbose: bool, mut report: Option<CacheReport>) -> Result<()> {
+    let json = report.is_some();
+    if refresh {
+        let client = get_authenticated_client(cache, api_url)?;
+        let mut failures = Vec::new();
+        let mut failed = |failure: String| {
+            if json {
+                failures.push(failure);
+            } else {
+                eprintln!("  Failed to refresh {}", failure);
+            }
+        };
 
-        let today = get_today_date();
+        if !json {
+            println!("Refreshing all data...");
+        }
 
-        for student in &students {
-            // Refresh homework
-            if let Ok(courses_response) = client.get_homework_courses(student.id).await {
-                let mut homework = Vec::new();
-                if let Some(courses) = courses_response.courses {
-                    let counts = courses_response.cyc_group_homeworks_count.unwrap_or_default();
-                    for course in courses {
-                        if let Some(cyc_group_id) = course.cyc_group_id {
-                            if counts.get(&cyc_group_id.to_string()).copied().unwrap_or(0) == 0 {
-                                continue;
-                            }
-                            let subject = course.course_short_name.or(course.course_name).unwrap_or_default();
-                            if let Ok(hw_response) = client.get_homework_list(cyc_group_id).await {
-                                if let Some(items) = hw_response.homeworks {
-                                    for item in items {
-                                        homework.push(Homework::from_item(&item, &subject));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                homework.sort_by(|a, b| b.date_sort.cmp(&a.date_sort));
-                cache.save_homework(student.id, &homework)?;
-            }
+        let (students, _) = service::fetch_students(&client, cache, true).await?;
+        let count = students.len();
+        if !json {
+            println!("  Refreshed {} students", count);
+        }
 
-            // Refresh grades
-            if let Ok(grades_response) = client.get_grades_summary(student.id).await {
-                let courses = grades_response.grades.or(grades_response.courses).unwrap_or_default();
-                let grades: Vec<_> = courses.iter()
-                    .map(Grade::from_course_grades)
-                    .filter(|g| g.has_grades())
-                    .collect();
-                cache.save_grades(student.id, &grades)?;
+        let today = util::today_local();
+        for student in students {
+            let name = student.name.clone();
+            let (_, errors) = StudentData::fetch(&client, cache, student, &today, true).await;
+            errors.into_iter().for_each(&mut failed);
+            if !json {
+                println!("  Refreshed data for {}", name);
             }
+        }
 
-            // Refresh schedule
-            if let Ok(schedule_response) = client.get_pupil_schedule(student.id, &today).await {
-                let hours = schedule_response.schedule_hours.or(schedule_response.data).unwrap_or_default();
-                let mut schedule: Vec<_> = hours.iter().map(ScheduleHour::from_raw).collect();
-                schedule.sort_by_key(|h| h.hour_number);
-                cache.save_schedule(student.id, &today, &schedule)?;
-            }
+        if let Err(e) = service::fetch_notifications(&client, cache, true).await {
+            failed(format!("notifications: {}", e));
+        }
+        if let Err(e) = service::fetch_messages(&client, cache, None, true).await {
+            failed(format!("messages: {}", e));
+        }
 
-            println!("  Refreshed data for {}", student.name);
+        match &mut report {
+            Some(report) => {
+                report.refreshed = Some(count);
+                report.errors = failures;
+            }
+            None => println!("{}", style::green("All data refreshed!")),
         }
+    }
 
-        println!("All data refreshed!");
+    if let Some(mut report) = report {
+        if info || (!cleared && !refresh) {
+            report.files = Some(cache.list_entries()?);
+        }
+        return output_json(&report, OutputFormat::Pretty);
     }
 
-    if !clear && !clear_all && !refresh {
-        println!("Cache directory: {}", cache.cache_dir().display());
+    if info {
+        cache_info(cache, verbose);
+    } else if !cleared && !refresh {
+        print_dirs(cache);
         println!("Cache TTL: {} seconds", cache.ttl());
         println!();
         println!("Options:");
-        println!("  --clear     Clear cache (preserves token)");
-        println!("  --clear-all Clear all cache including token");
-        println!("  --refresh   Force refresh all data");
+        println!("  --clear          Clear cache (preserves token)");
+        println!("  --clear-all      Clear all cache including token");
+        println!("  --clear-type T   Clear one data type, optionally with --student ID");
+        println!("  --dry-run        List the files a clear would remove");
+        println!("  --yes            Skip the --clear-all confirmation");
+        println!("  --refresh        Force refresh all data");
+        println!("  --info           Show cached entries");
+        println!("  --info --verbose Also show cache hit/miss/expiry counts");
     }
 
     Ok(())
 }
 
-fn get_authenticated_client(cache: &CacheStore) -> Result<ShkoloClient> {
-    let token_data = cache.load_token()
-        .map_err(|_| anyhow!("Not authenticated. Run 'shkolo login' or 'shkolo import-token' first."))?;
-
-    Ok(ShkoloClient::with_token(token_data.token, token_data.school_year))
-}
-
-async fn get_students(
-    client: &ShkoloClient,
-    cache: &CacheStore,
-    force_refresh: bool,
-) -> Result<(Vec<Student>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((students, age, expired)) = cache.get_students() {
-            if !expired {
-                return Ok((students, true, Some(age)));
-            }
+/// Print the state of every cached entry the app would look up
+fn cache_info(cache: &CacheStore, verbose: bool) {
+    fn entry<T>(label: &str, found: Option<(Vec<T>, String, bool, i64)>) {
+        match found {
+            Some((items, age, expired, _)) => println!(
+                "  {:<24} {:>4} items, {}{}",
+                label,
+                items.len(),
+                age,
+                if expired { " (expired)" } else { "" }
+            ),
+            None => println!("  {:<24} not cached", label),
         }
     }
 
-    // Fetch from API
-    let pupils_response = client.get_pupils().await?;
+    print_dirs(cache);
+    println!("Cache TTL: {} seconds", cache.ttl());
+    println!();
+
+    let students = cache.get_students();
+    let today = util::today_local();
+    println!("{}", style::bold("Entries:"));
+    entry("students", students.clone());
+    entry("notifications", cache.get_notifications());
+    entry("messages", cache.get_messages(None));
+    for student in students.map(|(s, _, _, _)| s).unwrap_or_default() {
+        println!("  {}:", student.name);
+        entry("  homework", cache.get_homework(student.id));
+        entry("  grades", cache.get_grades(student.id));
+        entry(&format!("  schedule {}", today), cache.get_schedule(student.id, &today));
+        entry("  events", cache.get_events(student.id));
+        entry("  absences", cache.get_absences(student.id));
+        entry("  feedbacks", cache.get_feedbacks(student.id));
+    }
 
-    let mut students = Vec::new();
-    if let Some(child_pupils) = pupils_response.child_pupils {
-        for (id, pupil) in child_pupils {
-            students.push(Student::from_child_pupil(&id, &pupil));
+    if verbose {
+        println!();
+        println!("{}", style::bold("Lookups:"));
+        println!("  {:<14} {:>6} {:>6} {:>8}", "type", "hits", "misses", "expired");
+        for (kind, counts) in cache.stats() {
+            println!("  {:<14} {:>6} {:>6} {:>8}", kind.name(), counts.hits, counts.misses, counts.expired);
         }
     }
-
-    students.sort_by(|a, b| a.name.cmp(&b.name));
-    cache.save_students(&students)?;
-
-    Ok((students, false, None))
 }
 
-async fn get_homework(
-    client: &ShkoloClient,
-    cache: &CacheStore,
-    student_id: i64,
-    force_refresh: bool,
-) -> Result<(Vec<Homework>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((homework, age, expired)) = cache.get_homework(student_id) {
-            if !expired {
-                return Ok((homework, true, Some(age)));
-            }
-        }
+/// Client for the --api-url override if there is one, otherwise the default host
+fn new_client(api_url: Option<&str>) -> ShkoloClient {
+    let client = ShkoloClient::new();
+    match api_url {
+        Some(url) => client.with_base_url(url),
+        None => client,
     }
+}
 
-    // Fetch from API
-    let courses_response = client.get_homework_courses(student_id).await?;
-
-    let mut all_homework = Vec::new();
-
-    if let Some(courses) = courses_response.courses {
-        let counts = courses_response.cyc_group_homeworks_count.unwrap_or_default();
-
-        for course in courses {
-            if let Some(cyc_group_id) = course.cyc_group_id {
-                let count = counts.get(&cyc_group_id.to_string()).copied().unwrap_or(0);
-                if count == 0 {
-                    continue;
-                }
-
-                let subject = course.course_short_name
-                    .or(course.course_name)
-                    .unwrap_or_else(|| "Unknown".to_string());
+/// Logged-in client for a data command: offline if asked, and speaking the
+/// --lang (or environment) language, with the cache switched to that language's data
+fn command_client(cache: &CacheStore, api_url: Option<&str>, offline: bool, lang: Option<i18n::Lang>) -> Result<(ShkoloClient, i18n::Lang)> {
+    let client = get_authenticated_client(cache, api_url)?;
+    client.set_offline(offline);
+    let lang = lang.or_else(i18n::Lang::from_env).unwrap_or_default();
+    client.set_language(lang);
+    cache.set_data_language(lang.api_code())?;
+    Ok((client, lang))
+}
 
-                if let Ok(hw_response) = client.get_homework_list(cyc_group_id).await {
-                    if let Some(homeworks) = hw_response.homeworks {
-                        for item in homeworks {
-                            all_homework.push(Homework::from_item(&item, &subject));
-                        }
-                    }
-                }
-            }
-        }
+/// `json --school-year`: a client asking for that year instead of the one saved at
+/// login, and a store keeping its data apart from the current year's
+fn school_year_scope(client: ShkoloClient, cache: &CacheStore, year: Option<i64>) -> Result<(ShkoloClient, CacheStore)> {
+    match year {
+        Some(year) => Ok((client.with_school_year(year), cache.for_school_year(year)?)),
+        None => Ok((client, cache.clone())),
     }
+}
 
-    all_homework.sort_by(|a, b| b.date_sort.cmp(&a.date_sort));
-    cache.save_homework(student_id, &all_homework)?;
+/// Logged-in client. An --api-url or environment override wins over the host saved at login.
+fn get_authenticated_client(cache: &CacheStore, api_url: Option<&str>) -> Result<ShkoloClient> {
+    let token_data = cache.load_token()
+        .map_err(|_| anyhow!("Not authenticated. Run 'shkolo login' or 'shkolo import-token' first."))?;
 
-    Ok((all_homework, false, None))
+    let client = ShkoloClient::with_token(token_data.token, token_data.school_year);
+    Ok(match api_url.or(token_data.api_url.as_deref()) {
+        Some(url) => client.with_base_url(url),
+        None => client,
+    })
 }
 
-async fn get_grades(
-    client: &ShkoloClient,
-    cache: &CacheStore,
-    student_id: i64,
-    force_refresh: bool,
-) -> Result<(Vec<Grade>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((grades, age, expired)) = cache.get_grades(student_id) {
-            if !expired {
-                return Ok((grades, true, Some(age)));
-            }
+/// `export-absences-csv`: one student's absences per subject and term
+async fn export_absences_csv(client: &ShkoloClient, cache: &CacheStore, selector: &str, out: Option<&Path>, force: bool) -> Result<()> {
+    let (students, _) = service::fetch_students(client, cache, force).await?;
+    let student = match service::find_students(&students, Some(selector)).as_deref() {
+        Some([student]) => *student,
+        Some(several) => {
+            let names: Vec<_> = several.iter().map(|s| s.name.as_str()).collect();
+            return Err(anyhow!("\"{}\" matches several students: {}", selector, names.join(", ")));
         }
+        None => return Err(anyhow!("No student matches \"{}\"", selector)),
+    };
+    let (absences, _) = service::fetch_absences(client, cache, student.id, force).await?;
+    let boundary = models::term_boundary(util::now_local().date());
+    let csv = output::absences_csv(&models::absence_summary(&absences, boundary));
+    match out {
+        Some(path) => {
+            std::fs::write(path, csv).map_err(|e| anyhow!("Could not write {}: {}", path.display(), e))?;
+            eprintln!("Wrote {}'s absences to {}", student.name, path.display());
+        }
+        None => print!("{}", csv),
     }
-
-    // Fetch from API
-    let response = client.get_grades_summary(student_id).await?;
-
-    let courses = response.grades.or(response.courses).unwrap_or_default();
-    let grades: Vec<Grade> = courses
-        .iter()
-        .map(Grade::from_course_grades)
-        .filter(|g| g.has_grades())
-        .collect();
-
-    cache.save_grades(student_id, &grades)?;
-
-    Ok((grades, false, None))
+    Ok(())
 }
 
-async fn get_schedule(
-    client: &ShkoloClient,
-    cache: &CacheStore,
-    student_id: i64,
-    date: &str,
-    force_refresh: bool,
-) -> Result<(Vec<ScheduleHour>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((schedule, age, expired)) = cache.get_schedule(student_id, date) {
-            if !expired {
-                return Ok((schedule, true, Some(age)));
-            }
+/// `agenda`: homework and events of the selected students merged by day.
+/// A student whose data can't be loaded is reported without stopping the rest.
+async fn print_agenda(client: &ShkoloClient, cache: &CacheStore, selector: Option<&str>, days: u32, json: bool, force: bool) -> Result<()> {
+    let started = std::time::Instant::now();
+    let today = util::now_local().date();
+    let keywords = exam_keywords(cache);
+    let (students, _) = service::fetch_students(client, cache, force).await?;
+
+    let mut agendas = Vec::new();
+    let mut failures = Vec::new();
+    for s in select_students(&students, selector) {
+        let entry = async {
+            let (homework, homework_info, _) = service::fetch_homework(client, cache, s.id, force).await?;
+            let (events, events_info) = service::fetch_events(client, cache, s.id, force).await?;
+            let parts = [
+                output::CacheState::from(homework_info),
+                output::CacheState::from(events_info),
+            ];
+            Ok::<_, anyhow::Error>(output::StudentAgenda {
+                student: s.clone(),
+                cache: output::CacheState::merge(&parts),
+                days: models::agenda(&homework, &events, &keywords, today, days),
+            })
+        }.await;
+        match entry {
+            Ok(entry) => agendas.push(entry),
+            Err(e) => failures.push(service::student_error(s, e)),
         }
     }
 
-    // Fetch from API
-    let response = client.get_pupil_schedule(student_id, date).await?;
-
-    let hours = response.schedule_hours.or(response.data).unwrap_or_default();
-    let mut schedule: Vec<ScheduleHour> = hours.iter().map(ScheduleHour::from_raw).collect();
-    schedule.sort_by_key(|h| h.hour_number);
-
-    cache.save_schedule(student_id, date, &schedule)?;
-
-    Ok((schedule, false, None))
+    if json {
+        let state = output::CacheState::merge(agendas.iter().map(|e| &e.cache));
+        return output_students(api::ApiResponse::new(agendas, state.cached, state.cached_at), failures, client, started, OutputFormat::Pretty);
+    }
+    print!("{}", output::agenda_text(&agendas, style::enabled()));
+    for failure in &failures {
+        eprintln!("{}: {}", failure.student, failure.error);
+    }
+    if agendas.is_empty() && !failures.is_empty() {
+        return Err(anyhow!("Could not load data for any student"));
+    }
+    Ok(())
 }
 
-async fn get_absences(
+/// `report`: the period of each selected student. Cached data is used while
+/// fresh; a category that can't be loaded is marked as such in the report.
+async fn print_period_report(
     client: &ShkoloClient,
     cache: &CacheStore,
-    student_id: i64,
-    force_refresh: bool,
-) -> Result<(Vec<Absence>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((absences, age, expired)) = cache.get_absences(student_id) {
-            if !expired {
-                return Ok((absences, true, Some(age)));
+    selector: Option<&str>,
+    period: period_report::Period,
+    format: period_report::ReportFormat,
+    lang: i18n::Lang,
+    force: bool,
+) -> Result<()> {
+    let today = util::now_local().date();
+    let range = period_report::DateRange::of(period, today);
+    let (students, _) = service::fetch_students(client, cache, force).await?;
+    let notifications = service::fetch_notifications(client, cache, force).await.ok().map(|(n, _)| n);
+
+    let mut reports = Vec::new();
+    for s in select_students(&students, selector) {
+        let homework = service::fetch_homework(client, cache, s.id, force).await.ok().map(|(d, _, _)| d);
+        let absences = service::fetch_absences(client, cache, s.id, force).await.ok().map(|(d, _)| d);
+        let feedbacks = service::fetch_feedbacks(client, cache, s.id, force).await.ok().map(|(d, _)| d);
+        let mut schedules = Some(Vec::new());
+        for day in range.school_days(today) {
+            let Some(loaded) = schedules.as_mut() else { break };
+            match service::fetch_schedule(client, cache, s.id, &day.to_string(), force).await {
+                Ok((hours, _)) => loaded.push((day, hours)),
+                Err(_) => schedules = None,
             }
         }
+        reports.push(period_report::PeriodReport::new(
+            s.clone(),
+            range,
+            notifications.as_deref(),
+            homework.as_deref(),
+            absences,
+            feedbacks,
+            schedules,
+        ));
     }
 
-    // Fetch from API
-    let response = client.get_absences(student_id).await?;
-
-    let mut absences: Vec<Absence> = response.absences
-        .unwrap_or_default()
-        .iter()
-        .map(Absence::from_raw)
-        .collect();
-
-    // Sort by date (newest first)
-    absences.sort_by(|a, b| {
-        b.date_sort.cmp(&a.date_sort)
-            .then_with(|| a.hour.cmp(&b.hour))
-    });
-
-    cache.save_absences(student_id, &absences)?;
+    print!("{}", period_report::render(&reports, &range.title(period, lang), format, lang));
+    Ok(())
+}
 
-    Ok((absences, false, None))
+/// Status of a category that loaded: Stale when it is expired data served offline
+fn load_status<T>(data: &[T], info: service::CacheInfo, client: &ShkoloClient) -> output::LoadStatus {
+    if info.cached && client.is_offline() {
+        output::LoadStatus::Stale
+    } else {
+        output::LoadStatus::loaded(data.is_empty())
+    }
 }
 
-async fn get_feedbacks(
+/// One category of `json all`: its data, or nothing when the fetch failed
+fn category<T>(
+    result: Result<(Vec<T>, service::CacheInfo)>,
     client: &ShkoloClient,
-    cache: &CacheStore,
-    student_id: i64,
-    force_refresh: bool,
-) -> Result<(Vec<Feedback>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((feedbacks, age, expired)) = cache.get_feedbacks(student_id) {
-            if !expired {
-                return Ok((feedbacks, true, Some(age)));
-            }
+    status: &mut output::LoadStatus,
+    parts: &mut Vec<output::CacheState>,
+) -> Vec<T> {
+    match result {
+        Ok((data, info)) => {
+            *status = load_status(&data, info, client);
+            parts.push(output::CacheState::from(info));
+            data
+        }
+        Err(e) => {
+            *status = output::LoadStatus::failed(&e, false);
+            Vec::new()
         }
     }
+}
 
-    // Fetch from API
-    let response = client.get_feedbacks(student_id).await?;
-
-    let mut feedbacks: Vec<Feedback> = response.data
-        .or(response.feedbacks)
-        .unwrap_or_default()
-        .iter()
-        .map(Feedback::from_raw)
-        .collect();
-
-    // Sort by date (newest first)
-    feedbacks.sort_by(Feedback::cmp_by_date);
-
-    cache.save_feedbacks(student_id, &feedbacks)?;
-
-    Ok((feedbacks, false, None))
+/// Students a command's selector picks; all of them when it matches none
+fn select_students<'a>(students: &'a [Student], selector: Option<&str>) -> Vec<&'a Student> {
+    service::find_students(students, selector).unwrap_or_else(|| students.iter().collect())
 }
 
-async fn get_notifications(
+/// Print a per-student response. Students that failed are listed in `errors`
+/// and make it unsuccessful; the command itself only fails when all of them did.
+fn output_students<T: serde::Serialize>(
+    response: api::ApiResponse<Vec<T>>,
+    failures: Vec<api::StudentError>,
     client: &ShkoloClient,
-    cache: &CacheStore,
-    force_refresh: bool,
-) -> Result<(Vec<Notification>, bool, Option<String>)> {
-    // Check cache first
-    if !force_refresh {
-        if let Some((notifications, age, expired)) = cache.get_notifications() {
-            if !expired {
-                return Ok((notifications, true, Some(age)));
-            }
+    started: std::time::Instant,
+    format: OutputFormat,
+) -> Result<()> {
+    let all_failed = response.data.is_empty() && !failures.is_empty();
+    if format == OutputFormat::Jsonl {
+        // Entries on stdout; failed students, which have no entry, on stderr
+        for failure in &failures {
+            eprintln!("{}: {}", failure.student, failure.error);
         }
+        let output = output::render_jsonl(&response.data)?;
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+    } else {
+        output_json(&response.with_errors(failures).stale(client.is_offline()).timed(started), format)?;
     }
-
-    // Fetch from API
-    let response = client.get_notifications(1).await?;
-
-    let notifications: Vec<Notification> = response.data
-        .or(response.notifications)
-        .unwrap_or_default()
-        .iter()
-        .map(Notification::from_raw)
-        .collect();
-
-    cache.save_notifications(&notifications)?;
-
-    Ok((notifications, false, None))
+    if all_failed {
+        return Err(anyhow!("Could not load data for any student"));
+    }
+    Ok(())
 }
 
-fn select_students<'a>(students: &'a [Student], selector: Option<&str>) -> Vec<&'a Student> {
-    match selector {
-        None => students.iter().collect(),
-        Some(s) => {
-            // Try parsing as index first
-            if let Ok(idx) = s.parse::<usize>() {
-                if idx > 0 && idx <= students.len() {
-                    return vec![&students[idx - 1]];
-                }
-            }
-
-            // Try matching by name (case-insensitive, partial match)
-            let lower = s.to_lowercase();
-            let matches: Vec<_> = students
-                .iter()
-                .filter(|student| student.name.to_lowercase().contains(&lower))
-                .collect();
-
-            if matches.is_empty() {
-                students.iter().collect()
-            } else {
-                matches
-            }
-        }
+/// Print a per-student table. Students that failed are reported on stderr;
+/// the command only fails when all of them did.
+fn output_table(text: String, empty: bool, failures: Vec<api::StudentError>) -> Result<()> {
+    print!("{}", text);
+    for failure in &failures {
+        eprintln!("{}: {}", failure.student, failure.error);
     }
+    if empty && !failures.is_empty() {
+        return Err(anyhow!("Could not load data for any student"));
+    }
+    Ok(())
 }
 
-fn output_json<T: serde::Serialize>(data: &T, format: &str) -> Result<()> {
-    let output = if format == "compact" {
-        serde_json::to_string(data)?
-    } else {
-        serde_json::to_string_pretty(data)?
-    };
+/// `--format` if given, otherwise the configured default
+fn json_format(flag: Option<OutputFormat>, cache: &CacheStore) -> Result<OutputFormat> {
+    match flag {
+        Some(format) => Ok(format),
+        None => output::default_format(
+            std::env::var(output::JSON_FORMAT_ENV).ok().as_deref(),
+            cache.load_ui_config().default_json_format.as_deref(),
+        ),
+    }
+}
 
-    println!("{}", output);
+fn output_json<T: serde::Serialize>(data: &T, format: OutputFormat) -> Result<()> {
+    println!("{}", output::render_json(data, format)?);
     Ok(())
 }
 
@@ -1429,9 +2629,14 @@ mod dirs {
     }
 }
 
-fn get_today_date() -> String {
-    let now = OffsetDateTime::now_utc();
-    format!("{:04}-{:02}-{:02}", now.year(), now.month() as u8, now.day())
+/// Days to excuse an absence from the config file, or the default
+fn excuse_days(cache: &CacheStore) -> i64 {
+    cache.load_ui_config().absence_excuse_days.unwrap_or(models::DEFAULT_EXCUSE_DAYS)
+}
+
+/// Exam keywords from the config file, or the built-in list
+fn exam_keywords(cache: &CacheStore) -> models::ExamKeywords {
+    models::ExamKeywords::from_config(cache.load_ui_config().exam_keywords.as_deref())
 }
 
 /// Refresh all data in the background and return the result
@@ -1441,62 +2646,40 @@ async fn refresh_data_background(
     force_refresh: bool,
     _student_ids: Vec<i64>,
 ) -> Result<BackgroundResult> {
-    // Fetch students
-    let (students, _, _) = get_students(client, cache, force_refresh).await?;
+    let (students, _) = service::fetch_students(client, cache, force_refresh).await?;
 
-    // Fetch data for each student
+    // A student's failed categories keep their cached data and are reported in `errors`
+    let today = util::today_local();
     let mut student_data_list = Vec::new();
-    let today = get_today_date();
-
+    let mut errors = Vec::new();
     for student in students {
-        let (homework, _, hw_age) = get_homework(client, cache, student.id, force_refresh).await?;
-        let (grades, _, grades_age) = get_grades(client, cache, student.id, force_refresh).await?;
-        let (absences, _, absences_age) = get_absences(client, cache, student.id, force_refresh).await?;
-        let (feedbacks, _, feedbacks_age) = get_feedbacks(client, cache, student.id, force_refresh).await?;
-
-        // Get schedule - use today for background refresh
-        let (schedule, _, schedule_age) = get_schedule(client, cache, student.id, &today, force_refresh).await?;
-
-        student_data_list.push(StudentData {
-            student,
-            homework,
-            grades,
-            schedule,
-            events: Vec::new(), // TODO: fetch events
-            absences,
-            feedbacks,
-            homework_age: hw_age,
-            grades_age,
-            schedule_age,
-            absences_age,
-            feedbacks_age,
-        });
+        let (data, student_errors) = StudentData::fetch(client, cache, student, &today, force_refresh).await;
+        errors.extend(student_errors);
+        student_data_list.push(data);
     }
 
-    // Fetch notifications
-    let (notifications, _, _) = get_notifications(client, cache, force_refresh).await?;
-
-    // Fetch messages
-    let messages: Vec<MessageThread> = match client.get_messenger_threads(None).await {
-        Ok(raw_threads) => raw_threads.iter().map(MessageThread::from_raw).collect(),
-        Err(_) => Vec::new(),
-    };
+    let (notifications, _) = service::fetch_notifications(client, cache, force_refresh).await?;
+    let messages = service::fetch_messages(client, cache, None, force_refresh).await
+        .map(|(messages, _)| messages)
+        .unwrap_or_default();
 
     Ok(BackgroundResult::DataRefresh {
         students: student_data_list,
         notifications,
         messages,
+        errors,
     })
 }
 
-/// Refresh schedule for a specific student/date in the background
+/// Load the schedule for a specific student/date in the background.
+/// Served from cache when still fresh, so paging through past days stays offline.
 async fn refresh_schedule_background(
     client: &ShkoloClient,
     cache: &CacheStore,
     student_id: i64,
     date: &str,
 ) -> Result<BackgroundResult> {
-    let (schedule, _, _) = get_schedule(client, cache, student_id, date, true).await?;
+    let (schedule, _) = service::fetch_schedule(client, cache, student_id, date, false).await?;
 
     Ok(BackgroundResult::ScheduleRefresh {
         student_id,
@@ -1504,3 +2687,215 @@ async fn refresh_schedule_background(
         schedule,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::TempCache;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_one_failing_student_does_not_abort_json_command() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/pupils/1/grades/summary"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "grades": [] })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/pupils/2/grades/summary"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(3600));
+        let student = |id, name: &str| Student { id, name: name.to_string(), class_name: None, school_name: None };
+        cache.save_students(&[student(1, "Мария"), student(2, "Петър")]).unwrap();
+        let client = ShkoloClient::with_token("token".to_string(), None).with_base_url(&server.uri());
+        let grades = |name: &str| JsonCommands::Grades { student: Some(name.to_string()), subject: None, term: None, min_count: 0 };
+
+        // Some students loaded: the command succeeds (the envelope reports the failure)
+        run_json_command(grades("Иван"), &client, &cache, false, i18n::Lang::Bg, OutputFormat::Compact, false).await.unwrap();
+        // Every selected student failed
+        assert!(run_json_command(grades("Петър"), &client, &cache, false, i18n::Lang::Bg, OutputFormat::Compact, false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_max_age_policies() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/pupils"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!("api/fixtures/pupils.json")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/pupils/300401/grades/summary"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!("api/fixtures/grades_summary.json")))
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(7 * 86400));
+        cache.save_students(&[Student { id: 300401, name: "Мария Иванова".into(), class_name: None, school_name: None }]).unwrap();
+        cache.save_grades(300401, &[]).unwrap();
+        // Saved three days ago, still within the TTL
+        for name in ["students", "grades_300401"] {
+            let file = cache.cache_dir().join(format!("{}.json", name));
+            let mut cached: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+            cached["cached_at"] = (util::now_local().unix_timestamp() - 3 * 86400).into();
+            std::fs::write(&file, cached.to_string()).unwrap();
+        }
+        let client = ShkoloClient::with_token("token".to_string(), None).with_base_url(&server.uri());
+        let grades = || JsonCommands::Grades { student: Some("Мария".into()), subject: None, term: None, min_count: 0 };
+        let run = |policy| run_json_max_age(grades(), &client, &cache, false, i18n::Lang::Bg, OutputFormat::Compact, false, 86400, policy);
+
+        // Reported, not refetched
+        assert!(run(MaxAgePolicy::Warn).await.unwrap());
+        assert!(run(MaxAgePolicy::Fail).await.unwrap());
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        // Fetched again, after which it is fresh for every policy
+        assert!(!run(MaxAgePolicy::Refetch).await.unwrap());
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert!(!run(MaxAgePolicy::Fail).await.unwrap());
+    }
+
+    #[test]
+    fn test_cache_json_reports_what_happened() {
+        let cache = TempCache::new(Some(3600));
+        cache.save_token("token", None, None, None).unwrap();
+        cache.save_grades(1, &[]).unwrap();
+
+        let mut report = CacheReport::new(&cache);
+        report.files = Some(cache.list_entries().unwrap());
+        let listed = serde_json::to_value(&report).unwrap();
+        assert_eq!(listed["ttl"], 3600);
+        assert_eq!(listed["files"].as_array().unwrap().len(), 2);
+        assert!(listed.get("cleared").is_none());
+
+        // A dry run removes nothing; the real clear removes what it listed
+        let dry = clear_cache_files(&cache, true, false, None, None, true, false, true).unwrap().unwrap();
+        assert_eq!(dry.len(), 1);
+        assert!(cache.get_grades(1).is_some());
+        let removed = clear_cache_files(&cache, true, false, None, None, false, false, true).unwrap().unwrap();
+        assert_eq!(removed, dry);
+        assert!(cache.get_grades(1).is_none() && cache.load_token().is_ok());
+
+        let mut report = CacheReport::new(&cache);
+        report.cleared = Some(removed.len());
+        assert_eq!(serde_json::to_value(&report).unwrap()["cleared"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_imported_token_verification() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/pupils"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!("api/fixtures/pupils.json")))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/pupils"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(3600));
+        cache.save_token("ios-token", None, None, Some(&server.uri())).unwrap();
+
+        verify_imported_token(&cache, None).await.unwrap();
+        let error = verify_imported_token(&cache, None).await.unwrap_err();
+        assert!(error.to_string().contains("iOS app"), "{}", error);
+
+        // Nothing listening: a warning, not a failure
+        cache.save_token("ios-token", None, None, Some("http://127.0.0.1:9")).unwrap();
+        verify_imported_token(&cache, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_school_year_option_scopes_client_and_cache() {
+        let server = MockServer::start().await;
+        for year in ["24", "25"] {
+            Mock::given(method("GET"))
+                .and(path("/v1/diary/pupils/1/grades/summary"))
+                .and(header("School-Year", year))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "grades": [] })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let cache = TempCache::new(Some(3600));
+        let client = ShkoloClient::with_token("token".to_string(), Some(25)).with_base_url(&server.uri());
+        let school_year = |args: &[&str]| match Cli::parse_from(args).command {
+            Commands::Json { school_year, .. } => school_year,
+            _ => unreachable!(),
+        };
+
+        let (last_year, last_year_cache) = school_year_scope(client.clone(), &cache, school_year(&["shkolo", "json", "--school-year", "24", "grades"])).unwrap();
+        last_year.get_grades_summary(1).await.unwrap();
+        assert_eq!(last_year_cache.cache_dir(), &cache.cache_dir().join("years").join("24"));
+
+        let (current, current_cache) = school_year_scope(client, &cache, school_year(&["shkolo", "json", "grades"])).unwrap();
+        current.get_grades_summary(1).await.unwrap();
+        assert_eq!(current_cache.cache_dir(), cache.cache_dir());
+    }
+
+    fn notification(id: Option<&str>, date: &str, title: &str) -> Notification {
+        Notification {
+            id: id.map(Into::into),
+            title: title.into(),
+            body: None,
+            date: date.into(),
+            is_read: false,
+            notification_type: None,
+            pupil_names: None,
+            target: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_token_json() {
+        assert_eq!(token_json("abc", Some(25)).to_string(), r#"{"school_year":25,"token":"abc"}"#);
+        assert_eq!(token_json("abc", None)["school_year"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_follow_prints_only_new_notifications() {
+        let mut seen = HashSet::new();
+        let first = vec![
+            notification(Some("b"), "2026-02-18 10:15:00", "Ново отсъствие"),
+            notification(Some("a"), "2026-02-17 12:30:00", "Нова оценка"),
+        ];
+        let fresh = fresh_notifications(&mut seen, first.clone());
+        assert_eq!(fresh.iter().map(|n| n.title.as_str()).collect::<Vec<_>>(), ["Нова оценка", "Ново отсъствие"]);
+
+        // The next poll: one arrival, the rest already seen; no id falls back to time and title
+        let mut second = first;
+        second.insert(0, notification(None, "2026-02-19 08:05:00", "Ново съобщение"));
+        let fresh = fresh_notifications(&mut seen, second.clone());
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(fresh[0].title, "Ново съобщение");
+        assert!(fresh_notifications(&mut seen, second).is_empty());
+    }
+
+    #[test]
+    fn test_notification_line() {
+        let mut n = notification(Some("a"), "2026-02-17 12:30:00", "Нова оценка по Математика");
+        assert_eq!(notification_line(&n), "2026-02-17 12:30  Нова оценка по Математика");
+        n.pupil_names = Some("Мария Иванова".into());
+        n.body = Some("Мария получи\nоценка Отличен 6".into());
+        assert_eq!(notification_line(&n), "2026-02-17 12:30  Мария Иванова: Нова оценка по Математика - Мария получи оценка Отличен 6");
+    }
+
+    #[test]
+    fn test_follow_interval_floor() {
+        assert_eq!(follow_interval(300, None), Duration::from_secs(300));
+        assert_eq!(follow_interval(10, None), Duration::from_secs(MIN_FOLLOW_INTERVAL_SECS));
+        // --cache-ttl sets the floor, lower or higher than the default one
+        assert_eq!(follow_interval(10, Some(30)), Duration::from_secs(30));
+        assert_eq!(follow_interval(300, Some(900)), Duration::from_secs(900));
+        assert_eq!(follow_interval(0, Some(0)), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,166 @@
+//! Counters for the long-running modes (`serve`, `notifications --follow`):
+//! API requests by endpoint and status, background refreshes and how long
+//! they took. Cache lookups are counted by `CacheStats`; both are reported
+//! together, as Prometheus text or as one JSON line.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::cache::{CacheCounts, CacheStats};
+
+/// Shared between clones of a client, like its offline flag
+#[derive(Debug, Default)]
+pub struct Metrics {
+    inner: Mutex<Counters>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Counters {
+    /// (endpoint, status) -> requests; status is "error" when nothing came back
+    requests: BTreeMap<(String, String), u64>,
+    /// Failed requests since the last one that succeeded
+    consecutive_failures: u64,
+    refreshes: u64,
+    failed_refreshes: u64,
+    refresh_seconds: f64,
+    last_refresh_seconds: Option<f64>,
+}
+
+/// Everything counted so far, as printed by `--stats-interval`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snapshot {
+    pub requests: Vec<RequestCount>,
+    pub consecutive_failures: u64,
+    pub refreshes: u64,
+    pub failed_refreshes: u64,
+    pub refresh_seconds_total: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_refresh_seconds: Option<f64>,
+    /// Lookups by data type
+    pub cache: BTreeMap<&'static str, CacheCounts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestCount {
+    pub endpoint: String,
+    pub status: String,
+    pub count: u64,
+}
+
+/// An endpoint without its query and with ids as `:id`, so each pupil or
+/// thread doesn't get counters of its own
+pub fn endpoint_label(endpoint: &str) -> String {
+    let path = endpoint.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/')
+        .map(|s| if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) { ":id" } else { s })
+        .collect();
+    segments.join("/")
+}
+
+impl Metrics {
+    fn counters(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count a request; `status` is None when it never got an answer
+    pub fn record_request(&self, endpoint: &str, status: Option<u16>, ok: bool) {
+        let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+        let mut counters = self.counters();
+        *counters.requests.entry((endpoint_label(endpoint), status)).or_default() += 1;
+        counters.consecutive_failures = if ok { 0 } else { counters.consecutive_failures + 1 };
+    }
+
+    /// Count a background refresh of the cache or a --follow poll
+    pub fn record_refresh(&self, took: Duration, ok: bool) {
+        let mut counters = self.counters();
+        counters.refreshes += 1;
+        counters.failed_refreshes += u64::from(!ok);
+        counters.refresh_seconds += took.as_secs_f64();
+        counters.last_refresh_seconds = Some(took.as_secs_f64());
+    }
+
+    pub fn snapshot(&self, cache: &CacheStats) -> Snapshot {
+        let counters = self.counters().clone();
+        Snapshot {
+            requests: counters.requests.into_iter()
+                .map(|((endpoint, status), count)| RequestCount { endpoint, status, count })
+                .collect(),
+            consecutive_failures: counters.consecutive_failures,
+            refreshes: counters.refreshes,
+            failed_refreshes: counters.failed_refreshes,
+            refresh_seconds_total: counters.refresh_seconds,
+            last_refresh_seconds: counters.last_refresh_seconds,
+            cache: cache.snapshot().into_iter().map(|(kind, counts)| (kind.name(), counts)).collect(),
+        }
+    }
+
+    /// Prometheus text exposition format, for `serve`'s /metrics
+    pub fn render_prometheus(&self, cache: &CacheStats) -> String {
+        let s = self.snapshot(cache);
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str| {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+        };
+        metric("shkolo_api_requests_total", "counter", "API requests by endpoint and HTTP status");
+        metric("shkolo_api_consecutive_failures", "gauge", "Failed API requests since the last successful one");
+        metric("shkolo_refreshes_total", "counter", "Background refreshes");
+        metric("shkolo_refresh_failures_total", "counter", "Background refreshes that failed");
+        metric("shkolo_refresh_duration_seconds", "summary", "Time spent in background refreshes");
+        metric("shkolo_cache_lookups_total", "counter", "Cache lookups by data type and result");
+
+        for r in &s.requests {
+            let _ = writeln!(out, "shkolo_api_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}", escape(&r.endpoint), r.status, r.count);
+        }
+        let _ = writeln!(out, "shkolo_api_consecutive_failures {}", s.consecutive_failures);
+        let _ = writeln!(out, "shkolo_refreshes_total {}", s.refreshes);
+        let _ = writeln!(out, "shkolo_refresh_failures_total {}", s.failed_refreshes);
+        let _ = writeln!(out, "shkolo_refresh_duration_seconds_sum {}", s.refresh_seconds_total);
+        let _ = writeln!(out, "shkolo_refresh_duration_seconds_count {}", s.refreshes);
+        for (kind, counts) in &s.cache {
+            for (result, count) in [("hit", counts.hits), ("miss", counts.misses), ("expired", counts.expired)] {
+                let _ = writeln!(out, "shkolo_cache_lookups_total{{kind=\"{}\",result=\"{}\"}} {}", kind, result, count);
+            }
+        }
+        out
+    }
+}
+
+/// Label values escape backslashes, quotes and line breaks
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_labels_hide_ids_and_queries() {
+        assert_eq!(endpoint_label("/v1/diary/pupils/42/grades/summary"), "/v1/diary/pupils/:id/grades/summary");
+        assert_eq!(endpoint_label("/v1/diary/homeworks/courses?pupilId=42"), "/v1/diary/homeworks/courses");
+        assert_eq!(endpoint_label("/v1/auth/usersAndYears"), "/v1/auth/usersAndYears");
+    }
+
+    #[test]
+    fn test_prometheus_output() {
+        let metrics = Metrics::default();
+        metrics.record_request("/v1/diary/pupils", Some(200), true);
+        metrics.record_request("/v1/diary/pupils", Some(200), true);
+        metrics.record_request("/v1/diary/pupils/7/absences", None, false);
+        metrics.record_refresh(Duration::from_millis(1500), true);
+
+        let text = metrics.render_prometheus(&CacheStats::default());
+        assert!(text.contains("# TYPE shkolo_api_requests_total counter\n"));
+        assert!(text.contains("shkolo_api_requests_total{endpoint=\"/v1/diary/pupils\",status=\"200\"} 2\n"));
+        assert!(text.contains("shkolo_api_requests_total{endpoint=\"/v1/diary/pupils/:id/absences\",status=\"error\"} 1\n"));
+        assert!(text.contains("shkolo_api_consecutive_failures 1\n"));
+        assert!(text.contains("shkolo_refresh_duration_seconds_sum 1.5\n"));
+        assert!(text.contains("shkolo_cache_lookups_total{kind=\"grades\",result=\"miss\"} 0\n"));
+        // Every sample line is "name{labels} value"
+        assert!(text.lines().filter(|l| !l.starts_with('#')).all(|l| l.rsplit_once(' ').is_some_and(|(_, v)| v.parse::<f64>().is_ok())));
+    }
+}
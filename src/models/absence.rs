@@ -1,4 +1,20 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use time::{Date, Duration, Month};
+
+use crate::util::parse_day;
+
+/// Default for the `absence_excuse_days` setting: the API doesn't say how long
+/// parents have to excuse an absence, so it is assumed to be this many days
+/// after the absence was entered
+pub const DEFAULT_EXCUSE_DAYS: i64 = 7;
+
+/// First day of the second term in the school year `day` falls in: 1 February.
+/// The API doesn't say when terms end, and schools move it by a few days at most
+pub fn term_boundary(day: Date) -> Date {
+    let year = if day.month() >= Month::August { day.year() + 1 } else { day.year() };
+    Date::from_calendar_date(year, Month::February, 1).expect("1 February")
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AbsenceRaw {
@@ -19,7 +35,7 @@ pub struct AbsencesResponse {
     pub absences: Option<Vec<AbsenceRaw>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Absence {
     pub id: String,
     pub date: String,         // DD.MM.YYYY format
@@ -29,6 +45,9 @@ pub struct Absence {
     pub is_excused: bool,
     pub excuse_reason: Option<String>,
     pub created_by: Option<String>,
+    /// When the absence was entered ("YYYY-MM-DD HH:MM:SS"); missing in older caches
+    #[serde(default)]
+    pub created_at: Option<String>,
 }
 
 impl Absence {
@@ -61,6 +80,137 @@ impl Absence {
             is_excused,
             excuse_reason: raw.absence_comment.clone(),
             created_by: raw.created_by.clone(),
+            created_at: raw.created_date_time.clone(),
+        }
+    }
+
+    /// Last day to excuse an unexcused absence: `excuse_days` after it was
+    /// entered, or after its date when that's unknown. None once excused.
+    pub fn excuse_deadline(&self, excuse_days: i64) -> Option<Date> {
+        if self.is_excused {
+            return None;
+        }
+        let date = parse_day(&self.date_sort).or_else(|| parse_day(&self.date));
+        let entered = self.created_at.as_deref().and_then(parse_day);
+        let start = date.max(entered)?;
+        Some(start + Duration::days(excuse_days))
+    }
+
+    /// 2 from the `term_boundary` on, otherwise 1 (also when the date can't be read)
+    pub fn term(&self, term_boundary: Date) -> u8 {
+        let date = parse_day(&self.date_sort).or_else(|| parse_day(&self.date));
+        if date.is_some_and(|date| date >= term_boundary) { 2 } else { 1 }
+    }
+
+    /// Unexcused with the deadline ending today or tomorrow, i.e. within 48 hours
+    pub fn excuse_deadline_soon(&self, today: Date, excuse_days: i64) -> bool {
+        self.excuse_deadline(excuse_days)
+            .is_some_and(|deadline| (0..=1).contains(&(deadline - today).whole_days()))
+    }
+}
+
+/// One subject's absences, by term
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubjectAbsences {
+    pub subject: String,
+    /// Excused absences in terms 1 and 2
+    pub excused: [usize; 2],
+    /// Unexcused absences in terms 1 and 2
+    pub unexcused: [usize; 2],
+}
+
+impl SubjectAbsences {
+    pub fn total_excused(&self) -> usize {
+        self.excused.iter().sum()
+    }
+
+    pub fn total_unexcused(&self) -> usize {
+        self.unexcused.iter().sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.total_excused() + self.total_unexcused()
+    }
+}
+
+/// Absences counted per subject and term, most absences first
+pub fn absence_summary(absences: &[Absence], term_boundary: Date) -> Vec<SubjectAbsences> {
+    let mut subjects: Vec<SubjectAbsences> = Vec::new();
+    for absence in absences {
+        let index = match subjects.iter().position(|s| s.subject == absence.subject) {
+            Some(index) => index,
+            None => {
+                subjects.push(SubjectAbsences { subject: absence.subject.clone(), ..Default::default() });
+                subjects.len() - 1
+            }
+        };
+        let term = usize::from(absence.term(term_boundary)) - 1;
+        let counts = if absence.is_excused { &mut subjects[index].excused } else { &mut subjects[index].unexcused };
+        counts[term] += 1;
+    }
+    subjects.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.subject.cmp(&b.subject)));
+    subjects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    fn absence(date_sort: &str, created_at: Option<&str>, is_excused: bool) -> Absence {
+        Absence {
+            id: "1".to_string(),
+            date: String::new(),
+            date_sort: date_sort.to_string(),
+            hour: 2,
+            subject: "Математика".to_string(),
+            is_excused,
+            excuse_reason: None,
+            created_by: None,
+            created_at: created_at.map(str::to_string),
         }
     }
+
+    #[test]
+    fn test_excuse_deadline() {
+        let today = date!(2026 - 02 - 19);
+        // Counted from the later of the absence date and when it was entered
+        assert_eq!(absence("2026-02-10", None, false).excuse_deadline(7), Some(date!(2026 - 02 - 17)));
+        assert_eq!(absence("2026-02-10", Some("2026-02-12 09:00:00"), false).excuse_deadline(7), Some(date!(2026 - 02 - 19)));
+        assert_eq!(absence("2026-02-10", None, true).excuse_deadline(7), None);
+
+        assert!(absence("2026-02-12", None, false).excuse_deadline_soon(today, 7));
+        assert!(absence("2026-02-13", None, false).excuse_deadline_soon(today, 7));
+        assert!(!absence("2026-02-14", None, false).excuse_deadline_soon(today, 7));
+        // Already past
+        assert!(!absence("2026-02-11", None, false).excuse_deadline_soon(today, 7));
+        assert!(!absence("2026-02-12", None, true).excuse_deadline_soon(today, 7));
+        assert!(absence("2026-02-16", None, false).excuse_deadline_soon(today, 3));
+    }
+
+    #[test]
+    fn test_absence_summary_by_term() {
+        assert_eq!(term_boundary(date!(2025 - 09 - 15)), date!(2026 - 02 - 01));
+        assert_eq!(term_boundary(date!(2026 - 05 - 20)), date!(2026 - 02 - 01));
+
+        let boundary = date!(2026 - 02 - 01);
+        let mut history = absence("2026-01-31", None, true);
+        history.subject = "История".to_string();
+        let absences = vec![
+            absence("2025-11-03", None, true),
+            absence("2026-02-01", None, false),
+            absence("2026-03-10", None, true),
+            history,
+            absence("", None, false),
+        ];
+        assert_eq!(absences[1].term(boundary), 2);
+        assert_eq!(absences[4].term(boundary), 1);
+
+        let summary = absence_summary(&absences, boundary);
+        assert_eq!(summary, vec![
+            SubjectAbsences { subject: "Математика".to_string(), excused: [1, 1], unexcused: [1, 1] },
+            SubjectAbsences { subject: "История".to_string(), excused: [1, 0], unexcused: [0, 0] },
+        ]);
+        assert_eq!(summary[0].total(), 4);
+    }
 }
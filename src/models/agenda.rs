@@ -0,0 +1,146 @@
+//! Day-by-day plan for the coming days (`shkolo agenda`): homework by due
+//! date, calendar events and the tests among either, merged into one list.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use time::{Date, Duration};
+
+use super::{Event, ExamKeywords, Homework};
+use crate::util::parse_day;
+
+/// What an agenda item comes from. Homework or an event that announces a
+/// test is listed once, as an exam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AgendaKind {
+    Exam,
+    Event,
+    Homework,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct AgendaItem {
+    pub kind: AgendaKind,
+    /// Homework subject; events don't have one
+    pub subject: Option<String>,
+    /// The homework text or the event title
+    pub text: String,
+}
+
+/// One day of the agenda; `items` is empty on a free day
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct AgendaDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Exams first, then events and homework, each by subject
+    pub items: Vec<AgendaItem>,
+}
+
+/// `days` days starting with `today`. Homework counts on its due date and
+/// events on every day they span; anything without a readable date is left out.
+pub fn agenda(homework: &[Homework], events: &[Event], keywords: &ExamKeywords, today: Date, days: u32) -> Vec<AgendaDay> {
+    let dates: Vec<Date> = (0..i64::from(days)).map(|n| today + Duration::days(n)).collect();
+    let mut agenda: Vec<AgendaDay> = dates.iter()
+        .map(|date| AgendaDay { date: date.to_string(), items: Vec::new() })
+        .collect();
+    let index = |date: Date| usize::try_from((date - today).whole_days()).ok().filter(|&i| i < agenda.len());
+
+    let mut placed = Vec::new();
+    for hw in homework {
+        let Some(day) = hw.due_date_sort.as_deref().and_then(parse_day).and_then(index) else { continue };
+        let kind = if hw.is_exam(keywords) { AgendaKind::Exam } else { AgendaKind::Homework };
+        placed.push((day, AgendaItem { kind, subject: Some(hw.subject.clone()), text: hw.text.clone() }));
+    }
+    for event in events {
+        let Some(start) = parse_day(&event.start_date) else { continue };
+        let end = event.end_date.as_deref().and_then(parse_day).filter(|end| *end >= start).unwrap_or(start);
+        let kind = if event.is_exam(keywords) { AgendaKind::Exam } else { AgendaKind::Event };
+        for day in dates.iter().filter(|d| (start..=end).contains(d)).filter_map(|d| index(*d)) {
+            placed.push((day, AgendaItem { kind, subject: None, text: event.title.clone() }));
+        }
+    }
+
+    for (day, item) in placed {
+        agenda[day].items.push(item);
+    }
+    for day in &mut agenda {
+        day.items.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.subject.cmp(&b.subject)));
+    }
+    agenda
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    fn homework(subject: &str, text: &str, due: Option<&str>) -> Homework {
+        Homework {
+            id: None,
+            subject: subject.to_string(),
+            text: text.to_string(),
+            date: "16.02.2026".to_string(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: due.map(str::to_string),
+            links: Vec::new(),
+            raw_html: None,
+        }
+    }
+
+    fn event(title: &str, start_date: &str, end_date: Option<&str>, is_test: bool) -> Event {
+        Event {
+            id: None,
+            title: title.to_string(),
+            description: None,
+            start_date: start_date.to_string(),
+            end_date: end_date.map(str::to_string),
+            event_type: None,
+            is_test,
+        }
+    }
+
+    #[test]
+    fn test_agenda_merges_by_day() {
+        let today = date!(2026 - 02 - 19);
+        let homework = vec![
+            homework("Математика", "Задачи 1-12", Some("2026-02-20")),
+            homework("БЕЛ", "Преразказ", Some("2026-02-20")),
+            homework("Математика", "Контролно върху дробите", Some("2026-02-23")),
+            // Already past, too far ahead, or without a due date
+            homework("История", "Урок 12", Some("2026-02-18")),
+            homework("История", "Урок 13", Some("2026-03-20")),
+            homework("Музика", "Песен", None),
+        ];
+        let events = vec![
+            event("Входно ниво", "2026-02-20 10:00:00", None, true),
+            event("Зелено училище", "2026-02-17", Some("2026-02-19"), false),
+        ];
+
+        let agenda = agenda(&homework, &events, &ExamKeywords::default(), today, 7);
+        assert_eq!(agenda.len(), 7);
+        assert_eq!(agenda[0].date, "2026-02-19");
+        assert_eq!(agenda[6].date, "2026-02-25");
+
+        let day = |i: usize| agenda[i].items.iter().map(|item| (item.kind, item.text.as_str())).collect::<Vec<_>>();
+        // An event that started before today is still on while it lasts
+        assert_eq!(day(0), vec![(AgendaKind::Event, "Зелено училище")]);
+        assert_eq!(day(1), vec![
+            (AgendaKind::Exam, "Входно ниво"),
+            (AgendaKind::Homework, "Преразказ"),
+            (AgendaKind::Homework, "Задачи 1-12"),
+        ]);
+        assert!(agenda[2].items.is_empty());
+        assert_eq!(day(4), vec![(AgendaKind::Exam, "Контролно върху дробите")]);
+        assert_eq!(agenda.iter().map(|d| d.items.len()).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_agenda_of_one_day() {
+        let today = date!(2026 - 02 - 19);
+        let events = vec![event("Екскурзия", "2026-02-18", Some("2026-02-21"), false)];
+        let agenda = agenda(&[], &events, &ExamKeywords::default(), today, 1);
+        assert_eq!(agenda.len(), 1);
+        assert_eq!(agenda[0].items.len(), 1);
+    }
+}
@@ -0,0 +1,223 @@
+//! Announced tests. Teachers enter a "контролно" as homework about as often as
+//! they put it in the calendar, so both are scanned for keywords.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use time::Date;
+
+use super::{Event, Homework};
+use crate::util::parse_day;
+
+/// Words that mark an item as a test. Matched as whole words, so inflected
+/// forms are listed one by one and e.g. "тестото" (dough) stays out.
+pub const DEFAULT_EXAM_KEYWORDS: &[&str] = &[
+    "контролно", "контролното", "контролна", "контролната", "контролни",
+    "класна работа", "класната работа",
+    "тест", "теста", "тестът", "тестове",
+    "изпитване", "изпитването", "изпит", "изпита",
+    "test", "exam", "quiz",
+    "klassenarbeit", "klausur", "prüfung",
+];
+
+/// Keyword matcher for exam detection; the list comes from `exam_keywords`
+/// in the config, or `DEFAULT_EXAM_KEYWORDS`
+#[derive(Debug, Clone)]
+pub struct ExamKeywords {
+    /// (keyword as configured, normalized form)
+    words: Vec<(String, String)>,
+}
+
+impl Default for ExamKeywords {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXAM_KEYWORDS)
+    }
+}
+
+impl ExamKeywords {
+    /// An empty list turns keyword matching off; events typed as tests still count
+    pub fn new<S: AsRef<str>>(words: &[S]) -> Self {
+        let words = words.iter()
+            .map(|w| (w.as_ref().trim().to_string(), normalize(w.as_ref())))
+            .filter(|(_, normalized)| !normalized.trim().is_empty())
+            .collect();
+        Self { words }
+    }
+
+    /// The configured list, or the defaults when none is configured
+    pub fn from_config(words: Option<&[String]>) -> Self {
+        words.map(Self::new).unwrap_or_default()
+    }
+
+    /// First keyword that occurs in `text` as a whole word (or phrase), ignoring case
+    pub fn find(&self, text: &str) -> Option<&str> {
+        let text = normalize(text);
+        self.words.iter()
+            .find(|(_, word)| text.contains(word.as_str()))
+            .map(|(word, _)| word.as_str())
+    }
+}
+
+/// Lowercase words separated by single spaces, with a space on each end so
+/// `contains` only matches whole words
+fn normalize(text: &str) -> String {
+    let cleaned: String = text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    format!(" {} ", cleaned.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Where an exam was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExamSource {
+    Homework,
+    Event,
+}
+
+/// A test found in homework or events
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct Exam {
+    /// YYYY-MM-DD: the homework due date or the event start
+    pub date: String,
+    /// Homework subject; events don't have one
+    pub subject: Option<String>,
+    /// Text of the source item: the homework text or the event title
+    pub text: String,
+    pub source: ExamSource,
+    /// Keyword that flagged the item; absent for events the school marked as a test
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+}
+
+impl Homework {
+    /// Whether the homework text announces a test
+    pub fn is_exam(&self, keywords: &ExamKeywords) -> bool {
+        keywords.find(&self.text).is_some()
+    }
+}
+
+impl Event {
+    /// Whether the school marked the event as a test or its text announces one
+    pub fn is_exam(&self, keywords: &ExamKeywords) -> bool {
+        self.is_test || self.exam_keyword(keywords).is_some()
+    }
+
+    /// Keyword announcing a test in the title or description
+    fn exam_keyword<'k>(&self, keywords: &'k ExamKeywords) -> Option<&'k str> {
+        keywords.find(&self.title)
+            .or_else(|| self.description.as_deref().and_then(|d| keywords.find(d)))
+    }
+}
+
+/// Exams from today on, soonest first. Items without a readable date are left
+/// out rather than guessed.
+pub fn upcoming_exams(homework: &[Homework], events: &[Event], keywords: &ExamKeywords, today: Date) -> Vec<Exam> {
+    let upcoming = |date: Option<&str>| date.and_then(parse_day).filter(|d| *d >= today);
+
+    let from_homework = homework.iter().filter_map(|hw| {
+        let keyword = keywords.find(&hw.text)?;
+        let date = upcoming(hw.due_date_sort.as_deref())?;
+        Some(Exam {
+            date: date.to_string(),
+            subject: Some(hw.subject.clone()),
+            text: hw.text.clone(),
+            source: ExamSource::Homework,
+            keyword: Some(keyword.to_string()),
+        })
+    });
+
+    let from_events = events.iter().filter_map(|event| {
+        let keyword = event.exam_keyword(keywords);
+        if !event.is_test && keyword.is_none() {
+            return None;
+        }
+        let date = upcoming(Some(&event.start_date))?;
+        Some(Exam {
+            date: date.to_string(),
+            subject: None,
+            text: event.title.clone(),
+            source: ExamSource::Event,
+            keyword: keyword.map(str::to_string),
+        })
+    });
+
+    let mut exams: Vec<Exam> = from_homework.chain(from_events).collect();
+    exams.sort_by(|a, b| a.date.cmp(&b.date));
+    exams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn homework(text: &str, due: &str) -> Homework {
+        Homework {
+            id: None,
+            subject: "Математика".to_string(),
+            text: text.to_string(),
+            date: "16.02.2026".to_string(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: Some(due.to_string()),
+            links: Vec::new(),
+            raw_html: None,
+        }
+    }
+
+    fn event(title: &str, start_date: &str, is_test: bool) -> Event {
+        Event {
+            id: None,
+            title: title.to_string(),
+            description: None,
+            start_date: start_date.to_string(),
+            end_date: None,
+            event_type: None,
+            is_test,
+        }
+    }
+
+    #[test]
+    fn test_keywords_match_whole_words() {
+        let keywords = ExamKeywords::default();
+        assert_eq!(keywords.find("Контролно върху дробите"), Some("контролно"));
+        assert_eq!(keywords.find("Подгответе се за КЛАСНА  работа!"), Some("класна работа"));
+        assert_eq!(keywords.find("Vocabulary quiz, unit 3"), Some("quiz"));
+        assert_eq!(keywords.find("Замесете тестото"), None);
+        assert_eq!(keywords.find("Протест на учениците"), None);
+        assert_eq!(keywords.find("Упр. 5 на стр. 12"), None);
+
+        let custom = ExamKeywords::from_config(Some(&["Проверка".to_string()]));
+        assert_eq!(custom.find("Проверка на знанията"), Some("Проверка"));
+        assert_eq!(custom.find("Контролно"), None);
+        assert_eq!(ExamKeywords::new::<&str>(&[]).find("Контролно"), None);
+    }
+
+    #[test]
+    fn test_upcoming_exams_sorted_with_source() {
+        let today = time::macros::date!(2026 - 02 - 19);
+        let homework = vec![
+            homework("Контролна работа върху дробите", "2026-02-26"),
+            homework("Упр. 5", "2026-02-20"),
+            homework("Тест по таблицата за умножение", "2026-02-18"),
+        ];
+        let events = vec![
+            event("Изпитване по история", "2026-02-24 08:00:00", false),
+            event("Входно ниво", "2026-02-20", true),
+            event("Родителска среща", "2026-02-21", false),
+        ];
+
+        let exams = upcoming_exams(&homework, &events, &ExamKeywords::default(), today);
+        let summary: Vec<_> = exams.iter()
+            .map(|e| (e.date.as_str(), e.source, e.keyword.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("2026-02-20", ExamSource::Event, None),
+            ("2026-02-24", ExamSource::Event, Some("изпитване")),
+            ("2026-02-26", ExamSource::Homework, Some("контролна")),
+        ]);
+        assert_eq!(exams[2].text, "Контролна работа върху дробите");
+        assert!(homework[0].is_exam(&ExamKeywords::default()));
+        assert_eq!(upcoming_exams(&[], &events, &ExamKeywords::new::<&str>(&[]), today).len(), 1);
+    }
+}
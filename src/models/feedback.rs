@@ -1,5 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::html;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeedbackRaw {
     pub id: Option<i64>,
@@ -24,7 +27,7 @@ pub struct FeedbacksResponse {
     pub feedbacks: Option<Vec<FeedbackRaw>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Feedback {
     pub id: i64,
     pub badge_name: String,
@@ -72,7 +75,7 @@ impl Feedback {
             id: raw.id.unwrap_or(0),
             badge_name: raw.badge_name.clone().unwrap_or_else(|| "Feedback".to_string()),
             badge_icon: raw.badge_icon.clone(),
-            comment: raw.text.clone(),
+            comment: raw.text.as_deref().map(|t| html::sanitize(t).text).filter(|t| !t.is_empty()),
             is_positive,
             date,
             teacher: raw.created_by.clone().unwrap_or_default(),
@@ -82,6 +85,11 @@ impl Feedback {
         }
     }
 
+    /// Plain-text marker for terminals that can't show emoji (--ascii)
+    pub fn ascii_marker(&self) -> &'static str {
+        if self.is_positive { "[+]" } else { "[-]" }
+    }
+
     /// Get emoji for badge type - map badge_icon filenames to emojis
     pub fn emoji(&self) -> String {
         if let Some(ref icon) = self.badge_icon {
@@ -149,6 +157,25 @@ mod tests {
         assert_eq!(feedback.subject, "Math"); // Uses short name if available
     }
 
+    #[test]
+    fn test_feedback_comment_html() {
+        let raw = FeedbackRaw {
+            id: Some(1),
+            badge_name: None,
+            badge_icon: None,
+            badge_type_id: Some(1),
+            text: Some("<p>&#1041;&#1088;&#1072;&#1074;&#1086;!&nbsp;</p>".to_string()),
+            created_date: None,
+            created_by: None,
+            course_name: None,
+            course_short_name: None,
+        };
+        assert_eq!(Feedback::from_raw(&raw).comment.as_deref(), Some("Браво!"));
+
+        let empty = FeedbackRaw { text: Some("<p>&nbsp;</p>".to_string()), ..raw };
+        assert_eq!(Feedback::from_raw(&empty).comment, None);
+    }
+
     #[test]
     fn test_feedback_negative_badge_type() {
         let raw = FeedbackRaw {
@@ -203,7 +230,7 @@ mod tests {
 
     #[test]
     fn test_feedback_sorting_newest_first() {
-        let mut feedbacks = vec![
+        let mut feedbacks = [
             Feedback {
                 id: 1,
                 badge_name: "Old".to_string(),
@@ -269,6 +296,7 @@ mod tests {
             subject: "Math".to_string(),
         };
         assert_eq!(positive.emoji(), "🌟");
+        assert_eq!(positive.ascii_marker(), "[+]");
 
         // Test negative badge icon
         let negative = Feedback {
@@ -282,6 +310,7 @@ mod tests {
             subject: "Math".to_string(),
         };
         assert_eq!(negative.emoji(), "📝❌");
+        assert_eq!(negative.ascii_marker(), "[-]");
 
         // Test fallback for unknown icon
         let unknown = Feedback {
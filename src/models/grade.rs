@@ -1,7 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Grade {
     pub subject: String,
     pub term1_grades: Vec<String>,
@@ -9,6 +11,33 @@ pub struct Grade {
     pub term1_final: Option<String>,
     pub term2_final: Option<String>,
     pub annual: Option<String>,
+    /// Current (non-final) grades with their term and id, for ordering them in time
+    #[serde(default)]
+    pub entries: Vec<GradeEntry>,
+}
+
+/// One current grade and where it falls in time
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GradeEntry {
+    pub value: String,
+    /// 1 or 2
+    pub term: u8,
+    /// API id of the grade; ids grow as grades are entered. None when the API sent a plain list.
+    pub id: Option<i64>,
+    /// How much the grade counts towards the weighted average (e.g. 2 for a
+    /// test); None when the school doesn't weight grades, which counts as 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+/// A current grade as listed: from `entries`, or from the term lists of data
+/// cached before entries existed, where `position` counts within the term
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradeItem<'a> {
+    pub value: &'a str,
+    pub term: u8,
+    pub id: Option<i64>,
+    pub position: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +46,9 @@ pub struct GradeDetail {
     pub grade_raw: Option<String>,
     pub numerical_value: Option<f64>,
     pub icon: Option<String>,
+    /// Only sent by schools that weight grades; a number or a numeric string
+    #[serde(default, alias = "coefficient")]
+    pub weight: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +86,8 @@ impl Grade {
         let term1_final = extract_final_grade(&course.term1final);
         let term2_final = extract_final_grade(&course.term2final);
         let annual = extract_final_grade(&course.annual);
+        let mut entries = extract_entries(&course.term1, 1);
+        entries.extend(extract_entries(&course.term2, 2));
 
         Self {
             subject,
@@ -62,6 +96,7 @@ impl Grade {
             term1_final,
             term2_final,
             annual,
+            entries,
         }
     }
 
@@ -72,6 +107,251 @@ impl Grade {
             || self.term2_final.is_some()
             || self.annual.is_some()
     }
+
+    /// Current and final grades together
+    pub fn grade_count(&self) -> usize {
+        self.term1_grades.len()
+            + self.term2_grades.len()
+            + [&self.term1_final, &self.term2_final, &self.annual].iter().filter(|g| g.is_some()).count()
+    }
+
+    /// Case-insensitive partial match against the subject name
+    pub fn subject_matches(&self, query: &str) -> bool {
+        subject_matches(&self.subject, query)
+    }
+
+    /// Current grades in list order, with what identifies them
+    pub fn items(&self) -> Vec<GradeItem<'_>> {
+        if self.entries.is_empty() {
+            let terms = [(1, &self.term1_grades), (2, &self.term2_grades)];
+            terms.into_iter()
+                .flat_map(|(term, values)| values.iter().enumerate().map(move |(position, value)| {
+                    GradeItem { value, term, id: None, position }
+                }))
+                .collect()
+        } else {
+            self.entries.iter().enumerate()
+                .map(|(position, entry)| GradeItem { value: &entry.value, term: entry.term, id: entry.id, position })
+                .collect()
+        }
+    }
+
+    /// `entries`, or the term lists without ids for data cached before entries existed
+    fn current_entries(&self) -> Vec<GradeEntry> {
+        if !self.entries.is_empty() {
+            return self.entries.clone();
+        }
+        let entry = |term: u8| move |value: &String| GradeEntry { value: value.clone(), term, id: None, weight: None };
+        self.term1_grades.iter().map(entry(1))
+            .chain(self.term2_grades.iter().map(entry(2)))
+            .collect()
+    }
+
+    /// Mean of the current grades of `term` (1 or 2) that `parse_bg_grade`
+    /// understands, each counted by its weight. Without weights from the API
+    /// this is the simple mean. None when the term has no such grades.
+    pub fn weighted_average(&self, term: u8) -> Option<f64> {
+        self.average_of(|e| e.term == term)
+    }
+
+    /// Like `weighted_average`, over the current grades of both terms
+    pub fn overall_average(&self) -> Option<f64> {
+        self.average_of(|_| true)
+    }
+
+    fn average_of(&self, include: impl Fn(&GradeEntry) -> bool) -> Option<f64> {
+        let (sum, total_weight) = self.current_entries().iter()
+            .filter(|e| include(e))
+            .filter_map(|e| Some((parse_bg_grade(&e.value)?, e.weight.unwrap_or(1.0))))
+            .fold((0.0, 0.0), |(sum, total), (value, weight)| (sum + value * weight, total + weight));
+        (total_weight > 0.0).then(|| sum / total_weight)
+    }
+
+    /// Whether any current grade of `term` counts for more or less than 1
+    pub fn has_weights(&self, term: u8) -> bool {
+        self.entries.iter().any(|e| e.term == term && e.weight.is_some_and(|w| w != 1.0))
+    }
+
+    /// Only the grades of `term`: its current grades and final for a term,
+    /// the final grades for `Annual`
+    pub fn for_term(mut self, term: GradeTerm) -> Self {
+        match term {
+            GradeTerm::First => {
+                self.term2_grades.clear();
+                self.term2_final = None;
+                self.annual = None;
+                self.entries.retain(|e| e.term == 1);
+            }
+            GradeTerm::Second => {
+                self.term1_grades.clear();
+                self.term1_final = None;
+                self.annual = None;
+                self.entries.retain(|e| e.term == 2);
+            }
+            GradeTerm::Annual => {
+                self.term1_grades.clear();
+                self.term2_grades.clear();
+                self.entries.clear();
+            }
+        }
+        self
+    }
+}
+
+/// A grade that was corrected or deleted between two fetches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GradeChange {
+    pub subject: String,
+    /// 1 or 2
+    pub term: u8,
+    pub old: String,
+    /// None when the grade was deleted
+    pub new: Option<String>,
+}
+
+/// Current grades that changed value or disappeared from `old` to `new`.
+/// Grades are matched by their API id, which survives edits. Plain lists
+/// carry no ids; there the values are compared as multisets and a vanished
+/// value is paired with a newly appeared one as an edit. Added grades are
+/// not changes.
+pub fn diff_grades(old: &[Grade], new: &[Grade]) -> Vec<GradeChange> {
+    let mut changes = Vec::new();
+    for old_grade in old {
+        let new_entries = new.iter()
+            .find(|g| g.subject == old_grade.subject)
+            .map(Grade::current_entries)
+            .unwrap_or_default();
+        let old_entries = old_grade.current_entries();
+
+        for term in [1, 2] {
+            let before: Vec<&GradeEntry> = old_entries.iter().filter(|e| e.term == term).collect();
+            let after: Vec<&GradeEntry> = new_entries.iter().filter(|e| e.term == term).collect();
+            let change = |old: &str, new: Option<&str>| GradeChange {
+                subject: old_grade.subject.clone(),
+                term,
+                old: old.to_string(),
+                new: new.map(str::to_string),
+            };
+
+            let has_ids = |entries: &[&GradeEntry]| entries.iter().any(|e| e.id.is_some());
+            if has_ids(&before) && has_ids(&after) {
+                for entry in &before {
+                    let Some(id) = entry.id else { continue };
+                    match after.iter().find(|e| e.id == Some(id)) {
+                        Some(now) if now.value != entry.value => changes.push(change(&entry.value, Some(&now.value))),
+                        Some(_) => {}
+                        None => changes.push(change(&entry.value, None)),
+                    }
+                }
+            } else {
+                let mut added: Vec<&str> = after.iter().map(|e| e.value.as_str()).collect();
+                let mut removed = Vec::new();
+                for entry in &before {
+                    match added.iter().position(|v| *v == entry.value) {
+                        Some(i) => { added.remove(i); }
+                        None => removed.push(entry.value.as_str()),
+                    }
+                }
+                let mut added = added.into_iter();
+                changes.extend(removed.into_iter().map(|old| change(old, added.next())));
+            }
+        }
+    }
+    changes
+}
+
+/// Word grades, matched at the start of the grade. None is the start of
+/// another ("много добър" doesn't start with "добър"), so order doesn't matter.
+const WORD_GRADES: &[(&str, f64)] = &[
+    ("отличен", 6.0),
+    ("много добър", 5.0),
+    ("мн. добър", 5.0),
+    ("мн.добър", 5.0),
+    ("добър", 4.0),
+    ("задоволителен", 3.0),
+    ("среден", 3.0),
+    ("слаб", 2.0),
+];
+
+/// Value of a grade on the Bulgarian 2-6 scale: a number ("5", "5.50",
+/// "5,50"), a number with a "+" or "-" that doesn't change its value ("6-"),
+/// or a word grade as used in the early school years ("Отличен",
+/// "Мн. добър 5"). None for anything else, including numbers off the scale.
+pub fn parse_bg_grade(grade: &str) -> Option<f64> {
+    let grade = grade.trim().to_lowercase();
+    let number = grade.trim_end_matches(['+', '-']).trim_end().replace(',', ".");
+    if let Ok(value) = number.parse::<f64>() {
+        return (2.0..=6.0).contains(&value).then_some(value);
+    }
+    WORD_GRADES.iter()
+        .find(|(word, _)| grade.starts_with(word))
+        .map(|&(_, value)| value)
+}
+
+/// Case-insensitive partial match of a subject name, shared by the
+/// `--subject` filters
+pub fn subject_matches(subject: &str, query: &str) -> bool {
+    subject.to_lowercase().contains(query.trim().to_lowercase().as_str())
+}
+
+/// Term selected with `json grades --term`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GradeTerm {
+    #[value(name = "1")]
+    First,
+    #[value(name = "2")]
+    Second,
+    /// Term and annual final grades
+    Annual,
+}
+
+/// What `json grades` keeps of each student's grades
+#[derive(Debug, Clone, Default)]
+pub struct GradeFilter {
+    pub subject: Option<String>,
+    pub term: Option<GradeTerm>,
+    /// Subjects with fewer grades (after the term filter) are dropped
+    pub min_count: usize,
+}
+
+impl GradeFilter {
+    pub fn is_empty(&self) -> bool {
+        self.subject.is_none() && self.term.is_none() && self.min_count == 0
+    }
+
+    /// Subjects that pass the filter. With a term, subjects without grades
+    /// in it are left out.
+    pub fn apply(&self, grades: Vec<Grade>) -> Vec<Grade> {
+        grades.into_iter()
+            .filter(|g| self.subject.as_deref().is_none_or(|q| g.subject_matches(q)))
+            .map(|g| match self.term {
+                Some(term) => g.for_term(term),
+                None => g,
+            })
+            .filter(|g| self.term.is_none() || g.has_grades())
+            .filter(|g| g.grade_count() >= self.min_count)
+            .collect()
+    }
+}
+
+/// The filter as its options, for warnings: `subject "мат", term 1`
+impl fmt::Display for GradeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(subject) = &self.subject {
+            parts.push(format!("subject \"{}\"", subject));
+        }
+        match self.term {
+            Some(GradeTerm::First) => parts.push("term 1".to_string()),
+            Some(GradeTerm::Second) => parts.push("term 2".to_string()),
+            Some(GradeTerm::Annual) => parts.push("term annual".to_string()),
+            None => {}
+        }
+        if self.min_count > 0 {
+            parts.push(format!("min count {}", self.min_count));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
 }
 
 fn extract_grade_value(detail: &GradeDetail) -> Option<String> {
@@ -87,26 +367,44 @@ fn extract_grade_value(detail: &GradeDetail) -> Option<String> {
     None
 }
 
-fn extract_grades(term: &Option<TermGrades>) -> Vec<String> {
-    let mut grades = Vec::new();
+/// Grade details in the order they were entered. Map keys are grade ids,
+/// which grow over time; lists already come in order.
+fn ordered_details(term: &Option<TermGrades>) -> Vec<(Option<i64>, &GradeDetail)> {
     match term {
         Some(TermGrades::Map(map)) => {
-            for detail in map.values() {
-                if let Some(g) = extract_grade_value(detail) {
-                    grades.push(g);
-                }
-            }
-        }
-        Some(TermGrades::List(list)) => {
-            for detail in list {
-                if let Some(g) = extract_grade_value(detail) {
-                    grades.push(g);
-                }
-            }
+            let mut details: Vec<_> = map.iter().map(|(key, detail)| (key.parse().ok(), detail)).collect();
+            details.sort_by_key(|(id, _)| *id);
+            details
         }
-        None => {}
+        Some(TermGrades::List(list)) => list.iter().map(|detail| (None, detail)).collect(),
+        None => Vec::new(),
     }
-    grades
+}
+
+fn extract_grades(term: &Option<TermGrades>) -> Vec<String> {
+    ordered_details(term)
+        .into_iter()
+        .filter_map(|(_, detail)| extract_grade_value(detail))
+        .collect()
+}
+
+fn extract_entries(term: &Option<TermGrades>, term_number: u8) -> Vec<GradeEntry> {
+    ordered_details(term)
+        .into_iter()
+        .filter_map(|(id, detail)| {
+            extract_grade_value(detail).map(|value| GradeEntry { value, term: term_number, id, weight: extract_weight(detail) })
+        })
+        .collect()
+}
+
+/// Positive weight of a grade, sent as a number or a string ("2", "1.5")
+fn extract_weight(detail: &GradeDetail) -> Option<f64> {
+    let weight = match detail.weight.as_ref()? {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().replace(',', ".").parse().ok(),
+        _ => None,
+    }?;
+    (weight > 0.0).then_some(weight)
 }
 
 fn extract_final_grade(term: &Option<TermGrades>) -> Option<String> {
@@ -129,3 +427,172 @@ fn extract_final_grade(term: &Option<TermGrades>) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(subject: &str, term1: &[&str], term2: &[&str], term1_final: Option<&str>) -> Grade {
+        let strings = |grades: &[&str]| grades.iter().map(|g| g.to_string()).collect::<Vec<_>>();
+        let entries = term1.iter().map(|g| (g, 1)).chain(term2.iter().map(|g| (g, 2)))
+            .map(|(g, term)| GradeEntry { value: g.to_string(), term, id: None, weight: None })
+            .collect();
+        Grade {
+            subject: subject.to_string(),
+            term1_grades: strings(term1),
+            term2_grades: strings(term2),
+            term1_final: term1_final.map(str::to_string),
+            term2_final: None,
+            annual: None,
+            entries,
+        }
+    }
+
+    fn subjects(grades: &[Grade]) -> Vec<&str> {
+        grades.iter().map(|g| g.subject.as_str()).collect()
+    }
+
+    fn with_ids(mut grade: Grade, ids: &[i64]) -> Grade {
+        for (entry, id) in grade.entries.iter_mut().zip(ids) {
+            entry.id = Some(*id);
+        }
+        grade
+    }
+
+    #[test]
+    fn test_diff_grades_by_id() {
+        let old = vec![
+            with_ids(grade("Математика", &["3", "5"], &["6"], None), &[10, 11, 20]),
+            with_ids(grade("Музика", &["6"], &[], None), &[12]),
+        ];
+        // 10 corrected 3 -> 5, 11 deleted, 21 added, Музика gone entirely
+        let new = vec![with_ids(grade("Математика", &["5"], &["6", "4"], None), &[10, 20, 21])];
+
+        let change = |subject: &str, term, old: &str, new: Option<&str>| GradeChange {
+            subject: subject.to_string(), term, old: old.to_string(), new: new.map(str::to_string),
+        };
+        assert_eq!(diff_grades(&old, &new), vec![
+            change("Математика", 1, "3", Some("5")),
+            change("Математика", 1, "5", None),
+            change("Музика", 1, "6", None),
+        ]);
+        assert!(diff_grades(&old, &old).is_empty());
+        assert!(diff_grades(&[], &new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_grades_without_ids() {
+        let mut old = grade("Математика", &["3", "5", "5"], &[], None);
+        old.entries.clear();
+        let added = grade("Математика", &["3", "5", "5", "6"], &[], None);
+        assert!(diff_grades(std::slice::from_ref(&old), &[added]).is_empty());
+
+        let corrected = grade("Математика", &["5", "5", "5"], &[], None);
+        let changes = diff_grades(std::slice::from_ref(&old), &[corrected]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].old.as_str(), changes[0].new.as_deref()), ("3", Some("5")));
+
+        let deleted = grade("Математика", &["5"], &[], None);
+        let changes = diff_grades(&[old], &[deleted]);
+        assert_eq!(changes.iter().map(|c| (c.old.as_str(), c.new.as_deref())).collect::<Vec<_>>(), vec![("3", None), ("5", None)]);
+    }
+
+    #[test]
+    fn test_weighted_average() {
+        let mut math = grade("Математика", &["6", "4", "3"], &["5", "Отличен"], None);
+        // No weights from the API: the simple mean
+        assert_eq!(math.weighted_average(1), Some(13.0 / 3.0));
+        assert!(!math.has_weights(1));
+
+        // The test (контролно) counts twice, the homework grade half
+        math.entries[0].weight = Some(2.0);
+        math.entries[2].weight = Some(0.5);
+        assert_eq!(math.weighted_average(1), Some((12.0 + 4.0 + 1.5) / 3.5));
+        assert!(math.has_weights(1));
+        assert!(!math.has_weights(2));
+
+        // Word grades count by their value
+        assert_eq!(math.weighted_average(2), Some(5.5));
+        assert_eq!(grade("Музика", &[], &["5", "отс."], None).weighted_average(2), Some(5.0));
+        assert_eq!(grade("Музика", &[], &[], None).weighted_average(1), None);
+    }
+
+    #[test]
+    fn test_parse_bg_grade() {
+        // Numeric, also as decimals from numerical_value
+        assert_eq!(parse_bg_grade("6"), Some(6.0));
+        assert_eq!(parse_bg_grade(" 2 "), Some(2.0));
+        assert_eq!(parse_bg_grade("5.5"), Some(5.5));
+        assert_eq!(parse_bg_grade("4,50"), Some(4.5));
+        assert_eq!(parse_bg_grade("1"), None);
+        assert_eq!(parse_bg_grade("7"), None);
+
+        // Words, in any case and with or without the number
+        assert_eq!(parse_bg_grade("Отличен"), Some(6.0));
+        assert_eq!(parse_bg_grade("Отличен 6"), Some(6.0));
+        assert_eq!(parse_bg_grade("Много добър"), Some(5.0));
+        assert_eq!(parse_bg_grade("Мн. добър 5"), Some(5.0));
+        assert_eq!(parse_bg_grade("мн.добър"), Some(5.0));
+        assert_eq!(parse_bg_grade("ДОБЪР"), Some(4.0));
+        assert_eq!(parse_bg_grade("Среден"), Some(3.0));
+        assert_eq!(parse_bg_grade("Задоволителен"), Some(3.0));
+        assert_eq!(parse_bg_grade("Слаб 2"), Some(2.0));
+
+        // "+" and "-" keep the grade's value
+        assert_eq!(parse_bg_grade("6-"), Some(6.0));
+        assert_eq!(parse_bg_grade("2+"), Some(2.0));
+        assert_eq!(parse_bg_grade("5 +"), Some(5.0));
+        assert_eq!(parse_bg_grade("6--"), Some(6.0));
+
+        assert_eq!(parse_bg_grade(""), None);
+        assert_eq!(parse_bg_grade("отс."), None);
+        assert_eq!(parse_bg_grade("+"), None);
+    }
+
+    #[test]
+    fn test_extract_weight() {
+        let detail = |weight: serde_json::Value| GradeDetail {
+            grade: Some("6".into()),
+            grade_raw: None,
+            numerical_value: None,
+            icon: None,
+            weight: Some(weight),
+        };
+        assert_eq!(extract_weight(&detail(serde_json::json!(2))), Some(2.0));
+        assert_eq!(extract_weight(&detail(serde_json::json!("1,5"))), Some(1.5));
+        assert_eq!(extract_weight(&detail(serde_json::json!(0))), None);
+        assert_eq!(extract_weight(&detail(serde_json::json!("контролно"))), None);
+        assert_eq!(extract_weight(&detail(serde_json::Value::Null)), None);
+    }
+
+    #[test]
+    fn test_grade_filter() {
+        let grades = vec![
+            grade("Математика", &["6", "5"], &["6"], Some("6")),
+            grade("Компютърно моделиране и информационни технологии", &["5"], &[], Some("5")),
+            grade("Музика", &[], &["6"], None),
+        ];
+
+        let by_subject = GradeFilter { subject: Some(" МАТ".to_string()), ..Default::default() };
+        assert_eq!(subjects(&by_subject.apply(grades.clone())), vec!["Математика"]);
+
+        let term2 = GradeFilter { term: Some(GradeTerm::Second), ..Default::default() };
+        let filtered = term2.apply(grades.clone());
+        assert_eq!(subjects(&filtered), vec!["Математика", "Музика"]);
+        assert!(filtered[0].term1_grades.is_empty() && filtered[0].term1_final.is_none());
+        assert!(filtered[0].entries.iter().all(|e| e.term == 2));
+
+        let annual = GradeFilter { term: Some(GradeTerm::Annual), ..Default::default() };
+        let filtered = annual.apply(grades.clone());
+        assert_eq!(subjects(&filtered), vec!["Математика", "Компютърно моделиране и информационни технологии"]);
+        assert_eq!(filtered[0].grade_count(), 1);
+
+        let min_count = GradeFilter { min_count: 3, ..Default::default() };
+        assert_eq!(subjects(&min_count.apply(grades.clone())), vec!["Математика"]);
+
+        let none = GradeFilter { subject: Some("химия".to_string()), term: Some(GradeTerm::First), min_count: 2 };
+        assert!(none.apply(grades.clone()).is_empty());
+        assert_eq!(none.to_string(), "subject \"химия\", term 1, min count 2");
+        assert_eq!(GradeFilter::default().apply(grades).len(), 3);
+    }
+}
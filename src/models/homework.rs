@@ -1,6 +1,26 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use time::Date;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::ScheduleHour;
+use crate::html;
+use crate::util::{minutes_of_day, parse_day};
+
+/// When lessons end if there's no schedule for the day, in minutes since
+/// midnight, unless `default_school_day_end` is set
+pub const DEFAULT_SCHOOL_DAY_END: i32 = 15 * 60;
+
+/// `default_school_day_end` from the config ("HH:MM") in minutes since midnight
+pub fn parse_school_day_end(value: &str) -> Result<i32, String> {
+    let invalid = || format!("invalid time '{}': expected HH:MM", value);
+    let (hour, minute) = value.trim().split_once(':').filter(|(_, m)| m.len() == 2).ok_or_else(invalid)?;
+    match (hour.parse::<i32>(), minute.parse::<i32>()) {
+        (Ok(hour), Ok(minute)) if (0..24).contains(&hour) && (0..60).contains(&minute) => Ok(hour * 60 + minute),
+        _ => Err(invalid()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Homework {
     pub id: Option<i64>,
     pub subject: String,
@@ -11,6 +31,25 @@ pub struct Homework {
     pub date_sort: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due_date_sort: Option<String>,
+    /// Link targets from the text, when it came as HTML
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    /// The text as the API sent it, kept when it had to be sanitized.
+    /// Only printed with `json --raw`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_html: Option<String>,
+}
+
+/// How soon homework is due, relative to today
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Urgency {
+    Overdue,
+    Today,
+    Tomorrow,
+    /// In two or three days
+    Soon,
+    Later,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,16 +93,99 @@ impl Homework {
             }
         });
 
+        let raw = item.homework_text.clone().unwrap_or_default();
+        let sanitized = html::sanitize(&raw);
+        let raw_html = html::has_tags(&raw).then_some(raw);
+
         Self {
             id: item.id,
             subject: subject.to_string(),
-            text: item.homework_text.clone().unwrap_or_default(),
+            text: sanitized.text,
             date: item.shi_date.clone().unwrap_or_default(),
             due_date: item.homework_due_date.clone(),
             date_sort: item.shi_date_for_sort.clone(),
             due_date_sort,
+            links: sanitized.links,
+            raw_html,
+        }
+    }
+
+    /// Case-insensitive partial match against the subject name
+    pub fn subject_matches(&self, query: &str) -> bool {
+        super::subject_matches(&self.subject, query)
+    }
+
+    /// Identity for duplicate detection. The same homework can be listed under
+    /// several course groups with different ids, so compare the content instead.
+    pub fn dedup_key(&self) -> (String, String, String) {
+        let text = self.text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        (self.date.clone(), self.subject.trim().to_string(), text)
+    }
+
+    /// None when the due date is missing or unparseable
+    pub fn urgency(&self, today: Date) -> Option<Urgency> {
+        let due = self.due_date_sort.as_deref().and_then(parse_day)?;
+        Some(match (due - today).whole_days() {
+            ..=-1 => Urgency::Overdue,
+            0 => Urgency::Today,
+            1 => Urgency::Tomorrow,
+            2..=3 => Urgency::Soon,
+            _ => Urgency::Later,
+        })
+    }
+
+    /// Still to be done: due after `today` (YYYY-MM-DD), due today while
+    /// school is still on, or without a due date
+    pub fn is_pending(&self, today: &str, school_day_over: bool) -> bool {
+        match self.due_date_sort.as_deref() {
+            Some(d) if d > today => true,
+            Some(d) if d < today => false,
+            Some(_) => !school_day_over,
+            None => true,
         }
     }
+
+    /// Drop duplicates and sort by assignment date, newest first
+    pub fn dedup_and_sort(homework: &mut Vec<Homework>) {
+        homework.sort_by_key(Homework::dedup_key);
+        homework.dedup_by_key(|hw| hw.dedup_key());
+        homework.sort_by(|a, b| b.date_sort.cmp(&a.date_sort));
+    }
+}
+
+/// Whether today's last lesson (per `schedule`) has ended at `now` (hour,
+/// minute), after which homework due today no longer counts as pending.
+/// Without a schedule school ends at `default_end` (minutes since midnight).
+pub fn school_day_over(schedule: &[ScheduleHour], now: (u8, u8), default_end: i32) -> bool {
+    let end = schedule.iter()
+        .map(|h| minutes_of_day(&h.to_time))
+        .max()
+        .unwrap_or(default_end);
+    now.0 as i32 * 60 + now.1 as i32 > end
+}
+
+/// What `json homework` keeps
+#[derive(Debug, Clone, Default)]
+pub struct HomeworkFilter {
+    /// Case-insensitive partial match against the subject
+    pub subject: Option<String>,
+    /// Due strictly before this day
+    pub due_before: Option<Date>,
+    /// Due strictly after this day
+    pub due_after: Option<Date>,
+    /// Only homework that `is_pending`
+    pub pending: bool,
+}
+
+impl HomeworkFilter {
+    /// Homework without a due date never passes a due date bound
+    pub fn matches(&self, homework: &Homework, today: &str, school_day_over: bool) -> bool {
+        let due = homework.due_date_sort.as_deref().and_then(parse_day);
+        self.subject.as_deref().is_none_or(|q| homework.subject_matches(q))
+            && self.due_before.is_none_or(|before| due.is_some_and(|d| d < before))
+            && self.due_after.is_none_or(|after| due.is_some_and(|d| d > after))
+            && (!self.pending || homework.is_pending(today, school_day_over))
+    }
 }
 
 #[cfg(test)]
@@ -102,10 +224,138 @@ mod tests {
         assert_eq!(hw.due_date_sort, None); // Invalid format returns None
     }
 
+    #[test]
+    fn test_html_text_is_sanitized() {
+        let raw = "<p>Прочетете&nbsp;<a href=\"https://example.com/lit\">текста</a></p><p>&#1047;&#1072;&#1076;&#1072;&#1095;&#1072; 2</p>";
+        let item = HomeworkItem {
+            id: Some(1),
+            homework_text: Some(raw.to_string()),
+            homework_due_date: None,
+            shi_date: None,
+            shi_date_for_sort: None,
+        };
+
+        let hw = Homework::from_item(&item, "БЕЛ");
+        assert_eq!(hw.text, "Прочетете текста\nЗадача 2");
+        assert_eq!(hw.links, vec!["https://example.com/lit"]);
+        assert_eq!(hw.raw_html.as_deref(), Some(raw));
+
+        // Plain text isn't kept twice
+        let plain = Homework::from_item(&HomeworkItem { homework_text: Some("Do math".to_string()), ..item }, "Math");
+        assert_eq!(plain.raw_html, None);
+        assert!(plain.links.is_empty());
+    }
+
+    #[test]
+    fn test_urgency_buckets() {
+        let due = |date: Option<&str>| Homework {
+            id: None,
+            subject: "Math".to_string(),
+            text: String::new(),
+            date: "20.02.2026".to_string(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: date.map(str::to_string),
+            links: Vec::new(),
+            raw_html: None,
+        };
+        let today = time::macros::date!(2026 - 02 - 27);
+
+        assert_eq!(due(Some("2026-02-26")).urgency(today), Some(Urgency::Overdue));
+        assert_eq!(due(Some("2026-02-27")).urgency(today), Some(Urgency::Today));
+        assert_eq!(due(Some("2026-02-28")).urgency(today), Some(Urgency::Tomorrow));
+        assert_eq!(due(Some("2026-03-02")).urgency(today), Some(Urgency::Soon));
+        assert_eq!(due(Some("2026-03-03")).urgency(today), Some(Urgency::Later));
+        assert_eq!(due(None).urgency(today), None);
+    }
+
+    #[test]
+    fn test_pending_and_filter() {
+        let due = |date: Option<&str>| Homework {
+            id: None,
+            subject: "Математика".to_string(),
+            text: String::new(),
+            date: "16.02.2026".to_string(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: date.map(str::to_string),
+            links: Vec::new(),
+            raw_html: None,
+        };
+        let hour = |to_time: &str| ScheduleHour {
+            hour_number: 1,
+            from_time: "12:00".to_string(),
+            to_time: to_time.to_string(),
+            subject: "Математика".to_string(),
+            teacher: None,
+            topic: None,
+            homework: None,
+            room: None,
+        };
+        let today = "2026-02-19";
+
+        assert!(!school_day_over(&[hour("13:10"), hour("12:40")], (13, 10), DEFAULT_SCHOOL_DAY_END));
+        assert!(school_day_over(&[hour("13:10")], (13, 11), DEFAULT_SCHOOL_DAY_END));
+        assert!(!school_day_over(&[], (14, 59), DEFAULT_SCHOOL_DAY_END));
+        assert!(school_day_over(&[], (15, 1), DEFAULT_SCHOOL_DAY_END));
+        assert!(school_day_over(&[], (13, 31), 13 * 60 + 30));
+        // A schedule wins over the default
+        assert!(!school_day_over(&[hour("14:00")], (13, 31), 13 * 60 + 30));
+
+        assert_eq!(parse_school_day_end("13:30"), Ok(13 * 60 + 30));
+        assert_eq!(parse_school_day_end(" 7:05 "), Ok(7 * 60 + 5));
+        for invalid in ["", "13", "24:00", "13:60", "13:5", "1pm"] {
+            assert!(parse_school_day_end(invalid).is_err(), "{}", invalid);
+        }
+
+        assert!(due(Some("2026-02-20")).is_pending(today, true));
+        assert!(due(Some(today)).is_pending(today, false));
+        assert!(!due(Some(today)).is_pending(today, true));
+        assert!(!due(Some("2026-02-18")).is_pending(today, false));
+        assert!(due(None).is_pending(today, true));
+
+        let this_week = HomeworkFilter {
+            due_before: Some(time::macros::date!(2026 - 02 - 23)),
+            pending: true,
+            ..Default::default()
+        };
+        assert!(this_week.matches(&due(Some("2026-02-20")), today, false));
+        assert!(!this_week.matches(&due(Some("2026-02-23")), today, false));
+        assert!(!this_week.matches(&due(Some("2026-02-18")), today, false));
+        assert!(!this_week.matches(&due(None), today, false));
+
+        let after = HomeworkFilter { due_after: Some(time::macros::date!(2026 - 02 - 19)), ..Default::default() };
+        assert!(!after.matches(&due(Some(today)), today, false));
+        assert!(after.matches(&due(Some("2026-02-20")), today, false));
+        let subject = HomeworkFilter { subject: Some("мат".to_string()), ..Default::default() };
+        assert!(subject.matches(&due(None), today, true));
+    }
+
+    #[test]
+    fn test_dedup_across_groups() {
+        let item = |id, text: &str| HomeworkItem {
+            id: Some(id),
+            homework_text: Some(text.to_string()),
+            homework_due_date: Some("25.02.2026".to_string()),
+            shi_date: Some("20.02.2026".to_string()),
+            shi_date_for_sort: Some("2026-02-20".to_string()),
+        };
+        let mut homework = vec![
+            Homework::from_item(&item(1, "Упр. 3 на стр. 12"), "Math"),
+            Homework::from_item(&item(2, "Essay"), "English"),
+            Homework::from_item(&item(7, "упр. 3  на стр. 12 "), "Math"),
+        ];
+
+        Homework::dedup_and_sort(&mut homework);
+
+        assert_eq!(homework.len(), 2);
+        assert_eq!(homework.iter().filter(|hw| hw.subject == "Math").count(), 1);
+    }
+
     #[test]
     fn test_homework_sorting_future_ascending() {
         // Future homework should be sorted by due_date ascending (soonest first)
-        let mut homework = vec![
+        let mut homework = [
             Homework {
                 id: Some(1),
                 subject: "Math".to_string(),
@@ -114,6 +364,8 @@ mod tests {
                 due_date: Some("28.02.2026".to_string()),
                 date_sort: Some("2026-02-20".to_string()),
                 due_date_sort: Some("2026-02-28".to_string()),
+                links: Vec::new(),
+                raw_html: None,
             },
             Homework {
                 id: Some(2),
@@ -123,6 +375,8 @@ mod tests {
                 due_date: Some("22.02.2026".to_string()),
                 date_sort: Some("2026-02-20".to_string()),
                 due_date_sort: Some("2026-02-22".to_string()),
+                links: Vec::new(),
+                raw_html: None,
             },
             Homework {
                 id: Some(3),
@@ -132,6 +386,8 @@ mod tests {
                 due_date: Some("25.02.2026".to_string()),
                 date_sort: Some("2026-02-20".to_string()),
                 due_date_sort: Some("2026-02-25".to_string()),
+                links: Vec::new(),
+                raw_html: None,
             },
         ];
 
@@ -150,7 +406,7 @@ mod tests {
     #[test]
     fn test_homework_sorting_past_descending() {
         // Past homework should be sorted by due_date descending (newest first)
-        let mut homework = vec![
+        let mut homework = [
             Homework {
                 id: Some(1),
                 subject: "Math".to_string(),
@@ -159,6 +415,8 @@ mod tests {
                 due_date: Some("12.02.2026".to_string()),
                 date_sort: Some("2026-02-10".to_string()),
                 due_date_sort: Some("2026-02-12".to_string()),
+                links: Vec::new(),
+                raw_html: None,
             },
             Homework {
                 id: Some(2),
@@ -168,6 +426,8 @@ mod tests {
                 due_date: Some("07.02.2026".to_string()),
                 date_sort: Some("2026-02-05".to_string()),
                 due_date_sort: Some("2026-02-07".to_string()),
+                links: Vec::new(),
+                raw_html: None,
             },
             Homework {
                 id: Some(3),
@@ -177,6 +437,8 @@ mod tests {
                 due_date: Some("17.02.2026".to_string()),
                 date_sort: Some("2026-02-15".to_string()),
                 due_date_sort: Some("2026-02-17".to_string()),
+                links: Vec::new(),
+                raw_html: None,
             },
         ];
 
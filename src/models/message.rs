@@ -1,6 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use time::Date;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::html;
+use crate::i18n::{self, Lang};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MessageFolder {
     pub id: i64,
     pub name: String,
@@ -9,6 +14,26 @@ pub struct MessageFolder {
     pub folder_unread_count: i32,
 }
 
+impl MessageFolder {
+    pub fn is_inbox(&self) -> bool {
+        self.system_folder_slug.as_deref() == Some("inbox")
+    }
+
+    /// Folder to ask `get_messenger_threads` for: None for the inbox, which is
+    /// the default thread list
+    pub fn thread_filter(&self) -> Option<i64> {
+        (!self.is_inbox()).then_some(self.id)
+    }
+
+    /// Whether `query` names this folder: its id, or its name or slug ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query == self.id.to_string()
+            || query == self.name.to_lowercase()
+            || self.system_folder_slug.as_deref().is_some_and(|slug| query == slug.to_lowercase())
+    }
+}
+
 /// Raw message within a thread (from API)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageRaw {
@@ -29,6 +54,7 @@ pub struct Message {
     pub body: String,
     pub sender_id: i64,
     pub sender_name: String,
+    /// As the API sent it; see `display_time`
     pub date: String,
     pub is_system: bool,
 }
@@ -37,36 +63,65 @@ impl Message {
     pub fn from_raw(raw: &MessageRaw) -> Self {
         Self {
             id: raw.id.unwrap_or(0),
-            body: raw.body.clone().unwrap_or_default(),
+            body: raw.body.as_deref().map(|b| html::sanitize(b).text).unwrap_or_default(),
             sender_id: raw.user_id.unwrap_or(0),
             sender_name: raw.user_names.clone()
                 .or_else(|| raw.user_name.clone())
                 .unwrap_or_default(),
-            date: Self::format_date(raw.created_at.as_deref()),
+            date: raw.created_at.clone().unwrap_or_default(),
             is_system: raw.is_system.unwrap_or(0) != 0,
         }
     }
 
-    fn format_date(date_str: Option<&str>) -> String {
-        match date_str {
-            Some(d) if d.len() >= 16 => {
-                // Format: "2026-02-18 09:47:18" -> "18.02.2026 09:47"
-                let parts: Vec<&str> = d.split(' ').collect();
-                if parts.len() >= 2 {
-                    let date_parts: Vec<&str> = parts[0].split('-').collect();
-                    if date_parts.len() == 3 {
-                        let time: String = parts[1].chars().take(5).collect();
-                        return format!("{}.{}.{} {}", date_parts[2], date_parts[1], date_parts[0], time);
-                    }
-                }
-                d.to_string()
-            }
-            Some(d) => d.to_string(),
-            None => String::new(),
+    /// When it was sent, in Sofia time relative to `today` ("вчера 09:10")
+    pub fn display_time(&self, today: Date, lang: Lang) -> String {
+        i18n::format_relative_datetime(&self.date, today, lang)
+    }
+}
+
+/// Thread participant as the thread's messages response lists them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantRaw {
+    pub id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub name: Option<String>,
+    pub names: Option<String>,
+    pub user_names: Option<String>,
+    pub roles_name: Option<String>,
+    pub role: Option<String>,
+}
+
+/// Someone in a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Participant {
+    pub id: i64,
+    pub name: String,
+    /// Role display name, e.g. "Класен ръководител"; may be empty
+    pub role: String,
+}
+
+impl Participant {
+    pub fn from_raw(raw: &ParticipantRaw) -> Self {
+        Self {
+            id: raw.user_id.or(raw.id).unwrap_or(0),
+            name: raw.names.clone()
+                .or_else(|| raw.user_names.clone())
+                .or_else(|| raw.name.clone())
+                .unwrap_or_default(),
+            role: raw.roles_name.clone()
+                .or_else(|| raw.role.clone())
+                .unwrap_or_default(),
         }
     }
 }
 
+/// An opened thread: its messages, oldest first, and who is in it
+#[derive(Debug, Clone, Default)]
+pub struct ThreadContents {
+    pub messages: Vec<Message>,
+    pub participants: Vec<Participant>,
+}
+
 /// Recipient for composing new messages (API returns "users" array)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecipientRaw {
@@ -103,7 +158,24 @@ impl Recipient {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The thread list when it comes wrapped in an object, as some schools get
+/// it, instead of as a bare array
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageThreadsResponse {
+    #[serde(alias = "threads")]
+    pub data: Option<Vec<MessageThreadRaw>>,
+}
+
+/// A thread's messages: under `messages` for most schools, `thread_messages`
+/// (or `data`) for others
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadMessagesResponse {
+    pub messages: Option<Vec<MessageRaw>>,
+    #[serde(alias = "data")]
+    pub thread_messages: Option<Vec<MessageRaw>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MessageThreadRaw {
     pub id: Option<i64>,
     pub subject: Option<String>,
@@ -117,7 +189,7 @@ pub struct MessageThreadRaw {
     pub thread_creator: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MessageThread {
     pub id: i64,
     pub subject: String,
@@ -143,19 +215,9 @@ impl MessageThread {
         }
     }
 
-    /// Format the update time for display (extract date or time)
-    pub fn display_time(&self) -> String {
-        // Format: "2026-02-18 09:47:18" -> extract relevant part
-        let parts: Vec<&str> = self.updated_at.split(' ').collect();
-        if parts.len() >= 2 {
-            let date_parts: Vec<&str> = parts[0].split('-').collect();
-            if date_parts.len() == 3 {
-                // Safely extract time (first 5 chars)
-                let time: String = parts[1].chars().take(5).collect();
-                return format!("{}.{} {}", date_parts[2], date_parts[1], time);
-            }
-        }
-        self.updated_at.clone()
+    /// Last update in Sofia time relative to `today` ("днес 14:32", "19.02 08:00")
+    pub fn display_time(&self, today: Date, lang: Lang) -> String {
+        i18n::format_relative_datetime(&self.updated_at, today, lang)
     }
 
     /// Truncate the last message for preview (UTF-8 safe)
@@ -169,3 +231,49 @@ impl MessageThread {
         }
     }
 }
+
+/// A message that was put aside unsent: a reply when `thread_id` is set,
+/// otherwise a new message
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Draft {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub body: String,
+    /// Selected recipient ids; replies go to the thread instead
+    #[serde(default)]
+    pub recipients: Vec<i64>,
+}
+
+impl Draft {
+    /// Nothing typed; recipients alone aren't worth keeping
+    pub fn is_empty(&self) -> bool {
+        self.subject.trim().is_empty() && self.body.trim().is_empty()
+    }
+}
+
+/// Saved drafts: at most one new message, and one reply per thread
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Drafts(Vec<Draft>);
+
+impl Drafts {
+    /// The new-message draft for None, else the reply draft for that thread
+    pub fn get(&self, thread_id: Option<i64>) -> Option<&Draft> {
+        self.0.iter().find(|d| d.thread_id == thread_id)
+    }
+
+    /// Replace the draft for the same thread; an empty draft just removes it
+    pub fn set(&mut self, draft: Draft) {
+        self.remove(draft.thread_id);
+        if !draft.is_empty() {
+            self.0.push(draft);
+        }
+    }
+
+    pub fn remove(&mut self, thread_id: Option<i64>) {
+        self.0.retain(|d| d.thread_id != thread_id);
+    }
+}
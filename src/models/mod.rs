@@ -6,6 +6,10 @@ pub mod notification;
 pub mod absence;
 pub mod message;
 pub mod feedback;
+pub mod weekly;
+pub mod exam;
+pub mod report;
+pub mod agenda;
 
 pub use student::*;
 pub use homework::*;
@@ -15,3 +19,7 @@ pub use notification::*;
 pub use absence::*;
 pub use message::*;
 pub use feedback::*;
+pub use weekly::*;
+pub use exam::*;
+pub use report::*;
+pub use agenda::*;
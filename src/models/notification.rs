@@ -1,6 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use time::Date;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::html;
+use crate::util::{parse_day, within_week};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Notification {
     pub id: Option<String>,
     pub title: String,
@@ -9,6 +14,30 @@ pub struct Notification {
     pub is_read: bool,
     pub notification_type: Option<String>,
     pub pupil_names: Option<String>,
+    /// What the notification is about, when the API says
+    #[serde(default, skip_serializing_if = "NotificationTarget::is_empty")]
+    pub target: NotificationTarget,
+}
+
+/// Identifiers a notification carries, so opening it can go straight to the
+/// student and the grade, homework or thread it's about
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationTarget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pupil_id: Option<i64>,
+    /// Subject name of a grade
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homework_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+}
+
+impl NotificationTarget {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +65,45 @@ pub struct NotificationRaw {
     pub pupil_names: Option<String>,
     pub pupil_name: Option<String>,
     pub pupils: Option<Vec<String>>,
+    // Identifiers of what it's about: numbers or numeric strings, at the top
+    // level or inside "params"
+    pub pupil_id: Option<serde_json::Value>,
+    pub course_name: Option<String>,
+    pub homework_id: Option<serde_json::Value>,
+    pub thread_id: Option<serde_json::Value>,
+    #[serde(alias = "payload")]
+    pub params: Option<serde_json::Value>,
+}
+
+impl NotificationRaw {
+    /// `key` at the top level, else inside "params"
+    fn param<'a>(&'a self, top: Option<&'a serde_json::Value>, key: &str) -> Option<&'a serde_json::Value> {
+        top.filter(|v| !v.is_null())
+            .or_else(|| self.params.as_ref()?.get(key).filter(|v| !v.is_null()))
+    }
+
+    fn id_param(&self, top: Option<&serde_json::Value>, key: &str) -> Option<i64> {
+        match self.param(top, key)? {
+            serde_json::Value::Number(n) => n.as_i64(),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn target(&self) -> NotificationTarget {
+        let course_name = self.course_name.clone().map(serde_json::Value::String);
+        let subject = self.param(course_name.as_ref(), "course_name")
+            .or_else(|| self.params.as_ref()?.get("subject"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        NotificationTarget {
+            pupil_id: self.id_param(self.pupil_id.as_ref(), "pupil_id"),
+            subject,
+            homework_id: self.id_param(self.homework_id.as_ref(), "homework_id"),
+            thread_id: self.id_param(self.thread_id.as_ref(), "thread_id"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,7 +112,31 @@ pub struct NotificationsResponse {
     pub notifications: Option<Vec<NotificationRaw>>,
 }
 
+impl NotificationsResponse {
+    /// Notifications from either field name, in the API's order
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.data.as_ref()
+            .or(self.notifications.as_ref())
+            .map(|raw| raw.iter().map(Notification::from_raw).collect())
+            .unwrap_or_default()
+    }
+}
+
 impl Notification {
+    /// A new-grade notification about the student with this first name from the
+    /// last 7 days. Grades carry no dates, so these are how new grades are found.
+    pub fn is_recent_grade_for(&self, first_name: &str, today: Date) -> bool {
+        self.is_grade_for(first_name) && parse_day(&self.date).is_some_and(|date| within_week(date, today))
+    }
+
+    /// A new-grade notification about the student with this first name, or
+    /// about nobody in particular
+    pub fn is_grade_for(&self, first_name: &str) -> bool {
+        let slug = self.notification_type.as_deref().unwrap_or_default();
+        (slug.contains("grade") || self.title.to_lowercase().contains("оценка"))
+            && self.pupil_names.as_deref().is_none_or(|names| names.contains(first_name))
+    }
+
     pub fn from_raw(raw: &NotificationRaw) -> Self {
         // Determine read status - seen_at being present means it's read
         let is_read = raw.seen_at.is_some()
@@ -58,11 +150,14 @@ impl Notification {
 
         Self {
             id: raw.id.clone(),
-            title: raw.text.clone()
-                .or_else(|| raw.title.clone())
-                .or_else(|| raw.subject.clone())
+            title: raw.text.as_deref()
+                .or(raw.title.as_deref())
+                .or(raw.subject.as_deref())
+                .map(|t| html::sanitize(t).text)
                 .unwrap_or_else(|| "No title".to_string()),
-            body: raw.body.clone().or_else(|| raw.message.clone()),
+            body: raw.body.as_deref()
+                .or(raw.message.as_deref())
+                .map(|b| html::sanitize(b).text),
             date: raw.created_at.clone()
                 .or_else(|| raw.date.clone())
                 .unwrap_or_default(),
@@ -70,6 +165,23 @@ impl Notification {
             notification_type: raw.notification_trigger_slug.clone()
                 .or_else(|| raw.notification_type.clone()),
             pupil_names,
+            target: raw.target(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_in_title_and_body() {
+        let raw: NotificationRaw = serde_json::from_value(serde_json::json!({
+            "text": "Нова оценка по &quot;Математика&quot;",
+            "body": "Мария получи <b>Отличен&nbsp;6</b><br>Браво!",
+        })).unwrap();
+        let notification = Notification::from_raw(&raw);
+        assert_eq!(notification.title, "Нова оценка по \"Математика\"");
+        assert_eq!(notification.body.as_deref(), Some("Мария получи Отличен 6\nБраво!"));
+    }
+}
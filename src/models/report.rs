@@ -0,0 +1,169 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::{Absence, Grade};
+
+/// Grades and absences of one subject side by side (`json report`, 'c' on
+/// the Grades tab)
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct SubjectReport {
+    pub subject: String,
+    /// Weighted mean of the current grades of both terms; None without any
+    pub average: Option<f64>,
+    /// Current grades, finals excluded
+    pub grade_count: usize,
+    pub excused: usize,
+    pub unexcused: usize,
+    /// Higher is worse; see `risk_score`
+    pub risk: f64,
+}
+
+/// Each point the average is below 6 counts twice, each unexcused absence
+/// once and each excused one a quarter. Without grades only the absences count.
+pub fn risk_score(average: Option<f64>, excused: usize, unexcused: usize) -> f64 {
+    let grades = average.map_or(0.0, |a| (6.0 - a).max(0.0) * 2.0);
+    let score = grades + unexcused as f64 + excused as f64 * 0.25;
+    (score * 100.0).round() / 100.0
+}
+
+/// One row per subject with grades or absences, riskiest first. Absences
+/// name subjects by their short name ("ФВС") and grades by the full one, so
+/// a short name also matches the initials of a full name.
+pub fn subject_report(grades: &[Grade], absences: &[Absence]) -> Vec<SubjectReport> {
+    let mut rows: Vec<SubjectReport> = grades.iter()
+        .filter(|g| g.has_grades())
+        .map(|g| SubjectReport {
+            subject: g.subject.clone(),
+            average: g.overall_average(),
+            grade_count: g.items().len(),
+            excused: 0,
+            unexcused: 0,
+            risk: 0.0,
+        })
+        .collect();
+
+    for absence in absences {
+        let index = match rows.iter().position(|r| same_subject(&r.subject, &absence.subject)) {
+            Some(index) => index,
+            None => {
+                rows.push(SubjectReport {
+                    subject: absence.subject.clone(),
+                    average: None,
+                    grade_count: 0,
+                    excused: 0,
+                    unexcused: 0,
+                    risk: 0.0,
+                });
+                rows.len() - 1
+            }
+        };
+        if absence.is_excused {
+            rows[index].excused += 1;
+        } else {
+            rows[index].unexcused += 1;
+        }
+    }
+
+    for row in &mut rows {
+        row.risk = risk_score(row.average, row.excused, row.unexcused);
+    }
+    rows.sort_by(|a, b| b.risk.total_cmp(&a.risk).then_with(|| a.subject.cmp(&b.subject)));
+    rows
+}
+
+/// Same name ignoring case, or one is the initials of the other
+fn same_subject(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim().to_lowercase(), b.trim().to_lowercase());
+    a == b || initials(&a).is_some_and(|i| i == b) || initials(&b).is_some_and(|i| i == a)
+}
+
+/// "физическо възпитание и спорт" -> "фвс"; one-letter words such as "и"
+/// don't count. None for single words, which have no meaningful initials.
+fn initials(name: &str) -> Option<String> {
+    let words: Vec<&str> = name.split_whitespace().filter(|w| w.chars().count() > 1).collect();
+    (words.len() > 1).then(|| words.iter().filter_map(|w| w.chars().next()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GradeEntry;
+
+    fn grade(subject: &str, values: &[&str]) -> Grade {
+        Grade {
+            subject: subject.to_string(),
+            term1_grades: values.iter().map(|v| v.to_string()).collect(),
+            term2_grades: Vec::new(),
+            term1_final: None,
+            term2_final: None,
+            annual: None,
+            entries: Vec::new(),
+        }
+    }
+
+    fn absence(subject: &str, is_excused: bool) -> Absence {
+        Absence {
+            id: "1".to_string(),
+            date: "10.02.2026".to_string(),
+            date_sort: "2026-02-10".to_string(),
+            hour: 1,
+            subject: subject.to_string(),
+            is_excused,
+            excuse_reason: None,
+            created_by: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_risk_score() {
+        assert_eq!(risk_score(Some(6.0), 0, 0), 0.0);
+        assert_eq!(risk_score(Some(3.5), 4, 2), 5.0 + 2.0 + 1.0);
+        assert_eq!(risk_score(None, 2, 1), 1.5);
+    }
+
+    #[test]
+    fn test_report_joins_grades_and_absences() {
+        let grades = vec![
+            grade("Математика", &["6", "6"]),
+            grade("Физическо възпитание и спорт", &["5"]),
+            grade("Български език и литература", &["3", "4"]),
+            grade("Музика", &[]),
+        ];
+        let absences = vec![
+            absence("ФВС", false),
+            absence("ФВС", true),
+            absence("математика", true),
+            absence("БЕЛ", false),
+            absence("БЕЛ", false),
+            absence("Химия", false),
+        ];
+
+        let report = subject_report(&grades, &absences);
+        let rows: Vec<(&str, Option<f64>, usize, usize, usize)> = report.iter()
+            .map(|r| (r.subject.as_str(), r.average, r.grade_count, r.excused, r.unexcused))
+            .collect();
+        assert_eq!(rows, vec![
+            ("Български език и литература", Some(3.5), 2, 0, 2),
+            ("Физическо възпитание и спорт", Some(5.0), 1, 1, 1),
+            // Absences of a subject without grades get a row of their own
+            ("Химия", None, 0, 0, 1),
+            ("Математика", Some(6.0), 2, 1, 0),
+        ]);
+        assert_eq!(report[0].risk, 7.0);
+    }
+
+    #[test]
+    fn test_report_weights_and_ties() {
+        let mut weighted = grade("Химия", &["6", "3"]);
+        weighted.entries = vec![
+            GradeEntry { value: "6".to_string(), term: 1, id: Some(1), weight: None },
+            GradeEntry { value: "3".to_string(), term: 2, id: Some(2), weight: Some(2.0) },
+        ];
+        let report = subject_report(&[weighted, grade("Биология", &["6"]), grade("Астрономия", &["6"])], &[]);
+        assert_eq!(report[0].average, Some(4.0));
+        // Equal risk keeps the subjects in alphabetical order
+        assert_eq!(report[1].subject, "Астрономия");
+        assert_eq!(report[2].subject, "Биология");
+    }
+}
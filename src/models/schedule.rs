@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScheduleHour {
     pub hour_number: i32,
     pub from_time: String,
@@ -31,7 +32,7 @@ pub struct ScheduleResponse {
     pub data: Option<Vec<ScheduleHourRaw>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Event {
     pub id: Option<i64>,
     pub title: String,
@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Student {
     pub id: i64,
     pub name: String,
@@ -27,6 +28,52 @@ pub struct PupilsResponse {
     pub pupils: Option<Vec<ChildPupil>>,
 }
 
+impl PupilsResponse {
+    /// Students from either shape: parents get `childPupils`, pupil accounts get `pupils`.
+    /// Sorted by name for consistent ordering.
+    pub fn students(&self) -> Vec<Student> {
+        let mut students: Vec<Student> = match &self.child_pupils {
+            Some(children) if !children.is_empty() => children.iter()
+                .map(|(id, pupil)| Student::from_child_pupil(id, pupil))
+                .collect(),
+            _ => self.pupils.iter()
+                .flatten()
+                .map(|pupil| Student::from_child_pupil("", pupil))
+                .collect(),
+        };
+        students.sort_by(|a, b| a.name.cmp(&b.name));
+        students
+    }
+}
+
+/// What kind of account is logged in, as far as the app cares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Parent,
+    Pupil,
+    /// Teachers and other school staff
+    Teacher,
+}
+
+impl Role {
+    /// Classify a role by its display name (the API returns localized names)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.contains("родител") || name.contains("parent") {
+            Some(Role::Parent)
+        } else if name.contains("учени") || name.contains("pupil") || name.contains("student") {
+            Some(Role::Pupil)
+        } else if ["учител", "ръководител", "директор", "teacher", "director", "admin"]
+            .iter()
+            .any(|r| name.contains(r))
+        {
+            Some(Role::Teacher)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserRole {
     pub role_id: Option<i64>,
@@ -62,3 +109,33 @@ impl Student {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_from_name() {
+        assert_eq!(Role::from_name("Родител"), Some(Role::Parent));
+        assert_eq!(Role::from_name("Ученик"), Some(Role::Pupil));
+        assert_eq!(Role::from_name("Учител"), Some(Role::Teacher));
+        assert_eq!(Role::from_name("Класен ръководител"), Some(Role::Teacher));
+        assert_eq!(Role::from_name("Teacher"), Some(Role::Teacher));
+        assert_eq!(Role::from_name("Гост"), None);
+    }
+
+    #[test]
+    fn test_students_from_pupils_list() {
+        let response: PupilsResponse = serde_json::from_value(serde_json::json!({
+            "childPupils": {},
+            "pupils": [
+                { "target_id": 7, "target_name": "Петър", "class_year_name": "5а" },
+                { "target_id": 3, "target_name": "Ана" },
+            ],
+        })).unwrap();
+
+        let students = response.students();
+        assert_eq!(students.iter().map(|s| s.id).collect::<Vec<_>>(), [3, 7]);
+        assert_eq!(students[1].class_name.as_deref(), Some("5а"));
+    }
+}
@@ -0,0 +1,92 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::{Absence, Feedback};
+use crate::util;
+
+/// Absences and feedbacks recorded in the last 7 days
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct WeeklySummary {
+    pub absences: usize,
+    pub unexcused_absences: usize,
+    pub positive_feedbacks: usize,
+    pub negative_feedbacks: usize,
+}
+
+impl WeeklySummary {
+    /// Count entries dated within the week up to `today` (YYYY-MM-DD).
+    /// Entries with unparseable dates are left out.
+    pub fn compute(absences: &[Absence], feedbacks: &[Feedback], today: &str) -> Self {
+        let Some(today) = util::parse_day(today) else {
+            return Self::default();
+        };
+        let this_week = |date: &str| util::parse_day(date).is_some_and(|d| util::within_week(d, today));
+
+        let absences: Vec<&Absence> = absences.iter()
+            .filter(|a| this_week(&a.date_sort) || this_week(&a.date))
+            .collect();
+        let feedbacks: Vec<&Feedback> = feedbacks.iter().filter(|f| this_week(&f.date)).collect();
+
+        Self {
+            absences: absences.len(),
+            unexcused_absences: absences.iter().filter(|a| !a.is_excused).count(),
+            positive_feedbacks: feedbacks.iter().filter(|f| f.is_positive).count(),
+            negative_feedbacks: feedbacks.iter().filter(|f| !f.is_positive).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn absence(date_sort: &str, is_excused: bool) -> Absence {
+        Absence {
+            id: date_sort.to_string(),
+            date: String::new(),
+            date_sort: date_sort.to_string(),
+            hour: 1,
+            subject: "Математика".to_string(),
+            is_excused,
+            excuse_reason: None,
+            created_by: None,
+            created_at: None,
+        }
+    }
+
+    fn feedback(date: &str, is_positive: bool) -> Feedback {
+        Feedback {
+            id: 1,
+            badge_name: "Badge".to_string(),
+            badge_icon: None,
+            comment: None,
+            is_positive,
+            date: date.to_string(),
+            teacher: "Петрова".to_string(),
+            subject: "Математика".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_counts_only_the_last_seven_days() {
+        let absences = [
+            absence("2026-02-19", false),
+            absence("2026-02-13", true),
+            absence("2026-02-12", false), // 7 days ago: last week
+            absence("2026-02-20", false), // future
+        ];
+        let feedbacks = [
+            feedback("18.02.2026", true),
+            feedback("16.02.2026 10:30", false),
+            feedback("01.02.2026", true),
+            feedback("", true),
+        ];
+        assert_eq!(WeeklySummary::compute(&absences, &feedbacks, "2026-02-19"), WeeklySummary {
+            absences: 2,
+            unexcused_absences: 1,
+            positive_feedbacks: 1,
+            negative_feedbacks: 1,
+        });
+        assert_eq!(WeeklySummary::compute(&absences, &feedbacks, "garbage"), WeeklySummary::default());
+    }
+}
@@ -0,0 +1,961 @@
+//! Payloads emitted by `shkolo json` commands, inside the `ApiResponse` envelope.
+//! The commands serialize these types and `json schema` derives its output from
+//! them, so the two can't drift. Field names are part of the wire format.
+
+use clap::ValueEnum;
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::Serialize;
+use time::Date;
+
+use crate::api::ApiResponse;
+use crate::cache::age_string;
+use crate::models::*;
+use crate::service::CacheInfo;
+use crate::style;
+
+/// Where one entry's data came from. Flattened into per-student entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct CacheState {
+    /// Whether this entry was served from the local cache
+    pub cached: bool,
+    /// Age of the cached data ("12m ago"); absent when fetched live
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<String>,
+    #[serde(skip)]
+    timestamp: Option<i64>,
+}
+
+/// How one category of a student's data loaded
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "state", content = "error", rename_all = "snake_case")]
+pub enum LoadStatus {
+    /// Loaded, with something in it
+    Loaded,
+    /// Loaded with nothing in it, or not loaded yet
+    #[default]
+    Empty,
+    /// Older cached data, because the fetch failed or the API is unreachable
+    Stale,
+    /// The fetch failed and there is nothing to show
+    Failed(String),
+}
+
+impl LoadStatus {
+    pub fn loaded(empty: bool) -> Self {
+        if empty { LoadStatus::Empty } else { LoadStatus::Loaded }
+    }
+
+    /// A failed fetch: Stale while older data is still shown
+    pub fn failed(error: &anyhow::Error, has_older: bool) -> Self {
+        if has_older { LoadStatus::Stale } else { LoadStatus::Failed(error.to_string()) }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            LoadStatus::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// `json all`: how each category loaded
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct CategoryStatus {
+    pub homework: LoadStatus,
+    pub grades: LoadStatus,
+    pub schedule: LoadStatus,
+    pub absences: LoadStatus,
+    pub feedbacks: LoadStatus,
+    pub events: LoadStatus,
+}
+
+impl CategoryStatus {
+    fn all(&self) -> [&LoadStatus; 6] {
+        [&self.homework, &self.grades, &self.schedule, &self.absences, &self.feedbacks, &self.events]
+    }
+
+    /// The first error, when no category loaded at all
+    pub fn all_failed(&self) -> Option<&str> {
+        let statuses = self.all();
+        statuses.iter().all(|s| s.error().is_some()).then(|| statuses[0].error()).flatten()
+    }
+}
+
+impl From<CacheInfo> for CacheState {
+    fn from(info: CacheInfo) -> Self {
+        Self::new(info.cached, info.cached_at)
+    }
+}
+
+impl CacheState {
+    pub fn new(cached: bool, cached_at: Option<i64>) -> Self {
+        Self {
+            cached,
+            cached_at: cached_at.filter(|_| cached).map(age_string),
+            timestamp: cached_at.filter(|_| cached),
+        }
+    }
+
+    /// When the cached data was saved; None when fetched live
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    /// State of several parts together: cached only when every part was,
+    /// dated by the oldest cached part
+    pub fn merge<'a>(states: impl IntoIterator<Item = &'a CacheState>) -> Self {
+        let mut any = false;
+        let mut all_cached = true;
+        let mut oldest: Option<i64> = None;
+        for state in states {
+            any = true;
+            all_cached &= state.cached;
+            if let Some(ts) = state.timestamp {
+                oldest = Some(oldest.map_or(ts, |o| o.min(ts)));
+            }
+        }
+
+        let cached = any && all_cached;
+        Self {
+            cached,
+            cached_at: oldest.map(age_string),
+            timestamp: oldest,
+        }
+    }
+}
+
+/// `json homework`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentHomework {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Newest assignment first
+    pub homework: Vec<HomeworkEntry>,
+    /// Courses whose homework could not be fetched ("<course>: <error>")
+    pub errors: Vec<String>,
+    /// Set when `--subject` matched none of the student's homework
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// An assignment with how soon it is due
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HomeworkEntry {
+    #[serde(flatten)]
+    pub homework: Homework,
+    /// Relative to the day of the request; absent without a due date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urgency: Option<Urgency>,
+    /// The text announces a test (see `json exams`)
+    pub is_exam: bool,
+    /// Due later, due today before the last lesson ends, or without a due date
+    pub is_pending: bool,
+}
+
+impl HomeworkEntry {
+    pub fn new(homework: Homework, today: Date, school_day_over: bool, keywords: &ExamKeywords) -> Self {
+        let urgency = homework.urgency(today);
+        let is_exam = homework.is_exam(keywords);
+        let is_pending = homework.is_pending(&today.to_string(), school_day_over);
+        Self { homework, urgency, is_exam, is_pending }
+    }
+}
+
+/// Drop the homework texts kept as the API sent them; only `json --raw`
+/// prints those
+pub fn strip_raw_html<'a>(homework: impl IntoIterator<Item = &'a mut Homework>) {
+    homework.into_iter().for_each(|hw| hw.raw_html = None);
+}
+
+/// `json grades`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentGrades {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// One entry per subject
+    pub grades: Vec<Grade>,
+    /// Set when the `--subject`/`--term`/`--min-count` filters left no subject
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// `json schedule`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentSchedule {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Day of the schedule, YYYY-MM-DD
+    pub date: String,
+    /// Lessons in hour order
+    pub schedule: Vec<ScheduleHour>,
+}
+
+/// `json summary`: one entry per student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentSummary {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Lessons on the requested day (today by default)
+    pub today_schedule: Vec<ScheduleHour>,
+    /// The five most recent assignments
+    pub recent_homework: Vec<Homework>,
+    /// Number of subjects with grades
+    pub grades_count: usize,
+    /// Absences and feedbacks from the last 7 days
+    pub this_week: WeeklySummary,
+    /// Unexcused absences whose excuse deadline ends within 48 hours
+    pub expiring_excuses: usize,
+}
+
+/// `json all`: everything about one student, unabridged
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentAll {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Newest assignment first
+    pub homework: Vec<HomeworkEntry>,
+    /// One entry per subject
+    pub grades: Vec<Grade>,
+    /// Day of the schedule, YYYY-MM-DD
+    pub date: String,
+    /// Lessons in hour order
+    pub schedule: Vec<ScheduleHour>,
+    pub absences: Vec<Absence>,
+    pub feedbacks: Vec<Feedback>,
+    /// Events and invitations, including announced tests
+    pub events: Vec<Event>,
+    pub counts: StudentCounts,
+    /// How each category loaded; a failed one is left empty above
+    pub status: CategoryStatus,
+    /// Courses whose homework could not be fetched ("<course>: <error>")
+    pub errors: Vec<String>,
+}
+
+/// Totals for `json all`
+#[derive(Debug, Default, PartialEq, Serialize, JsonSchema)]
+pub struct StudentCounts {
+    pub homework: usize,
+    /// Number of subjects with grades
+    pub graded_subjects: usize,
+    /// Current grades in both terms, finals excluded
+    pub grades: usize,
+    pub lessons: usize,
+    pub absences: usize,
+    pub unexcused_absences: usize,
+    pub positive_feedbacks: usize,
+    pub negative_feedbacks: usize,
+    pub events: usize,
+    /// Events that are tests or exams
+    pub tests: usize,
+}
+
+impl StudentAll {
+    /// Fills in the counts
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        student: Student,
+        cache: CacheState,
+        homework: Vec<HomeworkEntry>,
+        grades: Vec<Grade>,
+        date: String,
+        schedule: Vec<ScheduleHour>,
+        absences: Vec<Absence>,
+        feedbacks: Vec<Feedback>,
+        events: Vec<Event>,
+        status: CategoryStatus,
+        errors: Vec<String>,
+    ) -> Self {
+        let positive_feedbacks = feedbacks.iter().filter(|f| f.is_positive).count();
+        let unexcused_absences = absences.iter().filter(|a| !a.is_excused).count();
+        let counts = StudentCounts {
+            homework: homework.len(),
+            graded_subjects: grades.len(),
+            grades: grades.iter().map(|g| g.term1_grades.len() + g.term2_grades.len()).sum(),
+            lessons: schedule.len(),
+            absences: absences.len(),
+            unexcused_absences,
+            positive_feedbacks,
+            negative_feedbacks: feedbacks.len() - positive_feedbacks,
+            events: events.len(),
+            tests: events.iter().filter(|e| e.is_test).count(),
+        };
+        Self { student, cache, homework, grades, date, schedule, absences, feedbacks, events, counts, status, errors }
+    }
+}
+
+/// `json exams`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentExams {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Tests from today on, soonest first
+    pub exams: Vec<Exam>,
+}
+
+/// `json report`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentReport {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Average grade and absences per subject, riskiest first: a low average
+    /// and many unexcused absences come before the rest
+    pub subjects: Vec<SubjectReport>,
+}
+
+/// `agenda --json`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentAgenda {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    /// Every day from today on, free days included
+    pub days: Vec<AgendaDay>,
+}
+
+/// `json absences`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentAbsences {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    pub absences: Vec<AbsenceEntry>,
+    /// Number of absences
+    pub total: usize,
+    /// Number of excused absences
+    pub excused: usize,
+    /// Number of unexcused absences
+    pub unexcused: usize,
+    /// Unexcused absences whose excuse deadline ends within 48 hours
+    pub expiring: usize,
+}
+
+/// An absence with the last day it can be excused
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AbsenceEntry {
+    #[serde(flatten)]
+    pub absence: Absence,
+    /// YYYY-MM-DD, estimated as `absence_excuse_days` after the absence was entered;
+    /// absent once excused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excuse_deadline: Option<String>,
+    /// Unexcused and the deadline ends today or tomorrow
+    pub excuse_deadline_soon: bool,
+}
+
+impl StudentAbsences {
+    pub fn new(student: Student, cache: CacheState, absences: Vec<Absence>, today: Date, excuse_days: i64) -> Self {
+        let excused = absences.iter().filter(|a| a.is_excused).count();
+        let absences: Vec<AbsenceEntry> = absences.into_iter()
+            .map(|absence| AbsenceEntry {
+                excuse_deadline: absence.excuse_deadline(excuse_days).map(|d| d.to_string()),
+                excuse_deadline_soon: absence.excuse_deadline_soon(today, excuse_days),
+                absence,
+            })
+            .collect();
+        Self {
+            student,
+            cache,
+            total: absences.len(),
+            excused,
+            unexcused: absences.len() - excused,
+            expiring: absences.iter().filter(|a| a.excuse_deadline_soon).count(),
+            absences,
+        }
+    }
+}
+
+/// `json feedbacks`: one entry per selected student
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StudentFeedbacks {
+    pub student: Student,
+    #[serde(flatten)]
+    pub cache: CacheState,
+    pub feedbacks: Vec<Feedback>,
+    /// Number of feedbacks
+    pub total: usize,
+    /// Number of positive feedbacks (praise)
+    pub positive: usize,
+    /// Number of negative feedbacks (remarks)
+    pub negative: usize,
+}
+
+impl StudentFeedbacks {
+    pub fn new(student: Student, cache: CacheState, feedbacks: Vec<Feedback>) -> Self {
+        let positive = feedbacks.iter().filter(|f| f.is_positive).count();
+        Self {
+            student,
+            cache,
+            total: feedbacks.len(),
+            positive,
+            negative: feedbacks.len() - positive,
+            feedbacks,
+        }
+    }
+}
+
+/// `json notifications`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct NotificationList {
+    pub notifications: Vec<Notification>,
+    /// Number of notifications
+    pub total: usize,
+    /// Number of unread notifications
+    pub unread: usize,
+}
+
+impl NotificationList {
+    pub fn new(notifications: Vec<Notification>) -> Self {
+        Self {
+            total: notifications.len(),
+            unread: notifications.iter().filter(|n| !n.is_read).count(),
+            notifications,
+        }
+    }
+}
+
+/// `json messages`. Each part is fetched separately; a part that failed is
+/// replaced by its `*_error` field.
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct MessagesOverview {
+    /// Messenger folders (inbox, sent, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folders: Option<Vec<MessageFolder>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folders_error: Option<String>,
+    /// Inbox threads as returned by the API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<Vec<MessageThreadRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads_error: Option<String>,
+    /// Whether the account may start new conversations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_error: Option<String>,
+}
+
+/// `json messages list`: the threads of one folder
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MessageList {
+    /// The folder listed; absent when folders couldn't be loaded and the inbox was listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<MessageFolder>,
+    pub total: usize,
+    pub unread: usize,
+    pub threads: Vec<MessageThread>,
+}
+
+impl MessageList {
+    pub fn new(folder: Option<MessageFolder>, threads: Vec<MessageThread>) -> Self {
+        Self {
+            folder,
+            total: threads.len(),
+            unread: threads.iter().filter(|t| t.is_unread).count(),
+            threads,
+        }
+    }
+}
+
+/// Failure of a raw debugging command (`json thread`, `json feedbacks-raw`)
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DebugError {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<i64>,
+}
+
+impl DebugError {
+    pub fn new(error: impl ToString) -> Self {
+        Self { error: error.to_string(), thread_id: None, student_id: None }
+    }
+}
+
+/// `json --format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Pretty,
+    /// The whole response on one line
+    Compact,
+    /// One compact line per entry of `data`
+    Jsonl,
+    /// Plain-text digest, `json summary` only
+    Llm,
+    /// Aligned text tables, for students, grades and schedule
+    Table,
+}
+
+/// Environment variable with the `json` format to use without `--format`;
+/// it takes precedence over `default_json_format` in the config
+pub const JSON_FORMAT_ENV: &str = "SHKOLO_JSON_FORMAT";
+
+/// The `json` format when `--format` isn't given: `env` (from
+/// `JSON_FORMAT_ENV`), then `config`, then pretty. A value that isn't a
+/// format name is an error rather than silently pretty.
+pub fn default_format(env: Option<&str>, config: Option<&str>) -> anyhow::Result<OutputFormat> {
+    let parse = |value: &str, source: &str| {
+        OutputFormat::from_str(value.trim(), true).map_err(|_| {
+            let names: Vec<String> = OutputFormat::value_variants().iter()
+                .filter_map(|f| f.to_possible_value())
+                .map(|v| v.get_name().to_string())
+                .collect();
+            anyhow::anyhow!("{}: unknown format \"{}\" (expected {})", source, value.trim(), names.join(", "))
+        })
+    };
+    match (env.filter(|v| !v.trim().is_empty()), config) {
+        (Some(value), _) => parse(value, JSON_FORMAT_ENV),
+        (None, Some(value)) => parse(value, "default_json_format in ui_config.json"),
+        (None, None) => Ok(OutputFormat::Pretty),
+    }
+}
+
+/// A response as JSON in `format`. `jsonl` keeps a response that isn't a
+/// list on one line; formats that aren't JSON fall back to pretty.
+pub fn render_json<T: Serialize>(data: &T, format: OutputFormat) -> serde_json::Result<String> {
+    match format {
+        OutputFormat::Compact | OutputFormat::Jsonl => serde_json::to_string(data),
+        OutputFormat::Pretty | OutputFormat::Llm | OutputFormat::Table => serde_json::to_string_pretty(data),
+    }
+}
+
+/// `jsonl` for a list response: each entry on its own line, without the envelope
+pub fn render_jsonl<T: Serialize>(entries: &[T]) -> serde_json::Result<String> {
+    let lines = entries.iter().map(serde_json::to_string).collect::<serde_json::Result<Vec<_>>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// Per-subject absences as CSV, one row per subject under a header row
+pub fn absences_csv(subjects: &[SubjectAbsences]) -> String {
+    let mut csv = String::from("subject,term1_excused,term1_unexcused,term2_excused,term2_unexcused,total\n");
+    for s in subjects {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&s.subject), s.excused[0], s.unexcused[0], s.excused[1], s.unexcused[1], s.total()
+        ));
+    }
+    csv
+}
+
+/// `agenda` without --json: each student's days under a heading, the items
+/// of a day one per line and "—" on a day with nothing
+pub fn agenda_text(entries: &[StudentAgenda], color: bool) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let heading = match &entry.student.class_name {
+            Some(class) => format!("{} ({})", entry.student.name, class),
+            None => entry.student.name.clone(),
+        };
+        out.push_str(&style::paint("1", &heading, color));
+        out.push('\n');
+        for day in &entry.days {
+            let date = crate::util::parse_day(&day.date)
+                .map(|d| format!("{} {:02}.{:02}.{}", &d.weekday().to_string()[..3], d.day(), d.month() as u8, d.year()))
+                .unwrap_or_else(|| day.date.clone());
+            out.push_str(&format!("  {}\n", style::paint("1", &date, color)));
+            if day.items.is_empty() {
+                out.push_str("    —\n");
+            }
+            for item in &day.items {
+                let (label, code) = match item.kind {
+                    AgendaKind::Exam => ("exam", Some("31")),
+                    AgendaKind::Event => ("event", Some("36")),
+                    AgendaKind::Homework => ("homework", None),
+                };
+                let label = format!("{:<8}", label);
+                let label = code.map_or_else(|| label.clone(), |code| style::paint(code, &label, color));
+                // Only the first line of a long homework text
+                let text = item.text.lines().next().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ");
+                match &item.subject {
+                    Some(subject) => out.push_str(&format!("    {}  {}: {}\n", label, subject, text)),
+                    None => out.push_str(&format!("    {}  {}\n", label, text)),
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Quoted when it has a comma, quote or line break, with quotes doubled
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `json --max-age-policy`: what to do about data older than `--max-age`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MaxAgePolicy {
+    /// Fetch it again; a warning if only old data could be had
+    Refetch,
+    /// Print it with a warning on stderr
+    Warn,
+    /// Print it, warn and exit with code 6
+    Fail,
+}
+
+/// Commands with a declared output schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaKind {
+    Students,
+    Homework,
+    Grades,
+    Schedule,
+    Summary,
+    All,
+    Exams,
+    Report,
+    Agenda,
+    Absences,
+    Feedbacks,
+    Notifications,
+    Messages,
+    MessageList,
+}
+
+/// JSON Schema of the full response (envelope included) for a command
+pub fn schema(kind: SchemaKind) -> RootSchema {
+    match kind {
+        SchemaKind::Students => schema_for!(ApiResponse<Vec<Student>>),
+        SchemaKind::Homework => schema_for!(ApiResponse<Vec<StudentHomework>>),
+        SchemaKind::Grades => schema_for!(ApiResponse<Vec<StudentGrades>>),
+        SchemaKind::Schedule => schema_for!(ApiResponse<Vec<StudentSchedule>>),
+        SchemaKind::Summary => schema_for!(ApiResponse<Vec<StudentSummary>>),
+        SchemaKind::All => schema_for!(ApiResponse<Vec<StudentAll>>),
+        SchemaKind::Exams => schema_for!(ApiResponse<Vec<StudentExams>>),
+        SchemaKind::Report => schema_for!(ApiResponse<Vec<StudentReport>>),
+        SchemaKind::Agenda => schema_for!(ApiResponse<Vec<StudentAgenda>>),
+        SchemaKind::Absences => schema_for!(ApiResponse<Vec<StudentAbsences>>),
+        SchemaKind::Feedbacks => schema_for!(ApiResponse<Vec<StudentFeedbacks>>),
+        SchemaKind::Notifications => schema_for!(ApiResponse<NotificationList>),
+        SchemaKind::Messages => schema_for!(ApiResponse<MessagesOverview>),
+        SchemaKind::MessageList => schema_for!(ApiResponse<MessageList>),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student() -> Student {
+        Student { id: 7, name: "Ана".to_string(), class_name: Some("5а".to_string()), school_name: None }
+    }
+
+    #[test]
+    fn test_schema_matches_serialized_fields() {
+        let response = ApiResponse::new(vec![StudentAbsences::new(student(), CacheState::default(), Vec::new(), time::macros::date!(2026 - 02 - 20), 7)], false, None);
+        let value = serde_json::to_value(&response).unwrap();
+
+        let schema = serde_json::to_value(schema(SchemaKind::Absences)).unwrap();
+        let entry = &schema["definitions"]["StudentAbsences"]["properties"];
+        for field in value["data"][0].as_object().unwrap().keys() {
+            assert!(entry.get(field).is_some(), "schema is missing {}", field);
+        }
+        assert!(schema["properties"]["data"].is_object());
+    }
+
+    #[test]
+    fn test_absences_csv() {
+        let header = "subject,term1_excused,term1_unexcused,term2_excused,term2_unexcused,total\n";
+        assert_eq!(absences_csv(&[]), header);
+
+        let subjects = [
+            SubjectAbsences { subject: "Математика".to_string(), excused: [2, 0], unexcused: [1, 3] },
+            SubjectAbsences { subject: "ФВС, спорт \"А\"".to_string(), excused: [0, 1], unexcused: [0, 0] },
+        ];
+        assert_eq!(absences_csv(&subjects), format!(
+            "{}Математика,2,1,0,3,6\n\"ФВС, спорт \"\"А\"\"\",0,0,1,0,1\n",
+            header
+        ));
+    }
+
+    #[test]
+    fn test_agenda_text() {
+        let day = |date: &str, items: Vec<AgendaItem>| AgendaDay { date: date.to_string(), items };
+        let entries = [StudentAgenda {
+            student: student(),
+            cache: CacheState::default(),
+            days: vec![
+                day("2026-02-19", vec![
+                    AgendaItem { kind: AgendaKind::Exam, subject: Some("Математика".to_string()), text: "Контролно\nглава 3".to_string() },
+                    AgendaItem { kind: AgendaKind::Event, subject: None, text: "Родителска среща".to_string() },
+                ]),
+                day("2026-02-20", Vec::new()),
+            ],
+        }];
+        assert_eq!(agenda_text(&entries, false), concat!(
+            "Ана (5а)\n",
+            "  Thu 19.02.2026\n",
+            "    exam      Математика: Контролно\n",
+            "    event     Родителска среща\n",
+            "  Fri 20.02.2026\n",
+            "    —\n",
+        ));
+    }
+
+    #[test]
+    fn test_render_json_formats() {
+        let students = vec![student(), Student { id: 8, ..student() }];
+        let response = ApiResponse::new(students, false, None);
+
+        assert!(!render_json(&response, OutputFormat::Compact).unwrap().contains('\n'));
+        assert!(render_json(&response, OutputFormat::Pretty).unwrap().starts_with("{\n"));
+
+        let jsonl = render_jsonl(&response.data).unwrap();
+        assert_eq!(jsonl.lines().collect::<Vec<_>>(), [
+            r#"{"id":7,"name":"Ана","class_name":"5а","school_name":null}"#,
+            r#"{"id":8,"name":"Ана","class_name":"5а","school_name":null}"#,
+        ]);
+
+        assert_eq!(render_jsonl::<Student>(&[]).unwrap(), "");
+
+        // Not a list: the whole response on one line
+        let single = ApiResponse::new(DebugError::new("No students found"), false, None);
+        let line = render_json(&single, OutputFormat::Jsonl).unwrap();
+        assert_eq!(line.lines().count(), 1);
+        assert!(line.contains("\"success\""));
+    }
+
+    #[test]
+    fn test_default_format() {
+        assert_eq!(default_format(None, None).unwrap(), OutputFormat::Pretty);
+        assert_eq!(default_format(None, Some("compact")).unwrap(), OutputFormat::Compact);
+        // The environment wins over the config; a blank variable counts as unset
+        assert_eq!(default_format(Some(" JSONL "), Some("compact")).unwrap(), OutputFormat::Jsonl);
+        assert_eq!(default_format(Some(""), Some("table")).unwrap(), OutputFormat::Table);
+
+        let error = default_format(Some("yaml"), None).unwrap_err().to_string();
+        assert_eq!(error, "SHKOLO_JSON_FORMAT: unknown format \"yaml\" (expected pretty, compact, jsonl, llm, table)");
+        assert!(default_format(None, Some("csv")).unwrap_err().to_string().starts_with("default_json_format in ui_config.json"));
+    }
+
+    #[test]
+    fn test_cache_state_merge() {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let fresh = CacheState::new(false, None);
+        let recent = CacheState::new(true, Some(now - 120));
+        let old = CacheState::new(true, Some(now - 3 * 3600));
+
+        // Cached only when every student came from cache, dated by the oldest
+        let all = CacheState::merge([&recent, &old]);
+        assert!(all.cached);
+        assert_eq!(all.cached_at.as_deref(), Some("3h ago"));
+
+        let mixed = CacheState::merge([&fresh, &recent]);
+        assert!(!mixed.cached);
+        assert_eq!(mixed.cached_at.as_deref(), Some("2m ago"));
+
+        assert!(!CacheState::merge([]).cached);
+        // A timestamp without a cache hit is not reported
+        assert_eq!(CacheState::new(false, Some(now)).cached_at, None);
+    }
+
+    #[test]
+    fn test_student_absences_wire_format() {
+        let absence = Absence {
+            id: "1".to_string(),
+            date: "20.02.2026".to_string(),
+            date_sort: "2026-02-20".to_string(),
+            hour: 3,
+            subject: "Математика".to_string(),
+            is_excused: true,
+            excuse_reason: None,
+            created_by: None,
+            created_at: None,
+        };
+        let unexcused = Absence { id: "2".to_string(), is_excused: false, created_at: Some("2026-02-14 10:15:00".to_string()), ..absence.clone() };
+        let today = time::macros::date!(2026 - 02 - 26);
+        let json = serde_json::to_string_pretty(&StudentAbsences::new(student(), CacheState::default(), vec![absence, unexcused], today, 7)).unwrap();
+
+        assert_eq!(json, r#"{
+  "student": {
+    "id": 7,
+    "name": "Ана",
+    "class_name": "5а",
+    "school_name": null
+  },
+  "cached": false,
+  "absences": [
+    {
+      "id": "1",
+      "date": "20.02.2026",
+      "date_sort": "2026-02-20",
+      "hour": 3,
+      "subject": "Математика",
+      "is_excused": true,
+      "excuse_reason": null,
+      "created_by": null,
+      "created_at": null,
+      "excuse_deadline_soon": false
+    },
+    {
+      "id": "2",
+      "date": "20.02.2026",
+      "date_sort": "2026-02-20",
+      "hour": 3,
+      "subject": "Математика",
+      "is_excused": false,
+      "excuse_reason": null,
+      "created_by": null,
+      "created_at": "2026-02-14 10:15:00",
+      "excuse_deadline": "2026-02-27",
+      "excuse_deadline_soon": true
+    }
+  ],
+  "total": 2,
+  "excused": 1,
+  "unexcused": 1,
+  "expiring": 1
+}"#);
+    }
+
+    #[test]
+    fn test_summary_and_notifications_wire_format() {
+        let summary = StudentSummary {
+            student: student(),
+            cache: CacheState { cached: true, cached_at: Some("5m ago".to_string()), timestamp: None },
+            today_schedule: Vec::new(),
+            recent_homework: Vec::new(),
+            grades_count: 4,
+            this_week: WeeklySummary { absences: 2, unexcused_absences: 1, positive_feedbacks: 1, negative_feedbacks: 0 },
+            expiring_excuses: 1,
+        };
+        assert_eq!(
+            serde_json::to_string(&summary).unwrap(),
+            r#"{"student":{"id":7,"name":"Ана","class_name":"5а","school_name":null},"cached":true,"cached_at":"5m ago","today_schedule":[],"recent_homework":[],"grades_count":4,"this_week":{"absences":2,"unexcused_absences":1,"positive_feedbacks":1,"negative_feedbacks":0},"expiring_excuses":1}"#,
+        );
+
+        let notifications = NotificationList::new(vec![Notification {
+            id: None,
+            title: "Нова оценка".to_string(),
+            body: None,
+            date: "20.02.2026".to_string(),
+            is_read: false,
+            notification_type: None,
+            pupil_names: None,
+            target: Default::default(),
+        }]);
+        assert_eq!(
+            serde_json::to_string(&notifications).unwrap(),
+            r#"{"notifications":[{"id":null,"title":"Нова оценка","body":null,"date":"20.02.2026","is_read":false,"notification_type":null,"pupil_names":null}],"total":1,"unread":1}"#,
+        );
+    }
+
+    #[test]
+    fn test_homework_urgency_wire_format() {
+        let homework = |due: Option<&str>| Homework {
+            id: Some(1),
+            subject: "Математика".to_string(),
+            text: "Упр. 3".to_string(),
+            date: "20.02.2026".to_string(),
+            due_date: due.map(|d| d.split('-').rev().collect::<Vec<_>>().join(".")),
+            date_sort: None,
+            due_date_sort: due.map(str::to_string),
+            links: Vec::new(),
+            raw_html: None,
+        };
+        let today = time::macros::date!(2026 - 02 - 20);
+        let keywords = ExamKeywords::default();
+
+        assert_eq!(
+            serde_json::to_string(&HomeworkEntry::new(homework(Some("2026-02-21")), today, false, &keywords)).unwrap(),
+            r#"{"id":1,"subject":"Математика","text":"Упр. 3","date":"20.02.2026","due_date":"21.02.2026","due_date_sort":"2026-02-21","urgency":"tomorrow","is_exam":false,"is_pending":true}"#,
+        );
+        assert_eq!(
+            serde_json::to_string(&HomeworkEntry::new(homework(None), today, false, &keywords)).unwrap(),
+            r#"{"id":1,"subject":"Математика","text":"Упр. 3","date":"20.02.2026","due_date":null,"is_exam":false,"is_pending":true}"#,
+        );
+        let test = Homework { text: "Контролно по дроби".to_string(), ..homework(None) };
+        assert!(HomeworkEntry::new(test, today, false, &keywords).is_exam);
+        assert!(HomeworkEntry::new(homework(Some("2026-02-20")), today, false, &keywords).is_pending);
+        assert!(!HomeworkEntry::new(homework(Some("2026-02-20")), today, true, &keywords).is_pending);
+    }
+
+    #[test]
+    fn test_all_counts() {
+        let grade = Grade {
+            subject: "Математика".to_string(),
+            term1_grades: vec!["6".to_string(), "5".to_string()],
+            term2_grades: vec!["4".to_string()],
+            term1_final: Some("6".to_string()),
+            term2_final: None,
+            annual: None,
+            entries: Vec::new(),
+        };
+        let event = |is_test| Event {
+            id: None,
+            title: "Контролно".to_string(),
+            description: None,
+            start_date: "2026-02-24".to_string(),
+            end_date: None,
+            event_type: None,
+            is_test,
+        };
+        let all = StudentAll::new(
+            student(),
+            CacheState::default(),
+            Vec::new(),
+            vec![grade],
+            "2026-02-20".to_string(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            vec![event(true), event(false)],
+            CategoryStatus::default(),
+            Vec::new(),
+        );
+        assert_eq!(all.counts, StudentCounts { graded_subjects: 1, grades: 3, events: 2, tests: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn test_load_status_wire_format() {
+        let status = CategoryStatus {
+            grades: LoadStatus::failed(&anyhow::anyhow!("API error (500)"), false),
+            events: LoadStatus::failed(&anyhow::anyhow!("API error (500)"), true),
+            homework: LoadStatus::loaded(false),
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(value["homework"], serde_json::json!({ "state": "loaded" }));
+        assert_eq!(value["grades"], serde_json::json!({ "state": "failed", "error": "API error (500)" }));
+        assert_eq!(value["events"], serde_json::json!({ "state": "stale" }));
+        assert_eq!(value["schedule"], serde_json::json!({ "state": "empty" }));
+        assert_eq!(status.all_failed(), None);
+
+        let error = LoadStatus::Failed("timeout".into());
+        let failed = CategoryStatus {
+            homework: error.clone(),
+            grades: error.clone(),
+            schedule: error.clone(),
+            absences: error.clone(),
+            feedbacks: error.clone(),
+            events: error,
+        };
+        assert_eq!(failed.all_failed(), Some("timeout"));
+    }
+
+    #[test]
+    fn test_partial_messages_and_errors_wire_format() {
+        let messages = MessagesOverview {
+            folders_error: Some("timeout".to_string()),
+            can_send: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(serde_json::to_string(&messages).unwrap(), r#"{"folders_error":"timeout","can_send":true}"#);
+
+        let error = DebugError { thread_id: Some(5), ..DebugError::new("not found") };
+        assert_eq!(serde_json::to_string(&error).unwrap(), r#"{"error":"not found","thread_id":5}"#);
+    }
+}
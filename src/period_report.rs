@@ -0,0 +1,563 @@
+//! `shkolo report`: what happened in a week or a month, per student, as
+//! Markdown or as a single HTML file that can be attached to an email.
+
+use time::{Date, Duration};
+
+use crate::i18n::{Lang, T};
+use crate::models::*;
+use crate::util::{self, is_weekend, parse_day, WeekStart};
+
+/// `report --period`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Period {
+    /// The ISO week (Monday to Sunday) of today
+    Week,
+    /// The calendar month of today
+    Month,
+}
+
+/// `report --format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Md,
+    /// A complete page with inline styles
+    Html,
+}
+
+/// First and last day of a period, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: Date,
+    pub end: Date,
+}
+
+impl DateRange {
+    /// The period that contains `today` (a local date)
+    pub fn of(period: Period, today: Date) -> Self {
+        match period {
+            Period::Week => {
+                let start = util::week_start_date(today, WeekStart::Monday);
+                Self { start, end: start + Duration::days(6) }
+            }
+            Period::Month => {
+                let start = today.replace_day(1).unwrap_or(today);
+                let end = today.replace_day(today.month().length(today.year())).unwrap_or(today);
+                Self { start, end }
+            }
+        }
+    }
+
+    pub fn contains(&self, date: Date) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+
+    /// Whether a "YYYY-MM-DD..." or "DD.MM.YYYY..." date falls in the range
+    pub fn contains_str(&self, date: &str) -> bool {
+        parse_day(date).is_some_and(|d| self.contains(d))
+    }
+
+    /// Weekdays of the range up to `today`, the days that should have had lessons
+    pub fn school_days(&self, today: Date) -> Vec<Date> {
+        let last = self.end.min(today);
+        let mut days = Vec::new();
+        let mut day = self.start;
+        while day <= last {
+            if !is_weekend(day) {
+                days.push(day);
+            }
+            day += Duration::days(1);
+        }
+        days
+    }
+
+    /// "Week 8, 16.02.2026 – 22.02.2026" or "February 2026"
+    pub fn title(&self, period: Period, lang: Lang) -> String {
+        match period {
+            Period::Week => format!("{} {}, {} – {}", T::period_week(lang), self.start.iso_week(), day_string(self.start), day_string(self.end)),
+            Period::Month => format!("{} {}", T::month(lang, self.start.month()), self.start.year()),
+        }
+    }
+}
+
+/// DD.MM.YYYY, the way the API and the school write dates
+fn day_string(date: Date) -> String {
+    format!("{:02}.{:02}.{}", date.day(), date.month() as u8, date.year())
+}
+
+/// One student's period. A category that failed to load is None.
+#[derive(Debug, Clone)]
+pub struct PeriodReport {
+    pub student: Student,
+    /// New-grade notifications about the student; grades themselves carry no dates
+    pub grades: Option<Vec<Notification>>,
+    /// Homework given in the period, oldest first
+    pub assigned: Option<Vec<Homework>>,
+    /// Homework due in the period, soonest first
+    pub due: Option<Vec<Homework>>,
+    pub absences: Option<Vec<Absence>>,
+    pub feedbacks: Option<Vec<Feedback>>,
+    /// School days so far without any lessons: holidays, closures and the like
+    pub days_without_lessons: Option<Vec<Date>>,
+}
+
+impl PeriodReport {
+    /// Keep what falls in `range`. `schedules` has the lessons of each of
+    /// `range.school_days()`.
+    pub fn new(
+        student: Student,
+        range: DateRange,
+        notifications: Option<&[Notification]>,
+        homework: Option<&[Homework]>,
+        absences: Option<Vec<Absence>>,
+        feedbacks: Option<Vec<Feedback>>,
+        schedules: Option<Vec<(Date, Vec<ScheduleHour>)>>,
+    ) -> Self {
+        let first_name = student.name.split_whitespace().next().unwrap_or_default().to_string();
+        let grades = notifications.map(|notifications| {
+            let mut grades: Vec<Notification> = notifications.iter()
+                .filter(|n| n.is_grade_for(&first_name) && range.contains_str(&n.date))
+                .cloned()
+                .collect();
+            grades.sort_by_key(|n| parse_day(&n.date));
+            grades
+        });
+
+        let assigned = homework.map(|homework| {
+            let mut assigned: Vec<Homework> = homework.iter()
+                .filter(|hw| range.contains_str(hw.date_sort.as_deref().unwrap_or(&hw.date)))
+                .cloned()
+                .collect();
+            assigned.sort_by_key(|hw| parse_day(hw.date_sort.as_deref().unwrap_or(&hw.date)));
+            assigned
+        });
+        let due = homework.map(|homework| {
+            let mut due: Vec<Homework> = homework.iter()
+                .filter(|hw| hw.due_date_sort.as_deref().is_some_and(|d| range.contains_str(d)))
+                .cloned()
+                .collect();
+            due.sort_by(|a, b| a.due_date_sort.cmp(&b.due_date_sort));
+            due
+        });
+
+        let absences = absences.map(|absences| {
+            let mut absences: Vec<Absence> = absences.into_iter().filter(|a| range.contains_str(&a.date_sort)).collect();
+            absences.sort_by(|a, b| a.date_sort.cmp(&b.date_sort).then(a.hour.cmp(&b.hour)));
+            absences
+        });
+        let feedbacks = feedbacks.map(|feedbacks| {
+            let mut feedbacks: Vec<Feedback> = feedbacks.into_iter().filter(|f| range.contains_str(&f.date)).collect();
+            feedbacks.sort_by_key(|f| parse_day(&f.date));
+            feedbacks
+        });
+        let days_without_lessons = schedules.map(|schedules| {
+            schedules.into_iter().filter(|(_, hours)| hours.is_empty()).map(|(day, _)| day).collect()
+        });
+
+        Self { student, grades, assigned, due, absences, feedbacks, days_without_lessons }
+    }
+
+    /// The report as headed lists, shared by both formats
+    fn sections(&self, lang: Lang) -> Vec<Section> {
+        let absences_title = match &self.absences {
+            Some(absences) => format!(
+                "{} ({}, {}: {})",
+                T::absences(lang),
+                absences.len(),
+                T::period_unexcused(lang),
+                absences.iter().filter(|a| !a.is_excused).count(),
+            ),
+            None => T::absences(lang).to_string(),
+        };
+        vec![
+            Section::new(T::grades(lang), self.grades.as_deref(), |n| Item::plain(format!(
+                "{}: {}",
+                n.date.get(..10).unwrap_or(&n.date),
+                one_line(n.body.as_deref().unwrap_or(&n.title)),
+            ))),
+            Section::new(T::period_homework_assigned(lang), self.assigned.as_deref(), |hw| Item::plain(match &hw.due_date {
+                Some(due) => format!("{}, {}: {} ({} {})", hw.date, hw.subject, one_line(&hw.text), T::period_due(lang), due),
+                None => format!("{}, {}: {}", hw.date, hw.subject, one_line(&hw.text)),
+            })),
+            Section::new(T::period_homework_due(lang), self.due.as_deref(), |hw| Item::plain(format!(
+                "{}, {}: {}",
+                hw.due_date.as_deref().unwrap_or_default(),
+                hw.subject,
+                one_line(&hw.text),
+            ))),
+            Section { title: absences_title, ..Section::new("", self.absences.as_deref(), |a| Item {
+                text: format!(
+                    "{}, {} {}, {}: {}",
+                    a.date,
+                    T::hour_label(lang),
+                    a.hour,
+                    a.subject,
+                    if a.is_excused { T::excused(lang) } else { T::unexcused(lang) },
+                ),
+                tone: (!a.is_excused).then_some(Tone::Bad),
+            }) },
+            Section::new(T::feedbacks(lang), self.feedbacks.as_deref(), |f| {
+                let tone = if f.is_positive { T::period_positive(lang) } else { T::period_negative(lang) };
+                let mut text = format!("{}, {}: {} ({})", f.date, f.subject, f.badge_name, tone);
+                if let Some(comment) = f.comment.as_deref().filter(|c| !c.trim().is_empty()) {
+                    text.push_str(&format!(" – {}", one_line(comment)));
+                }
+                Item { text, tone: Some(if f.is_positive { Tone::Good } else { Tone::Bad }) }
+            }),
+            Section::new(T::period_days_without_lessons(lang), self.days_without_lessons.as_deref(), |day| {
+                Item::plain(format!("{} {}", T::weekday(lang, day.weekday()), day_string(*day)))
+            }),
+        ]
+    }
+}
+
+/// How an item reads: bad news (unexcused, negative) or good
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tone {
+    Good,
+    Bad,
+}
+
+struct Item {
+    text: String,
+    tone: Option<Tone>,
+}
+
+impl Item {
+    fn plain(text: String) -> Self {
+        Self { text, tone: None }
+    }
+}
+
+/// A heading and its items; None when the data couldn't be loaded
+struct Section {
+    title: String,
+    items: Option<Vec<Item>>,
+}
+
+impl Section {
+    /// "Title (count)" over one item per entry
+    fn new<T>(title: &str, entries: Option<&[T]>, item: impl Fn(&T) -> Item) -> Self {
+        Self {
+            title: match entries {
+                Some(entries) if !entries.is_empty() => format!("{} ({})", title, entries.len()),
+                _ => title.to_string(),
+            },
+            items: entries.map(|entries| entries.iter().map(item).collect()),
+        }
+    }
+}
+
+/// Line breaks and runs of spaces become single spaces
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn heading(student: &Student) -> String {
+    match &student.class_name {
+        Some(class) => format!("{} ({})", student.name, class),
+        None => student.name.clone(),
+    }
+}
+
+/// The report with headings and labels in `lang`
+pub fn render(reports: &[PeriodReport], title: &str, format: ReportFormat, lang: Lang) -> String {
+    match format {
+        ReportFormat::Md => render_markdown(reports, title, lang),
+        ReportFormat::Html => render_html(reports, title, lang),
+    }
+}
+
+pub fn render_markdown(reports: &[PeriodReport], title: &str, lang: Lang) -> String {
+    let mut out = format!("# {}: {}\n", T::period_report_title(lang), escape_markdown(title));
+    if reports.is_empty() {
+        out.push_str(&format!("\n{}\n", T::period_no_students(lang)));
+    }
+    for report in reports {
+        out.push_str(&format!("\n## {}\n", escape_markdown(&heading(&report.student))));
+        for section in report.sections(lang) {
+            out.push_str(&format!("\n### {}\n\n", section.title));
+            match section.items {
+                None => out.push_str(&format!("_{}_\n", T::period_not_loaded(lang))),
+                Some(items) if items.is_empty() => out.push_str(&format!("_{}_\n", T::period_none(lang))),
+                Some(items) => {
+                    for item in items {
+                        match item.tone {
+                            Some(Tone::Bad) => out.push_str(&format!("- **{}**\n", escape_markdown(&item.text))),
+                            _ => out.push_str(&format!("- {}\n", escape_markdown(&item.text))),
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Characters that would otherwise turn teacher-written text into markup
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '|') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Inline so the file shows the same wherever it's opened
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,\"Segoe UI\",Roboto,Arial,sans-serif;max-width:46em;margin:2em auto;padding:0 1em;color:#222;line-height:1.45}\
+h1{font-size:1.5em;border-bottom:2px solid #ddd;padding-bottom:.3em}\
+h2{font-size:1.25em;margin-top:1.6em}\
+h3{font-size:1em;margin:1.2em 0 .3em;color:#555}\
+ul{margin:0;padding-left:1.3em}\
+.none{color:#888;font-style:italic;margin:0}\
+.good{color:#1a7f37}\
+.bad{color:#c62828;font-weight:600}";
+
+pub fn render_html(reports: &[PeriodReport], title: &str, lang: Lang) -> String {
+    let title = format!("{}: {}", T::period_report_title(lang), title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape_html(&title), HTML_STYLE, escape_html(&title),
+    );
+    if reports.is_empty() {
+        out.push_str(&format!("<p class=\"none\">{}</p>\n", escape_html(T::period_no_students(lang))));
+    }
+    for report in reports {
+        out.push_str(&format!("<h2>{}</h2>\n", escape_html(&heading(&report.student))));
+        for section in report.sections(lang) {
+            out.push_str(&format!("<h3>{}</h3>\n", escape_html(&section.title)));
+            match section.items {
+                None => out.push_str(&format!("<p class=\"none\">{}</p>\n", escape_html(T::period_not_loaded(lang)))),
+                Some(items) if items.is_empty() => out.push_str(&format!("<p class=\"none\">{}</p>\n", escape_html(T::period_none(lang)))),
+                Some(items) => {
+                    out.push_str("<ul>\n");
+                    for item in items {
+                        match item.tone {
+                            Some(Tone::Good) => out.push_str(&format!("<li class=\"good\">{}</li>\n", escape_html(&item.text))),
+                            Some(Tone::Bad) => out.push_str(&format!("<li class=\"bad\">{}</li>\n", escape_html(&item.text))),
+                            None => out.push_str(&format!("<li>{}</li>\n", escape_html(&item.text))),
+                        }
+                    }
+                    out.push_str("</ul>\n");
+                }
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    fn report() -> PeriodReport {
+        let range = DateRange::of(Period::Week, date!(2026 - 02 - 19));
+        let student = Student { id: 1, name: "Мария Иванова".to_string(), class_name: Some("5А".to_string()), school_name: None };
+        let notification = |title: &str, body: &str, date: &str, pupil: &str| Notification {
+            id: None,
+            title: title.to_string(),
+            body: Some(body.to_string()),
+            date: date.to_string(),
+            is_read: false,
+            notification_type: Some("new_grade".to_string()),
+            pupil_names: Some(pupil.to_string()),
+            target: NotificationTarget::default(),
+        };
+        let notifications = [
+            notification("Нова оценка", "Мария получи Отличен 6 по Математика", "2026-02-17 12:00:00", "Мария Иванова"),
+            notification("Нова оценка", "Петър получи Добър 4", "2026-02-17 12:00:00", "Петър Иванов"),
+            notification("Нова оценка", "Мария получи Добър 4", "2026-02-10 12:00:00", "Мария Иванова"),
+        ];
+        let homework = |subject: &str, text: &str, date: &str, due: &str| Homework {
+            id: None,
+            subject: subject.to_string(),
+            text: text.to_string(),
+            date: date.to_string(),
+            due_date: Some(due.to_string()),
+            date_sort: None,
+            due_date_sort: parse_day(due).map(|d| d.to_string()),
+            links: Vec::new(),
+            raw_html: None,
+        };
+        let homework = [
+            homework("Математика", "Задачи 1-12\nна стр. 45", "17.02.2026", "20.02.2026"),
+            homework("БЕЛ", "Преразказ <кратък> & ясен", "12.02.2026", "16.02.2026"),
+            homework("История", "Урок 12", "05.02.2026", "09.02.2026"),
+        ];
+        let absence = |date_sort: &str, hour: i32, is_excused: bool| Absence {
+            id: hour.to_string(),
+            date: parse_day(date_sort).map(day_string).unwrap_or_default(),
+            date_sort: date_sort.to_string(),
+            hour,
+            subject: "ФВС".to_string(),
+            is_excused,
+            excuse_reason: None,
+            created_by: None,
+            created_at: None,
+        };
+        let feedback = |date: &str, is_positive: bool, comment: Option<&str>| Feedback {
+            id: 1,
+            badge_name: if is_positive { "Активно участие" } else { "Без домашно" }.to_string(),
+            badge_icon: None,
+            comment: comment.map(str::to_string),
+            is_positive,
+            date: date.to_string(),
+            teacher: "Петрова".to_string(),
+            subject: "Математика".to_string(),
+        };
+        let lesson = ScheduleHour {
+            hour_number: 1,
+            from_time: "08:00".to_string(),
+            to_time: "08:40".to_string(),
+            subject: "Математика".to_string(),
+            teacher: None,
+            topic: None,
+            homework: None,
+            room: None,
+        };
+        let schedules: Vec<_> = range.school_days(date!(2026 - 02 - 19)).into_iter()
+            .map(|day| (day, if day == date!(2026 - 02 - 16) { Vec::new() } else { vec![lesson.clone()] }))
+            .collect();
+
+        PeriodReport::new(
+            student,
+            range,
+            Some(&notifications),
+            Some(&homework),
+            Some(vec![absence("2026-02-18", 3, false), absence("2026-02-17", 2, true), absence("2026-02-02", 1, false)]),
+            Some(vec![feedback("18.02.2026", false, None), feedback("17.02.2026", true, Some("Браво!"))]),
+            Some(schedules),
+        )
+    }
+
+    #[test]
+    fn test_period_ranges() {
+        let week = DateRange::of(Period::Week, date!(2026 - 02 - 22));
+        assert_eq!((week.start, week.end), (date!(2026 - 02 - 16), date!(2026 - 02 - 22)));
+        assert_eq!(week.title(Period::Week, Lang::En), "Week 8, 16.02.2026 – 22.02.2026");
+        // Monday starts a new ISO week
+        assert_eq!(DateRange::of(Period::Week, date!(2026 - 02 - 23)).start, date!(2026 - 02 - 23));
+
+        let month = DateRange::of(Period::Month, date!(2026 - 02 - 19));
+        assert_eq!((month.start, month.end), (date!(2026 - 02 - 01), date!(2026 - 02 - 28)));
+        assert_eq!(month.title(Period::Month, Lang::En), "February 2026");
+        assert_eq!(DateRange::of(Period::Month, date!(2028 - 02 - 10)).end, date!(2028 - 02 - 29));
+        assert_eq!(week.title(Period::Week, Lang::Bg), "Седмица 8, 16.02.2026 – 22.02.2026");
+        assert_eq!(month.title(Period::Month, Lang::Bg), "февруари 2026");
+        assert_eq!(month.title(Period::Month, Lang::De), "Februar 2026");
+
+        // Only the weekdays that have already come
+        assert_eq!(week.school_days(date!(2026 - 02 - 18)), vec![date!(2026 - 02 - 16), date!(2026 - 02 - 17), date!(2026 - 02 - 18)]);
+        assert_eq!(week.school_days(date!(2026 - 03 - 01)).len(), 5);
+    }
+
+    #[test]
+    fn test_snapshot_markdown() {
+        let mut missing = report();
+        missing.student.name = "Петър Иванов".to_string();
+        missing.student.class_name = None;
+        missing.feedbacks = None;
+        missing.grades = Some(Vec::new());
+        assert_eq!(render_markdown(&[report()], "Week 8, 16.02.2026 – 22.02.2026", Lang::En), concat!(
+            "# Shkolo report: Week 8, 16.02.2026 – 22.02.2026\n",
+            "\n",
+            "## Мария Иванова (5А)\n",
+            "\n",
+            "### Grades (1)\n",
+            "\n",
+            "- 2026-02-17: Мария получи Отличен 6 по Математика\n",
+            "\n",
+            "### Homework assigned (1)\n",
+            "\n",
+            "- 17.02.2026, Математика: Задачи 1-12 на стр. 45 (due 20.02.2026)\n",
+            "\n",
+            "### Homework due (2)\n",
+            "\n",
+            "- 16.02.2026, БЕЛ: Преразказ \\<кратък\\> & ясен\n",
+            "- 20.02.2026, Математика: Задачи 1-12 на стр. 45\n",
+            "\n",
+            "### Absences (2, unexcused: 1)\n",
+            "\n",
+            "- 17.02.2026, hour 2, ФВС: excused\n",
+            "- **18.02.2026, hour 3, ФВС: unexcused**\n",
+            "\n",
+            "### Feedbacks (2)\n",
+            "\n",
+            "- 17.02.2026, Математика: Активно участие (positive) – Браво!\n",
+            "- **18.02.2026, Математика: Без домашно (negative)**\n",
+            "\n",
+            "### School days without lessons (1)\n",
+            "\n",
+            "- Monday 16.02.2026\n",
+        ));
+
+        let md = render_markdown(&[missing.clone()], "February 2026", Lang::En);
+        assert!(md.contains("## Петър Иванов\n\n### Grades\n\n_None._\n"), "{}", md);
+        assert!(md.contains("### Feedbacks\n\n_Could not be loaded._\n"), "{}", md);
+
+        // Headings and labels follow the language; the school's own text stays as it is
+        let md = render_markdown(&[report(), missing], "февруари 2026", Lang::Bg);
+        assert!(md.starts_with("# Отчет от Школо: февруари 2026\n"), "{}", md);
+        assert!(md.contains("### Отсъствия (2, неизвинени: 1)\n\n- 17.02.2026, час 2, ФВС: извинено\n"), "{}", md);
+        assert!(md.contains("- **18.02.2026, Математика: Без домашно (отрицателен)**\n"), "{}", md);
+        assert!(md.contains("### Учебни дни без часове (1)\n\n- понеделник 16.02.2026\n"), "{}", md);
+        assert!(md.contains("### Отзиви\n\n_Не можа да се зареди._\n"), "{}", md);
+        assert_eq!(render_markdown(&[], "февруари 2026", Lang::Bg), "# Отчет от Школо: февруари 2026\n\nНяма ученици.\n");
+    }
+
+    #[test]
+    fn test_snapshot_html() {
+        let html = render_html(&[report()], "Week 8, 16.02.2026 – 22.02.2026", Lang::En);
+        assert!(html.starts_with("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n"));
+        // Self-contained: no stylesheets, scripts or images from elsewhere
+        assert!(!html.contains("<link") && !html.contains("<script") && !html.contains("src="));
+        let body = &html[html.find("<body>").unwrap()..];
+        assert_eq!(body, concat!(
+            "<body>\n",
+            "<h1>Shkolo report: Week 8, 16.02.2026 – 22.02.2026</h1>\n",
+            "<h2>Мария Иванова (5А)</h2>\n",
+            "<h3>Grades (1)</h3>\n",
+            "<ul>\n",
+            "<li>2026-02-17: Мария получи Отличен 6 по Математика</li>\n",
+            "</ul>\n",
+            "<h3>Homework assigned (1)</h3>\n",
+            "<ul>\n",
+            "<li>17.02.2026, Математика: Задачи 1-12 на стр. 45 (due 20.02.2026)</li>\n",
+            "</ul>\n",
+            "<h3>Homework due (2)</h3>\n",
+            "<ul>\n",
+            "<li>16.02.2026, БЕЛ: Преразказ &lt;кратък&gt; &amp; ясен</li>\n",
+            "<li>20.02.2026, Математика: Задачи 1-12 на стр. 45</li>\n",
+            "</ul>\n",
+            "<h3>Absences (2, unexcused: 1)</h3>\n",
+            "<ul>\n",
+            "<li>17.02.2026, hour 2, ФВС: excused</li>\n",
+            "<li class=\"bad\">18.02.2026, hour 3, ФВС: unexcused</li>\n",
+            "</ul>\n",
+            "<h3>Feedbacks (2)</h3>\n",
+            "<ul>\n",
+            "<li class=\"good\">17.02.2026, Математика: Активно участие (positive) – Браво!</li>\n",
+            "<li class=\"bad\">18.02.2026, Математика: Без домашно (negative)</li>\n",
+            "</ul>\n",
+            "<h3>School days without lessons (1)</h3>\n",
+            "<ul>\n",
+            "<li>Monday 16.02.2026</li>\n",
+            "</ul>\n",
+            "</body>\n",
+            "</html>\n",
+        ));
+    }
+}
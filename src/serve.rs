@@ -0,0 +1,431 @@
+//! `shkolo serve`: a small read-only HTTP API answering with the same JSON as
+//! the `json` commands, for dashboards that poll it instead of running the CLI.
+//! Data goes through the cache like everywhere else; a background task keeps
+//! it warm on the cache TTL, so requests rarely wait for the API. `/metrics`
+//! reports API and cache health in the Prometheus text format.
+
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::{Method, Request, Response, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+
+use crate::api::{ApiResponse, ShkoloApi, ShkoloClient, StudentError};
+use crate::cache::CacheStore;
+use crate::metrics::Metrics;
+use crate::models::*;
+use crate::output::{self, CacheState};
+use crate::{service, util};
+
+pub const DEFAULT_PORT: u16 = 8642;
+
+/// Only loopback addresses, unless other machines are explicitly allowed and
+/// have to send a shared secret
+pub fn check_bind(ip: IpAddr, allow_remote: bool, token: Option<&str>) -> Result<()> {
+    if token.is_some_and(|t| t.trim().is_empty()) {
+        return Err(anyhow!("--token must not be empty"));
+    }
+    match (ip.is_loopback(), allow_remote, token) {
+        (true, _, _) | (false, true, Some(_)) => Ok(()),
+        (false, true, None) => Err(anyhow!("--allow-remote needs a --token for clients to send")),
+        (false, false, _) => Err(anyhow!(
+            "{} is reachable from other machines; pass --allow-remote and --token to serve them",
+            ip
+        )),
+    }
+}
+
+/// One answer: a status, a JSON body (or metrics text) and where its data came from
+#[derive(Debug)]
+pub struct Reply {
+    pub status: StatusCode,
+    pub content_type: &'static str,
+    pub body: String,
+    pub cache: Option<CacheState>,
+}
+
+const JSON: &str = "application/json; charset=utf-8";
+
+impl Reply {
+    fn error(status: StatusCode, message: impl std::fmt::Display) -> Self {
+        let body = serde_json::json!({ "success": false, "error": message.to_string() });
+        Reply { status, content_type: JSON, body: body.to_string(), cache: None }
+    }
+
+    /// A response envelope, unsuccessful when it has no data because every student failed
+    fn envelope<T: Serialize>(response: ApiResponse<T>, all_failed: bool, cache: CacheState) -> Self {
+        match serde_json::to_string(&response) {
+            Ok(body) => {
+                let status = if all_failed { StatusCode::BAD_GATEWAY } else { StatusCode::OK };
+                Reply { status, content_type: JSON, body, cache: Some(cache) }
+            }
+            Err(e) => Reply::error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        }
+    }
+
+    /// The HTTP response, with the cache state in `X-Shkolo-Cached` and `Age`
+    fn into_response(self) -> Response<Full<Bytes>> {
+        let mut response = Response::new(Full::new(Bytes::from(self.body)));
+        *response.status_mut() = self.status;
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(self.content_type));
+        if let Some(cache) = self.cache {
+            headers.insert("X-Shkolo-Cached", HeaderValue::from_static(if cache.cached { "true" } else { "false" }));
+            if let Some(at) = cache.timestamp() {
+                let age = (util::now_local().unix_timestamp() - at).max(0);
+                headers.insert(header::AGE, HeaderValue::from(age));
+            }
+        }
+        response
+    }
+}
+
+pub struct Server<A> {
+    client: A,
+    cache: CacheStore,
+    /// Clients must send `Authorization: Bearer <token>` when set
+    token: Option<String>,
+    keywords: ExamKeywords,
+    excuse_days: i64,
+    school_day_end: i32,
+    metrics: Arc<Metrics>,
+}
+
+impl<A: ShkoloApi> Server<A> {
+    pub fn new(client: A, cache: CacheStore, token: Option<String>) -> Result<Self> {
+        let config = cache.load_ui_config();
+        let school_day_end = service::school_day_end(&cache)?;
+        Ok(Server {
+            client,
+            token,
+            keywords: ExamKeywords::from_config(config.exam_keywords.as_deref()),
+            excuse_days: config.absence_excuse_days.unwrap_or(DEFAULT_EXCUSE_DAYS),
+            school_day_end,
+            metrics: Arc::default(),
+            cache,
+        })
+    }
+
+    /// Report these metrics at /metrics; the client's, so its requests are counted
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Answer a request for one of the endpoints:
+    /// `/students`, `/homework[/<student>]`, `/grades[/<student>]`,
+    /// `/schedule[/<student>]?date=`, `/summary?date=` and `/metrics`.
+    /// A student is a 1-based index or part of a name, like in the CLI.
+    pub async fn respond(&self, method: &Method, uri: &Uri, headers: &HeaderMap) -> Reply {
+        if let Some(token) = &self.token {
+            let expected = format!("Bearer {}", token);
+            let given = headers.get(header::AUTHORIZATION).map_or(&[][..], |v| v.as_bytes());
+            if !constant_time_eq(given, expected.as_bytes()) {
+                return Reply::error(StatusCode::UNAUTHORIZED, "missing or wrong token");
+            }
+        }
+        if method != Method::GET {
+            return Reply::error(StatusCode::METHOD_NOT_ALLOWED, "read-only API: only GET is supported");
+        }
+
+        let path: Vec<String> = uri.path().split('/').filter(|s| !s.is_empty()).map(percent_decode).collect();
+        if path == ["metrics"] {
+            let body = self.metrics.render_prometheus(&self.cache.stats_handle());
+            return Reply { status: StatusCode::OK, content_type: "text/plain; version=0.0.4; charset=utf-8", body, cache: None };
+        }
+        let date = match query_param(uri, "date").map(|d| util::parse_date_arg(&d)) {
+            Some(Ok(date)) => date.to_string(),
+            Some(Err(e)) => return Reply::error(StatusCode::BAD_REQUEST, e),
+            None => util::today_local(),
+        };
+        let (endpoint, student) = match path.as_slice() {
+            [endpoint] => (endpoint.as_str(), None),
+            [endpoint, student] if endpoint != "students" && endpoint != "summary" => (endpoint.as_str(), Some(student.as_str())),
+            _ => return Reply::error(StatusCode::NOT_FOUND, "no such endpoint"),
+        };
+
+        let started = Instant::now();
+        let force = false;
+        let (students, info) = match service::fetch_students(&self.client, &self.cache, force).await {
+            Ok(result) => result,
+            Err(e) => return Reply::error(StatusCode::BAD_GATEWAY, e),
+        };
+        if endpoint == "students" {
+            let state = CacheState::from(info);
+            let response = ApiResponse::new(students, state.cached, state.cached_at.clone());
+            return Reply::envelope(self.finish(response, Vec::new(), started), false, state);
+        }
+
+        let Some(selected) = service::find_students(&students, student) else {
+            return Reply::error(StatusCode::NOT_FOUND, format!("no student matches \"{}\"", student.unwrap_or_default()));
+        };
+        match endpoint {
+            "homework" => {
+                let filter = HomeworkFilter::default();
+                let (mut entries, failures) = service::homework_entries(&self.client, &self.cache, &selected, &filter, &self.keywords, self.school_day_end, force).await;
+                output::strip_raw_html(entries.iter_mut().flat_map(|e| e.homework.iter_mut().map(|h| &mut h.homework)));
+                self.entries(entries, |e| &e.cache, failures, started)
+            }
+            "grades" => {
+                let filter = GradeFilter::default();
+                let (entries, failures) = service::grades_entries(&self.client, &self.cache, &selected, &filter, force).await;
+                self.entries(entries, |e| &e.cache, failures, started)
+            }
+            "schedule" => {
+                let (entries, failures) = service::schedule_entries(&self.client, &self.cache, &selected, &date, force).await;
+                self.entries(entries, |e| &e.cache, failures, started)
+            }
+            "summary" => {
+                let (mut entries, failures) = service::summary_entries(&self.client, &self.cache, &students, &date, self.excuse_days, force).await;
+                output::strip_raw_html(entries.iter_mut().flat_map(|e| e.recent_homework.iter_mut()));
+                self.entries(entries, |e| &e.cache, failures, started)
+            }
+            _ => Reply::error(StatusCode::NOT_FOUND, "no such endpoint"),
+        }
+    }
+
+    /// Per-student entries in the envelope of the `json` commands
+    fn entries<T: Serialize>(
+        &self,
+        entries: Vec<T>,
+        cache_of: impl Fn(&T) -> &CacheState,
+        failures: Vec<StudentError>,
+        started: Instant,
+    ) -> Reply {
+        let state = CacheState::merge(entries.iter().map(cache_of));
+        let all_failed = entries.is_empty() && !failures.is_empty();
+        let response = ApiResponse::new(entries, state.cached, state.cached_at.clone());
+        Reply::envelope(self.finish(response, failures, started), all_failed, state)
+    }
+
+    fn finish<T>(&self, response: ApiResponse<T>, failures: Vec<StudentError>, started: Instant) -> ApiResponse<T> {
+        response.with_errors(failures).stale(self.client.is_offline()).timed(started)
+    }
+
+    /// Load what the endpoints serve, so the cache is fresh when asked.
+    /// Fresh entries are left alone; failures wait for the next round.
+    async fn warm(&self) {
+        let started = Instant::now();
+        let today = util::today_local();
+        let Ok((students, _)) = service::fetch_students(&self.client, &self.cache, false).await else {
+            self.metrics.record_refresh(started.elapsed(), false);
+            return;
+        };
+        let mut ok = true;
+        for s in &students {
+            ok &= service::fetch_homework(&self.client, &self.cache, s.id, false).await.is_ok();
+            ok &= service::fetch_grades(&self.client, &self.cache, s.id, false).await.is_ok();
+            ok &= service::fetch_schedule(&self.client, &self.cache, s.id, &today, false).await.is_ok();
+            ok &= service::fetch_absences(&self.client, &self.cache, s.id, false).await.is_ok();
+            ok &= service::fetch_feedbacks(&self.client, &self.cache, s.id, false).await.is_ok();
+        }
+        self.metrics.record_refresh(started.elapsed(), ok);
+    }
+}
+
+impl Server<ShkoloClient> {
+    /// Listen on `addr` until the process is stopped
+    pub async fn run(self, addr: SocketAddr) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await
+            .map_err(|e| anyhow!("Could not listen on {}: {}", addr, e))?;
+        eprintln!("Serving on http://{} (Ctrl+C to stop)", listener.local_addr()?);
+
+        let server = Arc::new(self);
+        let warm = server.clone();
+        tokio::spawn(async move {
+            // Every TTL, but not more often than once a minute
+            let mut interval = tokio::time::interval(Duration::from_secs(warm.cache.ttl().max(60) as u64));
+            loop {
+                interval.tick().await;
+                warm.warm().await;
+            }
+        });
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                let handler = hyper::service::service_fn(move |request: Request<Incoming>| {
+                    let server = server.clone();
+                    async move {
+                        let reply = server.respond(request.method(), request.uri(), request.headers()).await;
+                        Ok::<_, Infallible>(reply.into_response())
+                    }
+                });
+                let _ = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), handler)
+                    .await;
+            });
+        }
+    }
+}
+
+/// Compare without stopping at the first difference, so the time a wrong
+/// token takes doesn't tell how much of it was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Decode %XX escapes, e.g. a Cyrillic name in the path
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn query_param(uri: &Uri, name: &str) -> Option<String> {
+    uri.query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fake::FakeApi;
+    use crate::cache::TempCache;
+
+    fn api() -> FakeApi {
+        FakeApi::default()
+            .with("pupils", include_str!("api/fixtures/pupils.json"))
+            .with("homework_courses", include_str!("api/fixtures/homework_courses.json"))
+            .with("homework_list/9001", include_str!("api/fixtures/homework_list.json"))
+            .with("grades_summary", include_str!("api/fixtures/grades_summary.json"))
+            .with("schedule/2026-02-19", include_str!("api/fixtures/schedule.json"))
+    }
+
+    fn server(cache: &TempCache, api: FakeApi, token: Option<&str>) -> Server<FakeApi> {
+        Server::new(api, (*cache).clone(), token.map(Into::into)).unwrap()
+    }
+
+    async fn get(server: &Server<FakeApi>, uri: &str) -> (Reply, serde_json::Value) {
+        let reply = server.respond(&Method::GET, &uri.parse().unwrap(), &HeaderMap::new()).await;
+        let body = serde_json::from_str(&reply.body).unwrap();
+        (reply, body)
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"Bearer secret", b"Bearer secret"));
+        assert!(!constant_time_eq(b"Bearer secreT", b"Bearer secret"));
+        assert!(!constant_time_eq(b"Bearer secret2", b"Bearer secret"));
+        assert!(!constant_time_eq(b"", b"Bearer secret"));
+    }
+
+    #[test]
+    fn test_remote_bind_needs_allow_remote_and_token() {
+        let remote: IpAddr = "0.0.0.0".parse().unwrap();
+        assert!(check_bind("127.0.0.1".parse().unwrap(), false, None).is_ok());
+        assert!(check_bind("::1".parse().unwrap(), false, None).is_ok());
+        assert!(check_bind(remote, false, Some("secret")).is_err());
+        assert!(check_bind(remote, true, None).is_err());
+        assert!(check_bind(remote, true, Some(" ")).is_err());
+        assert!(check_bind(remote, true, Some("secret")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_match_json_commands() {
+        let cache = TempCache::new(Some(3600));
+        let server = server(&cache, api(), None);
+
+        let (reply, body) = get(&server, "/students").await;
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(body["data"][0]["name"], "Мария Иванова");
+        assert_eq!(reply.cache.map(|c| c.cached), Some(false));
+
+        // By name, percent-encoded, or by index; served from the cache the second time
+        let (reply, body) = get(&server, "/grades/%D0%BC%D0%B0%D1%80%D0%B8%D1%8F").await;
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(body["data"].as_array().unwrap().len(), 1);
+        assert_eq!(body["data"][0]["grades"][0]["subject"], "Математика");
+        let (reply, body) = get(&server, "/grades/1").await;
+        assert_eq!(body["data"][0]["student"]["name"], "Мария Иванова");
+        assert!(reply.cache.as_ref().unwrap().cached);
+        assert!(reply.into_response().headers().contains_key(header::AGE));
+
+        let (reply, body) = get(&server, "/schedule/1?date=19.02.2026").await;
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(body["data"][0]["date"], "2026-02-19");
+        assert!(!body["data"][0]["schedule"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bad_requests() {
+        let cache = TempCache::new(Some(3600));
+        let server = server(&cache, api().without("grades_summary"), None);
+
+        assert_eq!(get(&server, "/nothing").await.0.status, StatusCode::NOT_FOUND);
+        assert_eq!(get(&server, "/students/1").await.0.status, StatusCode::NOT_FOUND);
+        assert_eq!(get(&server, "/grades/Георги").await.0.status, StatusCode::NOT_FOUND);
+        assert_eq!(get(&server, "/schedule/1?date=tomorrow").await.0.status, StatusCode::BAD_REQUEST);
+        let post = server.respond(&Method::POST, &"/students".parse().unwrap(), &HeaderMap::new()).await;
+        assert_eq!(post.status, StatusCode::METHOD_NOT_ALLOWED);
+
+        // Every student failed: the envelope lists them
+        let (reply, body) = get(&server, "/grades").await;
+        assert_eq!(reply.status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["errors"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_token_is_required_when_set() {
+        let cache = TempCache::new(Some(3600));
+        let server = server(&cache, api(), Some("secret"));
+        let uri: Uri = "/students".parse().unwrap();
+
+        assert_eq!(server.respond(&Method::GET, &uri, &HeaderMap::new()).await.status, StatusCode::UNAUTHORIZED);
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer wrong"));
+        assert_eq!(server.respond(&Method::GET, &uri, &headers).await.status, StatusCode::UNAUTHORIZED);
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        assert_eq!(server.respond(&Method::GET, &uri, &headers).await.status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let metrics = Arc::new(Metrics::default());
+        metrics.record_request("/v1/diary/pupils", Some(200), true);
+        let cache = TempCache::new(Some(3600));
+        let server = server(&cache, api(), None).with_metrics(metrics);
+        get(&server, "/grades").await;
+
+        let reply = server.respond(&Method::GET, &"/metrics".parse().unwrap(), &HeaderMap::new()).await;
+        assert_eq!(reply.status, StatusCode::OK);
+        assert!(reply.content_type.starts_with("text/plain"));
+        assert!(reply.body.contains("shkolo_api_requests_total{endpoint=\"/v1/diary/pupils\",status=\"200\"} 1\n"));
+        // The lookups `/grades` just made
+        assert!(reply.body.contains("shkolo_cache_lookups_total{kind=\"grades\",result=\"miss\"} 2\n"));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("%D0%9C%D0%B0%D1%80%D0%B8%D1%8F"), "Мария");
+        assert_eq!(percent_decode("a%20b%2"), "a b%2");
+    }
+}
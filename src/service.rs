@@ -0,0 +1,842 @@
+//! Fetching through the cache, shared by the JSON commands, `cache --refresh`
+//! and the TUI. Every category follows the same rules: without `force`, cached
+//! data is served while it hasn't expired; otherwise the API is asked and the
+//! result saved. Offline, cached data of any age is served instead.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+
+use crate::api::*;
+use crate::cache::{CacheKind, CacheStore};
+use crate::i18n::{self, T};
+use crate::models::*;
+use crate::output;
+use crate::util;
+
+/// Where fetched data came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheInfo {
+    /// Served from the local cache rather than the API
+    pub cached: bool,
+    /// When the cached data was saved; None when fetched live
+    pub cached_at: Option<i64>,
+}
+
+impl CacheInfo {
+    fn cached(cached_at: i64) -> Self {
+        Self { cached: true, cached_at: Some(cached_at) }
+    }
+
+    /// Timestamp the data is as of: when it was cached, or now for live data
+    pub fn as_of(&self) -> i64 {
+        self.cached_at.unwrap_or_else(|| util::now_local().unix_timestamp())
+    }
+}
+
+/// What a fetch returns, to tell where its data came from
+trait Fetched {
+    fn info(&self) -> CacheInfo;
+}
+
+impl<T> Fetched for (T, CacheInfo) {
+    fn info(&self) -> CacheInfo {
+        self.1
+    }
+}
+
+impl<T, E> Fetched for (T, CacheInfo, E) {
+    fn info(&self) -> CacheInfo {
+        self.1
+    }
+}
+
+tokio::task_local! {
+    static OLDEST_CACHED: Cell<Option<i64>>;
+}
+
+/// Run `future`, also returning when the oldest cached data it was served had
+/// been saved (None when everything came from the API), for `json --max-age`
+pub async fn with_cache_age<F: Future>(future: F) -> (F::Output, Option<i64>) {
+    OLDEST_CACHED.scope(Cell::new(None), async {
+        let output = future.await;
+        (output, OLDEST_CACHED.with(Cell::get))
+    }).await
+}
+
+/// Run a fetch, noting how long it took for `--timing` and how old its data is
+/// for `with_cache_age`
+async fn timed<R: Fetched>(kind: CacheKind, student_id: Option<i64>, fetch: impl Future<Output = Result<R>>) -> Result<R> {
+    let started = Instant::now();
+    let result = fetch.await;
+    if let Some(cached_at) = result.as_ref().ok().and_then(|r| r.info().cached_at) {
+        let _ = OLDEST_CACHED.try_with(|oldest| oldest.set(Some(oldest.get().map_or(cached_at, |o| o.min(cached_at)))));
+    }
+    record_fetch(FetchTiming {
+        fetch: kind.name().to_string(),
+        student_id,
+        duration_ms: started.elapsed().as_millis() as u64,
+        cached: result.as_ref().is_ok_and(|r| r.info().cached),
+        ok: result.is_ok(),
+    });
+    result
+}
+
+/// Cached data that may be served without asking the API
+fn from_cache<T>(
+    client: &impl ShkoloApi,
+    force: bool,
+    read: impl FnOnce() -> Option<(T, String, bool, i64)>,
+) -> Option<(T, CacheInfo)> {
+    let offline = client.is_offline();
+    if force && !offline {
+        return None;
+    }
+    match read() {
+        Some((data, _, expired, cached_at)) if !expired || offline => Some((data, CacheInfo::cached(cached_at))),
+        _ => None,
+    }
+}
+
+/// Serve expired cached data when a fetch failed because we're offline
+fn offline_fallback<T>(
+    client: &impl ShkoloApi,
+    cached: Option<(T, String, bool, i64)>,
+    error: anyhow::Error,
+) -> Result<(T, CacheInfo)> {
+    match cached {
+        Some((data, _, _, cached_at)) if client.is_offline() => Ok((data, CacheInfo::cached(cached_at))),
+        _ => Err(error),
+    }
+}
+
+/// Explain an empty students list for account types we can't show students for
+pub fn unsupported_role_error(cache: &CacheStore) -> Option<anyhow::Error> {
+    let role = cache.load_token().ok()?.primary_role()?;
+    (role == Role::Teacher).then(|| anyhow!(T::teacher_unsupported(i18n::Lang::En)))
+}
+
+/// When school ends on days without a schedule (`default_school_day_end`), or the default
+pub fn school_day_end(cache: &CacheStore) -> Result<i32> {
+    match cache.load_ui_config().default_school_day_end {
+        Some(value) => parse_school_day_end(&value)
+            .map_err(|e| anyhow!("default_school_day_end in ui_config.json: {}", e)),
+        None => Ok(DEFAULT_SCHOOL_DAY_END),
+    }
+}
+
+pub async fn fetch_students(client: &impl ShkoloApi, cache: &CacheStore, force: bool) -> Result<(Vec<Student>, CacheInfo)> {
+    timed(CacheKind::Students, None, async {
+        if let Some(hit) = from_cache(client, force, || cache.get_students()) {
+            return Ok(hit);
+        }
+
+        let students = match client.get_pupils().await {
+            Ok(response) => response.students(),
+            Err(e) => return offline_fallback(client, cache.get_students(), e),
+        };
+        if students.is_empty() {
+            if let Some(err) = unsupported_role_error(cache) {
+                return Err(err);
+            }
+        }
+        cache.save_students(&students)?;
+
+        Ok((students, CacheInfo::default()))
+    }).await
+}
+
+/// Homework of every course with any. Courses that fail are returned as
+/// errors next to the rest of the list, which then isn't cached: a partial
+/// list must not replace a complete cached one.
+pub async fn fetch_homework(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    student_id: i64,
+    force: bool,
+) -> Result<(Vec<Homework>, CacheInfo, Vec<String>)> {
+    timed(CacheKind::Homework, Some(student_id), async {
+        if let Some((homework, info)) = from_cache(client, force, || cache.get_homework(student_id)) {
+            return Ok((homework, info, Vec::new()));
+        }
+
+        let courses_response = match client.get_homework_courses(student_id).await {
+            Ok(r) => r,
+            Err(e) => {
+                return offline_fallback(client, cache.get_homework(student_id), e)
+                    .map(|(homework, info)| (homework, info, Vec::new()));
+            }
+        };
+
+        let mut homework = Vec::new();
+        let mut errors = Vec::new();
+        let counts = courses_response.cyc_group_homeworks_count.unwrap_or_default();
+        for course in courses_response.courses.unwrap_or_default() {
+            let Some(cyc_group_id) = course.cyc_group_id else { continue };
+            if counts.get(&cyc_group_id.to_string()).copied().unwrap_or(0) == 0 {
+                continue;
+            }
+
+            let subject = course.course_short_name
+                .or(course.course_name)
+                .unwrap_or_else(|| "Unknown".to_string());
+            match client.get_homework_list(cyc_group_id).await {
+                Ok(response) => {
+                    homework.extend(response.homeworks.unwrap_or_default().iter().map(|item| Homework::from_item(item, &subject)));
+                }
+                Err(e) => errors.push(format!("{}: {}", subject, e)),
+            }
+        }
+
+        // The same homework can come back under several groups
+        Homework::dedup_and_sort(&mut homework);
+
+        if errors.is_empty() {
+            cache.save_homework(student_id, &homework)?;
+        }
+
+        Ok((homework, CacheInfo::default(), errors))
+    }).await
+}
+
+/// Subjects with at least one grade
+pub async fn fetch_grades(client: &impl ShkoloApi, cache: &CacheStore, student_id: i64, force: bool) -> Result<(Vec<Grade>, CacheInfo)> {
+    timed(CacheKind::Grades, Some(student_id), async {
+        if let Some(hit) = from_cache(client, force, || cache.get_grades(student_id)) {
+            return Ok(hit);
+        }
+
+        let response = match client.get_grades_summary(student_id).await {
+            Ok(r) => r,
+            Err(e) => return offline_fallback(client, cache.get_grades(student_id), e),
+        };
+        let grades: Vec<Grade> = response.grades
+            .or(response.courses)
+            .unwrap_or_default()
+            .iter()
+            .map(Grade::from_course_grades)
+            .filter(|g| g.has_grades())
+            .collect();
+        cache.save_grades(student_id, &grades)?;
+
+        Ok((grades, CacheInfo::default()))
+    }).await
+}
+
+/// Lessons on `date` (YYYY-MM-DD) in hour order
+pub async fn fetch_schedule(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    student_id: i64,
+    date: &str,
+    force: bool,
+) -> Result<(Vec<ScheduleHour>, CacheInfo)> {
+    timed(CacheKind::Schedule, Some(student_id), async {
+        if let Some(hit) = from_cache(client, force, || cache.get_schedule(student_id, date)) {
+            return Ok(hit);
+        }
+
+        let response = match client.get_pupil_schedule(student_id, date).await {
+            Ok(r) => r,
+            Err(e) => return offline_fallback(client, cache.get_schedule(student_id, date), e),
+        };
+        let mut schedule: Vec<ScheduleHour> = response.schedule_hours
+            .or(response.data)
+            .unwrap_or_default()
+            .iter()
+            .map(ScheduleHour::from_raw)
+            .collect();
+        schedule.sort_by_key(|h| h.hour_number);
+        cache.save_schedule(student_id, date, &schedule)?;
+
+        Ok((schedule, CacheInfo::default()))
+    }).await
+}
+
+/// Load the schedules of `dates` into the cache, all at once, so that paging
+/// to them is instant. Days still fresh in the cache aren't asked for again;
+/// failures are left for when the day is actually opened.
+pub async fn prefetch_schedules(client: &impl ShkoloApi, cache: &CacheStore, student_id: i64, dates: &[String]) {
+    let fetches = dates.iter().map(|date| fetch_schedule(client, cache, student_id, date, false));
+    futures::future::join_all(fetches).await;
+}
+
+/// Absences, newest first; a day's absences by hour, then subject
+pub async fn fetch_absences(client: &impl ShkoloApi, cache: &CacheStore, student_id: i64, force: bool) -> Result<(Vec<Absence>, CacheInfo)> {
+    timed(CacheKind::Absences, Some(student_id), async {
+        if let Some(hit) = from_cache(client, force, || cache.get_absences(student_id)) {
+            return Ok(hit);
+        }
+
+        let response = match client.get_absences(student_id).await {
+            Ok(r) => r,
+            Err(e) => return offline_fallback(client, cache.get_absences(student_id), e),
+        };
+        let mut absences: Vec<Absence> = response.absences
+            .unwrap_or_default()
+            .iter()
+            .map(Absence::from_raw)
+            .collect();
+        absences.sort_by(|a, b| {
+            b.date_sort.cmp(&a.date_sort)
+                .then_with(|| a.hour.cmp(&b.hour))
+                .then_with(|| a.subject.cmp(&b.subject))
+        });
+        cache.save_absences(student_id, &absences)?;
+
+        Ok((absences, CacheInfo::default()))
+    }).await
+}
+
+/// Feedbacks, newest first
+pub async fn fetch_feedbacks(client: &impl ShkoloApi, cache: &CacheStore, student_id: i64, force: bool) -> Result<(Vec<Feedback>, CacheInfo)> {
+    timed(CacheKind::Feedbacks, Some(student_id), async {
+        if let Some(hit) = from_cache(client, force, || cache.get_feedbacks(student_id)) {
+            return Ok(hit);
+        }
+
+        let response = match client.get_feedbacks(student_id).await {
+            Ok(r) => r,
+            Err(e) => return offline_fallback(client, cache.get_feedbacks(student_id), e),
+        };
+        let mut feedbacks: Vec<Feedback> = response.data
+            .or(response.feedbacks)
+            .unwrap_or_default()
+            .iter()
+            .map(Feedback::from_raw)
+            .collect();
+        feedbacks.sort_by(Feedback::cmp_by_date);
+        cache.save_feedbacks(student_id, &feedbacks)?;
+
+        Ok((feedbacks, CacheInfo::default()))
+    }).await
+}
+
+/// Events and invitations, which include announced tests
+pub async fn fetch_events(client: &impl ShkoloApi, cache: &CacheStore, student_id: i64, force: bool) -> Result<(Vec<Event>, CacheInfo)> {
+    timed(CacheKind::Events, Some(student_id), async {
+        if let Some(hit) = from_cache(client, force, || cache.get_events(student_id)) {
+            return Ok(hit);
+        }
+
+        let response = match client.get_pupil_events(student_id).await {
+            Ok(r) => r,
+            Err(e) => return offline_fallback(client, cache.get_events(student_id), e),
+        };
+        let events: Vec<Event> = response.invitations
+            .or(response.data)
+            .unwrap_or_default()
+            .iter()
+            .map(Event::from_raw)
+            .collect();
+        cache.save_events(student_id, &events)?;
+
+        Ok((events, CacheInfo::default()))
+    }).await
+}
+
+/// The first page of notifications
+pub async fn fetch_notifications(client: &impl ShkoloApi, cache: &CacheStore, force: bool) -> Result<(Vec<Notification>, CacheInfo)> {
+    timed(CacheKind::Notifications, None, async {
+        if let Some(hit) = from_cache(client, force, || cache.get_notifications()) {
+            return Ok(hit);
+        }
+
+        let notifications = match client.get_notifications(1).await {
+            Ok(response) => response.notifications(),
+            Err(e) => return offline_fallback(client, cache.get_notifications(), e),
+        };
+        cache.save_notifications(&notifications)?;
+
+        Ok((notifications, CacheInfo::default()))
+    }).await
+}
+
+/// Who a new message can be sent to; kept longer than other data
+pub async fn fetch_recipients(client: &impl ShkoloApi, cache: &CacheStore, force: bool) -> Result<(Vec<Recipient>, CacheInfo)> {
+    timed(CacheKind::Recipients, None, async {
+        if let Some(hit) = from_cache(client, force, || cache.get_recipients()) {
+            return Ok(hit);
+        }
+
+        let recipients: Vec<Recipient> = match client.get_recipients().await {
+            Ok(raw) => raw.iter().map(Recipient::from_raw).collect(),
+            Err(e) => return offline_fallback(client, cache.get_recipients(), e),
+        };
+        cache.save_recipients(&recipients)?;
+
+        Ok((recipients, CacheInfo::default()))
+    }).await
+}
+
+/// Threads of a messenger folder (None: the inbox)
+pub async fn fetch_messages(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    folder_id: Option<i64>,
+    force: bool,
+) -> Result<(Vec<MessageThread>, CacheInfo)> {
+    timed(CacheKind::Messages, None, async {
+        if let Some(hit) = from_cache(client, force, || cache.get_messages(folder_id)) {
+            return Ok(hit);
+        }
+
+        let messages: Vec<MessageThread> = match client.get_messenger_threads(folder_id).await {
+            Ok(raw_threads) => raw_threads.iter().map(MessageThread::from_raw).collect(),
+            Err(e) => return offline_fallback(client, cache.get_messages(folder_id), e),
+        };
+        cache.save_messages(folder_id, &messages)?;
+
+        Ok((messages, CacheInfo::default()))
+    }).await
+}
+
+/// Students picked by a 1-based index or part of a name (case-insensitive);
+/// all of them without a selector, None when it matches no one
+pub fn find_students<'a>(students: &'a [Student], selector: Option<&str>) -> Option<Vec<&'a Student>> {
+    let Some(selector) = selector else {
+        return Some(students.iter().collect());
+    };
+    if let Ok(index) = selector.parse::<usize>() {
+        if index > 0 && index <= students.len() {
+            return Some(vec![&students[index - 1]]);
+        }
+    }
+
+    let lower = selector.to_lowercase();
+    let matches: Vec<_> = students.iter()
+        .filter(|student| student.name.to_lowercase().contains(&lower))
+        .collect();
+    (!matches.is_empty()).then_some(matches)
+}
+
+/// A student left out of a per-student response
+pub fn student_error(student: &Student, error: anyhow::Error) -> StudentError {
+    StudentError { student_id: student.id, student: student.name.clone(), error: error.to_string() }
+}
+
+/// Homework entries of `json homework` and `serve`, and the students that failed
+pub async fn homework_entries(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    students: &[&Student],
+    filter: &HomeworkFilter,
+    keywords: &ExamKeywords,
+    school_day_end: i32,
+    force: bool,
+) -> (Vec<output::StudentHomework>, Vec<StudentError>) {
+    let now = util::now_local();
+    let today = now.date();
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    for &s in students {
+        let (homework, info, errors) = match fetch_homework(client, cache, s.id, force).await {
+            Ok(result) => result,
+            Err(e) => { failures.push(student_error(s, e)); continue; }
+        };
+        // Today's lessons tell when homework due today stops being pending
+        let schedule = fetch_schedule(client, cache, s.id, &today.to_string(), force).await
+            .map(|(schedule, _)| schedule)
+            .unwrap_or_default();
+        let cache = output::CacheState::from(info);
+        let school_day_over = school_day_over(&schedule, (now.hour(), now.minute()), school_day_end);
+        let warning = filter.subject.as_ref()
+            .filter(|q| !homework.iter().any(|hw| hw.subject_matches(q)))
+            .map(|q| format!("no homework subject matches \"{}\"", q));
+        let homework: Vec<_> = homework.into_iter()
+            .filter(|hw| filter.matches(hw, &today.to_string(), school_day_over))
+            .map(|hw| output::HomeworkEntry::new(hw, today, school_day_over, keywords))
+            .collect();
+        entries.push(output::StudentHomework { student: s.clone(), cache, homework, errors, warning });
+    }
+    (entries, failures)
+}
+
+/// Grade entries of `json grades` and `serve`, and the students that failed
+pub async fn grades_entries(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    students: &[&Student],
+    filter: &GradeFilter,
+    force: bool,
+) -> (Vec<output::StudentGrades>, Vec<StudentError>) {
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    for &s in students {
+        let (grades, info) = match fetch_grades(client, cache, s.id, force).await {
+            Ok(result) => result,
+            Err(e) => { failures.push(student_error(s, e)); continue; }
+        };
+        let cache = output::CacheState::from(info);
+        let grades = filter.apply(grades);
+        let warning = (!filter.is_empty() && grades.is_empty())
+            .then(|| format!("no subjects match {}", filter));
+        entries.push(output::StudentGrades { student: s.clone(), cache, grades, warning });
+    }
+    (entries, failures)
+}
+
+/// Schedule entries of `json schedule` and `serve` for `date`, and the students that failed
+pub async fn schedule_entries(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    students: &[&Student],
+    date: &str,
+    force: bool,
+) -> (Vec<output::StudentSchedule>, Vec<StudentError>) {
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    for &s in students {
+        let (schedule, info) = match fetch_schedule(client, cache, s.id, date, force).await {
+            Ok(result) => result,
+            Err(e) => { failures.push(student_error(s, e)); continue; }
+        };
+        let cache = output::CacheState::from(info);
+        entries.push(output::StudentSchedule { student: s.clone(), cache, date: date.to_string(), schedule });
+    }
+    (entries, failures)
+}
+
+/// Summary entries of `json summary` and `serve` with the schedule of `date`,
+/// and the students that failed
+pub async fn summary_entries(
+    client: &impl ShkoloApi,
+    cache: &CacheStore,
+    students: &[Student],
+    date: &str,
+    excuse_days: i64,
+    force: bool,
+) -> (Vec<output::StudentSummary>, Vec<StudentError>) {
+    let today = util::now_local().date();
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    for s in students {
+        let summary = async {
+            let (homework, homework_info, _) = fetch_homework(client, cache, s.id, force).await?;
+            let (grades, grades_info) = fetch_grades(client, cache, s.id, force).await?;
+            let (schedule, schedule_info) = fetch_schedule(client, cache, s.id, date, force).await?;
+            let (absences, absences_info) = fetch_absences(client, cache, s.id, force).await?;
+            let (feedbacks, feedbacks_info) = fetch_feedbacks(client, cache, s.id, force).await?;
+            let parts = [
+                output::CacheState::from(homework_info),
+                output::CacheState::from(grades_info),
+                output::CacheState::from(schedule_info),
+                output::CacheState::from(absences_info),
+                output::CacheState::from(feedbacks_info),
+            ];
+
+            // Get recent homework (last 5)
+            let recent_homework: Vec<_> = homework.into_iter().take(5).collect();
+
+            Ok::<_, anyhow::Error>(output::StudentSummary {
+                student: s.clone(),
+                cache: output::CacheState::merge(&parts),
+                today_schedule: schedule,
+                recent_homework,
+                grades_count: grades.len(),
+                this_week: WeeklySummary::compute(&absences, &feedbacks, &util::today_local()),
+                expiring_excuses: absences.iter().filter(|a| a.excuse_deadline_soon(today, excuse_days)).count(),
+            })
+        }.await;
+        match summary {
+            Ok(summary) => entries.push(summary),
+            Err(e) => failures.push(student_error(s, e)),
+        }
+    }
+    (entries, failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fake::FakeApi;
+    use crate::cache::TempCache;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_fresh_cache_served_until_forced() {
+        let cache = TempCache::new(Some(3600));
+        let api = FakeApi::default().with("absences", include_str!("api/fixtures/absences.json"));
+
+        let (absences, info) = fetch_absences(&api, &cache, 1, false).await.unwrap();
+        assert_eq!(info, CacheInfo::default());
+        assert!(!absences.is_empty());
+
+        // Fresh: no request
+        let (cached, info) = fetch_absences(&api, &cache, 1, false).await.unwrap();
+        assert!(info.cached && info.cached_at.is_some());
+        assert_eq!(cached.len(), absences.len());
+        assert_eq!(api.calls(), ["absences"]);
+
+        // Forced: asks again even though the cache is fresh
+        let (_, info) = fetch_absences(&api, &cache, 1, true).await.unwrap();
+        assert!(!info.cached);
+        assert_eq!(api.calls(), ["absences", "absences"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetches_are_timed() {
+        let cache = TempCache::new(Some(3600));
+        let api = FakeApi::default().with("absences", include_str!("api/fixtures/absences.json"));
+
+        let timing = with_timing(async {
+            fetch_absences(&api, &cache, 1, false).await.unwrap();
+            fetch_absences(&api, &cache, 1, false).await.unwrap();
+            fetch_grades(&api, &cache, 1, false).await.unwrap_err();
+            ApiResponse::new((), false, None).timed(Instant::now()).timing.unwrap()
+        }).await;
+        let fetches: Vec<_> = timing.fetches.iter().map(|f| (f.fetch.as_str(), f.student_id, f.cached, f.ok)).collect();
+        assert_eq!(fetches, [
+            ("absences", Some(1), false, true),
+            ("absences", Some(1), true, true),
+            ("grades", Some(1), false, false),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_expired_cache_only_served_offline() {
+        let cache = TempCache::new(Some(-1));
+        let grade = Grade {
+            subject: "Математика".into(),
+            term1_grades: vec!["6".into()],
+            term2_grades: Vec::new(),
+            term1_final: None,
+            term2_final: None,
+            annual: None,
+            entries: Vec::new(),
+        };
+        cache.save_grades(1, &[grade]).unwrap();
+
+        // Online, a failed fetch doesn't fall back to expired data
+        let api = FakeApi::default();
+        assert!(fetch_grades(&api, &cache, 1, false).await.is_err());
+        assert_eq!(api.calls(), ["grades_summary"]);
+
+        // Offline, expired data is served even when forced, without a request
+        let api = FakeApi::offline();
+        let (grades, info) = fetch_grades(&api, &cache, 1, true).await.unwrap();
+        assert_eq!(grades[0].subject, "Математика");
+        assert!(info.cached);
+        assert!(api.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_skips_cached_days() {
+        let cache = TempCache::new(Some(3600));
+        let schedule = include_str!("api/fixtures/schedule.json");
+        let api = FakeApi::default().with("schedule/2026-02-18", schedule).with("schedule/2026-02-20", schedule);
+        cache.save_schedule(1, "2026-02-18", &[]).unwrap();
+
+        prefetch_schedules(&api, &cache, 1, &["2026-02-18".to_string(), "2026-02-20".to_string()]).await;
+        assert_eq!(api.calls(), ["schedule/2026-02-20"]);
+        assert_eq!(cache.get_schedule(1, "2026-02-20").unwrap().0.len(), 2);
+
+        // A failing day doesn't stop the others
+        prefetch_schedules(&api, &cache, 1, &["2026-02-17".to_string(), "2026-02-18".to_string()]).await;
+        assert_eq!(api.calls(), ["schedule/2026-02-20", "schedule/2026-02-17"]);
+    }
+
+    #[tokio::test]
+    async fn test_absences_sorted_newest_first() {
+        let cache = TempCache::new(Some(3600));
+        let absence = |id: &str, date: &str, hour: i32, subject: &str| serde_json::json!({
+            "id": id, "date": date, "school_hour": hour, "course_short_name": subject,
+        });
+        let response = serde_json::json!({ "absences": [
+            absence("1", "10.02.2026", 1, "Математика"),
+            absence("2", "18.02.2026", 3, "ФВС"),
+            absence("3", "18.02.2026", 3, "БЕЛ"),
+            absence("4", "18.02.2026", 1, "История"),
+        ]});
+        let api = FakeApi::default().with("absences", &response.to_string());
+
+        let (absences, _) = fetch_absences(&api, &cache, 1, true).await.unwrap();
+        let ids: Vec<_> = absences.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["4", "3", "2", "1"]);
+        // What's saved is what was returned
+        let saved = cache.get_absences(1).unwrap().0;
+        assert_eq!(saved.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), ids);
+    }
+
+    #[tokio::test]
+    async fn test_homework_skips_courses_without_any() {
+        let cache = TempCache::new(Some(3600));
+        let api = FakeApi::default()
+            .with("homework_courses", include_str!("api/fixtures/homework_courses.json"))
+            .with("homework_list/9001", include_str!("api/fixtures/homework_list.json"));
+
+        let (homework, _, errors) = fetch_homework(&api, &cache, 1, false).await.unwrap();
+        assert!(errors.is_empty());
+        assert!(!homework.is_empty() && homework.iter().all(|hw| hw.subject == "БЕЛ"));
+        // 9002 has no homework, so it isn't asked for
+        assert_eq!(api.calls(), ["homework_courses", "homework_list/9001"]);
+        assert!(cache.get_homework(1).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_students_saved_for_next_time() {
+        let cache = TempCache::new(Some(3600));
+        let api = FakeApi::default().with("pupils", include_str!("api/fixtures/pupils.json"));
+
+        let (students, _) = fetch_students(&api, &cache, false).await.unwrap();
+        assert_eq!(students.len(), 2);
+        let (again, info) = fetch_students(&api, &cache, false).await.unwrap();
+        assert!(info.cached);
+        assert_eq!(again.iter().map(|s| s.id).collect::<Vec<_>>(), students.iter().map(|s| s.id).collect::<Vec<_>>());
+        assert_eq!(api.calls(), ["pupils"]);
+    }
+
+    fn homework_item(id: i64, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "homework_text": text,
+            "homework_due_date": "25.02.2026",
+            "shi_date": "20.02.2026",
+            "shi_date_for_sort": "2026-02-20",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_partial_homework_failure_keeps_cache() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/homeworks/courses"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "courses": [
+                    { "cyc_group_id": 1, "course_short_name": "БЕЛ" },
+                    { "cyc_group_id": 2, "course_short_name": "Математика" },
+                    { "cyc_group_id": 3, "course_short_name": "История" },
+                ],
+                "cycGroupHomeworksCount": { "1": 1, "2": 1, "3": 1 },
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/homeworks/list/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "homeworks": [homework_item(10, "Fresh БЕЛ")],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/homeworks/list/2"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/homeworks/list/3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "homeworks": [homework_item(30, "Fresh История")],
+            })))
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(0));
+        let previous: Vec<Homework> = (1..=3)
+            .map(|i| Homework::from_item(
+                &serde_json::from_value(homework_item(i, "Cached")).unwrap(),
+                "Cached",
+            ))
+            .collect();
+        cache.save_homework(42, &previous).unwrap();
+
+        let client = ShkoloClient::with_token("token".to_string(), None).with_base_url(&server.uri());
+        let (homework, info, errors) = fetch_homework(&client, &cache, 42, true).await.unwrap();
+
+        assert!(!info.cached);
+        assert_eq!(homework.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Математика"));
+
+        let (kept, _, _, _) = cache.get_homework(42).unwrap();
+        assert_eq!(kept.len(), 3);
+        assert!(kept.iter().all(|h| h.text == "Cached"));
+    }
+
+    #[tokio::test]
+    async fn test_events_fetched_then_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/events/invitations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "invitations": [
+                    { "id": 1, "title": "Класна работа", "start_date": "2026-02-24", "type": 13 },
+                    { "id": 2, "name": "Родителска среща", "start_date": "2026-02-26", "type": 3 },
+                ],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(3600));
+        let client = ShkoloClient::with_token("token".to_string(), None).with_base_url(&server.uri());
+
+        let (events, info) = fetch_events(&client, &cache, 42, false).await.unwrap();
+        assert!(!info.cached);
+        assert_eq!(events.iter().filter(|e| e.is_test).count(), 1);
+        assert_eq!(events[1].title, "Родителска среща");
+
+        let (events, info) = fetch_events(&client, &cache, 42, false).await.unwrap();
+        assert!(info.cached && info.cached_at.is_some());
+        assert_eq!(events.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_messages_cached_per_folder() {
+        let server = MockServer::start().await;
+        let thread = |id: i64, subject: &str| serde_json::json!({ "id": id, "subject": subject, "is_unread": true });
+        Mock::given(method("GET"))
+            .and(path("/v1/messenger/threads"))
+            .and(wiremock::matchers::query_param("folderId", "5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([thread(2, "Изпратено")])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messenger/threads"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([thread(1, "Входящо")])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(3600));
+        let client = ShkoloClient::with_token("token".to_string(), None).with_base_url(&server.uri());
+
+        let (sent, info) = fetch_messages(&client, &cache, Some(5), false).await.unwrap();
+        assert!(!info.cached);
+        assert_eq!(sent[0].subject, "Изпратено");
+        let (inbox, _) = fetch_messages(&client, &cache, None, false).await.unwrap();
+        assert_eq!(inbox[0].subject, "Входящо");
+
+        // Switching back is served from each folder's own file
+        let (sent, info) = fetch_messages(&client, &cache, Some(5), false).await.unwrap();
+        assert!(info.cached);
+        assert_eq!(sent[0].id, 2);
+        assert!(cache.cache_dir().join("messages_5.json").exists());
+        assert_eq!(cache.clear_type(crate::cache::CacheKind::Messages, None).unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_language_header_follows_setting() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/diary/homeworks/courses"))
+            .and(header("language", "en"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "courses": [] })))
+            .mount(&server)
+            .await;
+
+        let cache = TempCache::new(Some(0));
+        let client = ShkoloClient::with_token("token".to_string(), None).with_base_url(&server.uri());
+        assert!(fetch_homework(&client, &cache, 42, true).await.is_err());
+
+        // Clones share the setting, so background tasks pick it up too
+        client.clone().set_language(i18n::Lang::De);
+        let (homework, _, errors) = fetch_homework(&client, &cache, 42, true).await.unwrap();
+        assert!(homework.is_empty() && errors.is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+//! Colors for the human-readable commands (login, status, cache, ...) and
+//! `--format table`. The TUI draws its own colors; JSON and the llm digest
+//! never have any.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Colors on a terminal unless NO_COLOR is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Decide once at startup whether stdout gets ANSI colors
+pub fn init(choice: ColorChoice) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    COLOR.store(color_enabled(choice, no_color_env, std::io::stdout().is_terminal()), Ordering::Relaxed);
+}
+
+/// Whether `init` decided on colors
+pub fn enabled() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+/// `auto` colors only on a terminal without a non-empty `NO_COLOR`
+/// (https://no-color.org); an explicit `always` or `never` overrides both
+fn color_enabled(choice: ColorChoice, no_color_env: bool, is_tty: bool) -> bool {
+    match choice {
+        ColorChoice::Auto => !no_color_env && is_tty,
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    }
+}
+
+/// `text` in the ANSI SGR `code` when `enabled`
+pub fn paint(code: &str, text: &str, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn styled(code: &str, text: &str) -> String {
+    paint(code, text, enabled())
+}
+
+/// Success messages and good news
+pub fn green(text: &str) -> String {
+    styled("32", text)
+}
+
+/// Something the user should act on
+pub fn yellow(text: &str) -> String {
+    styled("33", text)
+}
+
+/// Headings
+pub fn bold(text: &str) -> String {
+    styled("1", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_only_on_terminal_without_opt_out() {
+        assert!(color_enabled(ColorChoice::Auto, false, true));
+        assert!(!color_enabled(ColorChoice::Never, false, true));
+        assert!(!color_enabled(ColorChoice::Auto, true, true));
+        assert!(!color_enabled(ColorChoice::Auto, false, false));
+        // Asked for explicitly, colors go even to pipes and past NO_COLOR
+        assert!(color_enabled(ColorChoice::Always, true, false));
+
+        assert_eq!(paint("32", "ok", true), "\x1b[32mok\x1b[0m");
+        assert_eq!(paint("32", "ok", false), "ok");
+        // Nothing enabled colors in tests, so helpers print plain text
+        assert_eq!(green("Logged in"), "Logged in");
+    }
+}
@@ -0,0 +1,264 @@
+//! Aligned text tables printed by `json --format table`, for people who want
+//! students, grades or a schedule in the terminal without the TUI.
+
+use crate::models::*;
+use crate::output::{StudentGrades, StudentSchedule};
+use crate::style::paint;
+
+/// Cell text and the ANSI SGR code it's painted in when colors are on
+type Cell = (String, Option<&'static str>);
+
+fn plain(text: impl Into<String>) -> Cell {
+    (text.into(), None)
+}
+
+/// A grade or average in the color of its place on the 2-6 scale
+fn graded(text: String, value: Option<f64>) -> Cell {
+    let code = value.map(|v| match v {
+        v if v >= 5.5 => "32",
+        v if v >= 4.5 => "36",
+        v if v >= 3.5 => "33",
+        v if v >= 2.5 => "35",
+        _ => "31",
+    });
+    (text, code)
+}
+
+/// Rows under a bold header, columns padded to their widest cell. Colors go
+/// around the padded text so they don't change the alignment.
+fn table(header: &[&str], rows: &[Vec<Cell>], color: bool) -> Vec<String> {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, (text, _)) in widths.iter_mut().zip(row) {
+            *width = (*width).max(text.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<(&str, Option<&str>)>| {
+        let last = cells.len().saturating_sub(1);
+        let line = cells.into_iter().zip(&widths).enumerate()
+            .map(|(i, ((text, code), &width))| {
+                let padded = if i == last { text.to_string() } else { format!("{:<width$}", text, width = width) };
+                match code {
+                    Some(code) => paint(code, &padded, color),
+                    None => padded,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        line.trim_end().to_string()
+    };
+
+    let mut lines = vec![line(header.iter().map(|h| (*h, Some("1"))).collect())];
+    lines.extend(rows.iter().map(|row| line(row.iter().map(|(text, code)| (text.as_str(), *code)).collect())));
+    lines
+}
+
+/// "Name (class)" heading above a student's table
+fn heading(student: &Student, suffix: Option<&str>, color: bool) -> String {
+    let mut text = match &student.class_name {
+        Some(class) => format!("{} ({})", student.name, class),
+        None => student.name.clone(),
+    };
+    if let Some(suffix) = suffix {
+        text = format!("{} - {}", text, suffix);
+    }
+    paint("1", &text, color)
+}
+
+fn finish(lines: Vec<String>) -> String {
+    if lines.is_empty() {
+        return "No students\n".to_string();
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+pub fn students(students: &[Student], color: bool) -> String {
+    if students.is_empty() {
+        return finish(Vec::new());
+    }
+    let rows: Vec<Vec<Cell>> = students.iter()
+        .map(|s| vec![
+            plain(s.id.to_string()),
+            plain(s.name.as_str()),
+            plain(s.class_name.clone().unwrap_or_default()),
+            plain(s.school_name.clone().unwrap_or_default()),
+        ])
+        .collect();
+    finish(table(&["ID", "Name", "Class", "School"], &rows, color))
+}
+
+/// One table per student: a row per subject with each term's grades, their
+/// average and final grade, and the annual grade
+pub fn grades(entries: &[StudentGrades], color: bool) -> String {
+    let mut lines = Vec::new();
+    for entry in entries {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(heading(&entry.student, None, color));
+        if entry.grades.is_empty() {
+            lines.push(entry.warning.clone().unwrap_or_else(|| "no grades".to_string()));
+            continue;
+        }
+
+        let final_cell = |grade: &Option<String>| match grade {
+            Some(g) => graded(g.clone(), parse_bg_grade(g)),
+            None => plain("-"),
+        };
+        let rows: Vec<Vec<Cell>> = entry.grades.iter()
+            .map(|grade| {
+                let mut row = vec![plain(grade.subject.as_str())];
+                for (term, values, final_grade) in [(1, &grade.term1_grades, &grade.term1_final), (2, &grade.term2_grades, &grade.term2_final)] {
+                    let average = grade.weighted_average(term);
+                    row.push(plain(values.join(" ")));
+                    row.push(match average {
+                        Some(a) => graded(format!("{:.2}", a), Some(a)),
+                        None => plain("-"),
+                    });
+                    row.push(final_cell(final_grade));
+                }
+                row.push(final_cell(&grade.annual));
+                row
+            })
+            .collect();
+        lines.extend(table(&["Subject", "Term 1", "Avg", "Final", "Term 2", "Avg", "Final", "Annual"], &rows, color));
+    }
+    finish(lines)
+}
+
+/// One table per student with the day's lessons in hour order
+pub fn schedule(entries: &[StudentSchedule], color: bool) -> String {
+    let mut lines = Vec::new();
+    for entry in entries {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(heading(&entry.student, Some(&entry.date), color));
+        if entry.schedule.is_empty() {
+            lines.push("no lessons".to_string());
+            continue;
+        }
+
+        let rows: Vec<Vec<Cell>> = entry.schedule.iter()
+            .map(|hour| vec![
+                plain(hour.hour_number.to_string()),
+                plain(format!("{}-{}", hour.from_time, hour.to_time)),
+                plain(hour.subject.as_str()),
+                plain(hour.teacher.clone().unwrap_or_default()),
+                plain(hour.room.clone().unwrap_or_default()),
+            ])
+            .collect();
+        lines.extend(table(&["#", "Time", "Subject", "Teacher", "Room"], &rows, color));
+    }
+    finish(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::CacheState;
+
+    fn student() -> Student {
+        Student { id: 300401, name: "Мария Иванова".into(), class_name: Some("5А".into()), school_name: Some("СУ \"Христо Ботев\"".into()) }
+    }
+
+    fn grade(subject: &str, term1: &[&str], term2: &[&str], term1_final: Option<&str>) -> Grade {
+        Grade {
+            subject: subject.into(),
+            term1_grades: term1.iter().map(|g| g.to_string()).collect(),
+            term2_grades: term2.iter().map(|g| g.to_string()).collect(),
+            term1_final: term1_final.map(Into::into),
+            term2_final: None,
+            annual: None,
+            entries: Vec::new(),
+        }
+    }
+
+    fn lesson(hour_number: i32, from_time: &str, to_time: &str, subject: &str, teacher: Option<&str>, room: Option<&str>) -> ScheduleHour {
+        ScheduleHour {
+            hour_number,
+            from_time: from_time.into(),
+            to_time: to_time.into(),
+            subject: subject.into(),
+            teacher: teacher.map(Into::into),
+            topic: None,
+            homework: None,
+            room: room.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_students_table() {
+        let petar = Student { id: 300402, name: "Петър Иванов".into(), class_name: None, school_name: None };
+        assert_eq!(students(&[student(), petar], false), concat!(
+            "ID      Name           Class  School\n",
+            "300401  Мария Иванова  5А     СУ \"Христо Ботев\"\n",
+            "300402  Петър Иванов\n",
+        ));
+        assert_eq!(students(&[], false), "No students\n");
+        assert_eq!(grades(&[], false), "No students\n");
+    }
+
+    #[test]
+    fn test_snapshot_grades_table() {
+        let entries = [StudentGrades {
+            student: student(),
+            cache: CacheState::default(),
+            grades: vec![
+                grade("Математика", &["6", "5"], &["6"], Some("6")),
+                grade("Човекът и природата", &["Добър"], &[], None),
+            ],
+            warning: None,
+        }];
+        assert_eq!(grades(&entries, false), concat!(
+            "Мария Иванова (5А)\n",
+            "Subject              Term 1  Avg   Final  Term 2  Avg   Final  Annual\n",
+            "Математика           6 5     5.50  6      6       6.00  -      -\n",
+            "Човекът и природата  Добър   4.00  -              -     -      -\n",
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_schedule_table() {
+        let entries = [
+            StudentSchedule {
+                student: student(),
+                cache: CacheState::default(),
+                date: "2026-02-19".into(),
+                schedule: vec![
+                    lesson(1, "08:00", "08:40", "Математика", Some("Петрова"), Some("21")),
+                    lesson(2, "08:50", "09:30", "Български език и литература", None, None),
+                ],
+            },
+            StudentSchedule {
+                student: Student { id: 300402, name: "Петър Иванов".into(), class_name: None, school_name: None },
+                cache: CacheState::default(),
+                date: "2026-02-19".into(),
+                schedule: Vec::new(),
+            },
+        ];
+        assert_eq!(schedule(&entries, false), concat!(
+            "Мария Иванова (5А) - 2026-02-19\n",
+            "#  Time         Subject                      Teacher  Room\n",
+            "1  08:00-08:40  Математика                   Петрова  21\n",
+            "2  08:50-09:30  Български език и литература\n",
+            "\n",
+            "Петър Иванов - 2026-02-19\n",
+            "no lessons\n",
+        ));
+    }
+
+    #[test]
+    fn test_colors_keep_alignment() {
+        let entries = [StudentGrades { student: student(), cache: CacheState::default(), grades: vec![grade("БЕЛ", &["2"], &[], None)], warning: None }];
+        let out = grades(&entries, true);
+        assert!(out.starts_with("\x1b[1mМария Иванова (5А)\x1b[0m\n\x1b[1mSubject\x1b[0m  "), "{}", out);
+        // The average is padded inside its color, so columns line up as without colors
+        assert!(out.contains("\x1b[31m2.00\x1b[0m  -"), "{}", out);
+        let stripped = out.replace("\x1b[0m", "").replace("\x1b[1m", "").replace("\x1b[31m", "");
+        assert_eq!(stripped, grades(&entries, false));
+    }
+}
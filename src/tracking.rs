@@ -0,0 +1,169 @@
+//! What appeared since the user last looked. Per student and category, the
+//! identities of the items already seen are kept in seen.json; anything in
+//! fresh data that isn't among them is new until its tab has been viewed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::models::{Grade, GradeItem, Homework};
+
+/// Kinds of data tracked for "new since last visit"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Grades,
+    Homework,
+}
+
+/// Identity of a current grade: its API id, which survives edits, else its
+/// subject, term, position and value
+pub fn grade_key(subject: &str, item: &GradeItem) -> String {
+    match item.id {
+        Some(id) => format!("grade:{}", id),
+        None => format!("grade:{}:{}:{}:{}", subject, item.term, item.position, item.value),
+    }
+}
+
+/// Identities of every current grade of the subjects
+pub fn grade_keys(grades: &[Grade]) -> Vec<String> {
+    grades.iter()
+        .flat_map(|grade| grade.items().into_iter().map(|item| grade_key(&grade.subject, &item)))
+        .collect()
+}
+
+/// Identity of a homework by content: the same homework comes with a
+/// different id under each course group it's listed in
+pub fn homework_key(homework: &Homework) -> String {
+    let (date, subject, text) = homework.dedup_key();
+    format!("homework:{}:{}:{}", date, subject, text)
+}
+
+pub fn homework_keys(homework: &[Homework]) -> Vec<String> {
+    homework.iter().map(homework_key).collect()
+}
+
+/// Seen identities per student id and category, stored as seen.json
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Seen(BTreeMap<i64, BTreeMap<Category, BTreeSet<String>>>);
+
+impl Seen {
+    /// Identities in `current` that weren't seen. Nothing is new before the
+    /// first snapshot, so a fresh install doesn't flag the whole year.
+    pub fn new_keys(&self, student_id: i64, category: Category, current: &[String]) -> HashSet<String> {
+        let Some(seen) = self.0.get(&student_id).and_then(|c| c.get(&category)) else {
+            return HashSet::new();
+        };
+        current.iter().filter(|key| !seen.contains(*key)).cloned().collect()
+    }
+
+    /// Take the first snapshot, so that only later additions are new. An
+    /// empty list may just not be loaded yet and is skipped. False when
+    /// nothing changed.
+    pub fn baseline(&mut self, student_id: i64, category: Category, current: &[String]) -> bool {
+        if current.is_empty() || self.0.get(&student_id).is_some_and(|c| c.contains_key(&category)) {
+            return false;
+        }
+        self.mark(student_id, category, current)
+    }
+
+    /// Everything in `current` has been seen; items gone from it are
+    /// forgotten. False when nothing changed.
+    pub fn mark(&mut self, student_id: i64, category: Category, current: &[String]) -> bool {
+        let current: BTreeSet<String> = current.iter().cloned().collect();
+        let snapshot = self.0.entry(student_id).or_default().entry(category).or_default();
+        if *snapshot == current {
+            return false;
+        }
+        *snapshot = current;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GradeEntry;
+
+    fn grade(subject: &str, entries: &[(&str, u8, Option<i64>)]) -> Grade {
+        Grade {
+            subject: subject.to_string(),
+            term1_grades: entries.iter().filter(|e| e.1 == 1).map(|e| e.0.to_string()).collect(),
+            term2_grades: entries.iter().filter(|e| e.1 == 2).map(|e| e.0.to_string()).collect(),
+            term1_final: None,
+            term2_final: None,
+            annual: None,
+            entries: entries.iter()
+                .filter(|e| e.2.is_some())
+                .map(|&(value, term, id)| GradeEntry { value: value.to_string(), term, id, weight: None })
+                .collect(),
+        }
+    }
+
+    fn homework(id: i64, text: &str) -> Homework {
+        Homework {
+            id: Some(id),
+            subject: "История".to_string(),
+            text: text.to_string(),
+            date: "18.02.2026".to_string(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: None,
+            links: Vec::new(),
+            raw_html: None,
+        }
+    }
+
+    #[test]
+    fn test_identities_are_deterministic() {
+        // Ids win and survive a changed value
+        let with_ids = grade("Математика", &[("5", 1, Some(501)), ("6", 2, Some(502))]);
+        assert_eq!(grade_keys(&[with_ids]), ["grade:501", "grade:502"]);
+        let edited = grade("Математика", &[("4", 1, Some(501))]);
+        assert_eq!(grade_keys(&[edited]), ["grade:501"]);
+
+        // Without ids: subject, term, position in the term and value
+        let plain = [grade("Математика", &[("5", 1, None), ("6", 1, None), ("4", 2, None)])];
+        assert_eq!(grade_keys(&plain), [
+            "grade:Математика:1:0:5",
+            "grade:Математика:1:1:6",
+            "grade:Математика:2:0:4",
+        ]);
+        assert_eq!(grade_keys(&plain), grade_keys(&plain.clone()));
+
+        // Homework ignores the id and whitespace
+        assert_eq!(homework_key(&homework(1, "Упр. 5")), homework_key(&homework(2, " Упр.  5 ")));
+        assert_ne!(homework_key(&homework(1, "Упр. 5")), homework_key(&homework(1, "Упр. 6")));
+    }
+
+    #[test]
+    fn test_new_since_snapshot() {
+        let mut seen = Seen::default();
+        let before = vec!["grade:1".to_string(), "grade:2".to_string()];
+        let after = vec!["grade:1".to_string(), "grade:2".to_string(), "grade:3".to_string()];
+
+        // No snapshot: nothing is new; an empty list doesn't make one
+        assert!(seen.new_keys(7, Category::Grades, &after).is_empty());
+        assert!(!seen.baseline(7, Category::Grades, &[]));
+        assert!(seen.baseline(7, Category::Grades, &before));
+        assert!(!seen.baseline(7, Category::Grades, &after));
+
+        assert_eq!(seen.new_keys(7, Category::Grades, &after), HashSet::from(["grade:3".to_string()]));
+        // Other students and categories are separate
+        assert!(seen.new_keys(8, Category::Grades, &after).is_empty());
+        assert!(seen.new_keys(7, Category::Homework, &after).is_empty());
+
+        assert!(seen.mark(7, Category::Grades, &after));
+        assert!(!seen.mark(7, Category::Grades, &after));
+        assert!(seen.new_keys(7, Category::Grades, &after).is_empty());
+    }
+
+    #[test]
+    fn test_seen_round_trips_as_json() {
+        let mut seen = Seen::default();
+        seen.mark(300401, Category::Homework, &["homework:a".to_string()]);
+        let json = serde_json::to_string(&seen).unwrap();
+        assert_eq!(json, r#"{"300401":{"homework":["homework:a"]}}"#);
+        assert_eq!(serde_json::from_str::<Seen>(&json).unwrap(), seen);
+    }
+}
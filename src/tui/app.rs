@@ -1,8 +1,18 @@
-use crate::api::ShkoloClient;
-use crate::cache::CacheStore;
+use crate::api::ShkoloApi;
+use crate::cache::{self, CacheStats, CacheStore, Freshness};
 use crate::i18n::{Lang, T};
 use crate::models::*;
-use time::OffsetDateTime;
+use crate::output::LoadStatus;
+use crate::service;
+use crate::tracking::{self, Category, Seen};
+use crate::util::{self, WeekStart};
+use std::collections::HashSet;
+use super::keymap::KeyMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Lines scrolled per mouse wheel notch
+const WHEEL_SCROLL_LINES: usize = 3;
 
 /// Calculate scroll offset to keep selected item centered with margins.
 /// This implements "scrolloff" behavior - the selected item stays near the center
@@ -60,7 +70,7 @@ impl AutoRefreshInterval {
 
     pub fn label(&self, lang: Lang) -> &'static str {
         match self {
-            Self::Off => match lang { Lang::Bg => "Изкл.", Lang::En => "Off" },
+            Self::Off => T::off(lang),
             Self::Min1 => "1 min",
             Self::Min5 => "5 min",
             Self::Min10 => "10 min",
@@ -89,6 +99,36 @@ pub enum InputMode {
     Reply,           // Replying to a thread
     ComposeSubject,  // Composing - entering subject
     ComposeBody,     // Composing - entering body
+    DateEntry,       // Schedule - typing a date to jump to
+    FeedbackSubject, // Feedbacks - typing a subject to filter by
+    StudentSwitch,   // Student switcher - typing to filter students
+}
+
+/// What a confirmed "Discard unsent message?" prompt goes on to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingDiscard {
+    /// Leave the reply or compose editor
+    CloseEditor,
+    Quit,
+}
+
+/// Which feedbacks the Feedbacks tab lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeedbackFilter {
+    #[default]
+    All,
+    Positive,
+    Negative,
+}
+
+impl FeedbackFilter {
+    pub fn matches(self, feedback: &Feedback) -> bool {
+        match self {
+            FeedbackFilter::All => true,
+            FeedbackFilter::Positive => feedback.is_positive,
+            FeedbackFilter::Negative => !feedback.is_positive,
+        }
+    }
 }
 
 /// Message view state
@@ -107,7 +147,7 @@ pub struct Location {
     pub selected_thread_id: Option<i64>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum Tab {
     Overview,
     Homework,
@@ -191,6 +231,26 @@ pub enum DragTarget {
     OverviewBottomSplit,
 }
 
+/// Glanceable counts shown in the Overview alerts line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StudentAlerts {
+    pub homework_due_today: usize,
+    pub new_grades_this_week: usize,
+    pub unexcused_absences: usize,
+    pub positive_feedbacks: usize,
+    pub negative_feedbacks: usize,
+}
+
+/// One grade in the Grades timeline view
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineGrade<'a> {
+    pub subject: &'a str,
+    pub value: &'a str,
+    pub term: u8,
+    /// Identity for "new since last visit"
+    pub key: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct StudentData {
     pub student: Student,
@@ -200,11 +260,17 @@ pub struct StudentData {
     pub events: Vec<Event>,
     pub absences: Vec<Absence>,
     pub feedbacks: Vec<Feedback>,
-    pub homework_age: Option<String>,
-    pub grades_age: Option<String>,
-    pub schedule_age: Option<String>,
-    pub absences_age: Option<String>,
-    pub feedbacks_age: Option<String>,
+    pub homework_age: Option<i64>, // Unix timestamp the data was cached/fetched at
+    pub grades_age: Option<i64>, // Unix timestamp the data was cached/fetched at
+    pub schedule_age: Option<i64>, // Unix timestamp the data was cached/fetched at
+    pub absences_age: Option<i64>, // Unix timestamp the data was cached/fetched at
+    pub feedbacks_age: Option<i64>, // Unix timestamp the data was cached/fetched at
+    // How each category loaded, so a failed fetch isn't shown as "no data"
+    pub homework_status: LoadStatus,
+    pub grades_status: LoadStatus,
+    pub schedule_status: LoadStatus,
+    pub absences_status: LoadStatus,
+    pub feedbacks_status: LoadStatus,
 }
 
 impl StudentData {
@@ -222,7 +288,188 @@ impl StudentData {
             schedule_age: None,
             absences_age: None,
             feedbacks_age: None,
+            homework_status: LoadStatus::Empty,
+            grades_status: LoadStatus::Empty,
+            schedule_status: LoadStatus::Empty,
+            absences_status: LoadStatus::Empty,
+            feedbacks_status: LoadStatus::Empty,
+        }
+    }
+
+    /// Current grades of every subject in one list, newest first: the later term
+    /// first, then by grade id. Grades without ids (plain lists, old caches) keep
+    /// their order within the subject and follow the ones with ids.
+    pub fn grades_timeline(&self) -> Vec<TimelineGrade<'_>> {
+        let mut timeline: Vec<(TimelineGrade, Option<i64>, usize)> = Vec::new();
+        for grade in &self.grades {
+            for item in grade.items() {
+                let key = tracking::grade_key(&grade.subject, &item);
+                timeline.push((TimelineGrade { subject: &grade.subject, value: item.value, term: item.term, key }, item.id, item.position));
+            }
+        }
+        timeline.sort_by_key(|(item, id, position)| std::cmp::Reverse((item.term, *id, *position)));
+        timeline.into_iter().map(|(item, _, _)| item).collect()
+    }
+
+    /// Average grade and absences per subject, riskiest first
+    pub fn subject_report(&self) -> Vec<SubjectReport> {
+        subject_report(&self.grades, &self.absences)
+    }
+
+    /// Build a student's data from whatever is in the cache (blocking disk reads)
+    pub fn from_cache(cache: &CacheStore, student: Student, date: &str) -> Self {
+        let mut data = StudentData::new(student);
+        let id = data.student.id;
+
+        if let Some((homework, _, _, cached_at)) = cache.get_homework(id) {
+            data.homework_status = LoadStatus::loaded(homework.is_empty());
+            data.homework = homework;
+            data.homework_age = Some(cached_at);
+        }
+
+        if let Some((grades, _, _, cached_at)) = cache.get_grades(id) {
+            data.grades_status = LoadStatus::loaded(grades.is_empty());
+            data.grades = grades;
+            data.grades_age = Some(cached_at);
+        }
+
+        // Schedule for the given day
+        if let Some((schedule, _, _, cached_at)) = cache.get_schedule(id, date) {
+            data.schedule_status = LoadStatus::loaded(schedule.is_empty());
+            data.schedule = schedule;
+            data.schedule_age = Some(cached_at);
+        }
+
+        if let Some((events, _, _, _)) = cache.get_events(id) {
+            data.events = events;
+        }
+
+        if let Some((absences, _, _, cached_at)) = cache.get_absences(id) {
+            data.absences_status = LoadStatus::loaded(absences.is_empty());
+            data.absences = absences;
+            data.absences_age = Some(cached_at);
+        }
+
+        if let Some((feedbacks, _, _, cached_at)) = cache.get_feedbacks(id) {
+            data.feedbacks_status = LoadStatus::loaded(feedbacks.is_empty());
+            data.feedbacks = feedbacks;
+            data.feedbacks_age = Some(cached_at);
+        }
+
+        data
+    }
+
+    /// Fetch everything for the student through the cache, with the schedule
+    /// of `date`. A category that fails keeps its cached data (Stale), or is
+    /// marked Failed without any, and is reported in the returned errors;
+    /// events only feed the exams list and fail quietly.
+    pub async fn fetch(client: &impl ShkoloApi, cache: &CacheStore, student: Student, date: &str, force: bool) -> (Self, Vec<String>) {
+        let mut data = StudentData::from_cache(cache, student, date);
+        let id = data.student.id;
+        let mut errors = Vec::new();
+
+        match service::fetch_homework(client, cache, id, force).await {
+            Ok((homework, info, failed_courses)) => {
+                data.homework_status = LoadStatus::loaded(homework.is_empty());
+                data.homework = homework;
+                data.homework_age = Some(info.as_of());
+                errors.extend(failed_courses);
+            }
+            Err(e) => {
+                data.homework_status = LoadStatus::failed(&e, data.homework_age.is_some());
+                errors.push(e.to_string());
+            }
+        }
+        match service::fetch_grades(client, cache, id, force).await {
+            Ok((grades, info)) => {
+                data.grades_status = LoadStatus::loaded(grades.is_empty());
+                data.grades = grades;
+                data.grades_age = Some(info.as_of());
+            }
+            Err(e) => {
+                data.grades_status = LoadStatus::failed(&e, data.grades_age.is_some());
+                errors.push(e.to_string());
+            }
+        }
+        match service::fetch_schedule(client, cache, id, date, force).await {
+            Ok((schedule, info)) => {
+                data.schedule_status = LoadStatus::loaded(schedule.is_empty());
+                data.schedule = schedule;
+                data.schedule_age = Some(info.as_of());
+            }
+            Err(e) => {
+                data.schedule_status = LoadStatus::failed(&e, data.schedule_age.is_some());
+                errors.push(e.to_string());
+            }
+        }
+        match service::fetch_absences(client, cache, id, force).await {
+            Ok((absences, info)) => {
+                data.absences_status = LoadStatus::loaded(absences.is_empty());
+                data.absences = absences;
+                data.absences_age = Some(info.as_of());
+            }
+            Err(e) => {
+                data.absences_status = LoadStatus::failed(&e, data.absences_age.is_some());
+                errors.push(e.to_string());
+            }
+        }
+        match service::fetch_feedbacks(client, cache, id, force).await {
+            Ok((feedbacks, info)) => {
+                data.feedbacks_status = LoadStatus::loaded(feedbacks.is_empty());
+                data.feedbacks = feedbacks;
+                data.feedbacks_age = Some(info.as_of());
+            }
+            Err(e) => {
+                data.feedbacks_status = LoadStatus::failed(&e, data.feedbacks_age.is_some());
+                errors.push(e.to_string());
+            }
+        }
+        if let Ok((events, _)) = service::fetch_events(client, cache, id, force).await {
+            data.events = events;
+        }
+
+        let errors = errors.into_iter().map(|e| format!("{}: {}", data.student.name, e)).collect();
+        (data, errors)
+    }
+
+    /// Summarize what needs attention for this student, or None if nothing has been loaded yet.
+    /// Grades carry no dates, so new grades are counted from this week's grade notifications.
+    pub fn alerts(&self, today: &str, notifications: &[Notification]) -> Option<StudentAlerts> {
+        let loaded = self.homework_age.is_some()
+            || self.grades_age.is_some()
+            || self.absences_age.is_some()
+            || self.feedbacks_age.is_some();
+        if !loaded {
+            return None;
         }
+
+        let today_date = util::parse_day(today);
+        let first_name = self.student.name.split_whitespace().next().unwrap_or_default();
+        let new_grades_this_week = today_date.map_or(0, |today| {
+            notifications.iter().filter(|n| n.is_recent_grade_for(first_name, today)).count()
+        });
+
+        Some(StudentAlerts {
+            homework_due_today: self.homework.iter()
+                .filter(|h| h.due_date_sort.as_deref() == Some(today))
+                .count(),
+            new_grades_this_week,
+            unexcused_absences: self.absences.iter().filter(|a| !a.is_excused).count(),
+            positive_feedbacks: self.feedbacks.iter().filter(|f| f.is_positive).count(),
+            negative_feedbacks: self.feedbacks.iter().filter(|f| !f.is_positive).count(),
+        })
+    }
+
+    /// New absences and feedbacks from the last 7 days
+    pub fn weekly_summary(&self, today: &str) -> WeeklySummary {
+        WeeklySummary::compute(&self.absences, &self.feedbacks, today)
+    }
+
+    /// Tests announced in homework or events from `today` (YYYY-MM-DD) on
+    pub fn upcoming_exams(&self, today: &str, keywords: &ExamKeywords) -> Vec<Exam> {
+        util::parse_day(today)
+            .map(|today| upcoming_exams(&self.homework, &self.events, keywords, today))
+            .unwrap_or_default()
     }
 
     /// Count total grades across all subjects
@@ -232,28 +479,53 @@ impl StudentData {
             .sum()
     }
 
-    /// Get all grades for all subjects
-    pub fn all_grades_summary(&self) -> Vec<(&str, Vec<&str>)> {
+    /// Get all grades for all subjects, with the year's final grade once
+    /// there is one
+    pub fn all_grades_summary(&self) -> Vec<SubjectSummary<'_>> {
         self.grades.iter()
-            .filter(|g| !g.term1_grades.is_empty() || !g.term2_grades.is_empty())
             .map(|g| {
                 // Combine term2 and term1 grades (term2 first as it's more recent)
-                let all: Vec<&str> = g.term2_grades.iter()
+                let grades: Vec<&str> = g.term2_grades.iter()
                     .chain(g.term1_grades.iter())
                     .map(|s| s.as_str())
                     .collect();
-                (g.subject.as_str(), all)
+                // Late in the year the annual grade is what counts, then the second term's
+                let final_grade = match (&g.annual, &g.term2_final) {
+                    (Some(annual), _) => Some((FinalKind::Annual, annual.as_str())),
+                    (None, Some(term2)) => Some((FinalKind::Term, term2.as_str())),
+                    (None, None) => None,
+                };
+                SubjectSummary { subject: g.subject.as_str(), grades, final_grade }
             })
+            .filter(|s| !s.grades.is_empty() || s.final_grade.is_some())
             .collect()
     }
 }
 
+/// Which final grade a subject's summary shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinalKind {
+    Term,
+    Annual,
+}
+
+/// A subject's line in the overview grades pane
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubjectSummary<'a> {
+    pub subject: &'a str,
+    /// Current grades, most recent first
+    pub grades: Vec<&'a str>,
+    pub final_grade: Option<(FinalKind, &'a str)>,
+}
+
 pub struct App {
     pub running: bool,
     pub current_tab: Tab,
     pub focus: Focus,
     pub lang: Lang,
     pub user_name: Option<String>,
+    /// Shkolo user id of the account, to tell it apart among thread participants
+    pub user_id: Option<i64>,
     pub students: Vec<StudentData>,
     pub selected_student: usize,
     pub list_offset: usize,
@@ -262,40 +534,107 @@ pub struct App {
     pub homework_offset: usize,
     pub grades_offset: usize,
     pub notifications: Vec<Notification>,
-    pub notifications_age: Option<String>,
+    pub notifications_age: Option<i64>,
+    // Indices into `notifications` whose body is shown in full
+    pub expanded_notifications: HashSet<usize>,
     pub messages: Vec<MessageThread>,
-    pub messages_age: Option<String>,
+    pub messages_age: Option<i64>,
+    // Messenger folders, fetched the first time the Messages tab is opened
+    pub message_folders: Vec<MessageFolder>,
+    pub folders_requested: bool,
+    /// Folder whose threads `messages` holds; None is the default list (the inbox)
+    pub selected_folder: Option<i64>,
     pub status_message: Option<String>,
     pub error_message: Option<String>,  // Persistent error message
     pub loading: bool,
+    pub offline: bool, // Serving cached data because the API is unreachable (or --offline)
+    pub needs_login: bool, // No token yet; the data tabs wait for a login
     pub last_refresh: Option<String>,
     pub current_date: String,
     pub schedule_date: String, // Date being viewed in schedule (can differ from current_date)
     pub current_time: (u8, u8), // (hour, minute)
     pub tick: usize, // Frame counter for animations
-    pub students_pane_width: u16, // Resizable pane width
+    pub students_pane_width: u16, // Resizable pane width, as configured
+    pub terminal_width: u16, // Last known terminal width; 0 until the first draw
     pub overview_split_percent: u16, // Vertical split for overview (schedule vs homework/grades)
     pub overview_bottom_split_percent: u16, // Vertical split for overview bottom (homework vs grades)
+    pub overview_homework_count: usize, // Homework items listed on the Overview
+    pub overview_grades_count: Option<usize>, // Grades listed per subject on the Overview; None for all
+    pub absence_excuse_days: i64, // Days parents have to excuse an absence
+    pub school_day_end: i32, // When school ends on days without a schedule, minutes since midnight
     // Message thread state
     pub message_view: MessageView,
     pub selected_thread_id: Option<i64>,
     pub thread_messages: Vec<Message>,
+    pub thread_participants: Vec<Participant>,
     pub thread_offset: usize,
     // Input mode for text entry
     pub input_mode: InputMode,
     pub input_buffer: String,
-    pub input_cursor: usize,
+    pub input_cursor: usize, // In characters, not bytes (Cyrillic is two bytes per letter)
+    pub date_entry_error: bool, // Last submitted date failed to parse
+    // Feedbacks tab filters; the summary counts ignore them
+    pub feedback_filter: FeedbackFilter,
+    pub feedback_subject: Option<String>,
     // Recipients for composing
     pub recipients: Vec<Recipient>,
     pub selected_recipients: Vec<i64>,
     pub compose_subject: String,
     pub compose_body: String,
+    // Waiting for confirmation before throwing away a typed message
+    pub confirm_discard: Option<PendingDiscard>,
+    // Unsent messages put aside, saved to drafts.json by the main loop when changed
+    pub drafts: Drafts,
+    pub drafts_changed: bool,
+    // Grades and homework already seen, saved to seen.json by the main loop when changed
+    pub seen: Seen,
+    pub seen_changed: bool,
+    /// Tab and student on screen and since when, for marking new items seen
+    viewing: Option<(Tab, usize, Instant)>,
     // Help overlay
     pub show_help: bool,
+    /// Text typed after '/' in the help overlay; None when not searching
+    pub help_filter: Option<String>,
+    // Debug overlay with the session's cache stats (hidden key F12)
+    pub show_cache_stats: bool,
+    pub cache_stats: Option<Arc<CacheStats>>,
+    /// Cache TTL in seconds, for coloring data ages
+    pub cache_ttl: i64,
     // Drag state for split resizing
     pub drag_target: DragTarget,
     // Auto-refresh settings
     pub auto_refresh_interval: AutoRefreshInterval,
+    /// Time between keep-alive requests that stop the session expiring; None: off
+    pub keep_alive_interval: Option<Duration>,
+    /// False while the terminal reports its window unfocused
+    window_focused: bool,
+    // Off: jump Friday <-> Monday when paging through the schedule
+    pub show_weekends: bool,
+    pub week_start: WeekStart,
+    /// Draw with ASCII instead of box-drawing characters, arrows and emoji (--ascii)
+    pub ascii: bool,
+    /// Grades tab lists every grade newest-first instead of grouping by subject
+    pub grades_timeline: bool,
+    /// Grades tab shows the per-subject table of averages and absences
+    pub grades_report: bool,
+    /// Thread view lists the newest message at the top instead of the bottom
+    pub thread_newest_first: bool,
+    /// Opening a thread selects its latest message, like a chat
+    pub thread_open_at_latest: bool,
+    /// Colored initials before each name in the students pane
+    pub student_initials: bool,
+    /// Keywords for the upcoming exams list in the Overview
+    pub exam_keywords: ExamKeywords,
+    /// Second click on the same row within this long opens a notification or thread
+    pub double_click_interval: Duration,
+    /// Keys remapped in ui_config.json
+    pub keymap: KeyMap,
+    /// Row, pane and time of the last click in a list
+    last_click: Option<(u16, Focus, Instant)>,
+    /// Count typed before a motion ("5j", "10G") and when its last digit came
+    pending_count: Option<(usize, Instant)>,
+    /// Highlighted row among the student switcher's matches
+    pub switcher_selected: usize,
     // Navigation history (for back/forward)
     nav_history: Vec<Location>,
     nav_index: usize,  // Current position in history
@@ -304,14 +643,15 @@ pub struct App {
 impl App {
     pub fn new() -> Self {
         // Use local time for schedule/homework comparison
-        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-        let today = format!("{:04}-{:02}-{:02}", now.year(), now.month() as u8, now.day());
+        let now = util::now_local();
+        let today = util::date_string(now);
         Self {
             running: true,
             current_tab: Tab::Overview,
             focus: Focus::Students,
             lang: Lang::default(), // Bulgarian by default
             user_name: None,
+            user_id: None,
             students: Vec::new(),
             selected_student: 0,
             list_offset: 0,
@@ -320,39 +660,80 @@ impl App {
             grades_offset: 0,
             notifications: Vec::new(),
             notifications_age: None,
+            expanded_notifications: HashSet::new(),
             messages: Vec::new(),
             messages_age: None,
+            message_folders: Vec::new(),
+            folders_requested: false,
+            selected_folder: None,
             status_message: None,
             error_message: None,
             loading: false,
+            offline: false,
+            needs_login: false,
             last_refresh: None,
             current_date: today.clone(),
             schedule_date: today,
             current_time: (now.hour(), now.minute()),
             tick: 0,
             students_pane_width: 30,
+            terminal_width: 0,
             overview_split_percent: 40, // 40% for schedule, 60% for homework/grades
             overview_bottom_split_percent: 60, // 60% for homework, 40% for grades
+            overview_homework_count: DEFAULT_OVERVIEW_HOMEWORK_COUNT,
+            overview_grades_count: None,
+            absence_excuse_days: DEFAULT_EXCUSE_DAYS,
+            school_day_end: DEFAULT_SCHOOL_DAY_END,
             // Message thread state
             message_view: MessageView::List,
             selected_thread_id: None,
             thread_messages: Vec::new(),
+            thread_participants: Vec::new(),
             thread_offset: 0,
             // Input mode
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             input_cursor: 0,
+            date_entry_error: false,
+            feedback_filter: FeedbackFilter::All,
+            feedback_subject: None,
             // Compose state
             recipients: Vec::new(),
             selected_recipients: Vec::new(),
             compose_subject: String::new(),
             compose_body: String::new(),
+            confirm_discard: None,
+            drafts: Drafts::default(),
+            drafts_changed: false,
+            seen: Seen::default(),
+            seen_changed: false,
+            viewing: None,
             // Help
             show_help: false,
+            help_filter: None,
+            show_cache_stats: false,
+            cache_stats: None,
+            cache_ttl: crate::cache::DEFAULT_TTL_SECONDS,
             // Drag state
             drag_target: DragTarget::None,
             // Auto-refresh (default 10 min)
             auto_refresh_interval: AutoRefreshInterval::default(),
+            show_weekends: false,
+            week_start: WeekStart::default(),
+            ascii: false,
+            grades_timeline: false,
+            grades_report: false,
+            thread_newest_first: false,
+            thread_open_at_latest: true,
+            student_initials: true,
+            exam_keywords: ExamKeywords::default(),
+            double_click_interval: Duration::from_millis(DEFAULT_DOUBLE_CLICK_MS),
+            keymap: KeyMap::default(),
+            keep_alive_interval: Some(Duration::from_secs(DEFAULT_KEEP_ALIVE_MINUTES * 60)),
+            window_focused: true,
+            last_click: None,
+            pending_count: None,
+            switcher_selected: 0,
             // Navigation history - start with Overview
             nav_history: vec![Location {
                 tab: Tab::Overview,
@@ -363,40 +744,272 @@ impl App {
         }
     }
 
+    /// Whether the keep-alive request is due, `elapsed` after the last one.
+    /// Offline there is no session to keep.
+    pub fn keep_alive_due(&self, elapsed: Duration) -> bool {
+        !self.offline && self.keep_alive_interval.is_some_and(|interval| elapsed >= interval)
+    }
+
+    /// The terminal window gained or lost focus. Auto-refresh and the clock
+    /// pause while it's in the background; on return the clock catches up at once.
+    pub fn set_window_focused(&mut self, focused: bool) {
+        if focused && !self.window_focused {
+            self.update_time();
+        }
+        self.window_focused = focused;
+    }
+
+    /// Whether auto-refresh and the clock are paused for an unfocused window
+    pub fn background_paused(&self) -> bool {
+        !self.window_focused
+    }
+
     /// Cycle auto-refresh interval to next value
     pub fn next_auto_refresh(&mut self) {
         self.auto_refresh_interval = self.auto_refresh_interval.next();
     }
 
+    pub fn toggle_show_weekends(&mut self) {
+        self.show_weekends = !self.show_weekends;
+    }
+
+    pub fn toggle_week_start(&mut self) {
+        self.week_start = self.week_start.toggle();
+    }
+
+    pub fn toggle_ascii(&mut self) {
+        self.ascii = !self.ascii;
+    }
+
+    /// Flip the thread order, keeping the same message selected in an open thread
+    pub fn toggle_thread_order(&mut self) {
+        self.thread_newest_first = !self.thread_newest_first;
+        self.thread_messages.reverse();
+        self.thread_offset = self.thread_messages.len().saturating_sub(1).saturating_sub(self.thread_offset);
+    }
+
+    /// One more (or fewer) homework item on the Overview, wrapping around at
+    /// either end so clicking the Settings row reaches every value
+    pub fn change_overview_homework_count(&mut self, up: bool) {
+        let (min, max) = (*OVERVIEW_HOMEWORK_COUNTS.start(), *OVERVIEW_HOMEWORK_COUNTS.end());
+        self.overview_homework_count = match (up, self.overview_homework_count) {
+            (true, n) if n >= max => min,
+            (true, n) => n + 1,
+            (false, n) if n <= min => max,
+            (false, n) => n - 1,
+        };
+    }
+
+    /// Switch the Grades tab between the per-subject and the timeline view
+    pub fn toggle_grades_timeline(&mut self) {
+        self.grades_timeline = !self.grades_timeline;
+        self.grades_report = false;
+        self.list_offset = 0;
+    }
+
+    /// Show or leave the absences and grades table on the Grades tab
+    pub fn toggle_grades_report(&mut self) {
+        self.grades_report = !self.grades_report;
+        self.list_offset = 0;
+    }
+
     /// Move schedule to next day
     pub fn schedule_next_day(&mut self) {
-        if let Ok(date) = time::Date::parse(&self.schedule_date, time::macros::format_description!("[year]-[month]-[day]")) {
-            let next = date + time::Duration::days(1);
-            self.schedule_date = format!("{:04}-{:02}-{:02}", next.year(), next.month() as u8, next.day());
-        }
+        self.schedule_step(1);
     }
 
     /// Move schedule to previous day
     pub fn schedule_prev_day(&mut self) {
-        if let Ok(date) = time::Date::parse(&self.schedule_date, time::macros::format_description!("[year]-[month]-[day]")) {
-            let prev = date - time::Duration::days(1);
-            self.schedule_date = format!("{:04}-{:02}-{:02}", prev.year(), prev.month() as u8, prev.day());
+        self.schedule_step(-1);
+    }
+
+    fn schedule_step(&mut self, days: i64) {
+        if let Some(date) = self.schedule_day() {
+            let target = if days > 0 {
+                util::next_school_day(date, self.show_weekends)
+            } else {
+                util::prev_school_day(date, self.show_weekends)
+            };
+            self.schedule_date = format!("{:04}-{:02}-{:02}", target.year(), target.month() as u8, target.day());
+        }
+    }
+
+    /// Move schedule to the first school day of the next (1) or previous (-1) week
+    pub fn schedule_step_week(&mut self, weeks: i64) {
+        if let Some(date) = self.schedule_day() {
+            let target = util::school_week_start(date, weeks, self.week_start, self.show_weekends);
+            self.schedule_date = format!("{:04}-{:02}-{:02}", target.year(), target.month() as u8, target.day());
         }
     }
 
+    /// School days before and after the viewed one, which `n` and `p` go to
+    pub fn adjacent_schedule_days(&self) -> Vec<String> {
+        let Some(date) = self.schedule_day() else { return Vec::new() };
+        [util::prev_school_day(date, self.show_weekends), util::next_school_day(date, self.show_weekends)]
+            .iter()
+            .map(|d| format!("{:04}-{:02}-{:02}", d.year(), d.month() as u8, d.day()))
+            .collect()
+    }
+
+    /// The schedule date, if it parses
+    pub fn schedule_day(&self) -> Option<time::Date> {
+        time::Date::parse(&self.schedule_date, time::macros::format_description!("[year]-[month]-[day]")).ok()
+    }
+
     /// Reset schedule to today
     pub fn schedule_today(&mut self) {
         self.schedule_date = self.current_date.clone();
     }
 
+    /// Open the date entry prompt, pre-filled with the currently viewed date
+    pub fn start_date_entry(&mut self) {
+        self.input_mode = InputMode::DateEntry;
+        self.input_buffer = self.schedule_date.clone();
+        self.input_cursor = self.input_buffer.chars().count();
+        self.date_entry_error = false;
+    }
+
+    /// Validate the typed date and jump the schedule to it.
+    /// Returns false (and keeps the prompt open) if the date is invalid.
+    pub fn submit_date_entry(&mut self) -> bool {
+        match time::Date::parse(self.input_buffer.trim(), time::macros::format_description!("[year]-[month]-[day]")) {
+            Ok(date) => {
+                self.schedule_date = format!("{:04}-{:02}-{:02}", date.year(), date.month() as u8, date.day());
+                self.date_entry_error = false;
+                self.take_input();
+                true
+            }
+            Err(_) => {
+                self.date_entry_error = true;
+                false
+            }
+        }
+    }
+
+    /// Show only positive or negative feedbacks (or all again)
+    pub fn set_feedback_filter(&mut self, filter: FeedbackFilter) {
+        self.feedback_filter = filter;
+        self.list_offset = 0;
+    }
+
+    /// Open the subject filter prompt, pre-filled with the active subject
+    pub fn start_feedback_subject_entry(&mut self) {
+        self.input_mode = InputMode::FeedbackSubject;
+        self.input_buffer = self.feedback_subject.clone().unwrap_or_default();
+        self.input_cursor = self.input_buffer.chars().count();
+    }
+
+    /// Apply the typed subject filter; an empty one shows every subject again
+    pub fn submit_feedback_subject(&mut self) {
+        let subject = self.take_input().trim().to_string();
+        self.feedback_subject = (!subject.is_empty()).then_some(subject);
+        self.list_offset = 0;
+    }
+
+    /// Open the student switcher with an empty filter
+    pub fn start_student_switch(&mut self) {
+        if self.students.is_empty() {
+            return;
+        }
+        self.input_mode = InputMode::StudentSwitch;
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.switcher_selected = 0;
+    }
+
+    /// Students whose name or class contains every typed word, ignoring case
+    pub fn switcher_matches(&self) -> Vec<&Student> {
+        let filter = self.input_buffer.to_lowercase();
+        let words: Vec<&str> = filter.split_whitespace().collect();
+        self.students.iter()
+            .map(|data| &data.student)
+            .filter(|student| {
+                let haystack = format!("{} {}", student.name, student.class_name.as_deref().unwrap_or_default()).to_lowercase();
+                words.iter().all(|w| haystack.contains(w))
+            })
+            .collect()
+    }
+
+    /// Move the switcher highlight, staying within the matches
+    pub fn switcher_move(&mut self, down: bool) {
+        let last = self.switcher_matches().len().saturating_sub(1);
+        self.switcher_selected = if down {
+            (self.switcher_selected + 1).min(last)
+        } else {
+            self.switcher_selected.saturating_sub(1)
+        };
+    }
+
+    /// Select the highlighted match and close the switcher. With no match the
+    /// switcher stays open so the filter can be corrected.
+    pub fn submit_student_switch(&mut self) {
+        let matches = self.switcher_matches();
+        let Some(id) = matches.get(self.switcher_selected.min(matches.len().saturating_sub(1))).map(|s| s.id) else {
+            return;
+        };
+        if let Some(index) = self.students.iter().position(|d| d.student.id == id) {
+            self.selected_student = index;
+            self.list_offset = 0;
+        }
+        self.take_input();
+    }
+
+    /// Feedbacks of the current student that pass the active filters, in list order
+    pub fn visible_feedbacks(&self) -> Vec<&Feedback> {
+        let subject = self.feedback_subject.as_ref().map(|s| s.to_lowercase());
+        self.current_student()
+            .map(|data| {
+                data.feedbacks
+                    .iter()
+                    .filter(|f| self.feedback_filter.matches(f))
+                    .filter(|f| subject.as_ref().is_none_or(|s| f.subject.to_lowercase().contains(s.as_str())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// How data cached at `cached_at` compares with `ttl` (None: never expires)
+    pub fn freshness(&self, cached_at: i64, ttl: Option<i64>) -> Freshness {
+        Freshness::of(cached_at, time::OffsetDateTime::now_utc().unix_timestamp(), ttl)
+    }
+
+    /// TTL of the schedule being viewed, which depends on its date
+    pub fn schedule_ttl(&self) -> Option<i64> {
+        cache::schedule_ttl(self.cache_ttl, &self.schedule_date, &self.current_date)
+    }
+
+    /// Age of the data being shown, for the offline indicator
+    pub fn offline_data_age(&self) -> Option<i64> {
+        self.current_student()
+            .and_then(|d| d.homework_age)
+            .or(self.notifications_age)
+    }
+
+    /// `current_date` as a date, for showing times relative to it
+    pub fn today(&self) -> time::Date {
+        util::parse_day(&self.current_date).unwrap_or_else(|| util::now_local().date())
+    }
+
     /// Check if schedule is showing today
     pub fn is_schedule_today(&self) -> bool {
         self.schedule_date == self.current_date
     }
 
-    /// Check if the students pane should be shown
-    /// Returns false for tabs that don't use it or when there's only one student
-    pub fn has_students_pane(&self) -> bool {
+    /// Rows of the Settings tab, top to bottom
+    pub fn settings_entries(&self) -> Vec<SettingsEntry> {
+        use SettingsEntry::*;
+        let mut entries = vec![AccountHeading, Blank];
+        if self.user_name.is_some() {
+            entries.extend([LoggedInAs, Blank, Logout]);
+        } else {
+            entries.extend([LoginHeading, Blank, LoginPassword, LoginGoogle, ImportToken, ImportTokenDesc]);
+        }
+        entries.extend([Blank, Separator, Blank, Language, Blank, AutoRefresh, Blank, ShowWeekends, Blank, WeekStart, Blank, ThreadOrder, Blank, Ascii, Blank, OverviewHomework]);
+        entries
+    }
+
+    /// Whether the current tab lists students at all, regardless of terminal width
+    fn tab_has_students_pane(&self) -> bool {
         // Tabs that don't show students pane
         if matches!(self.current_tab, Tab::Notifications | Tab::Settings | Tab::Messages) {
             return false;
@@ -405,26 +1018,39 @@ impl App {
         self.students.len() > 1
     }
 
+    /// Whether the students pane is on screen, full or compact
+    pub fn has_students_pane(&self) -> bool {
+        self.effective_students_width() > 0
+    }
+
     /// Get effective students pane width (0 if pane is hidden)
     pub fn effective_students_width(&self) -> u16 {
-        if self.has_students_pane() {
+        self.effective_students_width_for(self.terminal_width)
+    }
+
+    /// Students pane width on a terminal `total_width` columns wide. The
+    /// configured width is kept while the content still gets
+    /// `MIN_CONTENT_WIDTH`; narrower terminals get the numbered strip, and
+    /// below that no pane at all. 0 means the width isn't known yet.
+    pub fn effective_students_width_for(&self, total_width: u16) -> u16 {
+        if !self.tab_has_students_pane() {
+            0
+        } else if total_width == 0 || total_width >= self.students_pane_width + MIN_CONTENT_WIDTH {
             self.students_pane_width
+        } else if total_width >= COMPACT_STUDENTS_WIDTH + MIN_CONTENT_WIDTH {
+            COMPACT_STUDENTS_WIDTH
         } else {
             0
         }
     }
 
-    // Navigation history methods
-
-    /// Get current location state
-    fn current_location(&self) -> Location {
-        Location {
-            tab: self.current_tab,
-            message_view: self.message_view,
-            selected_thread_id: self.selected_thread_id,
-        }
+    /// Whether the students pane is collapsed to the numbered strip
+    pub fn students_pane_compact(&self) -> bool {
+        self.has_students_pane() && self.effective_students_width() < self.students_pane_width
     }
 
+    // Navigation history methods
+
     /// Push a new location to history (called when navigating)
     fn push_location(&mut self, location: Location) {
         // Don't push if it's the same as current location
@@ -487,6 +1113,7 @@ impl App {
     /// Apply a location (navigate to it without adding to history)
     fn apply_location(&mut self, location: Location) {
         self.current_tab = location.tab;
+        self.expanded_notifications.clear();
         self.message_view = location.message_view;
         self.selected_thread_id = location.selected_thread_id;
         self.list_offset = 0;
@@ -535,14 +1162,14 @@ impl App {
         let (content_x, content_y, _content_width, content_height) = content_area;
         let hit_zone = 2; // Pixels on either side of border to detect drag
 
-        // Check vertical border (students pane | content)
-        if self.has_students_pane() {
+        // Check vertical border (students pane | content); the compact strip has a fixed width
+        if self.has_students_pane() && !self.students_pane_compact() {
             let border_x = content_x + self.students_pane_width;
-            if column >= border_x.saturating_sub(hit_zone) && column <= border_x + hit_zone {
-                if row >= content_y && row < content_y + content_height {
-                    self.drag_target = DragTarget::StudentsPaneWidth;
-                    return true;
-                }
+            if column >= border_x.saturating_sub(hit_zone) && column <= border_x + hit_zone
+                && row >= content_y && row < content_y + content_height
+            {
+                self.drag_target = DragTarget::StudentsPaneWidth;
+                return true;
             }
         }
 
@@ -617,7 +1244,7 @@ impl App {
 
     pub fn update_time(&mut self) {
         // Use local time for schedule comparison (not UTC)
-        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let now = util::now_local();
         self.current_time = (now.hour(), now.minute());
     }
 
@@ -625,6 +1252,138 @@ impl App {
         self.running = false;
     }
 
+    /// Quit, unless that would lose a typed message; then ask first
+    pub fn request_quit(&mut self) {
+        if self.has_unsent_text() {
+            self.confirm_discard = Some(PendingDiscard::Quit);
+        } else {
+            self.quit();
+        }
+    }
+
+    /// A reply or new message has text that hasn't been sent
+    pub fn has_unsent_text(&self) -> bool {
+        let typing = matches!(self.input_mode, InputMode::Reply | InputMode::ComposeSubject | InputMode::ComposeBody)
+            && !self.input_buffer.trim().is_empty();
+        let composing = self.message_view == MessageView::Compose
+            && !(self.compose_subject.trim().is_empty() && self.compose_body.trim().is_empty());
+        typing || composing
+    }
+
+    /// Leave the reply or compose editor, asking first if that loses text
+    pub fn request_close_editor(&mut self) {
+        if self.has_unsent_text() {
+            self.confirm_discard = Some(PendingDiscard::CloseEditor);
+        } else {
+            self.close_editor();
+        }
+    }
+
+    fn close_editor(&mut self) {
+        if self.message_view == MessageView::Compose {
+            self.cancel_compose();
+        } else {
+            self.cancel_input();
+        }
+    }
+
+    /// Answer the discard prompt: carry out the pending action, or keep editing.
+    /// Discarding also drops the saved draft the editor was resumed from.
+    pub fn answer_discard(&mut self, discard: bool) {
+        match self.confirm_discard.take() {
+            Some(PendingDiscard::CloseEditor) if discard => {
+                self.remove_draft(self.editor_thread_id());
+                self.close_editor();
+            }
+            Some(PendingDiscard::Quit) if discard => {
+                self.remove_draft(self.editor_thread_id());
+                self.quit();
+            }
+            _ => {}
+        }
+    }
+
+    /// Third answer to the discard prompt: keep the text as a draft, then
+    /// carry out the pending action
+    pub fn answer_save_draft(&mut self) {
+        let Some(pending) = self.confirm_discard.take() else {
+            return;
+        };
+        self.save_draft();
+        match pending {
+            PendingDiscard::CloseEditor => self.close_editor(),
+            PendingDiscard::Quit => self.quit(),
+        }
+    }
+
+    /// Thread the open editor replies to; None for a new message
+    fn editor_thread_id(&self) -> Option<i64> {
+        match self.message_view {
+            MessageView::Compose => None,
+            _ => self.selected_thread_id,
+        }
+    }
+
+    /// What the reply or compose editor holds, with the field being typed in
+    fn current_draft(&self) -> Option<Draft> {
+        let draft = if self.message_view == MessageView::Compose {
+            Draft {
+                thread_id: None,
+                subject: match self.input_mode {
+                    InputMode::ComposeSubject => self.input_buffer.clone(),
+                    _ => self.compose_subject.clone(),
+                },
+                body: match self.input_mode {
+                    InputMode::ComposeBody => self.input_buffer.clone(),
+                    _ => self.compose_body.clone(),
+                },
+                recipients: self.selected_recipients.clone(),
+            }
+        } else if self.input_mode == InputMode::Reply {
+            Draft { thread_id: self.selected_thread_id, body: self.input_buffer.clone(), ..Default::default() }
+        } else {
+            return None;
+        };
+        (!draft.is_empty()).then_some(draft)
+    }
+
+    /// Put the editor's text aside as a draft; does nothing when it's empty
+    pub fn save_draft(&mut self) {
+        if let Some(draft) = self.current_draft() {
+            self.drafts.set(draft);
+            self.drafts_changed = true;
+            self.set_status(T::draft_saved(self.lang));
+        }
+    }
+
+    /// Drop the draft for a thread (None: the new message) once it's sent or discarded
+    pub fn remove_draft(&mut self, thread_id: Option<i64>) {
+        if self.drafts.get(thread_id).is_some() {
+            self.drafts.remove(thread_id);
+            self.drafts_changed = true;
+        }
+    }
+
+    /// Reopen the saved new-message draft with its recipients, subject and body.
+    /// Returns false when there is none.
+    pub fn resume_draft(&mut self) -> bool {
+        let Some(draft) = self.drafts.get(None).cloned() else {
+            return false;
+        };
+        self.start_compose();
+        self.compose_subject = draft.subject;
+        self.selected_recipients = draft.recipients;
+        // Continue typing the body once recipients and subject are filled in
+        if !self.selected_recipients.is_empty() && !self.compose_subject.is_empty() {
+            self.input_mode = InputMode::ComposeBody;
+            self.input_cursor = draft.body.chars().count();
+            self.input_buffer = draft.body;
+        } else {
+            self.compose_body = draft.body;
+        }
+        true
+    }
+
     pub fn next_tab(&mut self) {
         self.set_tab(self.current_tab.next());
     }
@@ -644,6 +1403,7 @@ impl App {
         self.push_location(new_location);
 
         self.current_tab = tab;
+        self.expanded_notifications.clear();
         self.message_view = MessageView::List;
         self.selected_thread_id = None;
         self.list_offset = 0;
@@ -723,10 +1483,10 @@ impl App {
 
         let relative_row = (row - header_offset - pane_border) as usize;
 
-        // Check if click is in students pane (left side)
-        if column < students_width {
-            self.focus = Focus::Students;
-            // Clicking on a student selects them
+        self.focus = self.pane_at(row, header_offset, column, students_width, content_height);
+
+        // Clicking on a student selects them
+        if self.focus == Focus::Students {
             if relative_row < self.students.len() {
                 self.selected_student = relative_row;
                 self.list_offset = 0;
@@ -735,28 +1495,23 @@ impl App {
             return ClickResult::None;
         }
 
-        // Click is in content pane - set focus based on tab and position
-        if self.current_tab == Tab::Overview {
-            // Calculate which overview pane was clicked based on split positions
-            let content_row = row.saturating_sub(header_offset);
-            let main_split_row = (content_height as u32 * self.overview_split_percent as u32 / 100) as u16;
-
-            if content_row < main_split_row {
-                self.focus = Focus::OverviewSchedule;
-            } else {
-                // Bottom section - homework on top, grades on bottom (vertical split)
-                let bottom_section_height = content_height.saturating_sub(main_split_row);
-                let bottom_split_offset = (bottom_section_height as u32 * self.overview_bottom_split_percent as u32 / 100) as u16;
-                let grades_start = main_split_row + bottom_split_offset;
+        // Settings rows don't scroll; the row picks the entry directly
+        if self.current_tab == Tab::Settings {
+            return match self.settings_entries().get(relative_row) {
+                Some(&entry) if entry.is_actionable() => ClickResult::Setting(entry),
+                _ => ClickResult::None,
+            };
+        }
 
-                if content_row < grades_start {
-                    self.focus = Focus::OverviewHomework;
-                } else {
-                    self.focus = Focus::OverviewGrades;
-                }
+        // The folder selector takes the first row of the message list
+        let mut relative_row = relative_row;
+        if self.current_tab == Tab::Messages && self.shows_folder_row() {
+            if relative_row == 0 {
+                // Inside the pane border
+                let x = column.saturating_sub(students_width + 1) as usize;
+                return self.folder_at_column(x).map_or(ClickResult::None, ClickResult::Folder);
             }
-        } else {
-            self.focus = Focus::Content;
+            relative_row -= 1;
         }
 
         // Calculate the actual item index: scroll offset + row position in visible area
@@ -764,36 +1519,85 @@ impl App {
 
         // Check bounds - clicking should NOT scroll, just select/activate the item
         if item_index < self.current_list_length() {
-            // Return activation result based on current tab with the item index
+            // A single click only focuses; opening takes a double click so a
+            // click meant to focus the pane can't misfire
+            let double_click = self.is_double_click(row);
             return match self.current_tab {
-                Tab::Notifications => ClickResult::ActivateNotification(item_index),
-                Tab::Messages => {
-                    if self.message_view == MessageView::List {
-                        ClickResult::ActivateMessage(item_index)
-                    } else {
-                        ClickResult::None
-                    }
-                }
-                _ => ClickResult::ItemSelected(item_index),
+                Tab::Notifications if double_click => ClickResult::ActivateNotification(item_index),
+                Tab::Messages if self.message_view != MessageView::List => ClickResult::None,
+                Tab::Messages if double_click => ClickResult::ActivateMessage(item_index),
+                _ => ClickResult::Select(item_index),
             };
         }
 
         ClickResult::None
     }
 
-    pub fn toggle_focus(&mut self) {
-        let has_students = self.has_students_pane();
+    /// Record a click on `row` of the focused pane; true when it completes a
+    /// double click. The pair is used up, so a third click starts over.
+    fn is_double_click(&mut self, row: u16) -> bool {
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(last_row, focus, at)| {
+            last_row == row && focus == self.focus && now.duration_since(at) < self.double_click_interval
+        });
+        self.last_click = if double { None } else { Some((row, self.focus, now)) };
+        double
+    }
 
-        self.focus = match self.current_tab {
-            Tab::Overview => {
-                if has_students {
-                    // Cycle: Students -> Schedule -> Homework -> Grades -> Students
-                    match self.focus {
-                        Focus::Students => Focus::OverviewSchedule,
-                        Focus::OverviewSchedule => Focus::OverviewHomework,
-                        Focus::OverviewHomework => Focus::OverviewGrades,
-                        Focus::OverviewGrades => Focus::Students,
-                        _ => Focus::OverviewSchedule,
+    /// Which pane is under a screen position (same parameters as `click_list_item`)
+    fn pane_at(&self, row: u16, header_offset: u16, column: u16, students_width: u16, content_height: u16) -> Focus {
+        if column < students_width {
+            return Focus::Students;
+        }
+        if self.current_tab != Tab::Overview {
+            return Focus::Content;
+        }
+
+        // Calculate which overview pane it is based on split positions
+        let content_row = row.saturating_sub(header_offset);
+        let main_split_row = (content_height as u32 * self.overview_split_percent as u32 / 100) as u16;
+        if content_row < main_split_row {
+            return Focus::OverviewSchedule;
+        }
+
+        // Bottom section - homework on top, grades on bottom (vertical split)
+        let bottom_section_height = content_height.saturating_sub(main_split_row);
+        let bottom_split_offset = (bottom_section_height as u32 * self.overview_bottom_split_percent as u32 / 100) as u16;
+        if content_row < main_split_row + bottom_split_offset {
+            Focus::OverviewHomework
+        } else {
+            Focus::OverviewGrades
+        }
+    }
+
+    /// Mouse wheel: focus the pane under the cursor and scroll it by a few lines.
+    /// Over the students pane it moves the selection one student at a time.
+    pub fn scroll_at(&mut self, down: bool, row: u16, header_offset: u16, column: u16, students_width: u16, content_height: u16) {
+        let pane = self.pane_at(row, header_offset, column, students_width, content_height);
+        if pane == Focus::Students {
+            if down { self.next_student() } else { self.prev_student() }
+            return;
+        }
+
+        self.focus = pane;
+        for _ in 0..WHEEL_SCROLL_LINES {
+            if down { self.scroll_down() } else { self.scroll_up() }
+        }
+    }
+
+    pub fn toggle_focus(&mut self) {
+        let has_students = self.has_students_pane();
+
+        self.focus = match self.current_tab {
+            Tab::Overview => {
+                if has_students {
+                    // Cycle: Students -> Schedule -> Homework -> Grades -> Students
+                    match self.focus {
+                        Focus::Students => Focus::OverviewSchedule,
+                        Focus::OverviewSchedule => Focus::OverviewHomework,
+                        Focus::OverviewHomework => Focus::OverviewGrades,
+                        Focus::OverviewGrades => Focus::Students,
+                        _ => Focus::OverviewSchedule,
                     }
                 } else {
                     // No students pane: Schedule -> Homework -> Grades -> Schedule
@@ -839,7 +1643,6 @@ impl App {
         }
     }
 
-    #[allow(dead_code)] // Keep for potential future use (e.g., mouse selection)
     pub fn select_student(&mut self, index: usize) {
         if index < self.students.len() {
             self.selected_student = index;
@@ -853,10 +1656,12 @@ impl App {
             Tab::Notifications => self.notifications.len(),
             Tab::Messages => self.messages.len(),
             Tab::Homework => self.current_student().map(|s| s.homework.len()).unwrap_or(0),
+            Tab::Grades if self.grades_report => self.current_student().map(|s| s.subject_report().len()).unwrap_or(0),
+            Tab::Grades if self.grades_timeline => self.current_student().map(|s| s.grades_timeline().len()).unwrap_or(0),
             Tab::Grades => self.current_student().map(|s| s.grades.len()).unwrap_or(0),
             Tab::Schedule => self.current_student().map(|s| s.schedule.len()).unwrap_or(0),
             Tab::Absences => self.current_student().map(|s| s.absences.len()).unwrap_or(0),
-            Tab::Feedbacks => self.current_student().map(|s| s.feedbacks.len()).unwrap_or(0),
+            Tab::Feedbacks => self.visible_feedbacks().len(),
             Tab::Overview | Tab::Settings => 0,
         }
     }
@@ -900,6 +1705,63 @@ impl App {
         }
     }
 
+    /// Select the `n`th item (from 1) of the focused list, or its last item
+    pub fn jump_to(&mut self, n: Option<usize>) {
+        let len = match self.focus {
+            Focus::Students => self.students.len(),
+            Focus::OverviewSchedule | Focus::OverviewHomework | Focus::OverviewGrades => self.overview_list_length(),
+            _ => self.current_list_length(),
+        };
+        let index = n.map_or(usize::MAX, |n| n.saturating_sub(1)).min(len.saturating_sub(1));
+        match self.focus {
+            Focus::Students => {
+                if index != self.selected_student && len > 0 {
+                    self.selected_student = index;
+                    self.list_offset = 0;
+                }
+            }
+            Focus::OverviewSchedule => self.schedule_offset = index,
+            Focus::OverviewHomework => self.homework_offset = index,
+            Focus::OverviewGrades => self.grades_offset = index,
+            _ => self.list_offset = index,
+        }
+    }
+
+    /// Add a typed digit to the count for the next motion
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self.pending_count().unwrap_or(0);
+        let count = (count * 10 + digit as usize).min(MAX_COUNT);
+        self.pending_count = Some((count, Instant::now()));
+    }
+
+    /// Count typed so far, shown in the status bar
+    pub fn pending_count(&self) -> Option<usize> {
+        self.pending_count.map(|(count, _)| count)
+    }
+
+    /// Use up the typed count
+    pub fn take_count(&mut self) -> Option<usize> {
+        self.pending_count.take().map(|(count, _)| count)
+    }
+
+    /// Drop the typed count once nothing followed it for `COUNT_TIMEOUT`.
+    /// A lone digit was a tab number after all, so that tab is selected.
+    pub fn expire_count(&mut self, now: Instant) {
+        if let Some((count, typed)) = self.pending_count {
+            if now.duration_since(typed) >= COUNT_TIMEOUT {
+                self.pending_count = None;
+                self.select_count_tab(count);
+            }
+        }
+    }
+
+    /// Select tab `count` when it's a lone digit that never got used as a count
+    pub fn select_count_tab(&mut self, count: usize) {
+        if (1..=9).contains(&count) {
+            self.select_tab(count - 1);
+        }
+    }
+
     pub fn scroll_up(&mut self) {
         match self.focus {
             Focus::OverviewSchedule => self.schedule_offset = self.schedule_offset.saturating_sub(1),
@@ -939,22 +1801,62 @@ impl App {
         None
     }
 
+    /// Show a thread's messages (oldest first, as the API returns them) in the
+    /// configured order. `at_latest` selects the newest message, which the
+    /// draw then scrolls into view; otherwise the selection starts at the top.
+    pub fn set_thread(&mut self, thread: ThreadContents, at_latest: bool) {
+        let mut messages = thread.messages;
+        if self.thread_newest_first {
+            messages.reverse();
+        }
+        self.thread_messages = messages;
+        self.thread_participants = thread.participants;
+        self.thread_offset = if at_latest { self.latest_thread_index() } else { 0 };
+    }
+
+    /// Who is in the open thread: "Name (role)", and "you" for this account
+    pub fn participant_labels(&self) -> Vec<String> {
+        self.thread_participants.iter()
+            .map(|p| {
+                if self.user_id.is_some_and(|id| id == p.id) {
+                    T::you(self.lang).to_string()
+                } else if p.role.is_empty() {
+                    p.name.clone()
+                } else {
+                    format!("{} ({})", p.name, p.role.to_lowercase())
+                }
+            })
+            .collect()
+    }
+
+    /// Index of the newest message in `thread_messages`
+    pub fn latest_thread_index(&self) -> usize {
+        if self.thread_newest_first {
+            0
+        } else {
+            self.thread_messages.len().saturating_sub(1)
+        }
+    }
+
     /// Close thread view and return to list
     pub fn close_thread(&mut self) {
         self.message_view = MessageView::List;
         self.selected_thread_id = None;
         self.thread_messages.clear();
+        self.thread_participants.clear();
         self.thread_offset = 0;
         self.input_mode = InputMode::Normal;
         self.input_buffer.clear();
     }
 
-    /// Start reply mode
+    /// Start reply mode, picking up the thread's draft if there is one
     pub fn start_reply(&mut self) {
         if self.message_view == MessageView::Thread {
             self.input_mode = InputMode::Reply;
-            self.input_buffer.clear();
-            self.input_cursor = 0;
+            self.input_buffer = self.drafts.get(self.selected_thread_id)
+                .map(|d| d.body.clone())
+                .unwrap_or_default();
+            self.input_cursor = self.input_buffer.chars().count();
         }
     }
 
@@ -963,26 +1865,61 @@ impl App {
         self.input_mode = InputMode::Normal;
         self.input_buffer.clear();
         self.input_cursor = 0;
+        self.date_entry_error = false;
+    }
+
+    /// Byte offset of `input_cursor`, which counts characters
+    fn input_byte_index(&self) -> usize {
+        self.input_buffer
+            .char_indices()
+            .nth(self.input_cursor)
+            .map_or(self.input_buffer.len(), |(i, _)| i)
     }
 
     /// Add character to input buffer
     pub fn input_char(&mut self, c: char) {
-        self.input_buffer.insert(self.input_cursor, c);
+        let at = self.input_byte_index();
+        self.input_buffer.insert(at, c);
         self.input_cursor += 1;
     }
 
+    /// Insert pasted text at the cursor in one go, rather than key by key.
+    /// Message bodies keep line breaks; one-line prompts get spaces instead.
+    pub fn input_paste(&mut self, text: &str) {
+        if self.input_mode == InputMode::Normal {
+            return;
+        }
+        let multiline = matches!(self.input_mode, InputMode::Reply | InputMode::ComposeBody);
+        let text: String = text.replace("\r\n", "\n")
+            .chars()
+            .map(|c| match c {
+                '\n' | '\r' if multiline => '\n',
+                '\n' | '\r' | '\t' => ' ',
+                c => c,
+            })
+            .filter(|c| *c == '\n' || !c.is_control())
+            .collect();
+        let at = self.input_byte_index();
+        self.input_buffer.insert_str(at, &text);
+        self.input_cursor += text.chars().count();
+        self.date_entry_error = false;
+        self.switcher_selected = 0;
+    }
+
     /// Delete character before cursor
     pub fn input_backspace(&mut self) {
         if self.input_cursor > 0 {
             self.input_cursor -= 1;
-            self.input_buffer.remove(self.input_cursor);
+            let at = self.input_byte_index();
+            self.input_buffer.remove(at);
         }
     }
 
     /// Delete character at cursor
     pub fn input_delete(&mut self) {
-        if self.input_cursor < self.input_buffer.len() {
-            self.input_buffer.remove(self.input_cursor);
+        if self.input_cursor < self.input_buffer.chars().count() {
+            let at = self.input_byte_index();
+            self.input_buffer.remove(at);
         }
     }
 
@@ -995,7 +1932,7 @@ impl App {
 
     /// Move input cursor right
     pub fn input_right(&mut self) {
-        if self.input_cursor < self.input_buffer.len() {
+        if self.input_cursor < self.input_buffer.chars().count() {
             self.input_cursor += 1;
         }
     }
@@ -1021,6 +1958,40 @@ impl App {
         self.list_offset = 0;  // Reset list position for recipients
     }
 
+    /// Start a new message to everyone in thread `thread_id` except this
+    /// account, with the subject "Re: <subject>". Participants come from the
+    /// thread response; without them, whoever wrote in the thread.
+    pub fn start_compose_from_thread(&mut self, thread_id: i64) {
+        let mut recipients: Vec<i64> = if self.thread_participants.is_empty() {
+            self.thread_messages.iter().filter(|m| !m.is_system).map(|m| m.sender_id).collect()
+        } else {
+            self.thread_participants.iter().map(|p| p.id).collect()
+        };
+        recipients.retain(|&id| id != 0 && Some(id) != self.user_id);
+        let mut seen = HashSet::new();
+        recipients.retain(|id| seen.insert(*id));
+
+        let subject = self.messages.iter()
+            .find(|m| m.id == thread_id)
+            .map(|m| m.subject.clone())
+            .unwrap_or_default();
+
+        self.start_compose();
+        self.selected_recipients = recipients;
+        self.compose_subject = match subject.as_str() {
+            "" => String::new(),
+            s if s.starts_with("Re: ") => subject,
+            s => format!("Re: {}", s),
+        };
+    }
+
+    /// Recipients the account may write to, fetched for composing. Preselected
+    /// ones not among them are dropped, so nothing goes to someone hidden.
+    pub fn set_recipients(&mut self, recipients: Vec<Recipient>) {
+        self.selected_recipients.retain(|id| recipients.iter().any(|r| r.id == *id));
+        self.recipients = recipients;
+    }
+
     /// Cancel compose and return to message list
     pub fn cancel_compose(&mut self) {
         self.message_view = MessageView::List;
@@ -1034,15 +2005,12 @@ impl App {
 
     /// Move to next compose step (recipients -> subject -> body -> recipients)
     pub fn compose_next_step(&mut self) {
-        match self.input_mode {
-            InputMode::ComposeSubject => {
-                // Save subject, load body
-                self.compose_subject = self.input_buffer.clone();
-                self.input_buffer = self.compose_body.clone();
-                self.input_cursor = self.input_buffer.len();
-                self.input_mode = InputMode::ComposeBody;
-            }
-            _ => {}
+        if self.input_mode == InputMode::ComposeSubject {
+            // Save subject, load body
+            self.compose_subject = self.input_buffer.clone();
+            self.input_buffer = self.compose_body.clone();
+            self.input_cursor = self.input_buffer.chars().count();
+            self.input_mode = InputMode::ComposeBody;
         }
     }
 
@@ -1053,7 +2021,7 @@ impl App {
                 // Save body, load subject
                 self.compose_body = self.input_buffer.clone();
                 self.input_buffer = self.compose_subject.clone();
-                self.input_cursor = self.input_buffer.len();
+                self.input_cursor = self.input_buffer.chars().count();
                 self.input_mode = InputMode::ComposeSubject;
             }
             InputMode::ComposeSubject => {
@@ -1079,13 +2047,202 @@ impl App {
         }
     }
 
-    /// Check if ready to send compose (has subject, body, and at least one recipient)
+    /// Check if ready to send compose (has subject, body, and at least one
+    /// recipient); a subject or body of only spaces doesn't count
     pub fn can_send_compose(&self) -> bool {
-        !self.compose_subject.is_empty()
-            && !self.input_buffer.is_empty()
+        !self.compose_subject.trim().is_empty()
+            && !self.input_buffer.trim().is_empty()
             && !self.selected_recipients.is_empty()
     }
 
+    /// The message list shows a folder selector once folders are known
+    pub fn shows_folder_row(&self) -> bool {
+        self.message_view == MessageView::List && !self.message_folders.is_empty()
+    }
+
+    /// Folder selector entries as (folder to show, label): the inbox first,
+    /// standing for the default list, then the other folders in API order.
+    /// Labels carry the folder's unread count.
+    pub fn folder_choices(&self) -> Vec<(Option<i64>, String)> {
+        let label = |name: &str, unread: i32| match unread {
+            0 => name.to_string(),
+            n => format!("{} ({})", name, n),
+        };
+        let inbox = self.message_folders.iter().find(|f| f.is_inbox());
+        let mut choices = vec![(None, match inbox {
+            Some(folder) => label(&folder.name, folder.folder_unread_count),
+            None => T::inbox(self.lang).to_string(),
+        })];
+        choices.extend(self.message_folders.iter()
+            .filter(|f| !f.is_inbox())
+            .map(|f| (Some(f.id), label(&f.name, f.folder_unread_count))));
+        choices
+    }
+
+    /// Separator between folder selector labels
+    pub fn folder_separator(&self) -> &'static str {
+        if self.ascii { " | " } else { " │ " }
+    }
+
+    /// Folder under column `x` of the selector row (0 = first column inside the border)
+    fn folder_at_column(&self, x: usize) -> Option<Option<i64>> {
+        // The row starts with one space of padding
+        let mut start = 1;
+        for (folder, label) in self.folder_choices() {
+            let end = start + label.chars().count();
+            if (start..end).contains(&x) {
+                return Some(folder);
+            }
+            start = end + self.folder_separator().chars().count();
+        }
+        None
+    }
+
+    /// Show another folder's threads; false if it is already shown. The caller
+    /// loads the threads (from the cache if still fresh).
+    pub fn select_folder(&mut self, folder: Option<i64>) -> bool {
+        if folder == self.selected_folder {
+            return false;
+        }
+        self.selected_folder = folder;
+        self.messages.clear();
+        self.messages_age = None;
+        self.list_offset = 0;
+        true
+    }
+
+    /// Switch to the next folder in the selector, wrapping around
+    pub fn cycle_folder(&mut self) -> bool {
+        let choices = self.folder_choices();
+        if choices.len() < 2 {
+            return false;
+        }
+        let current = choices.iter().position(|(f, _)| *f == self.selected_folder).unwrap_or(0);
+        self.select_folder(choices[(current + 1) % choices.len()].0)
+    }
+
+    /// Unread threads in the shown folder: the folder's own count when known,
+    /// since the API may page the thread list
+    pub fn unread_in_folder(&self) -> usize {
+        let folder = self.message_folders.iter()
+            .find(|f| f.thread_filter() == self.selected_folder);
+        match folder {
+            Some(folder) => folder.folder_unread_count.max(0) as usize,
+            None => self.messages.iter().filter(|m| m.is_unread).count(),
+        }
+    }
+
+    /// Toggle between the collapsed and full body of the selected notification
+    pub fn toggle_notification_expanded(&mut self) {
+        if self.current_tab != Tab::Notifications || self.list_offset >= self.notifications.len() {
+            return;
+        }
+        if !self.expanded_notifications.remove(&self.list_offset) {
+            self.expanded_notifications.insert(self.list_offset);
+        }
+    }
+
+    /// Status notice for grades that were corrected or deleted between the
+    /// loaded data and a refresh: the first change, with a count of the rest.
+    /// Students are named when there is more than one.
+    pub fn grade_change_notice(&self, refreshed: &[StudentData]) -> Option<String> {
+        let notices: Vec<String> = refreshed.iter()
+            .flat_map(|new| {
+                let old = self.students.iter().find(|s| s.student.id == new.student.id);
+                let changes = old.map(|old| diff_grades(&old.grades, &new.grades)).unwrap_or_default();
+                changes.into_iter().map(move |c| {
+                    let notice = T::grade_change(self.lang, &c.subject, &c.old, c.new.as_deref());
+                    match self.students.len() > 1 {
+                        true => format!("{}: {}", new.student.name.split_whitespace().next().unwrap_or_default(), notice),
+                        false => notice,
+                    }
+                })
+            })
+            .collect();
+
+        let first = notices.first()?;
+        Some(match notices.len() {
+            1 => first.clone(),
+            n => format!("{} (+{})", first, n - 1),
+        })
+    }
+
+    /// Identities of a student's items in a category, as `tracking` defines them
+    fn tracked_keys(data: &StudentData, category: Category) -> Vec<String> {
+        match category {
+            Category::Grades => tracking::grade_keys(&data.grades),
+            Category::Homework => tracking::homework_keys(&data.homework),
+        }
+    }
+
+    /// Category of items a tab shows "new" markers for
+    fn tracked_category(tab: Tab) -> Option<Category> {
+        match tab {
+            Tab::Grades => Some(Category::Grades),
+            Tab::Homework => Some(Category::Homework),
+            _ => None,
+        }
+    }
+
+    /// Identities of the current student's items in the tab's category that
+    /// appeared since the tab was last viewed
+    pub fn new_keys(&self, tab: Tab) -> HashSet<String> {
+        let (Some(category), Some(data)) = (Self::tracked_category(tab), self.current_student()) else {
+            return HashSet::new();
+        };
+        self.seen.new_keys(data.student.id, category, &Self::tracked_keys(data, category))
+    }
+
+    /// After loading data: snapshot what students have the first time, and
+    /// give new items on the tab being viewed their full time on screen
+    pub fn track_new(&mut self) {
+        for data in &self.students {
+            for category in [Category::Grades, Category::Homework] {
+                if self.seen.baseline(data.student.id, category, &Self::tracked_keys(data, category)) {
+                    self.seen_changed = true;
+                }
+            }
+        }
+        self.viewing = None;
+    }
+
+    /// Mark the current tab's items seen once it has been on screen for the
+    /// same student for `SEEN_AFTER`
+    pub fn update_seen(&mut self, now: Instant) {
+        let (tab, student) = (self.current_tab, self.selected_student);
+        match self.viewing {
+            Some((viewed_tab, viewed_student, since)) if viewed_tab == tab && viewed_student == student => {
+                if now.duration_since(since) < SEEN_AFTER {
+                    return;
+                }
+                let (Some(category), Some(data)) = (Self::tracked_category(tab), self.current_student()) else {
+                    return;
+                };
+                let keys = Self::tracked_keys(data, category);
+                let id = data.student.id;
+                if !self.seen.new_keys(id, category, &keys).is_empty() && self.seen.mark(id, category, &keys) {
+                    self.seen_changed = true;
+                }
+            }
+            _ => self.viewing = Some((tab, student, now)),
+        }
+    }
+
+    /// Replace the notification list, keeping expanded bodies whose ids are still present
+    pub fn set_notifications(&mut self, notifications: Vec<Notification>) {
+        let expanded_ids: HashSet<&str> = self.expanded_notifications
+            .iter()
+            .filter_map(|&i| self.notifications.get(i)?.id.as_deref())
+            .collect();
+        self.expanded_notifications = notifications
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.id.as_deref().is_some_and(|id| expanded_ids.contains(id)))
+            .map(|(i, _)| i)
+            .collect();
+        self.notifications = notifications;
+    }
+
     /// Activate the selected notification - navigate to the appropriate tab
     pub fn activate_notification(&mut self) -> bool {
         self.activate_notification_at(self.list_offset)
@@ -1110,9 +2267,14 @@ impl App {
                 };
 
                 if let Some(tab) = target_tab {
+                    let notification = notification.clone();
+                    if let Some(student) = self.notification_student(&notification) {
+                        self.selected_student = student;
+                    }
                     self.current_tab = tab;
-                    self.list_offset = 0;
+                    self.expanded_notifications.clear();
                     self.focus = Focus::Content;
+                    self.list_offset = self.notification_row(tab, &notification.target).unwrap_or(0);
                     return true;
                 }
             }
@@ -1120,6 +2282,113 @@ impl App {
         false
     }
 
+    /// The student a notification is about: by pupil id, else by its pupil
+    /// names when they name exactly one student
+    fn notification_student(&self, notification: &Notification) -> Option<usize> {
+        if let Some(id) = notification.target.pupil_id {
+            return self.students.iter().position(|data| data.student.id == id);
+        }
+        let names = notification.pupil_names.as_deref()?;
+        let mut matching = self.students.iter().enumerate().filter(|(_, data)| data.student.name == names);
+        match (matching.next(), matching.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Row of `tab` (for the current student) with the grade subject,
+    /// homework or thread the notification points at
+    fn notification_row(&self, tab: Tab, target: &NotificationTarget) -> Option<usize> {
+        match tab {
+            Tab::Grades => {
+                let subject = target.subject.as_deref()?.to_lowercase();
+                let data = self.current_student()?;
+                if self.grades_report {
+                    data.subject_report().iter().position(|r| r.subject.to_lowercase() == subject)
+                } else if self.grades_timeline {
+                    data.grades_timeline().iter().position(|g| g.subject.to_lowercase() == subject)
+                } else {
+                    data.grades.iter().position(|g| g.subject.to_lowercase() == subject)
+                        .or_else(|| data.grades.iter().position(|g| g.subject_matches(&subject)))
+                }
+            }
+            Tab::Homework => {
+                let id = target.homework_id?;
+                let (pending, past) = self.homework_sections();
+                if let Some(row) = pending.iter().position(|hw| hw.id == Some(id)) {
+                    return Some(row);
+                }
+                // Past homework comes after the divider
+                let divider = usize::from(!pending.is_empty());
+                past.iter().position(|hw| hw.id == Some(id)).map(|row| pending.len() + divider + row)
+            }
+            Tab::Messages => {
+                let id = target.thread_id?;
+                self.messages.iter().position(|m| m.id == id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The Homework tab's two sections for the current student: pending
+    /// homework soonest first, then past homework most recent first
+    pub fn homework_sections(&self) -> (Vec<&Homework>, Vec<&Homework>) {
+        let Some(data) = self.current_student() else {
+            return (Vec::new(), Vec::new());
+        };
+        // Homework due today stays pending until the last lesson ends
+        let school_day_over = school_day_over(&data.schedule, self.current_time, self.school_day_end);
+        let (mut pending, mut past): (Vec<_>, Vec<_>) = data.homework.iter()
+            .partition(|hw| hw.is_pending(&self.current_date, school_day_over));
+        pending.sort_by(|a, b| {
+            a.due_date_sort.as_deref().unwrap_or("9999-99-99").cmp(b.due_date_sort.as_deref().unwrap_or("9999-99-99"))
+        });
+        past.sort_by(|a, b| {
+            b.due_date_sort.as_deref().unwrap_or("0000-00-00").cmp(a.due_date_sort.as_deref().unwrap_or("0000-00-00"))
+        });
+        (pending, past)
+    }
+
+    /// Why the current tab's data failed to load, if it did: on the Overview,
+    /// the first of its panes that failed
+    pub fn load_error(&self) -> Option<&str> {
+        let data = self.current_student()?;
+        let statuses: &[&LoadStatus] = match self.current_tab {
+            Tab::Overview => &[&data.schedule_status, &data.homework_status, &data.grades_status],
+            Tab::Homework => &[&data.homework_status],
+            Tab::Grades => &[&data.grades_status],
+            Tab::Schedule => &[&data.schedule_status],
+            Tab::Absences => &[&data.absences_status],
+            Tab::Feedbacks => &[&data.feedbacks_status],
+            Tab::Messages | Tab::Notifications | Tab::Settings => &[],
+        };
+        statuses.iter().find_map(|status| status.error())
+    }
+
+    /// Started without a token: open on Settings, where the login options are
+    pub fn require_login(&mut self) {
+        self.needs_login = true;
+        self.user_name = None;
+        self.current_tab = Tab::Settings;
+    }
+
+    /// A token turned up (e.g. from `shkolo login` in another terminal); the
+    /// caller starts the first refresh
+    pub fn logged_in(&mut self) {
+        self.needs_login = false;
+        self.current_tab = Tab::Overview;
+        self.loading = true;
+        self.set_status(T::logged_in_loading(self.lang));
+    }
+
+    /// Show `load_error` in full, as the pane only says that loading failed
+    pub fn show_load_error(&mut self) {
+        match self.load_error().map(str::to_string) {
+            Some(error) => self.set_error(format!("{} {}", T::load_failed_details(self.lang), error)),
+            None => self.set_status(T::no_load_error(self.lang)),
+        }
+    }
+
     pub fn current_student(&self) -> Option<&StudentData> {
         self.students.get(self.selected_student)
     }
@@ -1140,371 +2409,270 @@ impl App {
         self.error_message = None;
     }
 
+    /// Load everything from the disk cache.
+    /// Each student's files are read concurrently on the blocking pool; results
+    /// are collected in the original order so the selected (first) student is stable.
     pub async fn load_from_cache(&mut self, cache: &CacheStore) {
         // Load students
-        if let Some((students, _, _)) = cache.get_students() {
-            for student in students {
-                let mut data = StudentData::new(student.clone());
-
-                // Load homework
-                if let Some((homework, age, _)) = cache.get_homework(student.id) {
-                    data.homework = homework;
-                    data.homework_age = Some(age);
-                }
-
-                // Load grades
-                if let Some((grades, age, _)) = cache.get_grades(student.id) {
-                    data.grades = grades;
-                    data.grades_age = Some(age);
-                }
-
-                // Load schedule for today
-                if let Some((schedule, age, _)) = cache.get_schedule(student.id, &self.current_date) {
-                    data.schedule = schedule;
-                    data.schedule_age = Some(age);
-                }
-
-                // Load events
-                if let Some((events, _, _)) = cache.get_events(student.id) {
-                    data.events = events;
-                }
-
-                // Load absences
-                if let Some((absences, age, _)) = cache.get_absences(student.id) {
-                    data.absences = absences;
-                    data.absences_age = Some(age);
-                }
-
-                // Load feedbacks
-                if let Some((feedbacks, age, _)) = cache.get_feedbacks(student.id) {
-                    data.feedbacks = feedbacks;
-                    data.feedbacks_age = Some(age);
-                }
-
-                self.students.push(data);
+        if let Some((students, _, _, _)) = cache.get_students() {
+            let tasks: Vec<_> = students
+                .iter()
+                .cloned()
+                .map(|student| {
+                    let cache = cache.clone();
+                    let date = self.current_date.clone();
+                    tokio::task::spawn_blocking(move || StudentData::from_cache(&cache, student, &date))
+                })
+                .collect();
+
+            let results = futures::future::join_all(tasks).await;
+            for (student, result) in students.into_iter().zip(results) {
+                self.students.push(result.unwrap_or_else(|_| StudentData::new(student)));
             }
         }
 
         // Load notifications
-        if let Some((notifications, age, _)) = cache.get_notifications() {
-            self.notifications = notifications;
-            self.notifications_age = Some(age);
+        if let Some((notifications, _, _, cached_at)) = cache.get_notifications() {
+            self.set_notifications(notifications);
+            self.notifications_age = Some(cached_at);
         }
 
         // Load messages
-        if let Some((messages, age, _)) = cache.get_messages() {
+        if let Some((messages, _, _, cached_at)) = cache.get_messages(self.selected_folder) {
             self.messages = messages;
-            self.messages_age = Some(age);
+            self.messages_age = Some(cached_at);
         }
     }
 
-    pub async fn refresh_data(&mut self, client: &ShkoloClient, cache: &CacheStore, force: bool) -> anyhow::Result<()> {
-        self.loading = true;
-        self.set_status("Refreshing...");
-
-        // Fetch students
-        let pupils_response = client.get_pupils().await?;
-
-        let mut students = Vec::new();
-        if let Some(child_pupils) = pupils_response.child_pupils {
-            for (id, pupil) in child_pupils {
-                students.push(Student::from_child_pupil(&id, &pupil));
+    /// Fill the compose recipients from the cache or the API. On failure
+    /// compose stays open with the error shown, so 'r' can try again.
+    pub async fn load_recipients(&mut self, client: &impl ShkoloApi, cache: &CacheStore, force: bool) {
+        match service::fetch_recipients(client, cache, force).await {
+            Ok((recipients, _)) => {
+                self.set_recipients(recipients);
+                self.clear_status();
             }
-        }
-
-        // Sort by name for consistent ordering
-        students.sort_by(|a, b| a.name.cmp(&b.name));
-
-        cache.save_students(&students)?;
-
-        // Fetch data for each student
-        self.students.clear();
-        for student in &students {
-            let mut data = StudentData::new(student.clone());
-
-            // Check cache for homework
-            let should_refresh_homework = force || cache.get_homework(student.id)
-                .map(|(_, _, expired)| expired)
-                .unwrap_or(true);
-
-            if should_refresh_homework {
-                if let Ok(homework) = self.fetch_homework(client, student.id).await {
-                    data.homework = homework.clone();
-                    data.homework_age = Some("just now".to_string());
-                    let _ = cache.save_homework(student.id, &homework);
-                }
-            } else if let Some((homework, age, _)) = cache.get_homework(student.id) {
-                data.homework = homework;
-                data.homework_age = Some(age);
-            }
-
-            // Check cache for grades
-            let should_refresh_grades = force || cache.get_grades(student.id)
-                .map(|(_, _, expired)| expired)
-                .unwrap_or(true);
-
-            if should_refresh_grades {
-                if let Ok(grades) = self.fetch_grades(client, student.id).await {
-                    data.grades = grades.clone();
-                    data.grades_age = Some("just now".to_string());
-                    let _ = cache.save_grades(student.id, &grades);
-                }
-            } else if let Some((grades, age, _)) = cache.get_grades(student.id) {
-                data.grades = grades;
-                data.grades_age = Some(age);
-            }
-
-            // Check cache for schedule
-            let should_refresh_schedule = force || cache.get_schedule(student.id, &self.current_date)
-                .map(|(_, _, expired)| expired)
-                .unwrap_or(true);
-
-            if should_refresh_schedule {
-                if let Ok(schedule) = self.fetch_schedule(client, student.id, &self.current_date).await {
-                    data.schedule = schedule.clone();
-                    data.schedule_age = Some("just now".to_string());
-                    let _ = cache.save_schedule(student.id, &self.current_date, &schedule);
-                }
-            } else if let Some((schedule, age, _)) = cache.get_schedule(student.id, &self.current_date) {
-                data.schedule = schedule;
-                data.schedule_age = Some(age);
+            Err(e) => {
+                self.clear_status();
+                self.set_error(format!("{}\n{}", T::failed_load_recipients(self.lang), e));
             }
+        }
+        self.loading = false;
+    }
 
-            // Fetch events
-            if let Ok(events_response) = client.get_pupil_events(student.id).await {
-                let events: Vec<Event> = events_response.invitations
-                    .unwrap_or_default()
-                    .iter()
-                    .map(Event::from_raw)
-                    .collect();
-                data.events = events.clone();
-                let _ = cache.save_events(student.id, &events);
-            }
+    /// Fetch all data, using the cache where it hasn't expired.
+    /// With `first_only` (cold start) only the first, visible student's data is fetched so
+    /// the UI becomes interactive sooner; the background refresh fills in the rest.
+    pub async fn refresh_data(&mut self, client: &impl ShkoloApi, cache: &CacheStore, force: bool, first_only: bool) -> anyhow::Result<()> {
+        self.loading = true;
+        self.set_status(T::refreshing(self.lang));
 
-            // Fetch absences
-            let should_refresh_absences = force || cache.get_absences(student.id)
-                .map(|(_, _, expired)| expired)
-                .unwrap_or(true);
+        let (students, _) = service::fetch_students(client, cache, force).await?;
 
-            if should_refresh_absences {
-                if let Ok(absences) = self.fetch_absences(client, student.id).await {
-                    data.absences = absences.clone();
-                    data.absences_age = Some("just now".to_string());
-                    let _ = cache.save_absences(student.id, &absences);
-                }
-            } else if let Some((absences, age, _)) = cache.get_absences(student.id) {
-                data.absences = absences;
-                data.absences_age = Some(age);
-            }
+        // Failures are collected rather than dropped so the user knows the data is incomplete
+        let mut errors: Vec<String> = Vec::new();
 
-            // Fetch feedbacks
-            let should_refresh_feedbacks = force || cache.get_feedbacks(student.id)
-                .map(|(_, _, expired)| expired)
-                .unwrap_or(true);
-
-            if should_refresh_feedbacks {
-                if let Ok(feedbacks) = self.fetch_feedbacks(client, student.id).await {
-                    data.feedbacks = feedbacks.clone();
-                    data.feedbacks_age = Some("just now".to_string());
-                    let _ = cache.save_feedbacks(student.id, &feedbacks);
-                }
-            } else if let Some((feedbacks, age, _)) = cache.get_feedbacks(student.id) {
-                data.feedbacks = feedbacks;
-                data.feedbacks_age = Some(age);
-            }
+        // Students are fetched side by side; on a cold start only the first one is
+        let fetched = if first_only { students.len().min(1) } else { students.len() };
+        let date = self.current_date.clone();
+        let fetches = students[..fetched].iter()
+            .map(|student| StudentData::fetch(client, cache, student.clone(), &date, force));
+        let results = futures::future::join_all(fetches).await;
 
+        self.students.clear();
+        for (data, student_errors) in results {
+            errors.extend(student_errors);
             self.students.push(data);
         }
+        self.students.extend(students.into_iter().skip(fetched).map(StudentData::new));
 
-        // Fetch notifications
-        let should_refresh_notifications = force || cache.get_notifications()
-            .map(|(_, _, expired)| expired)
-            .unwrap_or(true);
-
-        if should_refresh_notifications {
-            if let Ok(notifications) = self.fetch_notifications(client).await {
-                self.notifications = notifications.clone();
-                self.notifications_age = Some("just now".to_string());
-                let _ = cache.save_notifications(&notifications);
-            }
-        } else if let Some((notifications, age, _)) = cache.get_notifications() {
-            self.notifications = notifications;
-            self.notifications_age = Some(age);
+        if first_only {
+            self.finish_refresh(&errors);
+            return Ok(());
         }
 
-        // Fetch messages (global, not per-student)
-        let should_refresh_messages = force || cache.get_messages()
-            .map(|(_, _, expired)| expired)
-            .unwrap_or(true);
+        if let Ok((notifications, info)) = service::fetch_notifications(client, cache, force).await {
+            self.set_notifications(notifications);
+            self.notifications_age = Some(info.as_of());
+        }
 
-        if should_refresh_messages {
-            if let Ok(messages) = self.fetch_messages(client).await {
-                self.messages = messages.clone();
-                self.messages_age = Some("just now".to_string());
-                let _ = cache.save_messages(&messages);
-            }
-        } else if let Some((messages, age, _)) = cache.get_messages() {
+        // Messages are global, not per-student
+        if let Ok((messages, info)) = service::fetch_messages(client, cache, self.selected_folder, force).await {
             self.messages = messages;
-            self.messages_age = Some(age);
+            self.messages_age = Some(info.as_of());
         }
 
         self.last_refresh = Some({
-            let now = OffsetDateTime::now_utc();
+            let now = util::now_local();
             format!("{:02}:{:02}", now.hour(), now.minute())
         });
-        self.loading = false;
-        self.clear_status();
+        self.finish_refresh(&errors);
 
         Ok(())
     }
 
-    async fn fetch_homework(&self, client: &ShkoloClient, student_id: i64) -> anyhow::Result<Vec<Homework>> {
-        let courses_response = client.get_homework_courses(student_id).await?;
-
-        let mut all_homework = Vec::new();
-
-        if let Some(courses) = courses_response.courses {
-            let counts = courses_response.cyc_group_homeworks_count.unwrap_or_default();
-
-            for course in courses {
-                if let Some(cyc_group_id) = course.cyc_group_id {
-                    let count = counts.get(&cyc_group_id.to_string()).copied().unwrap_or(0);
-                    if count == 0 {
-                        continue;
-                    }
-
-                    let subject = course.course_short_name
-                        .or(course.course_name)
-                        .unwrap_or_else(|| "Unknown".to_string());
-
-                    if let Ok(hw_response) = client.get_homework_list(cyc_group_id).await {
-                        if let Some(homeworks) = hw_response.homeworks {
-                            for item in homeworks {
-                                all_homework.push(Homework::from_item(&item, &subject));
-                            }
-                        }
-                    }
-                }
-            }
+    /// Stop the loading state, reporting any partial failures in the status bar
+    fn finish_refresh(&mut self, errors: &[String]) {
+        self.loading = false;
+        if errors.is_empty() {
+            self.clear_status();
+        } else {
+            self.set_status(format!("{} {}", T::incomplete_data(self.lang), errors.join("; ")));
         }
-
-        // Sort by date, newest first
-        all_homework.sort_by(|a, b| {
-            b.date_sort.cmp(&a.date_sort)
-        });
-
-        Ok(all_homework)
     }
 
-    async fn fetch_grades(&self, client: &ShkoloClient, student_id: i64) -> anyhow::Result<Vec<Grade>> {
-        let response = client.get_grades_summary(student_id).await?;
-
-        let courses = response.grades.or(response.courses).unwrap_or_default();
-        let grades: Vec<Grade> = courses
-            .iter()
-            .map(Grade::from_course_grades)
-            .filter(|g| g.has_grades())
-            .collect();
-
-        Ok(grades)
+    /// Toggle the help overlay
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+        self.help_filter = None;
     }
+}
 
-    async fn fetch_schedule(&self, client: &ShkoloClient, student_id: i64, date: &str) -> anyhow::Result<Vec<ScheduleHour>> {
-        let response = client.get_pupil_schedule(student_id, date).await?;
-
-        let hours = response.schedule_hours.or(response.data).unwrap_or_default();
-        let mut schedule: Vec<ScheduleHour> = hours
-            .iter()
-            .map(ScheduleHour::from_raw)
-            .collect();
-
-        // Sort by hour number
-        schedule.sort_by_key(|h| h.hour_number);
-
-        Ok(schedule)
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    async fn fetch_notifications(&self, client: &ShkoloClient) -> anyhow::Result<Vec<Notification>> {
-        let response = client.get_notifications(1).await?;
-
-        let raw = response.data.or(response.notifications).unwrap_or_default();
-        let notifications: Vec<Notification> = raw.iter()
-            .map(Notification::from_raw)
-            .collect();
+/// Default `overview_homework_count`
+pub const DEFAULT_OVERVIEW_HOMEWORK_COUNT: usize = 5;
 
-        Ok(notifications)
-    }
+/// Values `overview_homework_count` can take
+pub const OVERVIEW_HOMEWORK_COUNTS: std::ops::RangeInclusive<usize> = 1..=20;
 
-    async fn fetch_absences(&self, client: &ShkoloClient, student_id: i64) -> anyhow::Result<Vec<Absence>> {
-        let response = client.get_absences(student_id).await?;
+/// Columns the content keeps before the students pane is collapsed
+pub const MIN_CONTENT_WIDTH: u16 = 50;
 
-        let mut absences: Vec<Absence> = response.absences
-            .unwrap_or_default()
-            .iter()
-            .map(Absence::from_raw)
-            .collect();
+/// Width of the collapsed students pane: borders around rows like ">1М"
+pub const COMPACT_STUDENTS_WIDTH: u16 = 5;
 
-        // Stable sort: by date (newest first), then by hour, then by subject for ties
-        absences.sort_by(|a, b| {
-            b.date_sort.cmp(&a.date_sort)
-                .then_with(|| a.hour.cmp(&b.hour))
-                .then_with(|| a.subject.cmp(&b.subject))
-        });
+/// How long a tab must be on screen before its new items count as seen
+pub const SEEN_AFTER: Duration = Duration::from_secs(2);
 
-        Ok(absences)
-    }
+/// Default `double_click_interval`, in milliseconds
+pub const DEFAULT_DOUBLE_CLICK_MS: u64 = 400;
 
-    async fn fetch_feedbacks(&self, client: &ShkoloClient, student_id: i64) -> anyhow::Result<Vec<Feedback>> {
-        let response = client.get_feedbacks(student_id).await?;
+/// Default `keep_alive_interval`, in minutes
+pub const DEFAULT_KEEP_ALIVE_MINUTES: u64 = 30;
 
-        let mut feedbacks: Vec<Feedback> = response.data
-            .or(response.feedbacks)
-            .unwrap_or_default()
-            .iter()
-            .map(Feedback::from_raw)
-            .collect();
+/// A count with no motion after it for this long is dropped; a lone digit
+/// then selects that tab
+pub const COUNT_TIMEOUT: Duration = Duration::from_secs(1);
 
-        // Stable sort: by date (newest first), then by subject for ties
-        feedbacks.sort_by(Feedback::cmp_by_date);
+/// Largest count a motion takes; more digits are ignored
+const MAX_COUNT: usize = 9999;
 
-        Ok(feedbacks)
-    }
-
-    pub async fn fetch_messages(&self, client: &ShkoloClient) -> anyhow::Result<Vec<MessageThread>> {
-        let raw_threads = client.get_messenger_threads(None).await?;
+/// Result of clicking on a list item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickResult {
+    None,
+    StudentSelected,
+    Select(usize),               // Item index clicked once: focus only
+    ActivateNotification(usize), // Notification index to activate
+    ActivateMessage(usize),      // Message index to open
+    Folder(Option<i64>),         // Folder selector entry: show that folder's threads
+    Setting(SettingsEntry),      // Settings row to act on, as its key would
+}
 
-        let messages: Vec<MessageThread> = raw_threads.iter()
-            .map(MessageThread::from_raw)
-            .collect();
+/// One row of the Settings tab. `draw_settings` renders `App::settings_entries`
+/// and clicks map back through the same list, so rows and actions can't drift apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsEntry {
+    AccountHeading,
+    LoggedInAs,
+    Logout,
+    LoginHeading,
+    LoginPassword,
+    LoginGoogle,
+    ImportToken,
+    ImportTokenDesc,
+    Separator,
+    Language,
+    AutoRefresh,
+    ShowWeekends,
+    WeekStart,
+    ThreadOrder,
+    Ascii,
+    OverviewHomework,
+    Blank,
+}
 
-        Ok(messages)
+impl SettingsEntry {
+    /// Entries with a key binding on the Settings tab
+    const KEYED: [SettingsEntry; 8] = [
+        SettingsEntry::Logout,
+        SettingsEntry::Language,
+        SettingsEntry::AutoRefresh,
+        SettingsEntry::ShowWeekends,
+        SettingsEntry::WeekStart,
+        SettingsEntry::ThreadOrder,
+        SettingsEntry::Ascii,
+        SettingsEntry::OverviewHomework,
+    ];
+
+    /// Key shown in brackets before the label
+    pub fn key(self) -> Option<char> {
+        match self {
+            SettingsEntry::Logout => Some('L'),
+            SettingsEntry::LoginPassword => Some('1'),
+            SettingsEntry::LoginGoogle => Some('2'),
+            SettingsEntry::ImportToken => Some('3'),
+            SettingsEntry::Language => Some('G'),
+            SettingsEntry::AutoRefresh => Some('A'),
+            SettingsEntry::ShowWeekends => Some('W'),
+            // 'S' is the student switcher everywhere
+            SettingsEntry::WeekStart => Some('D'),
+            SettingsEntry::ThreadOrder => Some('O'),
+            SettingsEntry::Ascii => Some('C'),
+            // '-' counts it down; see handle_key
+            SettingsEntry::OverviewHomework => Some('+'),
+            _ => None,
+        }
     }
 
-    /// Toggle the help overlay
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    /// The entry a key press on the Settings tab acts on. The login numbers
+    /// stay tab shortcuts; those rows only respond to clicks.
+    pub fn from_key(c: char) -> Option<Self> {
+        Self::KEYED.into_iter().find(|e| e.key() == Some(c.to_ascii_uppercase()))
     }
-}
 
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
+    /// CLI command that performs a login option; the TUI can't prompt for it
+    pub fn login_command(self) -> Option<&'static str> {
+        match self {
+            SettingsEntry::LoginPassword => Some("shkolo login"),
+            SettingsEntry::LoginGoogle => Some("shkolo login-google"),
+            SettingsEntry::ImportToken => Some("shkolo import-token"),
+            _ => None,
+        }
     }
-}
 
-/// Result of clicking on a list item
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ClickResult {
-    None,
-    StudentSelected,
-    ItemSelected(usize),         // Item index selected
-    ActivateNotification(usize), // Notification index to activate
-    ActivateMessage(usize),      // Message index to open
+    /// Whether clicking the row does anything
+    pub fn is_actionable(self) -> bool {
+        Self::KEYED.contains(&self) || self.login_command().is_some()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::fake::FakeApi;
+    use crate::cache::TempCache;
+
+    #[test]
+    fn test_login_required_until_token_found() {
+        let mut app = App::new();
+        app.user_name = Some("Иван Иванов".into());
+        app.require_login();
+        assert!(app.needs_login);
+        assert_eq!(app.current_tab, Tab::Settings);
+        let entries = app.settings_entries();
+        assert!(entries.contains(&SettingsEntry::LoginPassword) && !entries.contains(&SettingsEntry::Logout));
+
+        app.logged_in();
+        assert!(!app.needs_login && app.loading);
+        assert_eq!(app.current_tab, Tab::Overview);
+        assert_eq!(app.status_message.as_deref(), Some(T::logged_in_loading(app.lang)));
+    }
 
     #[test]
     fn test_calculate_scroll_center_biased() {
@@ -1518,7 +2686,7 @@ mod tests {
         // Normal case: selected item should be centered
         // 100 items, 10 visible, select item 50 -> scroll to ~45
         let scroll = calculate_scroll(50, 10, 100);
-        assert!(scroll >= 43 && scroll <= 47, "scroll={} should center item 50 in 10-item view", scroll);
+        assert!((43..=47).contains(&scroll), "scroll={} should center item 50 in 10-item view", scroll);
 
         // Near start: selected near beginning shouldn't scroll much
         assert_eq!(calculate_scroll(2, 10, 100), 0);
@@ -1574,6 +2742,44 @@ mod tests {
         assert_eq!(app.students_pane_width, 60); // Clamped to max
     }
 
+    #[test]
+    fn test_effective_students_width_across_terminal_widths() {
+        let mut app = App::new();
+        app.current_tab = Tab::Homework;
+        app.students_pane_width = 30;
+        app.students = vec![
+            StudentData::new(Student { id: 1, name: "Alice".into(), class_name: None, school_name: None }),
+            StudentData::new(Student { id: 2, name: "Bob".into(), class_name: None, school_name: None }),
+        ];
+
+        let widths: Vec<u16> = [0, 200, 80, 79, 60, 55, 54, 40]
+            .into_iter()
+            .map(|w| app.effective_students_width_for(w))
+            .collect();
+        assert_eq!(widths, vec![30, 30, 30, COMPACT_STUDENTS_WIDTH, COMPACT_STUDENTS_WIDTH, COMPACT_STUDENTS_WIDTH, 0, 0]);
+
+        // Collapsing only changes the effective width; growing back restores it
+        app.terminal_width = 60;
+        assert!(app.students_pane_compact());
+        assert_eq!(app.students_pane_width, 30);
+        app.terminal_width = 40;
+        assert!(!app.has_students_pane());
+        app.terminal_width = 120;
+        assert_eq!(app.effective_students_width(), 30);
+        assert!(!app.students_pane_compact());
+
+        // A narrower configured pane stays full on a smaller terminal
+        app.students_pane_width = 20;
+        assert_eq!(app.effective_students_width_for(70), 20);
+
+        // No pane on global tabs or with a single student, whatever the width
+        app.current_tab = Tab::Messages;
+        assert_eq!(app.effective_students_width_for(200), 0);
+        app.current_tab = Tab::Homework;
+        app.students.truncate(1);
+        assert_eq!(app.effective_students_width_for(200), 0);
+    }
+
     #[test]
     fn test_overview_split_resize() {
         let mut app = App::new();
@@ -1725,39 +2931,243 @@ mod tests {
             StudentData::new(Student { id: 2, name: "Student 2".to_string(), class_name: None, school_name: None }),
         ];
 
-        // Try to select beyond bounds - should be ignored
-        app.selected_student = 0;
-        app.select_student(5);
-        assert_eq!(app.selected_student, 0); // Unchanged (invalid index ignored)
+        // Try to select beyond bounds - should be ignored
+        app.selected_student = 0;
+        app.select_student(5);
+        assert_eq!(app.selected_student, 0); // Unchanged (invalid index ignored)
+
+        // prev_student wraps around to last student
+        app.selected_student = 0;
+        app.prev_student();
+        assert_eq!(app.selected_student, 1); // Wraps to last (index 1)
+    }
+
+    #[test]
+    fn test_help_toggle() {
+        let mut app = App::new();
+        assert!(!app.show_help);
+
+        app.toggle_help();
+        assert!(app.show_help);
+
+        app.toggle_help();
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn test_schedule_date_navigation() {
+        let mut app = App::new();
+        app.schedule_date = "2026-02-19".to_string();
+
+        app.schedule_next_day();
+        assert_eq!(app.schedule_date, "2026-02-20");
+
+        app.schedule_prev_day();
+        assert_eq!(app.schedule_date, "2026-02-19");
+    }
+
+    #[test]
+    fn test_adjacent_schedule_days_skip_weekends() {
+        let mut app = App::new();
+        app.schedule_date = "2026-02-20".to_string();
+        assert_eq!(app.adjacent_schedule_days(), ["2026-02-19", "2026-02-23"]);
+        app.show_weekends = true;
+        assert_eq!(app.adjacent_schedule_days(), ["2026-02-19", "2026-02-21"]);
+        app.schedule_date = "не е дата".to_string();
+        assert!(app.adjacent_schedule_days().is_empty());
+    }
+
+    #[test]
+    fn test_student_alerts() {
+        let mut data = StudentData::new(Student { id: 1, name: "Мария Иванова".into(), class_name: None, school_name: None });
+        assert_eq!(data.alerts("2026-02-19", &[]), None);
+
+        let homework = |due: &str| Homework {
+            id: None,
+            subject: "Математика".into(),
+            text: String::new(),
+            date: String::new(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: Some(due.to_string()),
+            links: Vec::new(),
+            raw_html: None,
+        };
+        data.homework = vec![homework("2026-02-19"), homework("2026-02-19"), homework("2026-02-20")];
+        data.homework_age = Some(1_771_500_000);
+        data.absences = vec![
+            Absence { id: "1".into(), date: "18.02.2026".into(), date_sort: "2026-02-18".into(), hour: 1, subject: "БЕЛ".into(), is_excused: false, excuse_reason: None, created_by: None, created_at: None },
+            Absence { id: "2".into(), date: "18.02.2026".into(), date_sort: "2026-02-18".into(), hour: 2, subject: "БЕЛ".into(), is_excused: true, excuse_reason: None, created_by: None, created_at: None },
+        ];
+
+        let notification = |date: &str, slug: &str, pupil: &str| Notification {
+            id: None,
+            title: "Нова оценка".into(),
+            body: None,
+            date: date.to_string(),
+            is_read: false,
+            notification_type: Some(slug.to_string()),
+            pupil_names: Some(pupil.to_string()),
+            target: Default::default(),
+        };
+        let notifications = vec![
+            notification("2026-02-17 10:00:00", "new_grade", "Мария Иванова"),
+            notification("16.02.2026", "new_grade", "Мария"),
+            notification("2026-02-17 10:00:00", "new_grade", "Петър Иванов"),
+            // Older than a week
+            notification("2026-02-10 10:00:00", "new_grade", "Мария Иванова"),
+        ];
+
+        let alerts = data.alerts("2026-02-19", &notifications).unwrap();
+        assert_eq!(alerts, StudentAlerts {
+            homework_due_today: 2,
+            new_grades_this_week: 2,
+            unexcused_absences: 1,
+            positive_feedbacks: 0,
+            negative_feedbacks: 0,
+        });
+    }
+
+    #[test]
+    fn test_homework_sections_respect_school_day_end() {
+        let mut app = App::new();
+        let mut data = StudentData::new(Student { id: 1, name: "Мария Иванова".into(), class_name: None, school_name: None });
+        data.homework = vec![Homework {
+            id: None,
+            subject: "Математика".into(),
+            text: String::new(),
+            date: String::new(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: Some("2026-02-19".into()),
+            links: Vec::new(),
+            raw_html: None,
+        }];
+        app.students = vec![data];
+        app.current_date = "2026-02-19".to_string();
+        app.current_time = (14, 0);
+
+        // No schedule cached: due today stays pending until 15:00 by default
+        assert_eq!(app.homework_sections().0.len(), 1);
+        app.school_day_end = 13 * 60 + 30;
+        let (pending, past) = app.homework_sections();
+        assert!(pending.is_empty());
+        assert_eq!(past.len(), 1);
+    }
+
+    #[test]
+    fn test_schedule_skips_weekends_across_month_boundaries() {
+        let mut app = App::new();
+        assert!(!app.show_weekends);
+
+        // Friday 30 Jan -> Monday 2 Feb
+        app.schedule_date = "2026-01-30".to_string();
+        app.schedule_next_day();
+        assert_eq!(app.schedule_date, "2026-02-02");
+        assert_eq!(app.schedule_day().unwrap().weekday(), time::Weekday::Monday);
+
+        // Monday 2 Mar -> Friday 27 Feb
+        app.schedule_date = "2026-03-02".to_string();
+        app.schedule_prev_day();
+        assert_eq!(app.schedule_date, "2026-02-27");
+
+        // Friday 31 Dec -> Monday 3 Jan across the year
+        app.schedule_date = "2027-12-31".to_string();
+        app.schedule_next_day();
+        assert_eq!(app.schedule_date, "2028-01-03");
+
+        // Starting on a weekend still lands on a school day
+        app.schedule_date = "2026-02-28".to_string();
+        app.schedule_next_day();
+        assert_eq!(app.schedule_date, "2026-03-02");
+
+        // Whole weeks land on the first school day
+        app.schedule_date = "2026-02-18".to_string();
+        app.schedule_step_week(1);
+        assert_eq!(app.schedule_date, "2026-02-23");
+        app.toggle_week_start();
+        app.schedule_step_week(-1);
+        assert_eq!(app.schedule_date, "2026-02-16");
+
+        app.toggle_show_weekends();
+        app.schedule_date = "2026-01-30".to_string();
+        app.schedule_next_day();
+        assert_eq!(app.schedule_date, "2026-01-31");
+        assert!(util::is_weekend(app.schedule_day().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_load_from_cache_keeps_student_order() {
+        let cache = TempCache::new(None);
 
-        // prev_student wraps around to last student
-        app.selected_student = 0;
-        app.prev_student();
-        assert_eq!(app.selected_student, 1); // Wraps to last (index 1)
+        let students: Vec<Student> = (1..=4)
+            .map(|id| Student { id, name: format!("Student {}", id), class_name: None, school_name: None })
+            .collect();
+        cache.save_students(&students).unwrap();
+        for s in &students {
+            let grade = Grade {
+                subject: format!("Subject {}", s.id),
+                term1_grades: vec!["6".to_string()],
+                term2_grades: Vec::new(),
+                term1_final: None,
+                term2_final: None,
+                annual: None,
+                entries: Vec::new(),
+            };
+            cache.save_grades(s.id, &[grade]).unwrap();
+        }
+
+        let mut app = App::new();
+        app.load_from_cache(&cache).await;
+
+        let ids: Vec<i64> = app.students.iter().map(|d| d.student.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        for d in &app.students {
+            assert_eq!(d.grades[0].subject, format!("Subject {}", d.student.id));
+            assert!(d.grades_age.is_some());
+            // Nothing cached for homework
+            assert!(d.homework_age.is_none());
+        }
     }
 
     #[test]
-    fn test_help_toggle() {
+    fn test_offline_data_age() {
         let mut app = App::new();
-        assert!(!app.show_help);
+        assert_eq!(app.offline_data_age(), None);
 
-        app.toggle_help();
-        assert!(app.show_help);
+        app.notifications_age = Some(1_771_500_000);
+        assert_eq!(app.offline_data_age(), Some(1_771_500_000));
 
-        app.toggle_help();
-        assert!(!app.show_help);
+        // The selected student's data age wins over notifications
+        let mut data = StudentData::new(Student { id: 1, name: "Alice".into(), class_name: None, school_name: None });
+        data.homework_age = Some(1_771_496_400);
+        app.students.push(data);
+        assert_eq!(app.offline_data_age(), Some(1_771_496_400));
     }
 
     #[test]
-    fn test_schedule_date_navigation() {
+    fn test_schedule_date_entry() {
         let mut app = App::new();
         app.schedule_date = "2026-02-19".to_string();
 
-        app.schedule_next_day();
-        assert_eq!(app.schedule_date, "2026-02-20");
+        // Prompt is pre-filled with the viewed date
+        app.start_date_entry();
+        assert_eq!(app.input_mode, InputMode::DateEntry);
+        assert_eq!(app.input_buffer, "2026-02-19");
 
-        app.schedule_prev_day();
+        // Invalid date keeps the prompt open with an error
+        app.input_buffer = "2026-02-30".to_string();
+        assert!(!app.submit_date_entry());
+        assert_eq!(app.input_mode, InputMode::DateEntry);
+        assert!(app.date_entry_error);
         assert_eq!(app.schedule_date, "2026-02-19");
+
+        // Valid date closes the prompt and sets the schedule date
+        app.input_buffer = " 2026-03-05 ".to_string();
+        assert!(app.submit_date_entry());
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(!app.date_entry_error);
+        assert_eq!(app.schedule_date, "2026-03-05");
     }
 
     #[test]
@@ -1769,9 +3179,9 @@ mod tests {
 
         // Add some items so scrolling works
         app.notifications = vec![
-            Notification { id: Some("1".into()), title: "N1".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None },
-            Notification { id: Some("2".into()), title: "N2".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None },
-            Notification { id: Some("3".into()), title: "N3".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None },
+            Notification { id: Some("1".into()), title: "N1".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None, target: Default::default() },
+            Notification { id: Some("2".into()), title: "N2".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None, target: Default::default() },
+            Notification { id: Some("3".into()), title: "N3".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None, target: Default::default() },
         ];
 
         assert_eq!(app.list_offset, 0);
@@ -1915,6 +3325,98 @@ mod tests {
         assert!(matches!(result, ClickResult::None));
     }
 
+    #[test]
+    fn test_student_switcher_filters_and_selects_by_id() {
+        use crate::models::student::Student;
+
+        let mut app = App::new();
+        let student = |id, name: &str, class: &str| StudentData::new(Student { id, name: name.into(), class_name: Some(class.into()), school_name: None });
+        app.students = vec![
+            student(7, "Мария Иванова", "5А"),
+            student(3, "Петър Иванов", "2Б"),
+            student(9, "Мартин Петров", "5Б"),
+        ];
+        app.list_offset = 4;
+
+        app.start_student_switch();
+        assert_eq!(app.switcher_matches().len(), 3);
+
+        // Every word has to match, in the name or the class
+        app.input_buffer = "МАР 5б".into();
+        assert_eq!(app.switcher_matches().iter().map(|s| s.id).collect::<Vec<_>>(), vec![9]);
+        app.input_buffer = "иванов".into();
+        assert_eq!(app.switcher_matches().iter().map(|s| s.id).collect::<Vec<_>>(), vec![7, 3]);
+
+        // The highlight stays within the matches and picks the student by id
+        app.switcher_move(true);
+        app.switcher_move(true);
+        assert_eq!(app.switcher_selected, 1);
+        app.submit_student_switch();
+        assert_eq!(app.selected_student, 1);
+        assert_eq!(app.current_student().unwrap().student.id, 3);
+        assert_eq!((app.input_mode, app.list_offset), (InputMode::Normal, 0));
+
+        // No match: the switcher stays open
+        app.start_student_switch();
+        app.input_buffer = "Георги".into();
+        app.submit_student_switch();
+        assert_eq!((app.input_mode, app.selected_student), (InputMode::StudentSwitch, 1));
+    }
+
+    #[test]
+    fn test_click_settings_rows() {
+        let mut app = App::new();
+        app.current_tab = Tab::Settings;
+        // Rows start below the tab bar (3) and the pane border (1)
+        let click = |app: &mut App, row: u16| app.click_list_item(4 + row, 3, 30, 0, 20);
+
+        app.user_name = Some("Иван Иванов".into());
+        assert_eq!(click(&mut app, 0), ClickResult::None); // "Account" heading
+        assert_eq!(click(&mut app, 2), ClickResult::None); // Logged in as
+        assert_eq!(click(&mut app, 4), ClickResult::Setting(SettingsEntry::Logout));
+        assert_eq!(click(&mut app, 8), ClickResult::Setting(SettingsEntry::Language));
+        assert_eq!(click(&mut app, 10), ClickResult::Setting(SettingsEntry::AutoRefresh));
+        assert_eq!(click(&mut app, 16), ClickResult::Setting(SettingsEntry::ThreadOrder));
+        assert_eq!(click(&mut app, 18), ClickResult::Setting(SettingsEntry::Ascii));
+        assert_eq!(click(&mut app, 19), ClickResult::None);
+
+        // Logged out, the login options push the rest down
+        app.user_name = None;
+        assert_eq!(click(&mut app, 4), ClickResult::Setting(SettingsEntry::LoginPassword));
+        assert_eq!(click(&mut app, 6), ClickResult::Setting(SettingsEntry::ImportToken));
+        assert_eq!(click(&mut app, 7), ClickResult::None); // import token description
+        assert_eq!(click(&mut app, 11), ClickResult::Setting(SettingsEntry::Language));
+    }
+
+    #[test]
+    fn test_single_click_only_selects() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.messages = (1..=3).map(|id| MessageThread {
+            id,
+            subject: format!("Тема {}", id),
+            last_message: String::new(),
+            last_sender: String::new(),
+            participant_count: 2,
+            is_unread: false,
+            updated_at: String::new(),
+            creator: String::new(),
+        }).collect();
+        let click = |app: &mut App, row: u16| app.click_list_item(row, 3, 30, 0, 20);
+
+        // Clicks on different rows never pair up
+        assert_eq!(click(&mut app, 5), ClickResult::Select(1));
+        assert_eq!(click(&mut app, 6), ClickResult::Select(2));
+        assert_eq!(click(&mut app, 6), ClickResult::ActivateMessage(2));
+        // The pair is spent: the next click starts over
+        assert_eq!(click(&mut app, 6), ClickResult::Select(2));
+
+        // Too slow for a double click
+        app.double_click_interval = Duration::ZERO;
+        assert_eq!(click(&mut app, 4), ClickResult::Select(0));
+        assert_eq!(click(&mut app, 4), ClickResult::Select(0));
+    }
+
     #[test]
     fn test_click_content_does_not_scroll() {
         let mut app = App::new();
@@ -1923,10 +3425,10 @@ mod tests {
 
         // Setup notifications
         app.notifications = vec![
-            Notification { id: Some("1".into()), title: "N1".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None },
-            Notification { id: Some("2".into()), title: "N2".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_homework".into()), pupil_names: None },
-            Notification { id: Some("3".into()), title: "N3".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None },
-            Notification { id: Some("4".into()), title: "N4".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None },
+            Notification { id: Some("1".into()), title: "N1".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None, target: Default::default() },
+            Notification { id: Some("2".into()), title: "N2".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_homework".into()), pupil_names: None, target: Default::default() },
+            Notification { id: Some("3".into()), title: "N3".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None, target: Default::default() },
+            Notification { id: Some("4".into()), title: "N4".into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None, target: Default::default() },
         ];
 
         let header_offset = 3;
@@ -1937,7 +3439,9 @@ mod tests {
         app.list_offset = 1;
         let initial_offset = app.list_offset;
 
-        // Click on visible item at row 4 (should be index 1 in visible area, so actual item index = 1 + 1 = 2)
+        // Double click on visible item at row 4 (should be index 1 in visible area, so actual item index = 1 + 1 = 2)
+        let result = app.click_list_item(4, header_offset, 30, students_width, content_height);
+        assert!(matches!(result, ClickResult::Select(1)));
         let result = app.click_list_item(4, header_offset, 30, students_width, content_height);
 
         // Click should return the correct index
@@ -1950,6 +3454,71 @@ mod tests {
         assert_eq!(app.focus, Focus::Content);
     }
 
+    #[test]
+    fn test_grade_change_notice() {
+        let grade = |values: &[&str]| Grade {
+            subject: "Математика".into(),
+            term1_grades: values.iter().map(|v| v.to_string()).collect(),
+            term2_grades: Vec::new(),
+            term1_final: None,
+            term2_final: None,
+            annual: None,
+            entries: values.iter().zip(1..).map(|(v, id)| GradeEntry { value: v.to_string(), term: 1, id: Some(id), weight: None }).collect(),
+        };
+        let student = |id, name: &str, values: &[&str]| {
+            let mut data = StudentData::new(Student { id, name: name.into(), class_name: None, school_name: None });
+            data.grades = vec![grade(values)];
+            data
+        };
+
+        let mut app = App::new();
+        app.lang = Lang::En;
+        app.students = vec![student(1, "Мария Иванова", &["3", "6"])];
+        assert_eq!(app.grade_change_notice(&[student(1, "Мария Иванова", &["3", "6", "4"])]), None);
+        assert_eq!(
+            app.grade_change_notice(&[student(1, "Мария Иванова", &["5", "6"])]).as_deref(),
+            Some("Grade changed in Математика: 3 → 5"),
+        );
+
+        app.students.push(student(2, "Петър Иванов", &["2"]));
+        let refreshed = [student(1, "Мария Иванова", &["5"]), student(2, "Петър Иванов", &["2"])];
+        assert_eq!(
+            app.grade_change_notice(&refreshed).as_deref(),
+            Some("Мария: Grade changed in Математика: 3 → 5 (+1)"),
+        );
+    }
+
+    #[test]
+    fn test_notification_expansion_follows_ids() {
+        let notification = |id: &str| Notification { id: Some(id.into()), title: id.into(), body: Some("Body".into()), date: "".into(), is_read: false, notification_type: None, pupil_names: None, target: Default::default() };
+        let mut app = App::new();
+        app.current_tab = Tab::Notifications;
+        app.set_notifications(vec![notification("a"), notification("b"), notification("c")]);
+
+        app.list_offset = 1;
+        app.toggle_notification_expanded();
+        assert!(app.expanded_notifications.contains(&1));
+
+        // A new notification shifts "b" down; its expansion moves with it
+        app.set_notifications(vec![notification("new"), notification("a"), notification("b")]);
+        assert_eq!(app.expanded_notifications, HashSet::from([2]));
+
+        // Gone ids are dropped
+        app.set_notifications(vec![notification("a")]);
+        assert!(app.expanded_notifications.is_empty());
+
+        app.set_notifications(vec![notification("a")]);
+        app.list_offset = 0;
+        app.toggle_notification_expanded();
+        app.toggle_notification_expanded();
+        assert!(app.expanded_notifications.is_empty());
+
+        // Switching tabs resets expansions
+        app.toggle_notification_expanded();
+        app.set_tab(Tab::Grades);
+        assert!(app.expanded_notifications.is_empty());
+    }
+
     #[test]
     fn test_click_sets_focus_on_overview() {
         let mut app = App::new();
@@ -1994,6 +3563,142 @@ mod tests {
         assert_eq!(app.focus, Focus::OverviewGrades);
     }
 
+    #[test]
+    fn test_wheel_scrolls_pane_under_cursor() {
+        let mut app = App::new();
+        app.current_tab = Tab::Overview;
+        app.overview_split_percent = 50;
+        app.overview_bottom_split_percent = 60;
+        let mut student = StudentData::new(Student { id: 1, name: "Alice".into(), class_name: None, school_name: None });
+        student.homework = (0..10).map(|i| Homework {
+            id: Some(i),
+            subject: "Math".into(),
+            text: format!("HW {}", i),
+            date: "20.02.2026".into(),
+            due_date: None,
+            date_sort: None,
+            due_date_sort: None,
+            links: Vec::new(),
+            raw_html: None,
+        }).collect();
+        app.students = vec![
+            student,
+            StudentData::new(Student { id: 2, name: "Bob".into(), class_name: None, school_name: None }),
+        ];
+        app.focus = Focus::OverviewSchedule;
+
+        // Homework area (content row 12) scrolls by several lines and takes focus
+        app.scroll_at(true, 15, 3, 30, 25, 20);
+        assert_eq!(app.focus, Focus::OverviewHomework);
+        assert_eq!(app.homework_offset, WHEEL_SCROLL_LINES);
+        assert_eq!(app.schedule_offset, 0);
+
+        // Offsets stay within the list
+        for _ in 0..10 {
+            app.scroll_at(true, 15, 3, 30, 25, 20);
+        }
+        assert_eq!(app.homework_offset, 9);
+        app.scroll_at(false, 15, 3, 30, 25, 20);
+        assert_eq!(app.homework_offset, 9 - WHEEL_SCROLL_LINES);
+
+        // Over the students pane the wheel moves the selection
+        app.scroll_at(true, 5, 3, 5, 25, 20);
+        assert_eq!(app.selected_student, 1);
+    }
+
+    #[test]
+    fn test_new_items_seen_after_viewing_the_tab() {
+        let mut app = App::new();
+        let mut data = StudentData::new(Student { id: 7, name: "Alice".into(), class_name: None, school_name: None });
+        data.grades = vec![Grade {
+            subject: "Math".into(),
+            term1_grades: vec!["5".into()],
+            term2_grades: vec![],
+            term1_final: None,
+            term2_final: None,
+            annual: None,
+            entries: vec![],
+        }];
+        app.students = vec![data];
+        app.track_new();
+        assert!(app.seen_changed);
+        assert!(app.new_keys(Tab::Grades).is_empty());
+
+        app.students[0].grades[0].term1_grades.push("6".into());
+        app.track_new();
+        assert_eq!(app.new_keys(Tab::Grades).len(), 1);
+        assert!(app.new_keys(Tab::Homework).is_empty());
+
+        // Another tab on screen doesn't count
+        let start = Instant::now();
+        app.seen_changed = false;
+        app.update_seen(start);
+        app.update_seen(start + SEEN_AFTER * 2);
+        assert_eq!(app.new_keys(Tab::Grades).len(), 1);
+
+        // The Grades tab has to stay on screen for SEEN_AFTER
+        app.current_tab = Tab::Grades;
+        app.update_seen(start);
+        app.update_seen(start + SEEN_AFTER / 2);
+        assert_eq!(app.new_keys(Tab::Grades).len(), 1);
+        assert!(!app.seen_changed);
+        app.update_seen(start + SEEN_AFTER);
+        assert!(app.new_keys(Tab::Grades).is_empty());
+        assert!(app.seen_changed);
+    }
+
+    #[test]
+    fn test_keep_alive_due() {
+        let mut app = App::new();
+        let interval = Duration::from_secs(DEFAULT_KEEP_ALIVE_MINUTES * 60);
+        assert!(!app.keep_alive_due(interval - Duration::from_secs(1)));
+        assert!(app.keep_alive_due(interval));
+
+        app.offline = true;
+        assert!(!app.keep_alive_due(interval));
+
+        app.offline = false;
+        app.keep_alive_interval = None;
+        assert!(!app.keep_alive_due(Duration::from_secs(24 * 3600)));
+    }
+
+    #[test]
+    fn test_paste_inserts_at_cursor() {
+        let mut app = App::new();
+        app.input_paste("ignored");
+        assert!(app.input_buffer.is_empty());
+
+        app.input_mode = InputMode::Reply;
+        app.input_buffer = "Здравейте!".into();
+        app.input_cursor = 9;
+        app.input_paste(" госпожо\r\nПетрова");
+        assert_eq!(app.input_buffer, "Здравейте госпожо\nПетрова!");
+        assert_eq!(app.input_cursor, 25);
+        app.input_char('?');
+        assert_eq!(app.input_buffer, "Здравейте госпожо\nПетрова?!");
+
+        // One-line prompts take line breaks and tabs as spaces
+        app.input_mode = InputMode::ComposeSubject;
+        app.input_buffer.clear();
+        app.input_cursor = 0;
+        app.input_paste("Родителска\nсреща\t\u{7}");
+        assert_eq!(app.input_buffer, "Родителска среща ");
+    }
+
+    #[test]
+    fn test_unfocused_window_pauses_background_work() {
+        let mut app = App::new();
+        assert!(!app.background_paused());
+
+        app.set_window_focused(false);
+        assert!(app.background_paused());
+
+        app.current_time = (99, 99);
+        app.set_window_focused(true);
+        assert!(!app.background_paused());
+        assert_ne!(app.current_time, (99, 99));
+    }
+
     #[test]
     fn test_click_notification_activates() {
         let mut app = App::new();
@@ -2001,8 +3706,8 @@ mod tests {
         app.focus = Focus::Content;
 
         app.notifications = vec![
-            Notification { id: Some("1".into()), title: "N1".into(), body: None, date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None },
-            Notification { id: Some("2".into()), title: "N2".into(), body: None, date: "".into(), is_read: false, notification_type: Some("new_homework".into()), pupil_names: None },
+            Notification { id: Some("1".into()), title: "N1".into(), body: None, date: "".into(), is_read: false, notification_type: Some("new_grade".into()), pupil_names: None, target: Default::default() },
+            Notification { id: Some("2".into()), title: "N2".into(), body: None, date: "".into(), is_read: false, notification_type: Some("new_homework".into()), pupil_names: None, target: Default::default() },
         ];
 
         // Activate notification at index 1
@@ -2019,6 +3724,129 @@ mod tests {
         assert_eq!(app.current_tab, Tab::Grades);
     }
 
+    fn targeted(notification_type: &str, pupil_names: Option<&str>, target: NotificationTarget) -> Notification {
+        Notification {
+            id: None,
+            title: "N".into(),
+            body: None,
+            date: "".into(),
+            is_read: false,
+            notification_type: Some(notification_type.into()),
+            pupil_names: pupil_names.map(Into::into),
+            target,
+        }
+    }
+
+    fn deep_link_app() -> App {
+        let mut app = App::new();
+        app.current_date = "2026-02-19".into();
+        app.current_tab = Tab::Notifications;
+        let grade = |subject: &str| Grade {
+            subject: subject.into(),
+            term1_grades: vec!["6".into()],
+            term2_grades: vec![],
+            annual: None,
+            term1_final: None,
+            term2_final: None,
+            entries: vec![],
+        };
+        let homework = |id: i64, due: &str| Homework {
+            id: Some(id),
+            subject: "История".into(),
+            text: format!("HW {}", id),
+            date: "10.02.2026".into(),
+            due_date: None,
+            date_sort: Some("2026-02-10".into()),
+            due_date_sort: Some(due.into()),
+            links: Vec::new(),
+            raw_html: None,
+        };
+        let mut maria = StudentData::new(Student { id: 1001, name: "Мария Иванова".into(), class_name: None, school_name: None });
+        maria.grades = vec![grade("Български език"), grade("Математика")];
+        let mut petar = StudentData::new(Student { id: 1002, name: "Петър Иванов".into(), class_name: None, school_name: None });
+        // Shown as: 9003 (pending), divider, 9002, 9001 (past, newest first)
+        petar.homework = vec![homework(9001, "2026-02-11"), homework(9002, "2026-02-16"), homework(9003, "2026-02-25")];
+        app.students = vec![maria, petar];
+        app.messages = [100, 55001].into_iter().map(|id| MessageThread {
+            id,
+            subject: "".into(),
+            last_message: "".into(),
+            last_sender: "".into(),
+            participant_count: 1,
+            is_unread: false,
+            updated_at: "".into(),
+            creator: "".into(),
+        }).collect();
+        app
+    }
+
+    #[test]
+    fn test_notification_opens_grade_subject_for_student() {
+        let mut app = deep_link_app();
+        app.selected_student = 1;
+        app.notifications = vec![targeted("new_grade", None, NotificationTarget {
+            pupil_id: Some(1001),
+            subject: Some("математика".into()),
+            ..Default::default()
+        })];
+
+        assert!(app.activate_notification_at(0));
+        assert_eq!(app.current_tab, Tab::Grades);
+        assert_eq!(app.selected_student, 0);
+        assert_eq!(app.list_offset, 1);
+    }
+
+    #[test]
+    fn test_notification_opens_homework_in_display_order() {
+        let mut app = deep_link_app();
+        app.notifications = vec![
+            targeted("new_homework", None, NotificationTarget { pupil_id: Some(1002), homework_id: Some(9001), ..Default::default() }),
+            targeted("new_homework", None, NotificationTarget { pupil_id: Some(1002), homework_id: Some(9003), ..Default::default() }),
+        ];
+
+        assert!(app.activate_notification_at(0));
+        assert_eq!(app.current_tab, Tab::Homework);
+        assert_eq!(app.selected_student, 1);
+        assert_eq!(app.list_offset, 3);
+
+        app.current_tab = Tab::Notifications;
+        assert!(app.activate_notification_at(1));
+        assert_eq!(app.list_offset, 0);
+    }
+
+    #[test]
+    fn test_notification_opens_thread() {
+        let mut app = deep_link_app();
+        app.notifications = vec![targeted("new_thread_message", None, NotificationTarget { thread_id: Some(55001), ..Default::default() })];
+
+        assert!(app.activate_notification_at(0));
+        assert_eq!(app.current_tab, Tab::Messages);
+        assert_eq!(app.list_offset, 1);
+    }
+
+    #[test]
+    fn test_notification_without_ids_falls_back() {
+        let mut app = deep_link_app();
+        app.list_offset = 5;
+        app.notifications = vec![
+            // Pupil names matching one student still pick that student
+            targeted("new_homework", Some("Петър Иванов"), NotificationTarget::default()),
+            // Unknown ids keep the student and go to the top of the tab
+            targeted("new_grade", None, NotificationTarget { pupil_id: Some(42), subject: Some("Химия".into()), ..Default::default() }),
+        ];
+
+        assert!(app.activate_notification_at(0));
+        assert_eq!(app.current_tab, Tab::Homework);
+        assert_eq!(app.selected_student, 1);
+        assert_eq!(app.list_offset, 0);
+
+        app.current_tab = Tab::Notifications;
+        assert!(app.activate_notification_at(1));
+        assert_eq!(app.current_tab, Tab::Grades);
+        assert_eq!(app.selected_student, 1);
+        assert_eq!(app.list_offset, 0);
+    }
+
     #[test]
     fn test_click_message_opens_thread() {
         let mut app = App::new();
@@ -2258,4 +4086,509 @@ mod tests {
         app.go_back();
         assert_eq!(app.current_tab, Tab::Overview);
     }
+
+    #[test]
+    fn test_feedback_filters_limit_list_length() {
+        let feedback = |id: i64, is_positive: bool, subject: &str| Feedback {
+            id,
+            badge_name: "Badge".into(),
+            badge_icon: None,
+            comment: None,
+            is_positive,
+            date: "19.02.2026".into(),
+            teacher: "Петрова".into(),
+            subject: subject.into(),
+        };
+        let mut app = App::new();
+        let mut data = StudentData::new(Student { id: 1, name: "Alice".into(), class_name: None, school_name: None });
+        data.feedbacks = vec![
+            feedback(1, true, "Математика"),
+            feedback(2, false, "Математика"),
+            feedback(3, true, "Английски език"),
+        ];
+        app.students = vec![data];
+        app.current_tab = Tab::Feedbacks;
+        assert_eq!(app.current_list_length(), 3);
+
+        app.list_offset = 2;
+        app.set_feedback_filter(FeedbackFilter::Positive);
+        assert_eq!(app.list_offset, 0);
+        assert_eq!(app.current_list_length(), 2);
+
+        app.set_feedback_filter(FeedbackFilter::Negative);
+        assert_eq!(app.current_list_length(), 1);
+        assert_eq!(app.visible_feedbacks()[0].id, 2);
+
+        // Subject filter is case-insensitive and combines with the sign filter
+        app.set_feedback_filter(FeedbackFilter::All);
+        app.start_feedback_subject_entry();
+        for c in "матем".chars() {
+            app.input_char(c);
+        }
+        app.submit_feedback_subject();
+        assert_eq!(app.feedback_subject.as_deref(), Some("матем"));
+        assert_eq!(app.current_list_length(), 2);
+        app.set_feedback_filter(FeedbackFilter::Positive);
+        assert_eq!(app.current_list_length(), 1);
+
+        // Submitting an empty subject clears it
+        app.start_feedback_subject_entry();
+        while !app.input_buffer.is_empty() {
+            app.input_backspace();
+        }
+        app.submit_feedback_subject();
+        assert_eq!(app.feedback_subject, None);
+        assert_eq!(app.current_list_length(), 2);
+
+        // Scrolling stops at the end of the filtered list
+        for _ in 0..5 {
+            app.scroll_down();
+        }
+        assert_eq!(app.list_offset, 1);
+    }
+
+    #[test]
+    fn test_input_editing_counts_characters() {
+        let mut app = App::new();
+        app.input_mode = InputMode::Reply;
+        for c in "Здр".chars() {
+            app.input_char(c);
+        }
+        app.input_left();
+        app.input_char('а');
+        assert_eq!(app.input_buffer, "Здар");
+        assert_eq!(app.input_cursor, 3);
+        app.input_delete();
+        app.input_backspace();
+        assert_eq!(app.input_buffer, "Зд");
+        app.input_right();
+        app.input_right();
+        assert_eq!(app.input_cursor, 2);
+    }
+
+    #[test]
+    fn test_grades_timeline_newest_first_across_subjects() {
+        let course = |json: serde_json::Value| -> CourseGrades { serde_json::from_value(json).unwrap() };
+        let math = course(serde_json::json!({
+            "course_name": "Математика",
+            "term1": { "12": { "grade": "5" }, "3": { "grade": "6" } },
+            "term2": { "40": { "grade": "4" } },
+        }));
+        let music = course(serde_json::json!({
+            "course_name": "Музика",
+            "term1": { "20": { "grade": "3" } },
+            "term2": [{ "grade": "6" }],
+        }));
+        let mut data = StudentData::new(Student { id: 1, name: "Alice".into(), class_name: None, school_name: None });
+        data.grades = vec![Grade::from_course_grades(&math), Grade::from_course_grades(&music)];
+
+        // Map entries come out in id order
+        assert_eq!(data.grades[0].term1_grades, vec!["6", "5"]);
+
+        let timeline: Vec<(&str, &str, u8)> = data.grades_timeline()
+            .iter()
+            .map(|g| (g.subject, g.value, g.term))
+            .collect();
+        assert_eq!(timeline, vec![
+            ("Математика", "4", 2),
+            ("Музика", "6", 2),
+            ("Музика", "3", 1),
+            ("Математика", "5", 1),
+            ("Математика", "6", 1),
+        ]);
+    }
+
+    #[test]
+    fn test_grades_timeline_toggle_sets_list_length() {
+        let mut app = App::new();
+        let mut data = StudentData::new(Student { id: 1, name: "Alice".into(), class_name: None, school_name: None });
+        data.grades = vec![Grade {
+            subject: "Математика".into(),
+            term1_grades: vec!["6".into(), "5".into()],
+            term2_grades: vec!["4".into()],
+            term1_final: None,
+            term2_final: None,
+            annual: None,
+            entries: Vec::new(),
+        }];
+        app.students = vec![data];
+        app.current_tab = Tab::Grades;
+        assert_eq!(app.current_list_length(), 1);
+
+        app.toggle_grades_timeline();
+        assert_eq!(app.current_list_length(), 3);
+        // Cached grades without entries keep their per-term order
+        assert_eq!(app.students[0].grades_timeline()[0].value, "4");
+        assert_eq!(app.students[0].grades_timeline()[1].value, "5");
+    }
+
+    #[test]
+    fn test_compose_draft_saved_and_resumed() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.start_compose();
+        app.selected_recipients = vec![7, 9];
+        app.compose_subject = "Отсъствие".into();
+        app.input_mode = InputMode::ComposeBody;
+        app.input_buffer = "Здравейте,".into();
+
+        // Closing with "s" keeps all fields, including the body being typed
+        app.request_close_editor();
+        app.answer_save_draft();
+        assert_eq!(app.message_view, MessageView::List);
+        assert!(app.drafts_changed);
+        assert_eq!(app.drafts.get(None), Some(&Draft {
+            thread_id: None,
+            subject: "Отсъствие".into(),
+            body: "Здравейте,".into(),
+            recipients: vec![7, 9],
+        }));
+
+        assert!(app.resume_draft());
+        assert_eq!(app.message_view, MessageView::Compose);
+        assert_eq!(app.selected_recipients, vec![7, 9]);
+        assert_eq!(app.compose_subject, "Отсъствие");
+        assert_eq!((app.input_mode, app.input_buffer.as_str(), app.input_cursor), (InputMode::ComposeBody, "Здравейте,", 10));
+
+        // Discarding explicitly drops the draft
+        app.request_close_editor();
+        app.answer_discard(true);
+        assert_eq!(app.drafts.get(None), None);
+        assert!(!app.resume_draft());
+    }
+
+    #[test]
+    fn test_thread_opens_at_latest_in_either_order() {
+        let message = |id: i64| Message {
+            id,
+            body: format!("Съобщение {}", id),
+            sender_id: 5,
+            sender_name: "Петрова".into(),
+            date: "17.02.2026 12:00".into(),
+            is_system: false,
+        };
+        let ids = |app: &App| app.thread_messages.iter().map(|m| m.id).collect::<Vec<_>>();
+        let mut app = App::new();
+
+        app.set_thread(ThreadContents { messages: vec![message(1), message(2), message(3)], ..Default::default() }, true);
+        assert_eq!((ids(&app), app.thread_offset), (vec![1, 2, 3], 2));
+        app.set_thread(ThreadContents { messages: vec![message(1), message(2), message(3)], ..Default::default() }, false);
+        assert_eq!(app.thread_offset, 0);
+
+        // Flipping the order keeps the selected message
+        app.thread_offset = 1;
+        app.toggle_thread_order();
+        assert_eq!((ids(&app), app.thread_offset), (vec![3, 2, 1], 1));
+        app.thread_offset = 2;
+        app.toggle_thread_order();
+        assert_eq!(app.thread_messages[app.thread_offset].id, 1);
+
+        app.thread_newest_first = true;
+        app.set_thread(ThreadContents { messages: vec![message(1), message(2), message(3), message(4)], ..Default::default() }, true);
+        assert_eq!((ids(&app), app.thread_offset), (vec![4, 3, 2, 1], 0));
+    }
+
+    #[test]
+    fn test_compose_from_thread_preselects_participants() {
+        let participant = |id: i64, name: &str| Participant { id, name: name.into(), role: String::new() };
+        let recipient = |id: i64, name: &str| Recipient { id, name: name.into(), role: String::new() };
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.user_id = Some(7);
+        app.messages = vec![MessageThread {
+            id: 42, subject: "Екскурзия".into(), last_message: "".into(), last_sender: "".into(),
+            participant_count: 3, is_unread: false, updated_at: "".into(), creator: "".into(),
+        }];
+        app.message_view = MessageView::Thread;
+        app.selected_thread_id = Some(42);
+        app.thread_participants = vec![participant(5, "Петрова"), participant(7, "Иванов"), participant(9, "Димитров")];
+
+        // Everyone but this account, replying to the thread's subject
+        app.start_compose_from_thread(42);
+        assert_eq!(app.message_view, MessageView::Compose);
+        assert_eq!((app.selected_recipients.clone(), app.compose_subject.as_str()), (vec![5, 9], "Re: Екскурзия"));
+
+        // Recipients this account can't write to are dropped once the list loads
+        app.set_recipients(vec![recipient(5, "Петрова"), recipient(11, "Директор")]);
+        assert_eq!(app.selected_recipients, vec![5]);
+        app.toggle_recipient(0);
+        assert!(app.selected_recipients.is_empty());
+
+        // Without participants, the senders in the thread; "Re: " isn't doubled
+        app.thread_participants.clear();
+        app.messages[0].subject = "Re: Екскурзия".into();
+        app.thread_messages = [5, 7, 5, 0].iter().map(|&sender_id| Message {
+            id: 1, body: "".into(), sender_id, sender_name: "".into(), date: "".into(), is_system: false,
+        }).collect();
+        app.start_compose_from_thread(42);
+        assert_eq!((app.selected_recipients.clone(), app.compose_subject.as_str()), (vec![5], "Re: Екскурзия"));
+    }
+
+    #[test]
+    fn test_reply_drafts_kept_per_thread() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.selected_thread_id = Some(42);
+        app.start_reply();
+        app.input_buffer = "Благодаря".into();
+        app.save_draft();
+        app.cancel_input();
+
+        // Another thread starts empty; the first picks its draft back up
+        app.selected_thread_id = Some(43);
+        app.start_reply();
+        assert!(app.input_buffer.is_empty());
+        app.cancel_input();
+        app.selected_thread_id = Some(42);
+        app.start_reply();
+        assert_eq!((app.input_buffer.as_str(), app.input_cursor), ("Благодаря", 9));
+
+        // Without subject or recipients a resumed draft starts at recipient selection
+        app.drafts.set(Draft { body: "Текст".into(), ..Default::default() });
+        app.message_view = MessageView::List;
+        assert!(app.resume_draft());
+        assert_eq!((app.input_mode, app.compose_body.as_str()), (InputMode::Normal, "Текст"));
+
+        app.remove_draft(Some(42));
+        assert_eq!(app.drafts.get(Some(42)), None);
+        assert!(app.drafts.get(None).is_some());
+    }
+
+    #[test]
+    fn test_folder_selector_cycles_and_clicks() {
+        let folder = |id: i64, name: &str, slug: Option<&str>, unread: i32| MessageFolder {
+            id,
+            name: name.into(),
+            system_folder_slug: slug.map(str::to_string),
+            folder_total_count: 10,
+            folder_unread_count: unread,
+        };
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.focus = Focus::Content;
+        app.messages = (1..=3).map(|id| MessageThread {
+            id,
+            subject: format!("Тема {}", id),
+            last_message: String::new(),
+            last_sender: String::new(),
+            participant_count: 2,
+            is_unread: id == 1,
+            updated_at: String::new(),
+            creator: String::new(),
+        }).collect();
+        assert!(!app.shows_folder_row());
+        assert!(!app.cycle_folder());
+        assert_eq!(app.unread_in_folder(), 1);
+
+        app.message_folders = vec![folder(5, "Изпратени", Some("sent"), 0), folder(4, "Входящи", Some("inbox"), 3)];
+        assert_eq!(app.folder_choices(), vec![(None, "Входящи (3)".to_string()), (Some(5), "Изпратени".to_string())]);
+        assert_eq!(app.unread_in_folder(), 3);
+
+        // 'f' wraps around; switching drops the other folder's threads
+        assert!(app.cycle_folder());
+        assert_eq!((app.selected_folder, app.messages.len()), (Some(5), 0));
+        assert!(app.cycle_folder());
+        assert_eq!(app.selected_folder, None);
+
+        // Row 0 of the list is the selector: " Входящи (3) │ Изпратени"
+        let students_width = 0;
+        assert_eq!(app.click_list_item(4, 3, 3, students_width, 20), ClickResult::Folder(None));
+        assert_eq!(app.click_list_item(4, 3, 18, students_width, 20), ClickResult::Folder(Some(5)));
+        assert_eq!(app.click_list_item(4, 3, 14, students_width, 20), ClickResult::None);
+        assert!(!app.select_folder(None));
+        assert!(app.select_folder(Some(5)));
+    }
+
+    /// Every endpoint a refresh asks, answered from the API fixtures
+    fn refresh_api() -> FakeApi {
+        FakeApi::default()
+            .with("pupils", include_str!("../api/fixtures/pupils.json"))
+            .with("homework_courses", include_str!("../api/fixtures/homework_courses.json"))
+            .with("homework_list/9001", include_str!("../api/fixtures/homework_list.json"))
+            .with("grades_summary", include_str!("../api/fixtures/grades_summary.json"))
+            .with("schedule/2026-02-19", include_str!("../api/fixtures/schedule.json"))
+            .with("events", r#"{ "invitations": [{ "id": 1, "title": "Класна работа", "start_date": "2026-02-24", "type": 13 }] }"#)
+            .with("absences", include_str!("../api/fixtures/absences.json"))
+            .with("feedbacks", include_str!("../api/fixtures/feedbacks.json"))
+            .with("notifications", include_str!("../api/fixtures/notifications.json"))
+            .with("messenger_threads", include_str!("../api/fixtures/messenger_threads.json"))
+    }
+
+    fn refresh_app() -> App {
+        let mut app = App::new();
+        app.current_date = "2026-02-19".to_string();
+        app
+    }
+
+    #[tokio::test]
+    async fn test_refresh_reports_partial_failures() {
+        let cache = TempCache::new(Some(3600));
+        let api = refresh_api().without("absences").without("events");
+        let mut app = refresh_app();
+
+        app.refresh_data(&api, &cache, false, false).await.unwrap();
+        assert!(!app.loading);
+        assert_eq!(app.students.len(), 2);
+        let maria = &app.students[0];
+        assert!(!maria.homework.is_empty() && !maria.grades.is_empty() && !maria.schedule.is_empty());
+        assert!(maria.absences.is_empty() && maria.absences_age.is_none());
+        assert!(!app.notifications.is_empty() && !app.messages.is_empty());
+
+        // Failed absences are reported for each student; events fail quietly
+        let status = app.status_message.clone().unwrap();
+        assert!(status.contains("Мария Иванова: absences unavailable"), "{}", status);
+        assert!(status.contains("Петър Иванов: absences unavailable"), "{}", status);
+        assert!(!status.contains("events"), "{}", status);
+    }
+
+    #[tokio::test]
+    async fn test_failed_category_keeps_its_own_status() {
+        let cache = TempCache::new(Some(3600));
+        let mut app = refresh_app();
+
+        app.refresh_data(&refresh_api().without("grades_summary"), &cache, false, false).await.unwrap();
+        let maria = &app.students[0];
+        assert!(matches!(maria.grades_status, LoadStatus::Failed(_)));
+        assert_eq!(maria.homework_status, LoadStatus::Loaded);
+        assert_eq!(maria.schedule_status, LoadStatus::Loaded);
+        assert_eq!(maria.absences_status, LoadStatus::Loaded);
+
+        // Only the tabs showing grades have an error to explain
+        app.current_tab = Tab::Homework;
+        assert_eq!(app.load_error(), None);
+        app.current_tab = Tab::Grades;
+        assert!(app.load_error().is_some_and(|e| e.contains("grades_summary")));
+        app.show_load_error();
+        assert!(app.status_message.as_deref().is_some_and(|m| m.contains("grades_summary")));
+
+        // With grades in the cache, a failed refresh keeps showing them
+        app.refresh_data(&refresh_api(), &cache, true, false).await.unwrap();
+        assert_eq!(app.students[0].grades_status, LoadStatus::Loaded);
+        app.refresh_data(&refresh_api().without("grades_summary"), &cache, true, false).await.unwrap();
+        assert_eq!(app.students[0].grades_status, LoadStatus::Stale);
+        assert!(!app.students[0].grades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compose_loads_recipients() {
+        let cache = TempCache::new(Some(3600));
+        let api = FakeApi::default().with("recipients", r#"[
+            { "id": 5, "names": "Елена Петрова", "roles_name": "Класен ръководител" },
+            { "id": 7, "name": "Иван Георгиев", "roles_name": "Учител" }
+        ]"#);
+        let mut app = App::new();
+
+        // A failure keeps compose open with the error shown
+        app.start_compose();
+        app.load_recipients(&FakeApi::default(), &cache, false).await;
+        assert_eq!(app.message_view, MessageView::Compose);
+        assert!(app.recipients.is_empty() && !app.loading);
+        assert!(app.error_message.as_deref().is_some_and(|e| e.contains("recipients")));
+
+        app.load_recipients(&api, &cache, true).await;
+        let names: Vec<&str> = app.recipients.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Елена Петрова", "Иван Георгиев"]);
+        assert_eq!(app.recipients[0].role, "Класен ръководител");
+
+        // The next compose is served from the cache
+        let mut again = App::new();
+        again.start_compose();
+        again.load_recipients(&api, &cache, false).await;
+        assert_eq!(again.recipients.len(), 2);
+        assert_eq!(api.calls(), vec!["recipients"]);
+    }
+
+    #[tokio::test]
+    async fn test_students_are_fetched_side_by_side() {
+        // Fetched one after another, never more than one request would be waiting
+        let api = refresh_api().slow();
+        let cache = TempCache::new(Some(3600));
+        let mut app = refresh_app();
+        app.refresh_data(&api, &cache, false, false).await.unwrap();
+        assert_eq!(api.max_in_flight(), 2);
+        assert!(app.students.iter().all(|s| !s.homework.is_empty() && !s.grades.is_empty()));
+
+        // A cold start fetches the first student alone and leaves the rest to later
+        let api = refresh_api().slow();
+        let cache = TempCache::new(Some(3600));
+        let mut app = refresh_app();
+        app.refresh_data(&api, &cache, false, true).await.unwrap();
+        assert_eq!(api.max_in_flight(), 1);
+        assert!(!app.students[0].grades.is_empty());
+        assert!(app.students[1].grades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_uses_cache_unless_forced() {
+        let cache = TempCache::new(Some(3600));
+        let api = refresh_api();
+        let mut app = refresh_app();
+
+        app.refresh_data(&api, &cache, false, false).await.unwrap();
+        assert_eq!(app.status_message, None);
+        let fetched = api.calls().len();
+        assert!(api.calls().contains(&"absences".to_string()));
+
+        // Everything is fresh in the cache: no requests
+        let mut again = refresh_app();
+        again.refresh_data(&api, &cache, false, false).await.unwrap();
+        assert_eq!(api.calls().len(), fetched);
+        assert_eq!(again.students[1].grades.len(), app.students[1].grades.len());
+        assert!(again.students[0].homework_age.is_some());
+
+        // Forced: every request again
+        app.refresh_data(&api, &cache, true, false).await.unwrap();
+        assert_eq!(api.calls().len(), 2 * fetched);
+
+        // A cold start only fetches the first student
+        let cache = TempCache::new(Some(3600));
+        let api = refresh_api();
+        let mut app = refresh_app();
+        app.refresh_data(&api, &cache, false, true).await.unwrap();
+        assert_eq!(api.calls().iter().filter(|c| *c == "absences").count(), 1);
+        assert!(app.students[1].absences_age.is_none());
+        assert!(!api.calls().contains(&"notifications".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_sorts_data() {
+        let cache = TempCache::new(Some(3600));
+        let homework = |id: i64, text: &str, date: &str| serde_json::json!({
+            "id": id,
+            "homework_text": text,
+            "shi_date": date.split('-').rev().collect::<Vec<_>>().join("."),
+            "shi_date_for_sort": date,
+        });
+        let api = refresh_api()
+            .with("homework_courses", r#"{
+                "courses": [
+                    { "cyc_group_id": 9001, "course_short_name": "БЕЛ" },
+                    { "cyc_group_id": 9002, "course_short_name": "БЕЛ" }
+                ],
+                "cycGroupHomeworksCount": { "9001": 2, "9002": 2 }
+            }"#)
+            .with("homework_list/9002", &serde_json::json!({ "homeworks": [
+                homework(72001, "Прочетете приказката и напишете преразказ.", "2026-02-18"),
+                homework(72002, "Научете стихотворението", "2026-02-19"),
+            ]}).to_string())
+            .with("schedule/2026-02-19", r#"{ "scheduleHours": [
+                { "school_hour": 3, "from_time": "09:40", "to_time": "10:20", "course_name": "История" },
+                { "school_hour": 1, "from_time": "08:00", "to_time": "08:40", "course_name": "Математика" }
+            ] }"#);
+        let mut app = refresh_app();
+
+        app.refresh_data(&api, &cache, false, false).await.unwrap();
+        let maria = &app.students[0];
+        // Newest assignment first; the copy under the second group is dropped
+        let texts: Vec<_> = maria.homework.iter().map(|hw| hw.text.as_str()).collect();
+        assert_eq!(texts, [
+            "Научете стихотворението",
+            "Прочетете приказката и напишете преразказ.",
+            "Упр. 3 и 4 от учебната тетрадка",
+        ]);
+        assert_eq!(maria.schedule.iter().map(|h| h.hour_number).collect::<Vec<_>>(), [1, 3]);
+        assert!(maria.absences.windows(2).all(|w| w[0].date_sort >= w[1].date_sort));
+        assert!(maria.feedbacks.windows(2).all(|w| Feedback::cmp_by_date(&w[0], &w[1]).is_le()));
+    }
 }
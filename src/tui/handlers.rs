@@ -1,41 +1,122 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::i18n::{Lang, T};
-use super::app::{App, Focus, Tab, InputMode, MessageView};
+use crate::i18n::T;
+use super::app::{App, FeedbackFilter, Focus, Tab, InputMode, MessageView, SettingsEntry};
+use super::keymap::KeyAction;
 
+#[derive(Debug, PartialEq)]
 pub enum Action {
     None,
     Refresh,
     RefreshAll,
     RefreshSchedule, // Refresh schedule for current schedule_date
     Logout,
+    ChangeLanguage,        // app.lang was changed; API data must follow
     // Message actions
     OpenThread(i64),       // Open thread with given ID
     CloseThread,           // Close current thread
     SendReply(String),     // Send reply message
     StartCompose,          // Start composing a new message
+    ReloadRecipients,      // Fetch the compose recipients again, past the cache
     SendCompose { subject: String, body: String, recipients: Vec<i64> }, // Send new message
+    SwitchFolder,          // app.selected_folder changed; load its threads
     // Navigation history
     NavigateBack,          // Go back in history (may need to reload data)
     NavigateForward,       // Go forward in history (may need to reload data)
 }
 
+/// Act on a Settings row, the same for its key and a click on it
+pub fn activate_setting(app: &mut App, entry: SettingsEntry) -> Action {
+    if let Some(command) = entry.login_command() {
+        // Logging in needs a terminal prompt, which the TUI doesn't have
+        app.set_status(format!("{}: {}", T::run_in_terminal(app.lang), command));
+        return Action::None;
+    }
+
+    match entry {
+        SettingsEntry::Language => {
+            // Cycle through the available languages
+            app.lang = app.lang.next();
+            Action::ChangeLanguage
+        }
+        SettingsEntry::Logout => Action::Logout,
+        SettingsEntry::AutoRefresh => {
+            app.next_auto_refresh();
+            Action::None
+        }
+        SettingsEntry::ShowWeekends => {
+            app.toggle_show_weekends();
+            Action::None
+        }
+        SettingsEntry::WeekStart => {
+            app.toggle_week_start();
+            Action::None
+        }
+        SettingsEntry::ThreadOrder => {
+            app.toggle_thread_order();
+            Action::None
+        }
+        SettingsEntry::Ascii => {
+            app.toggle_ascii();
+            Action::None
+        }
+        SettingsEntry::OverviewHomework => {
+            app.change_overview_homework_count(true);
+            Action::None
+        }
+        _ => Action::None,
+    }
+}
+
 pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
-    // Handle Ctrl+C (always works)
+    // Handle Ctrl+C (always works, keeping anything typed as a draft)
     if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        app.save_draft();
         app.quit();
         return Action::None;
     }
 
+    // The discard prompt only takes an answer, so stray typing can't get past it
+    if app.confirm_discard.is_some() {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.answer_discard(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.answer_discard(false),
+            KeyCode::Char('s') | KeyCode::Char('S') => app.answer_save_draft(),
+            _ => {}
+        }
+        return Action::None;
+    }
+
+    // Searching the help overlay takes every key, '?' included
+    if app.show_help && app.help_filter.is_some() {
+        handle_help_filter(app, key);
+        return Action::None;
+    }
+
     // Handle ? for help (always works, toggles help overlay)
     if key.code == KeyCode::Char('?') {
         app.toggle_help();
         return Action::None;
     }
 
-    // Any key dismisses help overlay if shown
+    // Hidden debug overlay with cache stats
+    if key.code == KeyCode::F(12) {
+        app.show_cache_stats = !app.show_cache_stats;
+        return Action::None;
+    }
+
+    if app.show_cache_stats {
+        app.show_cache_stats = false;
+        return Action::None;
+    }
+
+    // '/' searches the help overlay, any other key dismisses it
     if app.show_help {
-        app.show_help = false;
+        if key.code == KeyCode::Char('/') {
+            app.help_filter = Some(String::new());
+        } else {
+            app.toggle_help();
+        }
         return Action::None;
     }
 
@@ -50,43 +131,63 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
         return handle_input_mode(app, key);
     }
 
+    // Keys remapped in ui_config.json stand in for their action's default key
+    let in_view = app.current_tab == Tab::Messages && app.message_view != MessageView::List;
+    let remapped = app.keymap.translate(key, in_view);
+
     // Handle message thread view
     if app.current_tab == Tab::Messages && app.message_view == MessageView::Thread {
-        return handle_thread_view(app, key);
+        return handle_thread_view(app, remapped);
     }
 
     // Handle compose view
     if app.current_tab == Tab::Messages && app.message_view == MessageView::Compose {
-        return handle_compose_view(app, key);
+        return handle_compose_view(app, remapped);
     }
 
+    // Digits make a count for the next motion: "5j" moves five rows, "10G" goes
+    // to the tenth. A lone digit nothing takes as a count still selects that tab,
+    // with the next key or after COUNT_TIMEOUT; Alt+digit selects it at once.
+    if let KeyCode::Char(c @ '0'..='9') = key.code {
+        let digit = c.to_digit(10).unwrap_or(0);
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            app.select_count_tab(digit as usize);
+        } else if digit != 0 || app.pending_count().is_some() {
+            app.push_count_digit(digit);
+        }
+        return Action::None;
+    }
+    let count = app.take_count();
+    if let Some(count) = count {
+        // Esc only drops the count
+        if key.code == KeyCode::Esc {
+            return Action::None;
+        }
+        if !is_count_motion(app, remapped) {
+            app.select_count_tab(count);
+        }
+    }
+    let repeat = count.unwrap_or(1);
+
     // Settings tab has special key bindings
     if app.current_tab == Tab::Settings {
-        match key.code {
-            KeyCode::Char('g') | KeyCode::Char('G') => {
-                // Toggle language
-                app.lang = match app.lang {
-                    Lang::Bg => Lang::En,
-                    Lang::En => Lang::Bg,
-                };
-                return Action::None;
-            }
-            KeyCode::Char('l') | KeyCode::Char('L') => {
-                return Action::Logout;
-            }
-            KeyCode::Char('a') | KeyCode::Char('A') => {
-                // Cycle auto-refresh interval
-                app.next_auto_refresh();
-                return Action::None;
-            }
-            _ => {}
+        if key.code == KeyCode::Char('-') {
+            app.change_overview_homework_count(false);
+            return Action::None;
+        }
+        if let Some(entry) = match key.code {
+            KeyCode::Char(c) => SettingsEntry::from_key(c),
+            _ => None,
+        } {
+            return activate_setting(app, entry);
         }
     }
 
+    let key = remapped;
     match key.code {
-        // Quit
+        // Quit (overlays and the thread view have already taken Esc as "back")
         KeyCode::Char('q') | KeyCode::Esc => {
-            app.quit();
+            app.request_quit();
             Action::None
         }
 
@@ -106,33 +207,31 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
             Action::None
         }
 
-        // Up/Down behavior depends on focus
+        // Up/Down behavior depends on focus; a count repeats them
         KeyCode::Down | KeyCode::Char('j') => {
-            match app.focus {
-                Focus::Students => app.next_student(),
-                _ => app.scroll_down(),
+            for _ in 0..repeat {
+                match app.focus {
+                    Focus::Students => app.next_student(),
+                    _ => app.scroll_down(),
+                }
             }
             Action::None
         }
         KeyCode::Up | KeyCode::Char('k') => {
-            match app.focus {
-                Focus::Students => app.prev_student(),
-                _ => app.scroll_up(),
+            for _ in 0..repeat {
+                match app.focus {
+                    Focus::Students => app.prev_student(),
+                    _ => app.scroll_up(),
+                }
             }
             Action::None
         }
 
-        // Number keys for quick tab selection (1-9)
-        // Note: On Settings tab, 1-3 are handled above for login options
-        KeyCode::Char('1') => { app.select_tab(0); Action::None }
-        KeyCode::Char('2') => { app.select_tab(1); Action::None }
-        KeyCode::Char('3') => { app.select_tab(2); Action::None }
-        KeyCode::Char('4') => { app.select_tab(3); Action::None }
-        KeyCode::Char('5') => { app.select_tab(4); Action::None }
-        KeyCode::Char('6') => { app.select_tab(5); Action::None }
-        KeyCode::Char('7') => { app.select_tab(6); Action::None }
-        KeyCode::Char('8') => { app.select_tab(7); Action::None }
-        KeyCode::Char('9') => { app.select_tab(8); Action::None }
+        // G goes to the item numbered by the count, or the last one
+        KeyCode::Char('G') => {
+            app.jump_to(count);
+            Action::None
+        }
 
         // Refresh
         KeyCode::Char('r') => {
@@ -148,12 +247,12 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
         // Resize students pane (horizontal)
         KeyCode::Char('-') => {
             app.resize_students_pane(-2);
-            app.set_status(format!("Pane width: {}", app.students_pane_width));
+            app.set_status(T::pane_width(app.lang, app.students_pane_width));
             Action::None
         }
         KeyCode::Char('+') | KeyCode::Char('=') => {
             app.resize_students_pane(2);
-            app.set_status(format!("Pane width: {}", app.students_pane_width));
+            app.set_status(T::pane_width(app.lang, app.students_pane_width));
             Action::None
         }
 
@@ -161,18 +260,24 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
         KeyCode::Char('<') => {
             if app.current_tab == Tab::Overview {
                 app.resize_overview_split(-5);
-                app.set_status(format!("Overview split: {}%", app.overview_split_percent));
+                app.set_status(T::overview_split(app.lang, app.overview_split_percent));
             }
             Action::None
         }
         KeyCode::Char('>') => {
             if app.current_tab == Tab::Overview {
                 app.resize_overview_split(5);
-                app.set_status(format!("Overview split: {}%", app.overview_split_percent));
+                app.set_status(T::overview_split(app.lang, app.overview_split_percent));
             }
             Action::None
         }
 
+        // Space to expand/collapse a notification body
+        KeyCode::Char(' ') if app.current_tab == Tab::Notifications => {
+            app.toggle_notification_expanded();
+            Action::None
+        }
+
         // Enter to activate/select
         KeyCode::Enter => {
             // On Notifications tab, navigate to related tab
@@ -188,26 +293,70 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
             Action::None
         }
 
+        // 'f' cycles the messenger folders
+        KeyCode::Char('f') => {
+            if app.current_tab == Tab::Messages && app.message_view == MessageView::List && app.cycle_folder() {
+                return Action::SwitchFolder;
+            }
+            Action::None
+        }
+
         // 'c' to compose new message (only on Messages tab)
         KeyCode::Char('c') | KeyCode::Char('C') => {
             if app.current_tab == Tab::Messages && app.message_view == MessageView::List {
                 app.start_compose();
                 return Action::StartCompose;
             }
+            if app.current_tab == Tab::Grades {
+                app.toggle_grades_report();
+            }
             Action::None
         }
 
-        // Schedule date navigation (only on Schedule tab)
+        // Schedule date navigation (only on Schedule tab); feedback filters on Feedbacks
         KeyCode::Char('n') => {
             if app.current_tab == Tab::Schedule {
-                app.schedule_next_day();
+                for _ in 0..repeat {
+                    app.schedule_next_day();
+                }
                 return Action::RefreshSchedule;
             }
+            if app.current_tab == Tab::Feedbacks {
+                app.set_feedback_filter(FeedbackFilter::Negative);
+            }
             Action::None
         }
         KeyCode::Char('p') => {
             if app.current_tab == Tab::Schedule {
-                app.schedule_prev_day();
+                for _ in 0..repeat {
+                    app.schedule_prev_day();
+                }
+                return Action::RefreshSchedule;
+            }
+            if app.current_tab == Tab::Feedbacks {
+                app.set_feedback_filter(FeedbackFilter::Positive);
+            }
+            Action::None
+        }
+        KeyCode::Char('a') if app.current_tab == Tab::Feedbacks => {
+            app.set_feedback_filter(FeedbackFilter::All);
+            Action::None
+        }
+        KeyCode::Char('S') => {
+            app.start_student_switch();
+            Action::None
+        }
+        KeyCode::Char('e') => {
+            app.show_load_error();
+            Action::None
+        }
+        KeyCode::Char('/') if app.current_tab == Tab::Feedbacks => {
+            app.start_feedback_subject_entry();
+            Action::None
+        }
+        KeyCode::Char('N') | KeyCode::Char('P') => {
+            if app.current_tab == Tab::Schedule {
+                app.schedule_step_week(if key.code == KeyCode::Char('N') { 1 } else { -1 });
                 return Action::RefreshSchedule;
             }
             Action::None
@@ -217,31 +366,31 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
                 app.schedule_today();
                 return Action::RefreshSchedule;
             }
+            if app.current_tab == Tab::Grades {
+                app.toggle_grades_timeline();
+            }
+            Action::None
+        }
+        KeyCode::Char('d') => {
+            if app.current_tab == Tab::Schedule {
+                app.start_date_entry();
+            }
+            // Resume the saved new message; recipients get loaded like for 'c'
+            if app.current_tab == Tab::Messages && app.message_view == MessageView::List && app.resume_draft() {
+                return Action::StartCompose;
+            }
             Action::None
         }
 
         // Navigation history: Backspace = back, Shift+Backspace or Alt+Right = forward
         KeyCode::Backspace => {
+            // The main loop reloads thread messages if we landed on a thread view
             if key.modifiers.contains(KeyModifiers::SHIFT) {
-                // Forward
                 if app.go_forward() {
-                    // Check if we need to reload thread messages
-                    if app.message_view == MessageView::Thread {
-                        if let Some(thread_id) = app.selected_thread_id {
-                            return Action::OpenThread(thread_id);
-                        }
-                    }
-                }
-            } else {
-                // Back
-                if app.go_back() {
-                    // Check if we need to reload thread messages
-                    if app.message_view == MessageView::Thread {
-                        if let Some(thread_id) = app.selected_thread_id {
-                            return Action::OpenThread(thread_id);
-                        }
-                    }
+                    return Action::NavigateForward;
                 }
+            } else if app.go_back() {
+                return Action::NavigateBack;
             }
             Action::None
         }
@@ -250,17 +399,26 @@ pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
     }
 }
 
+/// Whether a count typed before the key applies to it
+fn is_count_motion(app: &App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Down | KeyCode::Up | KeyCode::Char('j') | KeyCode::Char('k') | KeyCode::Char('G') => true,
+        KeyCode::Char('n') | KeyCode::Char('p') => app.current_tab == Tab::Schedule,
+        _ => false,
+    }
+}
+
 /// Handle keys when in input mode (reply/compose)
 fn handle_input_mode(app: &mut App, key: KeyEvent) -> Action {
     match key.code {
-        // Escape cancels input
+        // Escape cancels input; a typed message needs confirmation first
         KeyCode::Esc => {
             match app.input_mode {
-                InputMode::Reply => {
-                    app.cancel_input();
+                InputMode::Reply | InputMode::ComposeSubject | InputMode::ComposeBody => {
+                    app.request_close_editor();
                 }
-                InputMode::ComposeSubject | InputMode::ComposeBody => {
-                    app.cancel_compose();
+                InputMode::DateEntry | InputMode::FeedbackSubject | InputMode::StudentSwitch => {
+                    app.cancel_input();
                 }
                 _ => {}
             }
@@ -287,33 +445,55 @@ fn handle_input_mode(app: &mut App, key: KeyEvent) -> Action {
         // Enter submits the input
         KeyCode::Enter => {
             match app.input_mode {
+                // A blank reply stays open with a hint instead of being sent
+                InputMode::Reply if app.input_buffer.trim().is_empty() => {
+                    app.set_status(T::empty_message(app.lang));
+                }
                 InputMode::Reply => {
-                    if !app.input_buffer.is_empty() {
-                        let message = app.take_input();
-                        return Action::SendReply(message);
-                    }
+                    let message = app.take_input().trim().to_string();
+                    return Action::SendReply(message);
                 }
                 InputMode::ComposeSubject => {
                     // Move to body entry
                     app.compose_next_step();
                 }
-                InputMode::ComposeBody => {
+                InputMode::ComposeBody if app.can_send_compose() => {
                     // Send the composed message
-                    if app.can_send_compose() {
-                        let subject = app.compose_subject.clone();
-                        let body = app.input_buffer.clone();
-                        let recipients = app.selected_recipients.clone();
-                        app.cancel_compose();
-                        return Action::SendCompose { subject, body, recipients };
-                    }
+                    let subject = app.compose_subject.trim().to_string();
+                    let body = app.input_buffer.trim().to_string();
+                    let recipients = app.selected_recipients.clone();
+                    app.cancel_compose();
+                    return Action::SendCompose { subject, body, recipients };
+                }
+                InputMode::ComposeBody if app.input_buffer.trim().is_empty() => {
+                    app.set_status(T::empty_message(app.lang));
+                }
+                InputMode::ComposeBody if app.compose_subject.trim().is_empty() => {
+                    app.set_status(T::empty_subject(app.lang));
+                }
+                InputMode::DateEntry => {
+                    // Invalid dates keep the prompt open with an error hint
+                    return if app.submit_date_entry() { Action::RefreshSchedule } else { Action::None };
+                }
+                InputMode::FeedbackSubject => {
+                    app.submit_feedback_subject();
+                }
+                InputMode::StudentSwitch => {
+                    app.submit_student_switch();
                 }
                 _ => {}
             }
             Action::None
         }
+        // Up/Down pick among the student switcher's matches
+        KeyCode::Up | KeyCode::Down if app.input_mode == InputMode::StudentSwitch => {
+            app.switcher_move(key.code == KeyCode::Down);
+            Action::None
+        }
         // Backspace deletes character before cursor
         KeyCode::Backspace => {
             app.input_backspace();
+            app.switcher_selected = 0;
             Action::None
         }
         // Delete deletes character at cursor
@@ -336,12 +516,14 @@ fn handle_input_mode(app: &mut App, key: KeyEvent) -> Action {
             Action::None
         }
         KeyCode::End => {
-            app.input_cursor = app.input_buffer.len();
+            app.input_cursor = app.input_buffer.chars().count();
             Action::None
         }
         // Character input
         KeyCode::Char(c) => {
             app.input_char(c);
+            app.date_entry_error = false;
+            app.switcher_selected = 0;
             Action::None
         }
         _ => Action::None,
@@ -351,9 +533,9 @@ fn handle_input_mode(app: &mut App, key: KeyEvent) -> Action {
 /// Handle keys when in compose view (recipient selection)
 fn handle_compose_view(app: &mut App, key: KeyEvent) -> Action {
     match key.code {
-        // Escape cancels compose
+        // Escape cancels compose, asking first if a subject or body was typed
         KeyCode::Esc => {
-            app.cancel_compose();
+            app.request_close_editor();
             Action::None
         }
         // Enter or Space toggles recipient selection
@@ -370,6 +552,8 @@ fn handle_compose_view(app: &mut App, key: KeyEvent) -> Action {
             }
             Action::None
         }
+        // 'r' loads the recipients again, e.g. after a failure
+        KeyCode::Char('r') | KeyCode::Char('R') if !app.loading => Action::ReloadRecipients,
         // Tab moves to subject (regardless of selection), Shift+Tab cycles from recipients to body
         KeyCode::Tab => {
             app.input_mode = InputMode::ComposeSubject;
@@ -416,6 +600,16 @@ fn handle_thread_view(app: &mut App, key: KeyEvent) -> Action {
             app.start_reply();
             Action::None
         }
+        // C writes a new message to the thread's participants
+        KeyCode::Char('C') => {
+            match app.selected_thread_id {
+                Some(thread_id) => {
+                    app.start_compose_from_thread(thread_id);
+                    Action::StartCompose
+                }
+                None => Action::None,
+            }
+        }
         // j/k or Down/Up scroll messages
         KeyCode::Down | KeyCode::Char('j') => {
             let max = app.thread_messages.len().saturating_sub(1);
@@ -432,80 +626,130 @@ fn handle_thread_view(app: &mut App, key: KeyEvent) -> Action {
     }
 }
 
+/// Typing in the help overlay's search; Esc and Enter close the overlay
+fn handle_help_filter(app: &mut App, key: KeyEvent) {
+    let Some(filter) = app.help_filter.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc | KeyCode::Enter => app.toggle_help(),
+        KeyCode::Backspace => {
+            filter.pop();
+        }
+        KeyCode::Char(c) => filter.push(c),
+        _ => {}
+    }
+}
+
+/// The help overlay's bindings whose description contains the search text,
+/// ignoring case; all of them when not searching
+pub fn help_keybindings(app: &App) -> Vec<(String, &'static str)> {
+    let filter = app.help_filter.as_deref().unwrap_or_default().trim().to_lowercase();
+    get_keybindings(app)
+        .into_iter()
+        .filter(|(_, desc)| desc.to_lowercase().contains(filter.as_str()))
+        .collect()
+}
+
 /// Get context-aware keybindings for the current app state
 /// Returns a list of (key, description) pairs
-/// This is defined here alongside the actual key handlers to keep them in sync
-pub fn get_keybindings(app: &App) -> Vec<(&'static str, &'static str)> {
+/// This is defined here alongside the actual key handlers to keep them in sync,
+/// with remapped keys in place of the defaults they replace
+pub fn get_keybindings(app: &App) -> Vec<(String, &'static str)> {
     let lang = app.lang;
-    let mut bindings = Vec::new();
+    let keys = |action| app.keymap.label(action);
+    let scroll = format!("{} {}", keys(KeyAction::ScrollDown), keys(KeyAction::ScrollUp));
+    let mut bindings: Vec<(String, &'static str)> = Vec::new();
 
     // Always available
-    bindings.push(("?", T::key_show_help(lang)));
+    bindings.push(("?".into(), T::key_show_help(lang)));
 
     // Check for special modes first
     if app.input_mode != InputMode::Normal {
         // Input mode keybindings (see handle_input_mode)
-        bindings.push(("Esc", T::key_cancel_input(lang)));
-        bindings.push(("Enter", T::key_submit(lang)));
-        bindings.push(("Backspace", T::key_delete_char(lang)));
-        bindings.push(("←/→", T::key_move_cursor(lang)));
-        bindings.push(("Home/End", T::key_jump_start_end(lang)));
+        bindings.push(("Esc".into(), T::key_cancel_input(lang)));
+        bindings.push(("Enter".into(), T::key_submit(lang)));
+        bindings.push(("Backspace".into(), T::key_delete_char(lang)));
+        bindings.push(("←/→".into(), T::key_move_cursor(lang)));
+        bindings.push(("Home/End".into(), T::key_jump_start_end(lang)));
         if app.input_mode == InputMode::ComposeSubject {
-            bindings.push(("Tab", T::key_move_to_body(lang)));
+            bindings.push(("Tab".into(), T::key_move_to_body(lang)));
         }
         return bindings;
     }
 
     // Message thread view (see handle_thread_view)
     if app.current_tab == Tab::Messages && app.message_view == MessageView::Thread {
-        bindings.push(("⌫/Esc/q", T::key_go_back(lang)));
-        bindings.push(("r", T::key_reply(lang)));
-        bindings.push(("↓/j ↑/k", T::key_scroll(lang)));
+        bindings.push((format!("{}/{}", keys(KeyAction::Back), keys(KeyAction::Quit)), T::key_go_back(lang)));
+        bindings.push(("r".into(), T::key_reply(lang)));
+        bindings.push(("C".into(), T::key_compose_to_participants(lang)));
+        bindings.push((scroll, T::key_scroll(lang)));
         return bindings;
     }
 
     // Compose view - recipient selection (see handle_compose_view)
     if app.current_tab == Tab::Messages && app.message_view == MessageView::Compose {
-        bindings.push(("Esc", T::key_cancel_compose(lang)));
-        bindings.push(("↓/j ↑/k", T::key_navigate(lang)));
-        bindings.push(("Enter/Space", T::key_toggle_recipient(lang)));
-        bindings.push(("s", T::key_start_subject(lang)));
+        bindings.push(("Esc".into(), T::key_cancel_compose(lang)));
+        bindings.push((scroll, T::key_navigate(lang)));
+        bindings.push(("Enter/Space".into(), T::key_toggle_recipient(lang)));
+        bindings.push(("s".into(), T::key_start_subject(lang)));
+        bindings.push(("r".into(), T::key_reload_recipients(lang)));
         return bindings;
     }
 
     // Normal mode - common bindings (see handle_key)
     // q/Esc/Ctrl+C all quit - consolidated into one entry
-    bindings.push(("q/Esc/^C", T::key_quit(lang)));
-    bindings.push(("←/h/[ →/l/]", T::key_switch_tabs(lang)));
-    bindings.push(("Tab", T::key_toggle_focus(lang)));
-    bindings.push(("↓/j ↑/k", T::key_navigate_scroll(lang)));
-    bindings.push(("1-9", T::key_quick_select_tab(lang)));
-    bindings.push(("r", T::key_refresh(lang)));
-    bindings.push(("R", T::key_force_refresh(lang)));
-    bindings.push(("G", T::key_toggle_lang(lang)));
-    bindings.push(("-/+/=", T::key_resize_pane(lang)));
-    bindings.push(("⌫", T::key_go_back(lang)));
-    bindings.push(("⇧⌫", T::key_go_forward(lang)));
+    bindings.push((format!("{}/^C", keys(KeyAction::Quit)), T::key_quit(lang)));
+    bindings.push((format!("{} {}", keys(KeyAction::PrevTab), keys(KeyAction::NextTab)), T::key_switch_tabs(lang)));
+    bindings.push((keys(KeyAction::ToggleFocus), T::key_toggle_focus(lang)));
+    bindings.push((scroll, T::key_navigate_scroll(lang)));
+    bindings.push(("1-9/Alt+1-9".into(), T::key_quick_select_tab(lang)));
+    bindings.push(("[n]j/k/G".into(), T::key_count_prefix(lang)));
+    bindings.push((keys(KeyAction::Refresh), T::key_refresh(lang)));
+    bindings.push((keys(KeyAction::ForceRefresh), T::key_force_refresh(lang)));
+    bindings.push(("G".into(), T::key_toggle_lang(lang)));
+    bindings.push((keys(KeyAction::SwitchStudent), T::key_switch_student(lang)));
+    bindings.push(("e".into(), T::key_show_load_error(lang)));
+    bindings.push(("-/+/=".into(), T::key_resize_pane(lang)));
+    bindings.push((keys(KeyAction::Back), T::key_go_back(lang)));
+    bindings.push(("⇧⌫".into(), T::key_go_forward(lang)));
 
     // Tab-specific bindings
     match app.current_tab {
         Tab::Overview => {
-            bindings.push(("</>", T::key_resize_split(lang)));
+            bindings.push(("</>".into(), T::key_resize_split(lang)));
         }
         Tab::Schedule => {
-            bindings.push(("p", T::key_prev_day(lang)));
-            bindings.push(("n", T::key_next_day(lang)));
-            bindings.push(("t", T::key_go_today(lang)));
+            bindings.push(("p".into(), T::key_prev_day(lang)));
+            bindings.push(("n".into(), T::key_next_day(lang)));
+            bindings.push(("P".into(), T::key_prev_week(lang)));
+            bindings.push(("N".into(), T::key_next_week(lang)));
+            bindings.push(("t".into(), T::key_go_today(lang)));
+            bindings.push(("d".into(), T::key_enter_date(lang)));
+        }
+        Tab::Grades => {
+            bindings.push(("t".into(), T::key_grades_timeline(lang)));
+            bindings.push(("c".into(), T::key_grades_report(lang)));
+        }
+        Tab::Feedbacks => {
+            bindings.push(("p".into(), T::key_filter_positive(lang)));
+            bindings.push(("n".into(), T::key_filter_negative(lang)));
+            bindings.push(("a".into(), T::key_filter_all(lang)));
+            bindings.push(("/".into(), T::key_filter_subject(lang)));
         }
         Tab::Notifications => {
-            bindings.push(("Enter", T::key_go_to_tab(lang)));
+            bindings.push((keys(KeyAction::Open), T::key_go_to_tab(lang)));
+            bindings.push(("Space".into(), T::key_expand(lang)));
         }
         Tab::Messages => {
-            bindings.push(("Enter", T::key_open_thread(lang)));
-            bindings.push(("c", T::key_compose(lang)));
+            bindings.push((keys(KeyAction::Open), T::key_open_thread(lang)));
+            bindings.push((keys(KeyAction::Compose), T::key_compose(lang)));
+            bindings.push(("d".into(), T::key_resume_draft(lang)));
+            bindings.push((keys(KeyAction::NextFolder), T::key_next_folder(lang)));
         }
         Tab::Settings => {
-            bindings.push(("L", T::logout(lang)));
+            bindings.push(("L".into(), T::logout(lang)));
+            bindings.push(("+/-".into(), T::overview_homework_count(lang).trim_end_matches([':', ' '])));
         }
         _ => {}
     }
@@ -516,6 +760,8 @@ pub fn get_keybindings(app: &App) -> Vec<(&'static str, &'static str)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tui::app::{PendingDiscard, COUNT_TIMEOUT};
+    use crate::tui::keymap::KeyMap;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
     fn key_event(code: KeyCode) -> KeyEvent {
@@ -556,6 +802,28 @@ mod tests {
         assert!(matches!(action, Action::RefreshAll));
     }
 
+    #[test]
+    fn test_overview_homework_count_keys_on_settings() {
+        let mut app = App::new();
+        app.current_tab = Tab::Settings;
+        let width = app.students_pane_width;
+
+        handle_key(&mut app, key_event(KeyCode::Char('+')));
+        handle_key(&mut app, key_event(KeyCode::Char('+')));
+        assert_eq!(app.overview_homework_count, 7);
+        handle_key(&mut app, key_event(KeyCode::Char('-')));
+        assert_eq!(app.overview_homework_count, 6);
+        // The pane width keys don't apply here
+        assert_eq!(app.students_pane_width, width);
+
+        // Both ends wrap around
+        app.overview_homework_count = 1;
+        handle_key(&mut app, key_event(KeyCode::Char('-')));
+        assert_eq!(app.overview_homework_count, 20);
+        handle_key(&mut app, key_event(KeyCode::Char('+')));
+        assert_eq!(app.overview_homework_count, 1);
+    }
+
     #[test]
     fn test_auto_refresh_toggle_on_settings() {
         use crate::tui::app::AutoRefreshInterval;
@@ -599,4 +867,395 @@ mod tests {
         assert_eq!(AutoRefreshInterval::Min30.minutes(), Some(30));
         assert_eq!(AutoRefreshInterval::Min60.minutes(), Some(60));
     }
+
+    #[test]
+    fn test_date_entry_on_schedule_tab() {
+        let mut app = App::new();
+        app.current_tab = Tab::Schedule;
+        app.schedule_date = "2026-02-19".to_string();
+
+        // 'd' opens the prompt
+        handle_key(&mut app, key_event(KeyCode::Char('d')));
+        assert_eq!(app.input_mode, InputMode::DateEntry);
+
+        // Replace the pre-filled date with a bad one
+        for _ in 0..10 {
+            handle_key(&mut app, key_event(KeyCode::Backspace));
+        }
+        for c in "2026-13-01".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        let action = handle_key(&mut app, key_event(KeyCode::Enter));
+        assert!(matches!(action, Action::None));
+        assert_eq!(app.input_mode, InputMode::DateEntry);
+        assert!(app.date_entry_error);
+
+        // Fix the month and submit
+        for _ in 0..5 {
+            handle_key(&mut app, key_event(KeyCode::Backspace));
+        }
+        for c in "05-04".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        assert!(!app.date_entry_error);
+        let action = handle_key(&mut app, key_event(KeyCode::Enter));
+        assert!(matches!(action, Action::RefreshSchedule));
+        assert_eq!(app.schedule_date, "2026-05-04");
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        // 'd' does nothing outside the Schedule tab
+        app.current_tab = Tab::Homework;
+        handle_key(&mut app, key_event(KeyCode::Char('d')));
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn test_esc_steps_back_and_confirms_discarding_a_reply() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.start_reply();
+        for c in "Благодаря".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+
+        // Esc asks first; "n" keeps the text
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.confirm_discard, Some(PendingDiscard::CloseEditor));
+        handle_key(&mut app, key_event(KeyCode::Char('x')));
+        assert_eq!(app.input_buffer, "Благодаря");
+        handle_key(&mut app, key_event(KeyCode::Char('n')));
+        assert_eq!(app.confirm_discard, None);
+        assert_eq!((app.input_mode, app.input_buffer.as_str()), (InputMode::Reply, "Благодаря"));
+
+        // "y" discards the reply but stays in the thread
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        handle_key(&mut app, key_event(KeyCode::Char('y')));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.input_buffer.is_empty());
+        assert_eq!(app.message_view, MessageView::Thread);
+
+        // Then Esc leaves the thread, and only after that quits
+        assert!(matches!(handle_key(&mut app, key_event(KeyCode::Esc)), Action::CloseThread));
+        assert_eq!(app.message_view, MessageView::List);
+        assert!(app.running);
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_blank_messages_are_not_sent() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.start_reply();
+        app.input_buffer = "   ".to_string();
+        assert!(matches!(handle_key(&mut app, key_event(KeyCode::Enter)), Action::None));
+        assert_eq!(app.input_mode, InputMode::Reply);
+        assert_eq!(app.status_message.as_deref(), Some(T::empty_message(app.lang)));
+
+        // Padding around a real reply is dropped
+        app.input_buffer = "  Благодаря!\n ".to_string();
+        match handle_key(&mut app, key_event(KeyCode::Enter)) {
+            Action::SendReply(message) => assert_eq!(message, "Благодаря!"),
+            _ => panic!("reply not sent"),
+        }
+
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.start_compose();
+        app.selected_recipients = vec![5];
+        app.compose_subject = "  ".to_string();
+        app.input_mode = InputMode::ComposeBody;
+        app.input_buffer = "Здравейте".to_string();
+        assert!(!app.can_send_compose());
+        assert!(matches!(handle_key(&mut app, key_event(KeyCode::Enter)), Action::None));
+        assert_eq!(app.status_message.as_deref(), Some(T::empty_subject(app.lang)));
+
+        app.compose_subject = " Среща ".to_string();
+        app.input_buffer = " \t ".to_string();
+        assert!(matches!(handle_key(&mut app, key_event(KeyCode::Enter)), Action::None));
+        assert_eq!(app.status_message.as_deref(), Some(T::empty_message(app.lang)));
+        assert_eq!(app.input_mode, InputMode::ComposeBody);
+
+        app.input_buffer = "\nЗдравейте, г-жо Петрова  ".to_string();
+        match handle_key(&mut app, key_event(KeyCode::Enter)) {
+            Action::SendCompose { subject, body, recipients } => {
+                assert_eq!((subject.as_str(), body.as_str(), recipients), ("Среща", "Здравейте, г-жо Петрова", vec![5]));
+            }
+            _ => panic!("message not sent"),
+        }
+    }
+
+    #[test]
+    fn test_quit_asks_while_a_message_is_being_composed() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.start_compose();
+        app.compose_subject = "Среща".to_string();
+
+        // Esc in recipient selection would throw the subject away
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.confirm_discard, Some(PendingDiscard::CloseEditor));
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.message_view, MessageView::Compose);
+
+        // Switched away by mouse, q still asks; Ctrl+C never does
+        app.current_tab = Tab::Overview;
+        handle_key(&mut app, key_event(KeyCode::Char('q')));
+        assert_eq!(app.confirm_discard, Some(PendingDiscard::Quit));
+        assert!(app.running);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(!app.running);
+
+        // Nothing typed: Esc leaves compose at once
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.start_compose();
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!((app.confirm_discard, app.message_view), (None, MessageView::List));
+    }
+
+    #[test]
+    fn test_unsent_text_kept_as_draft() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.start_compose();
+        app.compose_subject = "Среща".to_string();
+
+        // "s" in the prompt saves and closes; 'd' in the list resumes
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        handle_key(&mut app, key_event(KeyCode::Char('s')));
+        assert_eq!((app.confirm_discard, app.message_view), (None, MessageView::List));
+        assert!(matches!(handle_key(&mut app, key_event(KeyCode::Char('d'))), Action::StartCompose));
+        assert_eq!((app.message_view, app.compose_subject.as_str()), (MessageView::Compose, "Среща"));
+
+        // Ctrl+C never asks, but doesn't lose the text either
+        app.drafts = crate::models::Drafts::default();
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(!app.running);
+        assert_eq!(app.drafts.get(None).map(|d| d.subject.as_str()), Some("Среща"));
+    }
+
+    #[test]
+    fn test_help_search_narrows_bindings() {
+        let mut app = App::new();
+        app.current_tab = Tab::Schedule;
+        handle_key(&mut app, key_event(KeyCode::Char('?')));
+        let all = help_keybindings(&app).len();
+        assert_eq!(all, get_keybindings(&app).len());
+
+        // '/' starts the search; keys that are shortcuts elsewhere just type
+        handle_key(&mut app, key_event(KeyCode::Char('/')));
+        for c in "ДЕН?".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        assert!(app.show_help);
+        assert_eq!(app.help_filter.as_deref(), Some("ДЕН?"));
+        assert!(help_keybindings(&app).is_empty());
+
+        handle_key(&mut app, key_event(KeyCode::Backspace));
+        let found = help_keybindings(&app);
+        assert!(!found.is_empty() && found.len() < all);
+        assert!(found.iter().all(|(_, desc)| desc.to_lowercase().contains("ден")));
+        assert!(found.contains(&("p".to_string(), T::key_prev_day(app.lang))));
+
+        // Esc closes the overlay and forgets the search
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert!(!app.show_help && app.help_filter.is_none());
+        assert_eq!(app.current_tab, Tab::Schedule);
+
+        // Without a search any key still just closes it
+        handle_key(&mut app, key_event(KeyCode::Char('?')));
+        assert_eq!(handle_key(&mut app, key_event(KeyCode::Char('n'))), Action::None);
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn test_remapped_keys_act_and_show_in_help() {
+        let mut app = App::new();
+        let keys = [("scroll_down", "k"), ("scroll_up", "j"), ("refresh", "F5")];
+        app.keymap = KeyMap::from_config(&keys.iter().map(|(a, k)| (a.to_string(), k.to_string())).collect()).0;
+        app.students = vec![crate::tui::app::StudentData::new(crate::models::Student { id: 1, name: "Мария".into(), class_name: None, school_name: None }); 2];
+        app.focus = Focus::Students;
+
+        handle_key(&mut app, key_event(KeyCode::Char('k')));
+        assert_eq!(app.selected_student, 1);
+        handle_key(&mut app, key_event(KeyCode::Char('j')));
+        assert_eq!(app.selected_student, 0);
+        assert_eq!(handle_key(&mut app, key_event(KeyCode::F(5))), Action::Refresh);
+        // The default still works while no other action took it
+        assert_eq!(handle_key(&mut app, key_event(KeyCode::Char('r'))), Action::Refresh);
+
+        let bindings = get_keybindings(&app);
+        assert!(bindings.contains(&("k/↓ j/↑".to_string(), T::key_navigate_scroll(app.lang))));
+        assert!(bindings.contains(&("F5/r".to_string(), T::key_refresh(app.lang))));
+
+        // Typing is never remapped
+        app.input_mode = InputMode::Reply;
+        handle_key(&mut app, key_event(KeyCode::Char('k')));
+        assert_eq!(app.input_buffer, "k");
+    }
+
+    #[test]
+    fn test_shift_c_in_thread_composes_to_participants() {
+        let mut app = App::new();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.selected_thread_id = Some(42);
+        app.thread_participants = vec![crate::models::Participant { id: 5, name: "Петрова".into(), role: String::new() }];
+
+        // Plain 'r' still replies in the thread
+        assert_eq!(handle_key(&mut app, key_event(KeyCode::Char('r'))), Action::None);
+        assert_eq!(app.input_mode, InputMode::Reply);
+        app.cancel_input();
+
+        assert_eq!(handle_key(&mut app, key_event(KeyCode::Char('C'))), Action::StartCompose);
+        assert_eq!((app.message_view, app.selected_recipients.clone()), (MessageView::Compose, vec![5]));
+
+        app.message_view = MessageView::Thread;
+        assert!(get_keybindings(&app).contains(&("C".to_string(), T::key_compose_to_participants(app.lang))));
+    }
+
+    #[test]
+    fn test_settings_click_acts_like_key() {
+        let mut by_key = App::new();
+        let mut by_click = App::new();
+        by_key.current_tab = Tab::Settings;
+        by_click.current_tab = Tab::Settings;
+
+        for (c, entry) in [('w', SettingsEntry::ShowWeekends), ('d', SettingsEntry::WeekStart), ('a', SettingsEntry::AutoRefresh), ('c', SettingsEntry::Ascii), ('o', SettingsEntry::ThreadOrder)] {
+            assert_eq!(handle_key(&mut by_key, key_event(KeyCode::Char(c))), activate_setting(&mut by_click, entry));
+        }
+        assert_eq!(
+            (by_key.show_weekends, by_key.week_start, by_key.auto_refresh_interval, by_key.ascii, by_key.thread_newest_first),
+            (by_click.show_weekends, by_click.week_start, by_click.auto_refresh_interval, by_click.ascii, by_click.thread_newest_first),
+        );
+        assert_ne!(by_click.show_weekends, App::new().show_weekends);
+
+        assert_eq!(activate_setting(&mut by_click, SettingsEntry::Language), Action::ChangeLanguage);
+        assert_eq!(by_click.lang, App::new().lang.next());
+        assert_eq!(activate_setting(&mut by_click, SettingsEntry::Logout), Action::Logout);
+
+        // Login needs a terminal prompt; the row says how
+        assert_eq!(activate_setting(&mut by_click, SettingsEntry::LoginGoogle), Action::None);
+        assert!(by_click.status_message.as_deref().unwrap().ends_with("shkolo login-google"));
+
+        // Number keys keep switching tabs, once it's clear they aren't a count
+        handle_key(&mut by_key, key_event(KeyCode::Char('1')));
+        by_key.expire_count(std::time::Instant::now() + COUNT_TIMEOUT);
+        assert_eq!(by_key.current_tab, Tab::Overview);
+    }
+
+    #[test]
+    fn test_count_prefix_repeats_motions() {
+        let mut app = App::new();
+        app.current_tab = Tab::Schedule;
+        app.schedule_date = "2026-02-16".into();
+        app.show_weekends = false;
+        for c in "3n".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        assert_eq!(app.schedule_date, "2026-02-19");
+        assert_eq!(app.current_tab, Tab::Schedule);
+        assert_eq!(app.pending_count(), None);
+
+        // Counts of more than one digit, shown while typed; G goes to that row
+        app.current_tab = Tab::Notifications;
+        app.focus = Focus::Content;
+        app.notifications = (0..15).map(|i| crate::models::Notification {
+            id: Some(i.to_string()), title: format!("N{}", i), body: None, date: String::new(), is_read: true,
+            notification_type: None, pupil_names: None, target: Default::default(),
+        }).collect();
+        for c in "12".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        assert_eq!(app.pending_count(), Some(12));
+        handle_key(&mut app, key_event(KeyCode::Char('G')));
+        assert_eq!(app.list_offset, 11);
+        assert_eq!(app.current_tab, Tab::Notifications);
+        // Without a count, to the last; counts past the end stop there
+        handle_key(&mut app, key_event(KeyCode::Char('G')));
+        assert_eq!(app.list_offset, 14);
+        for c in "5k20j".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        assert_eq!(app.list_offset, 14);
+        for c in "5k".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        assert_eq!(app.list_offset, 9);
+
+        // '0' doesn't start a count; Esc drops one without quitting
+        handle_key(&mut app, key_event(KeyCode::Char('0')));
+        assert_eq!(app.pending_count(), None);
+        handle_key(&mut app, key_event(KeyCode::Char('4')));
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert!(app.running);
+        assert_eq!((app.pending_count(), app.current_tab), (None, Tab::Notifications));
+    }
+
+    #[test]
+    fn test_lone_digit_still_selects_tab() {
+        let mut app = App::new();
+
+        // With the next key that isn't a motion: the tab first, then the key
+        handle_key(&mut app, key_event(KeyCode::Char('4')));
+        assert_eq!(app.current_tab, Tab::Overview);
+        assert_eq!(handle_key(&mut app, key_event(KeyCode::Char('r'))), Action::RefreshSchedule);
+        assert_eq!(app.current_tab, Tab::Schedule);
+
+        // After the timeout, but not before
+        handle_key(&mut app, key_event(KeyCode::Char('2')));
+        app.expire_count(std::time::Instant::now());
+        assert_eq!(app.pending_count(), Some(2));
+        app.expire_count(std::time::Instant::now() + COUNT_TIMEOUT);
+        assert_eq!((app.pending_count(), app.current_tab), (None, Tab::all()[1]));
+
+        // Alt+digit right away; a count of two digits is never a tab
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('1'), KeyModifiers::ALT));
+        assert_eq!(app.current_tab, Tab::Overview);
+        for c in "12".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        app.expire_count(std::time::Instant::now() + COUNT_TIMEOUT);
+        assert_eq!(app.current_tab, Tab::Overview);
+    }
+
+    #[test]
+    fn test_student_switcher_keys() {
+        use crate::models::Student;
+        use crate::tui::app::StudentData;
+
+        let mut app = App::new();
+        app.current_tab = Tab::Notifications;
+        app.students = ["Мария Иванова", "Петър Иванов"].iter().enumerate()
+            .map(|(i, name)| StudentData::new(Student { id: i as i64 + 1, name: name.to_string(), class_name: None, school_name: None }))
+            .collect();
+
+        handle_key(&mut app, key_event(KeyCode::Char('S')));
+        assert_eq!(app.input_mode, InputMode::StudentSwitch);
+        // Letters go to the filter, not to the tab's own bindings
+        for c in "пе".chars() {
+            handle_key(&mut app, key_event(KeyCode::Char(c)));
+        }
+        handle_key(&mut app, key_event(KeyCode::Enter));
+        assert_eq!((app.input_mode, app.selected_student), (InputMode::Normal, 1));
+
+        handle_key(&mut app, key_event(KeyCode::Char('S')));
+        handle_key(&mut app, key_event(KeyCode::Down));
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!((app.input_mode, app.selected_student), (InputMode::Normal, 1));
+        assert!(app.running);
+
+        // Settings has its own letters, but 'S' still opens the switcher there
+        app.current_tab = Tab::Settings;
+        let week_start = app.week_start;
+        handle_key(&mut app, key_event(KeyCode::Char('S')));
+        assert_eq!(app.input_mode, InputMode::StudentSwitch);
+        assert_eq!(app.week_start, week_start);
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        handle_key(&mut app, key_event(KeyCode::Char('D')));
+        assert_ne!(app.week_start, week_start);
+    }
 }
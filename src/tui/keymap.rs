@@ -0,0 +1,362 @@
+//! Key bindings remapped in the `keys` section of `ui_config.json`, e.g.
+//! `"keys": {"scroll_down": "k", "scroll_up": "j", "refresh": "F5"}`.
+//!
+//! A remapped key stands in for its action's default key, so `handle_key`
+//! keeps its one set of hardcoded bindings. The defaults keep working unless
+//! another action took them over.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A key and the modifiers held with it. Shift is part of a character
+/// ('R' rather than shift+'r'), the way terminals report it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    const fn plain(code: KeyCode) -> Self {
+        KeySpec { code, modifiers: KeyModifiers::NONE }
+    }
+
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => KeySpec {
+                code: KeyCode::Char(c.to_uppercase().next().unwrap_or(c)),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            code => KeySpec { code, modifiers },
+        }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    pub fn to_event(self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+
+    /// Parse a key spec from the config: a character ("r", "R", "+"), a named
+    /// key ("F5", "enter", "space", "pagedown"...), either after modifiers
+    /// ("ctrl+r", "alt+shift+x"). Case only matters for a single character.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        // '+' separates the modifiers, but can be the key too: "+", "ctrl++"
+        let (mods, key) = match spec.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None if spec == "+" => ("", "+"),
+            None => spec.rsplit_once('+').unwrap_or(("", spec)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match m.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return None,
+                },
+            },
+        };
+        Some(Self::new(code, modifiers))
+    }
+}
+
+/// Labels in the style of the help overlay: "^R", "F5", "↓", "Space"
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "⇧")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "⌫"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// What a key can be remapped to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Quit,
+    ToggleFocus,
+    PrevTab,
+    NextTab,
+    ScrollDown,
+    ScrollUp,
+    Refresh,
+    ForceRefresh,
+    SwitchStudent,
+    Open,
+    Compose,
+    NextFolder,
+    Back,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 13] = [
+        KeyAction::Quit,
+        KeyAction::ToggleFocus,
+        KeyAction::PrevTab,
+        KeyAction::NextTab,
+        KeyAction::ScrollDown,
+        KeyAction::ScrollUp,
+        KeyAction::Refresh,
+        KeyAction::ForceRefresh,
+        KeyAction::SwitchStudent,
+        KeyAction::Open,
+        KeyAction::Compose,
+        KeyAction::NextFolder,
+        KeyAction::Back,
+    ];
+
+    /// Name in the config's `keys` section
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyAction::Quit => "quit",
+            KeyAction::ToggleFocus => "toggle_focus",
+            KeyAction::PrevTab => "prev_tab",
+            KeyAction::NextTab => "next_tab",
+            KeyAction::ScrollDown => "scroll_down",
+            KeyAction::ScrollUp => "scroll_up",
+            KeyAction::Refresh => "refresh",
+            KeyAction::ForceRefresh => "force_refresh",
+            KeyAction::SwitchStudent => "switch_student",
+            KeyAction::Open => "open",
+            KeyAction::Compose => "compose",
+            KeyAction::NextFolder => "next_folder",
+            KeyAction::Back => "back",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    /// The keys `handle_key` has for the action, as listed in the help
+    pub fn default_keys(self) -> &'static [KeySpec] {
+        macro_rules! keys {
+            ($($code:expr),+) => { &[$(KeySpec { code: $code, modifiers: KeyModifiers::NONE }),+] };
+        }
+        use KeyCode::Char;
+        match self {
+            KeyAction::Quit => keys![Char('q'), KeyCode::Esc],
+            KeyAction::ToggleFocus => keys![KeyCode::Tab],
+            KeyAction::PrevTab => keys![KeyCode::Left, Char('h'), Char('[')],
+            KeyAction::NextTab => keys![KeyCode::Right, Char('l'), Char(']')],
+            KeyAction::ScrollDown => keys![KeyCode::Down, Char('j')],
+            KeyAction::ScrollUp => keys![KeyCode::Up, Char('k')],
+            KeyAction::Refresh => keys![Char('r')],
+            KeyAction::ForceRefresh => keys![Char('R')],
+            KeyAction::SwitchStudent => keys![Char('S')],
+            KeyAction::Open => keys![KeyCode::Enter],
+            KeyAction::Compose => keys![Char('c')],
+            KeyAction::NextFolder => keys![Char('f')],
+            KeyAction::Back => keys![KeyCode::Backspace],
+        }
+    }
+
+    /// The default key a remapped one stands in for. Esc rather than 'q' for
+    /// quit, as Esc also leaves the thread and compose views.
+    fn target(self) -> KeySpec {
+        match self {
+            KeyAction::Quit => KeySpec::plain(KeyCode::Esc),
+            action => action.default_keys()[0],
+        }
+    }
+
+    /// Whether the action means the same in the thread and compose views,
+    /// where 'r' replies and Enter picks a recipient
+    pub fn works_in_views(self) -> bool {
+        matches!(self, KeyAction::Quit | KeyAction::ScrollDown | KeyAction::ScrollUp | KeyAction::Back)
+    }
+}
+
+/// Remapped keys, consulted by `handle_key` before its defaults
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    bindings: Vec<(KeySpec, KeyAction)>,
+}
+
+impl KeyMap {
+    /// Bindings from the config's action → key entries, and a line for each
+    /// entry left out: unknown actions, unparseable keys, and keys given to
+    /// more than one action (none of those get the key).
+    pub fn from_config(entries: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut ignored = Vec::new();
+        let mut parsed = Vec::new();
+        for (name, spec) in entries {
+            match (KeyAction::from_name(name), KeySpec::parse(spec)) {
+                (None, _) => ignored.push(format!("{}: unknown action", name)),
+                (_, None) => ignored.push(format!("{} = \"{}\": unknown key", name, spec)),
+                (Some(action), Some(key)) => parsed.push((key, action)),
+            }
+        }
+
+        let mut bindings = Vec::new();
+        for &(key, action) in &parsed {
+            let sharing: Vec<&str> = parsed.iter().filter(|(k, _)| *k == key).map(|(_, a)| a.name()).collect();
+            if sharing.len() == 1 {
+                bindings.push((key, action));
+            } else if sharing[0] == action.name() {
+                ignored.push(format!("{}: same key {}", sharing.join(", "), key));
+            }
+        }
+        (KeyMap { bindings }, ignored)
+    }
+
+    /// The remapped action of a key press, if any
+    pub fn action(&self, key: &KeyEvent) -> Option<KeyAction> {
+        let key = KeySpec::from_event(key);
+        self.bindings.iter().find(|(k, _)| *k == key).map(|(_, action)| *action)
+    }
+
+    /// A remapped key press as the default key of its action; other keys as they are.
+    /// `in_view`: in the thread or compose view, where only some actions apply.
+    pub fn translate(&self, key: KeyEvent, in_view: bool) -> KeyEvent {
+        match self.action(&key) {
+            Some(action) if !in_view || action.works_in_views() => action.target().to_event(),
+            _ => key,
+        }
+    }
+
+    /// Help label for an action: its remapped keys, then the defaults no other
+    /// action took over
+    pub fn label(&self, action: KeyAction) -> String {
+        let remapped = self.bindings.iter().filter(|(_, a)| *a == action).map(|(k, _)| *k);
+        let defaults = action.default_keys().iter().copied()
+            .filter(|d| !self.bindings.iter().any(|(k, _)| k == d));
+        remapped.chain(defaults).map(|k| k.to_string()).collect::<Vec<_>>().join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries.iter().map(|(a, k)| (a.to_string(), k.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_key_specs() {
+        let spec = |code, modifiers| Some(KeySpec { code, modifiers });
+        assert_eq!(KeySpec::parse("r"), spec(KeyCode::Char('r'), KeyModifiers::NONE));
+        assert_eq!(KeySpec::parse("R"), spec(KeyCode::Char('R'), KeyModifiers::NONE));
+        assert_eq!(KeySpec::parse("shift+r"), KeySpec::parse("R"));
+        assert_eq!(KeySpec::parse("Ctrl+r"), spec(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert_eq!(KeySpec::parse("alt+ctrl+x"), spec(KeyCode::Char('x'), KeyModifiers::CONTROL | KeyModifiers::ALT));
+        assert_eq!(KeySpec::parse("F5"), spec(KeyCode::F(5), KeyModifiers::NONE));
+        assert_eq!(KeySpec::parse(" pagedown "), spec(KeyCode::PageDown, KeyModifiers::NONE));
+        assert_eq!(KeySpec::parse("space"), spec(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(KeySpec::parse("+"), spec(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!(KeySpec::parse("ctrl++"), spec(KeyCode::Char('+'), KeyModifiers::CONTROL));
+        assert_eq!(KeySpec::parse("ж"), spec(KeyCode::Char('ж'), KeyModifiers::NONE));
+
+        for bad in ["", "ctrl+", "hyper+r", "F0", "F25", "pgdown", "rr"] {
+            assert_eq!(KeySpec::parse(bad), None, "{}", bad);
+        }
+
+        // Terminals send Shift+r as 'R' with the shift flag
+        let event = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        assert_eq!(Some(KeySpec::from_event(&event)), KeySpec::parse("R"));
+        assert_eq!(KeySpec::parse("ctrl+r").unwrap().to_string(), "^R");
+    }
+
+    #[test]
+    fn test_conflicts_and_bad_entries_are_reported() {
+        let (map, ignored) = KeyMap::from_config(&keys(&[
+            ("refresh", "F5"),
+            ("next_tab", "x"),
+            ("compose", "x"),
+            ("scroll_down", "ctrl+"),
+            ("jump", "g"),
+        ]));
+        assert_eq!(ignored, vec![
+            "jump: unknown action",
+            "scroll_down = \"ctrl+\": unknown key",
+            "compose, next_tab: same key x",
+        ]);
+
+        // The clashing key does nothing extra; the good entry still applies
+        assert_eq!(map.action(&KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE)), None);
+        assert_eq!(map.action(&KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE)), Some(KeyAction::Refresh));
+
+        let (_, ignored) = KeyMap::from_config(&keys(&[("refresh", "F5"), ("scroll_up", "k")]));
+        assert!(ignored.is_empty());
+    }
+
+    #[test]
+    fn test_swapped_keys_translate_and_label() {
+        let (map, ignored) = KeyMap::from_config(&keys(&[("scroll_down", "k"), ("scroll_up", "j"), ("refresh", "ctrl+r")]));
+        assert!(ignored.is_empty());
+
+        let press = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(map.translate(press('k'), false).code, KeyCode::Down);
+        assert_eq!(map.translate(press('j'), true).code, KeyCode::Up);
+        assert_eq!(map.translate(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL), false), press('r'));
+        // Refresh doesn't apply in a thread, where 'r' replies
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(map.translate(ctrl_r, true), ctrl_r);
+        assert_eq!(map.translate(press('x'), false), press('x'));
+
+        assert_eq!(map.label(KeyAction::ScrollDown), "k/↓");
+        assert_eq!(map.label(KeyAction::ScrollUp), "j/↑");
+        assert_eq!(map.label(KeyAction::Refresh), "^R/r");
+        assert_eq!(KeyMap::default().label(KeyAction::PrevTab), "←/h/[");
+    }
+}
@@ -1,6 +1,8 @@
 pub mod app;
 pub mod ui;
 pub mod handlers;
+pub mod keymap;
+pub mod snapshot;
 
 pub use app::App;
 pub use ui::draw;
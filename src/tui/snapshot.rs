@@ -0,0 +1,139 @@
+//! The TUI drawn off-screen (`shkolo snapshot`): the same `draw` as the live
+//! terminal, into a `TestBackend` buffer that is then written out as text.
+
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use ratatui::Terminal;
+
+use super::{draw, App};
+
+/// `app` drawn on a `width` x `height` screen. Plain text has trailing
+/// spaces trimmed; with `ansi` every cell keeps its colors and modifiers.
+pub fn render(app: &App, width: u16, height: u16, ansi: bool) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("TestBackend never fails");
+    terminal.draw(|frame| draw(frame, app)).expect("TestBackend never fails");
+    let buffer = terminal.backend().buffer();
+    if ansi { ansi_text(buffer) } else { plain_text(buffer) }
+}
+
+fn plain_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut row = String::new();
+        for x in 0..buffer.area.width {
+            row.push_str(buffer[(x, y)].symbol());
+        }
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Rows with SGR escapes wherever the style changes, reset at each line end
+fn ansi_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut current = None;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(x, y)];
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color_code(fg, false));
+    codes.extend(color_code(bg, true));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameters for a color; None for the terminal's default
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let basic = |n: u8| Some((base + n).to_string());
+    let bright = |n: u8| Some((base + 60 + n).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => basic(0),
+        Color::Red => basic(1),
+        Color::Green => basic(2),
+        Color::Yellow => basic(3),
+        Color::Blue => basic(4),
+        Color::Magenta => basic(5),
+        Color::Cyan => basic(6),
+        Color::Gray => basic(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(n) => Some(format!("{};5;{}", base + 8, n)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::app::Tab;
+
+    #[test]
+    fn test_render_plain_and_ansi() {
+        let mut app = App::new();
+        app.current_tab = Tab::Settings;
+        let plain = render(&app, 80, 24, false);
+        assert_eq!(plain.lines().count(), 24);
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.lines().all(|line| line == line.trim_end()));
+
+        let ansi = render(&app, 80, 24, true);
+        assert!(ansi.contains("\x1b[0;"), "{}", ansi);
+        assert!(ansi.lines().all(|line| line.ends_with("\x1b[0m")));
+        // The same text once the escapes are taken out
+        let stripped: String = ansi.lines()
+            .map(|line| {
+                let mut text = String::new();
+                let mut rest = line;
+                while let Some(start) = rest.find('\x1b') {
+                    text.push_str(&rest[..start]);
+                    rest = rest[start..].split_once('m').map_or("", |(_, after)| after);
+                }
+                text.push_str(rest);
+                format!("{}\n", text.trim_end())
+            })
+            .collect();
+        assert_eq!(stripped, plain);
+    }
+
+    #[test]
+    fn test_color_codes() {
+        assert_eq!(sgr(Color::Reset, Color::Reset, Modifier::empty()), "\x1b[0m");
+        assert_eq!(sgr(Color::Yellow, Color::Blue, Modifier::BOLD), "\x1b[0;1;33;44m");
+        assert_eq!(sgr(Color::Indexed(208), Color::Rgb(1, 2, 3), Modifier::empty()), "\x1b[0;38;5;208;48;2;1;2;3m");
+        assert_eq!(color_code(Color::DarkGray, false).as_deref(), Some("90"));
+    }
+}
@@ -1,4 +1,5 @@
 use ratatui::{
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
@@ -6,18 +7,29 @@ use ratatui::{
     Frame,
 };
 
-use crate::i18n::T;
-use super::app::{App, Focus, Tab, InputMode, MessageView, calculate_scroll};
-use super::handlers::get_keybindings;
+use crate::cache::Freshness;
+use crate::i18n::{format_age, Lang, T};
+use super::app::{App, FeedbackFilter, FinalKind, Focus, Tab, InputMode, MessageView, SettingsEntry, StudentData, SubjectSummary, calculate_scroll};
+use crate::models::{Absence, Exam, Grade, Homework, Urgency, absence_summary, parse_bg_grade, school_day_over, term_boundary};
+use crate::tracking;
+use crate::util::{self, is_weekend, parse_day};
+use time::Date;
+use super::handlers::{get_keybindings, help_keybindings};
+use super::keymap::KeyAction;
+use crate::output::LoadStatus;
 
 const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const ASCII_SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+/// Body lines shown for a notification that has not been expanded
+const COLLAPSED_BODY_LINES: usize = 2;
 
 pub fn draw(frame: &mut Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),  // Tabs
-            Constraint::Min(10),    // Main content
+            Constraint::Min(0),     // Main content (tabs and status bar win on short terminals)
             Constraint::Length(3),  // Status bar
         ])
         .split(frame.area());
@@ -26,33 +38,204 @@ pub fn draw(frame: &mut Frame, app: &App) {
     draw_content(frame, app, chunks[1]);
     draw_status_bar(frame, app, chunks[2]);
 
+    if app.input_mode == InputMode::StudentSwitch {
+        draw_student_switcher(frame, app);
+    }
+
     // Draw error overlay if there's an error (loading indicator is now in status bar only)
     if let Some(ref error) = app.error_message {
-        draw_error_overlay(frame, error);
+        draw_error_overlay(frame, error, app.lang);
     }
 
     // Draw help overlay if requested
     if app.show_help {
         draw_help_overlay(frame, app);
     }
+
+    if app.show_cache_stats {
+        draw_cache_stats_overlay(frame, app);
+    }
+
+    if app.confirm_discard.is_some() {
+        draw_discard_overlay(frame, app.lang);
+    }
+
+    if app.ascii {
+        asciify(frame.buffer_mut());
+    }
+}
+
+/// ASCII stand-in for a box-drawing, arrow or punctuation glyph (--ascii)
+fn ascii_symbol(symbol: &str) -> Option<&'static str> {
+    Some(match symbol {
+        "─" | "━" | "═" => "-",
+        "│" | "┃" | "║" => "|",
+        "┌" | "┐" | "└" | "┘" | "├" | "┤" | "┬" | "┴" | "┼" | "╭" | "╮" | "╰" | "╯" => "+",
+        "▸" | "▶" | "→" => ">",
+        "◂" | "◀" | "←" => "<",
+        "▲" | "↑" => "^",
+        "▼" | "↓" => "v",
+        "·" | "•" => "*",
+        "—" | "–" => "-",
+        "…" => ".",
+        "„" | "“" | "”" | "«" | "»" => "\"",
+        "‘" | "’" => "'",
+        "✓" => "x",
+        _ => return None,
+    })
+}
+
+/// Replace the glyphs ratatui and this module draw with ASCII after the frame is
+/// built, so borders, tab dividers, markers and dividers all follow --ascii
+fn asciify(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if let Some(ascii) = ascii_symbol(cell.symbol()) {
+            cell.set_symbol(ascii);
+        }
+    }
+}
+
+/// Spell out the arrow keys in help key labels (--ascii)
+fn ascii_key_label(key: &str) -> String {
+    key.replace("⇧⌫", "Shift+Bksp")
+        .replace('⌫', "Bksp")
+        .replace('←', "Left")
+        .replace('→', "Right")
+        .replace('↑', "Up")
+        .replace('↓', "Down")
+}
+
+/// Debug overlay listing cache hits/misses/expiries for this session
+fn draw_cache_stats_overlay(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let stats = app.cache_stats.as_ref().map(|s| s.snapshot()).unwrap_or_default();
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{:<14} {:>6} {:>6} {:>8}", "type", "hits", "misses", "expired"),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))];
+    for (kind, counts) in &stats {
+        lines.push(Line::from(format!(
+            "{:<14} {:>6} {:>6} {:>8}",
+            kind.name(), counts.hits, counts.misses, counts.expired
+        )));
+    }
+
+    let width = 42.min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let stats_area = centered_rect(area, width, height);
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta))
+            .title(" Cache stats ")
+            .title_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)));
+
+    frame.render_widget(Clear, stats_area);
+    frame.render_widget(paragraph, stats_area);
+}
+
+/// One list item per line, so an entry taller than the pane is clipped instead of dropped
+fn line_items(lines: Vec<Line<'_>>) -> Vec<ListItem<'_>> {
+    lines.into_iter().map(ListItem::new).collect()
+}
+
+/// Centre a popup of the given size in `area`, shrinking it to fit small terminals
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Student picker opened with `S`: a filter line above the matching students
+fn draw_student_switcher(frame: &mut Frame, app: &App) {
+    const MAX_ROWS: usize = 10;
+    let area = frame.area();
+    let lang = app.lang;
+    let matches = app.switcher_matches();
+    let selected = app.switcher_selected.min(matches.len().saturating_sub(1));
+
+    let width = (T::student_switch_title(lang).chars().count() as u16 + 4).max(40).min(area.width.saturating_sub(4));
+    let rows = matches.len().clamp(1, MAX_ROWS);
+    let popup = centered_rect(area, width, rows as u16 + 4);
+    let inner_width = popup.width.saturating_sub(2) as usize;
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Green)),
+            Span::raw(app.input_buffer.clone()),
+        ]),
+        Line::from(Span::styled("─".repeat(inner_width), Style::default().fg(Color::DarkGray))),
+    ];
+    if matches.is_empty() {
+        lines.push(Line::from(Span::styled(format!("  {}", T::no_matches(lang)), Style::default().fg(Color::DarkGray))));
+    }
+    // Keep the highlighted row in view
+    let first = selected.saturating_sub(MAX_ROWS - 1);
+    for (i, student) in matches.iter().enumerate().skip(first).take(MAX_ROWS) {
+        let style = if i == selected {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        let marker = if i == selected { "> " } else { "  " };
+        let room = inner_width.saturating_sub(marker.len());
+        let label = fit_pair(&student.name, student.class_name.as_deref(), " ", room);
+        lines.push(Line::from(Span::styled(format!("{}{:<width$}", marker, label, width = room), style)));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green))
+            .title(T::student_switch_title(lang)));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
+    frame.set_cursor_position((popup.x + 3 + app.input_cursor as u16, popup.y + 1));
+}
+
+/// "Discard unsent message?" prompt before a typed reply or message is lost
+fn draw_discard_overlay(frame: &mut Frame, lang: Lang) {
+    let area = frame.area();
+    let lines = vec![
+        Line::from(Span::styled(T::discard_unsent(lang), Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(Span::styled(T::discard_hint(lang), Style::default().fg(Color::DarkGray))),
+    ];
+    let width = (T::discard_hint(lang).chars().count() as u16 + 4).max(40).min(area.width.saturating_sub(4));
+    let popup = centered_rect(area, width, lines.len() as u16 + 2);
+
+    let paragraph = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(T::discard_title(lang))
+            .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
 }
 
-fn draw_error_overlay(frame: &mut Frame, error: &str) {
+fn draw_error_overlay(frame: &mut Frame, error: &str, lang: Lang) {
     let area = frame.area();
 
     // Fixed width for error box
-    let width = (area.width as usize * 2 / 3).max(40).min(area.width as usize - 4) as u16;
+    let width = (area.width * 2 / 3).max(40).min(area.width.saturating_sub(4));
     let inner_width = width.saturating_sub(4) as usize;
 
     // Wrap text to fit within the box
     let wrapped_lines = wrap_text(error, inner_width, "");
 
-    let height = (wrapped_lines.len() as u16 + 4).min(area.height - 4);
-
-    let x = area.width.saturating_sub(width) / 2;
-    let y = area.height.saturating_sub(height) / 2;
-
-    let error_area = Rect::new(x, y, width, height);
+    let height = (wrapped_lines.len() as u16 + 4).min(area.height.saturating_sub(4));
+    let error_area = centered_rect(area, width, height);
 
     // Join wrapped lines
     let wrapped_text = wrapped_lines.join("\n");
@@ -64,7 +247,7 @@ fn draw_error_overlay(frame: &mut Frame, error: &str) {
         .block(Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Red))
-            .title(" Error [Press any key to dismiss] ")
+            .title(T::error_title(lang))
             .title_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
 
     frame.render_widget(Clear, error_area);
@@ -73,22 +256,23 @@ fn draw_error_overlay(frame: &mut Frame, error: &str) {
 
 fn draw_help_overlay(frame: &mut Frame, app: &App) {
     let area = frame.area();
-    let bindings = get_keybindings(app);
+    let key_label = |key: &str| if app.ascii { ascii_key_label(key) } else { key.to_string() };
+    let all_bindings = get_keybindings(app);
+    let bindings: Vec<(String, &str)> = help_keybindings(app)
+        .into_iter()
+        .map(|(key, desc)| (key_label(&key), desc))
+        .collect();
 
-    // Calculate dimensions
-    let max_key_len = bindings.iter().map(|(k, _)| k.len()).max().unwrap_or(10);
-    let max_desc_len = bindings.iter().map(|(_, d)| d.len()).max().unwrap_or(20);
+    // Sized for every binding, so the box stays put while searching
+    let max_key_len = all_bindings.iter().map(|(k, _)| key_label(k).chars().count()).max().unwrap_or(10);
+    let max_desc_len = all_bindings.iter().map(|(_, d)| d.chars().count()).max().unwrap_or(20);
     let content_width = max_key_len + max_desc_len + 5; // key + " : " + desc + padding
-    let width = (content_width as u16 + 4).min(area.width - 4).max(40);
-    let height = (bindings.len() as u16 + 4).min(area.height - 4);
-
-    let x = area.width.saturating_sub(width) / 2;
-    let y = area.height.saturating_sub(height) / 2;
-
-    let help_area = Rect::new(x, y, width, height);
+    let width = (content_width as u16 + 4).min(area.width.saturating_sub(4)).max(40);
+    let height = (all_bindings.len() as u16 + 5).min(area.height.saturating_sub(4));
+    let help_area = centered_rect(area, width, height);
 
     // Build help text with aligned columns
-    let lines: Vec<Line> = bindings
+    let mut lines: Vec<Line> = bindings
         .iter()
         .map(|(key, desc)| {
             Line::from(vec![
@@ -101,6 +285,24 @@ fn draw_help_overlay(frame: &mut Frame, app: &App) {
             ])
         })
         .collect();
+    if bindings.is_empty() {
+        lines.push(Line::from(Span::styled(format!("  {}", T::no_matches(app.lang)), Style::default().fg(Color::DarkGray))));
+    }
+
+    // Search line at the bottom of the box
+    let inner_height = help_area.height.saturating_sub(2) as usize;
+    while lines.len() + 1 < inner_height {
+        lines.push(Line::from(""));
+    }
+    lines.truncate(inner_height.saturating_sub(1));
+    lines.push(match &app.help_filter {
+        Some(filter) => Line::from(vec![
+            Span::styled("/ ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(filter.clone()),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        None => Line::from(Span::styled(T::help_search_hint(app.lang), Style::default().fg(Color::DarkGray))),
+    });
 
     // Get context description
     let context = get_context_description(app);
@@ -129,6 +331,9 @@ fn get_context_description(app: &App) -> &'static str {
             InputMode::Reply => T::ctx_replying(lang),
             InputMode::ComposeSubject => T::ctx_composing_subject(lang),
             InputMode::ComposeBody => T::ctx_composing_body(lang),
+            InputMode::DateEntry => T::ctx_date_entry(lang),
+            InputMode::FeedbackSubject => T::ctx_subject_filter(lang),
+            InputMode::StudentSwitch => T::ctx_student_switch(lang),
             InputMode::Normal => T::overview(lang),
         };
     }
@@ -164,7 +369,14 @@ fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
             } else {
                 Style::default().fg(Color::White)
             };
-            Line::from(Span::styled(t.name(lang), style))
+            let mut spans = vec![Span::styled(t.name(lang), style)];
+            // Items that appeared since the tab was last viewed
+            let new_count = app.new_keys(*t).len();
+            if new_count > 0 {
+                let dot = if app.ascii { "*" } else { "•" };
+                spans.push(Span::styled(format!(" {}{}", dot, new_count), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+            }
+            Line::from(spans)
         })
         .collect();
 
@@ -178,6 +390,11 @@ fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_content(frame: &mut Frame, app: &App, area: Rect) {
+    if app.needs_login && app.current_tab != Tab::Settings {
+        draw_login_required(frame, app, area);
+        return;
+    }
+
     // Notifications and Settings are global (not per-student), so show them full-width
     match app.current_tab {
         Tab::Notifications => {
@@ -195,19 +412,23 @@ fn draw_content(frame: &mut Frame, app: &App, area: Rect) {
         _ => {}
     }
 
-    // Only show students pane if there's more than one student
-    let show_students_pane = app.students.len() > 1;
+    // Only show students pane if there's more than one student and room for it
+    let students_width = app.effective_students_width();
 
-    let content_area = if show_students_pane {
+    let content_area = if students_width > 0 {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Length(app.students_pane_width),  // Resizable students list
-                Constraint::Min(40),     // Main content
+                Constraint::Length(students_width),  // Resizable students list, or the compact strip
+                Constraint::Min(0),     // Main content
             ])
             .split(area);
 
-        draw_students_list(frame, app, chunks[0]);
+        if app.students_pane_compact() {
+            draw_students_strip(frame, app, chunks[0]);
+        } else {
+            draw_students_list(frame, app, chunks[0]);
+        }
         chunks[1]
     } else {
         area
@@ -224,9 +445,61 @@ fn draw_content(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Placeholder for the data tabs until there is a token
+fn draw_login_required(frame: &mut Frame, app: &App, area: Rect) {
+    let lang = app.lang;
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(format!("  {}", T::login_required(lang)), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(Span::styled(format!("  {}", T::login_required_hint(lang)), Style::default().fg(Color::DarkGray))),
+    ];
+    let title = format!(" {} ", app.current_tab.name(lang));
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// Students pane collapsed on a narrow terminal: a number and an initial per
+/// student, with the full name moved into the pane titles
+fn draw_students_strip(frame: &mut Frame, app: &App, area: Rect) {
+    let is_focused = app.focus == Focus::Students;
+    let text_width = area.width.saturating_sub(2) as usize;
+
+    let items: Vec<ListItem> = app.students
+        .iter()
+        .enumerate()
+        .map(|(i, data)| {
+            let is_selected = i == app.selected_student;
+            let style = match (is_selected, is_focused) {
+                (true, true) => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                (true, false) => Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                (false, _) => Style::default().fg(Color::DarkGray),
+            };
+            let prefix = if is_selected { ">" } else { " " };
+            let initial = data.student.name.chars().next().unwrap_or(' ');
+            let label: String = format!("{}{}{}", prefix, i + 1, initial).chars().take(text_width).collect();
+            ListItem::new(label).style(style)
+        })
+        .collect();
+
+    let border_style = if is_focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(border_style));
+    frame.render_widget(list, area);
+}
+
 fn draw_students_list(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
     let is_focused = app.focus == Focus::Students;
+    // Borders plus the "> " selection prefix, and the initials badge with its space
+    let badge_width = if app.student_initials { 3 } else { 0 };
+    let text_width = (area.width.saturating_sub(4) as usize).saturating_sub(badge_width);
 
     let items: Vec<ListItem> = app.students
         .iter()
@@ -244,12 +517,16 @@ fn draw_students_list(frame: &mut Frame, app: &App, area: Rect) {
             };
 
             let prefix = if is_selected { "> " } else { "  " };
-            let class_suffix = data.student.class_name
-                .as_ref()
-                .map(|c| format!(" {}", c))
-                .unwrap_or_default();
+            let label = fit_pair(&data.student.name, data.student.class_name.as_deref(), " ", text_width);
 
-            ListItem::new(format!("{}{}{}", prefix, data.student.name, class_suffix))
+            let mut spans = vec![Span::raw(prefix)];
+            if app.student_initials {
+                let badge = Style::default().fg(Color::Black).bg(badge_color(data.student.id)).add_modifier(Modifier::BOLD);
+                spans.push(Span::styled(format!("{:<2}", initials(&data.student.name)), badge));
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::raw(label));
+            ListItem::new(Line::from(spans))
                 .style(style)
         })
         .collect();
@@ -270,6 +547,24 @@ fn draw_students_list(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
+/// Up to two capital letters for a name: the first and last words' initials
+/// ("Мария Петрова Иванова" is МИ), or the first two letters of a single name
+fn initials(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let letters: Vec<char> = match words.as_slice() {
+        [] => Vec::new(),
+        [single] => single.chars().filter(|c| c.is_alphanumeric()).take(2).collect(),
+        [first, .., last] => [first, last].iter().filter_map(|w| w.chars().find(|c| c.is_alphanumeric())).collect(),
+    };
+    letters.into_iter().flat_map(char::to_uppercase).collect()
+}
+
+/// Badge background for a student, the same on every run
+fn badge_color(student_id: i64) -> Color {
+    const COLORS: [Color; 6] = [Color::Cyan, Color::Green, Color::Magenta, Color::LightBlue, Color::LightRed, Color::LightYellow];
+    COLORS[student_id.rem_euclid(COLORS.len() as i64) as usize]
+}
+
 fn draw_overview(frame: &mut Frame, app: &App, area: Rect) {
     // First split: schedule vs rest (configurable)
     let main_chunks = Layout::default()
@@ -289,19 +584,111 @@ fn draw_overview(frame: &mut Frame, app: &App, area: Rect) {
         ])
         .split(main_chunks[1]);
 
-    draw_overview_schedule(frame, app, main_chunks[0]);
-    draw_overview_homework(frame, app, bottom_chunks[0]);
+    // Alerts lines sit on top of the schedule section, so the split borders don't move
+    let top_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(main_chunks[0]);
+
+    // Upcoming exams take the top of the homework section while there are any
+    let exams = app.current_student()
+        .map(|d| d.upcoming_exams(&app.current_date, &app.exam_keywords))
+        .unwrap_or_default();
+    let homework_area = if exams.is_empty() {
+        bottom_chunks[0]
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(exams.len().min(3) as u16 + 2), Constraint::Min(0)])
+            .split(bottom_chunks[0]);
+        draw_overview_exams(frame, app, &exams, chunks[0]);
+        chunks[1]
+    };
+
+    draw_overview_alerts(frame, app, top_chunks[0]);
+    draw_overview_schedule(frame, app, top_chunks[1]);
+    draw_overview_homework(frame, app, homework_area);
     draw_overview_grades(frame, app, bottom_chunks[1]);
 }
 
+/// A row of colored chips summarizing what needs attention for the selected
+/// student, and a row with the last 7 days' absences and feedbacks
+fn draw_overview_alerts(frame: &mut Frame, app: &App, area: Rect) {
+    let lang = app.lang;
+    let alerts = app.current_student()
+        .and_then(|d| d.alerts(&app.current_date, &app.notifications));
+
+    let Some(alerts) = alerts else {
+        frame.render_widget(Paragraph::new(Span::styled(" —", Style::default().fg(Color::DarkGray))), area);
+        return;
+    };
+
+    let chip = |count: usize, label: &str, color: Color| {
+        let style = if count > 0 {
+            Style::default().fg(Color::Black).bg(color).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        Span::styled(format!(" {} {} ", count, label), style)
+    };
+
+    let line = Line::from(vec![
+        Span::raw(" "),
+        chip(alerts.homework_due_today, T::alert_homework_due(lang), Color::Yellow),
+        Span::raw(" "),
+        chip(alerts.new_grades_this_week, T::alert_new_grades(lang), Color::Cyan),
+        Span::raw(" "),
+        chip(alerts.unexcused_absences, T::unexcused(lang), Color::Red),
+        Span::raw(" "),
+        chip(alerts.positive_feedbacks, T::positive(lang), Color::Green),
+        Span::raw(" "),
+        chip(alerts.negative_feedbacks, T::negative(lang), Color::Magenta),
+    ]);
+
+    let week = app.current_student()
+        .map(|d| d.weekly_summary(&app.current_date))
+        .unwrap_or_default();
+    let count = |count: usize, label: &str, color: Color| {
+        let style = if count > 0 { Style::default().fg(color) } else { Style::default().fg(Color::DarkGray) };
+        Span::styled(format!("{} {}", count, label), style)
+    };
+    let dim = Style::default().fg(Color::DarkGray);
+    let week_line = Line::from(vec![
+        Span::styled(format!(" {} ", T::this_week(lang)), dim),
+        count(week.absences, T::absences_count(lang), Color::Yellow),
+        Span::styled(" (", dim),
+        count(week.unexcused_absences, T::unexcused(lang), Color::Red),
+        Span::styled(") · ", dim),
+        count(week.positive_feedbacks, T::positive(lang), Color::Green),
+        Span::styled(" · ", dim),
+        count(week.negative_feedbacks, T::negative(lang), Color::Red),
+    ]);
+
+    frame.render_widget(Paragraph::new(vec![line, week_line]), area);
+}
+
+/// What an empty pane says: that loading failed, when that's why it is empty
+fn empty_pane(app: &App, status: &LoadStatus, empty: &str) -> ListItem<'static> {
+    let lang = app.lang;
+    if status.error().is_none() {
+        return ListItem::new(format!("  {}", empty));
+    }
+    let text = format!(
+        "  ⚠ {} ({} {}, e {})",
+        T::load_failed(lang), app.keymap.label(KeyAction::Refresh), T::to_retry(lang), T::for_details(lang)
+    );
+    ListItem::new(Span::styled(text, Style::default().fg(Color::Yellow)))
+}
+
 fn draw_overview_schedule(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
+    let inner_width = area.width.saturating_sub(2) as usize;
     let current_time = app.current_time;
     let current_minutes = current_time.0 as i32 * 60 + current_time.1 as i32;
 
     let content = if let Some(data) = app.current_student() {
         if data.schedule.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_schedule(lang)))]
+            vec![empty_pane(app, &data.schedule_status, T::no_schedule(lang))]
         } else {
             data.schedule
                 .iter()
@@ -327,9 +714,11 @@ fn draw_overview_schedule(frame: &mut Frame, app: &App, area: Rect) {
 
                     let marker = if is_current { " <" } else { "" };
 
+                    let prefix = format!("  {}. [{}] ", hour.hour_number, time);
+                    let room = inner_width.saturating_sub(prefix.chars().count() + marker.len());
                     let line = format!(
-                        "  {}. [{}] {}{}",
-                        hour.hour_number, time, hour.subject, marker
+                        "{}{}{}",
+                        prefix, fit_pair(&hour.subject, hour.teacher.as_deref(), " · ", room), marker
                     );
 
                     ListItem::new(line).style(style)
@@ -341,7 +730,7 @@ fn draw_overview_schedule(frame: &mut Frame, app: &App, area: Rect) {
     };
 
     let time_str = format!("{:02}:{:02}", current_time.0, current_time.1);
-    let title = format!(" {} ({}) [{}] ", T::today_schedule(lang), app.current_date, time_str);
+    let title = format!(" {} ({}) [{}] ", pane_title(T::today_schedule(lang), app), app.current_date, time_str);
 
     let is_focused = app.focus == Focus::OverviewSchedule;
     let border_style = if is_focused {
@@ -363,10 +752,11 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
     let text_width = area.width.saturating_sub(4) as usize;
     let today = &app.current_date;
+    let today_date = parse_day(today);
 
     let content = if let Some(data) = app.current_student() {
         if data.homework.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_homework(lang)))]
+            vec![empty_pane(app, &data.homework_status, T::no_homework(lang))]
         } else {
             // Sort homework by due date (soonest first) - same logic as Homework tab
             let mut sorted_homework: Vec<_> = data.homework.iter().collect();
@@ -376,27 +766,12 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
                 a_due.cmp(b_due)
             });
 
-            // Determine when school day ends today (from schedule)
-            let school_day_end_minutes = data.schedule.iter()
-                .map(|h| {
-                    let (to_h, to_m) = parse_time(&h.to_time);
-                    to_h * 60 + to_m
-                })
-                .max()
-                .unwrap_or(15 * 60); // Default to 15:00 if no schedule
-
-            let current_minutes = app.current_time.0 as i32 * 60 + app.current_time.1 as i32;
-            let school_day_over = current_minutes > school_day_end_minutes;
+            // Homework due today stays pending until the last lesson ends
+            let school_day_over = school_day_over(&data.schedule, app.current_time, app.school_day_end);
 
             // Split into future and past based on due date AND school day - same logic as Homework tab
-            let (future, mut past): (Vec<_>, Vec<_>) = sorted_homework.into_iter().partition(|hw| {
-                match hw.due_date_sort.as_ref() {
-                    Some(d) if d > today => true,  // Future date
-                    Some(d) if d < today => false, // Past date
-                    Some(_) => !school_day_over,    // Today - depends on school day
-                    None => true,                   // No due date - treat as future
-                }
-            });
+            let (future, mut past): (Vec<_>, Vec<_>) = sorted_homework.into_iter()
+                .partition(|hw| hw.is_pending(today, school_day_over));
 
             // Sort past by descending due date (newest first)
             past.sort_by(|a, b| {
@@ -405,9 +780,10 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
                 b_due.cmp(a_due)
             });
 
-            // Show up to 5 items: prioritize future homework, then fill with past
-            let mut items_to_show: Vec<_> = future.into_iter().take(5).collect();
-            let remaining = 5 - items_to_show.len();
+            // Show up to overview_homework_count items: prioritize future homework, then fill with past
+            let count = app.overview_homework_count;
+            let mut items_to_show: Vec<_> = future.into_iter().take(count).collect();
+            let remaining = count - items_to_show.len();
             if remaining > 0 {
                 items_to_show.extend(past.into_iter().take(remaining));
             }
@@ -415,22 +791,18 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
             items_to_show.iter()
                 .flat_map(|hw| {
                     // Check if homework is still pending (considering school day end)
-                    let is_future = match hw.due_date_sort.as_ref() {
-                        Some(d) if d > today => true,
-                        Some(d) if d < today => false,
-                        Some(_) => !school_day_over, // Today - depends on school day
-                        None => true,
-                    };
+                    let is_future = hw.is_pending(today, school_day_over);
 
-                    let style = if is_future {
-                        Style::default().fg(Color::Green)
+                    let (color, suffix) = if is_future {
+                        pending_homework_style(hw, today_date, lang)
                     } else {
-                        Style::default().fg(Color::DarkGray)
+                        (Color::DarkGray, String::new())
                     };
+                    let style = Style::default().fg(color);
 
                     let due_str = hw.due_date
                         .as_ref()
-                        .map(|d| format!(" -> {}", d))
+                        .map(|d| format!(" -> {}{}", d, suffix))
                         .unwrap_or_default();
 
                     let mut lines = vec![
@@ -441,11 +813,11 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
                     ];
 
                     // Wrap the homework text
-                    for wrapped_line in wrap_text(&hw.text, text_width, "    ") {
+                    for wrapped_line in wrap_text_hanging(&hw.text, text_width, "    ", "      ") {
                         lines.push(Line::from(Span::styled(wrapped_line, style)));
                     }
 
-                    vec![ListItem::new(lines)]
+                    line_items(lines)
                 })
                 .collect()
         }
@@ -460,7 +832,7 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
-    let title = format!(" {} ", T::recent_homework(lang));
+    let title = format!(" {} ", pane_title(T::recent_homework(lang), app));
     let list = List::new(content)
         .block(Block::default()
             .borders(Borders::ALL)
@@ -470,6 +842,54 @@ fn draw_overview_homework(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
+/// Tests announced in homework or events, soonest first, each shown with the
+/// text of the item it was found in
+fn draw_overview_exams(frame: &mut Frame, app: &App, exams: &[Exam], area: Rect) {
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let today = parse_day(&app.current_date);
+
+    let items: Vec<ListItem> = exams.iter()
+        .map(|exam| {
+            let date = parse_day(&exam.date);
+            let color = match (date, today) {
+                (Some(date), Some(today)) if (date - today).whole_days() <= 1 => Color::Red,
+                (Some(date), Some(today)) if (date - today).whole_days() <= 3 => Color::Yellow,
+                _ => Color::Magenta,
+            };
+            let day = date
+                .map(|d| format!("{:02}.{:02}", d.day(), d.month() as u8))
+                .unwrap_or_else(|| exam.date.clone());
+            let prefix = format!("  {} ", day);
+            let text = match &exam.subject {
+                Some(subject) => format!("{}: {}", subject, exam.text.split_whitespace().collect::<Vec<_>>().join(" ")),
+                None => exam.text.clone(),
+            };
+            let room = inner_width.saturating_sub(prefix.chars().count());
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::raw(truncate(&text, room)),
+            ]))
+        })
+        .collect();
+
+    let title = format!(" {} ({}) ", T::upcoming_exams(app.lang), exams.len());
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    frame.render_widget(list, area);
+}
+
+/// Color and due date suffix for homework that is still pending: red when
+/// due today, yellow tomorrow, light yellow within three days
+fn pending_homework_style(hw: &Homework, today: Option<Date>, lang: Lang) -> (Color, String) {
+    match today.and_then(|today| hw.urgency(today)) {
+        Some(Urgency::Today) => (Color::Red, format!(" {}", T::due_today(lang))),
+        Some(Urgency::Tomorrow) => (Color::Yellow, format!(" {}", T::due_tomorrow(lang))),
+        Some(Urgency::Soon) => (Color::LightYellow, String::new()),
+        _ => (Color::Green, String::new()),
+    }
+}
+
 fn draw_overview_grades(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
     let content = if let Some(data) = app.current_student() {
@@ -491,7 +911,7 @@ fn draw_overview_grades(frame: &mut Frame, app: &App, area: Rect) {
             let visible_items = (area.height as usize / estimated_item_height).max(1);
             let scroll = calculate_scroll(app.grades_offset, visible_items, summary.len());
 
-            for (subject, grades) in summary.iter().skip(scroll) {
+            for SubjectSummary { subject, grades, final_grade } in summary.iter().skip(scroll) {
                 // Calculate average for these grades
                 let grade_strings: Vec<String> = grades.iter().map(|s| s.to_string()).collect();
                 let avg = calculate_average(&grade_strings);
@@ -500,6 +920,22 @@ fn draw_overview_grades(frame: &mut Frame, app: &App, area: Rect) {
                     Span::raw(format!("  {}: ", subject)),
                 ];
 
+                // The final grade, once given, before everything else
+                if let Some((kind, value)) = final_grade {
+                    let label = match kind {
+                        FinalKind::Annual => T::annual(lang),
+                        FinalKind::Term => T::final_grade(lang),
+                    };
+                    spans.push(Span::raw(format!("{} ", label)));
+                    spans.push(Span::styled(
+                        value.to_string(),
+                        Style::default().fg(grade_color(value)).add_modifier(Modifier::BOLD),
+                    ));
+                    if !grades.is_empty() {
+                        spans.push(Span::raw(" | "));
+                    }
+                }
+
                 // Average first (colored)
                 if let Some(a) = avg {
                     spans.push(Span::styled(
@@ -509,11 +945,15 @@ fn draw_overview_grades(frame: &mut Frame, app: &App, area: Rect) {
                     spans.push(Span::raw(" <- "));
                 }
 
-                // Individual grades (colored)
-                for (i, g) in grades.iter().enumerate() {
+                // Individual grades (colored), the most recent overview_grades_count of them
+                let shown = app.overview_grades_count.unwrap_or(grades.len());
+                for (i, g) in grades.iter().take(shown).enumerate() {
                     if i > 0 { spans.push(Span::raw(", ")); }
                     spans.push(Span::styled(g.to_string(), Style::default().fg(grade_color(g))));
                 }
+                if grades.len() > shown {
+                    spans.push(Span::styled(", …", Style::default().fg(Color::DarkGray)));
+                }
 
                 items.push(ListItem::new(Line::from(spans)));
             }
@@ -531,7 +971,7 @@ fn draw_overview_grades(frame: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
-    let title = format!(" {} ", T::grades_summary(lang));
+    let title = format!(" {} ", pane_title(T::grades_summary(lang), app));
     let list = List::new(content)
         .block(Block::default()
             .borders(Borders::ALL)
@@ -545,46 +985,16 @@ fn draw_homework(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
     let text_width = area.width.saturating_sub(4) as usize; // Account for borders and padding
     let today = &app.current_date;
+    let today_date = parse_day(today);
 
     let content = if let Some(data) = app.current_student() {
         if data.homework.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_homework(lang)))]
+            vec![empty_pane(app, &data.homework_status, T::no_homework(lang))]
         } else {
-            // Determine when school day ends today (from schedule)
-            let school_day_end_minutes = data.schedule.iter()
-                .map(|h| {
-                    let (to_h, to_m) = parse_time(&h.to_time);
-                    to_h * 60 + to_m
-                })
-                .max()
-                .unwrap_or(15 * 60); // Default to 15:00 if no schedule
-
-            let current_minutes = app.current_time.0 as i32 * 60 + app.current_time.1 as i32;
-            let school_day_over = current_minutes > school_day_end_minutes;
-
-            // Split into future and past based on due date AND school day
-            let (mut future, mut past): (Vec<_>, Vec<_>) = data.homework.iter().partition(|hw| {
-                match hw.due_date_sort.as_ref() {
-                    Some(d) if d > today => true,  // Future date
-                    Some(d) if d < today => false, // Past date
-                    Some(_) => !school_day_over,    // Today - depends on school day
-                    None => true,                   // No due date - treat as future
-                }
-            });
-
-            // Sort future by due date (soonest first)
-            future.sort_by(|a, b| {
-                let a_due = a.due_date_sort.as_deref().unwrap_or("9999-99-99");
-                let b_due = b.due_date_sort.as_deref().unwrap_or("9999-99-99");
-                a_due.cmp(b_due)
-            });
-
-            // Sort past by due date (newest/most recent first)
-            past.sort_by(|a, b| {
-                let a_due = a.due_date_sort.as_deref().unwrap_or("0000-00-00");
-                let b_due = b.due_date_sort.as_deref().unwrap_or("0000-00-00");
-                b_due.cmp(a_due) // Reversed for newest first
-            });
+            // Pending by due date (soonest first), then past (newest first)
+            let (future, past) = app.homework_sections();
+            let new_keys = app.new_keys(Tab::Homework);
+            let is_new = |hw: &Homework| new_keys.contains(&tracking::homework_key(hw));
 
             // Build combined list of items with divider
             #[derive(Clone)]
@@ -622,28 +1032,29 @@ fn draw_homework(frame: &mut Frame, app: &App, area: Rect) {
             for item in all_items.into_iter().skip(scroll) {
                 match item {
                     HomeworkItem::Future(hw) => {
+                        let (color, suffix) = pending_homework_style(hw, today_date, lang);
                         let due_str = hw.due_date
                             .as_ref()
-                            .map(|d| format!(" -> Due: {}", d))
+                            .map(|d| format!(" -> Due: {}{}", d, suffix))
                             .unwrap_or_default();
 
-                        let mut lines = vec![
-                            Line::from(Span::styled(
-                                format!("  [{}] {}{}", hw.date, hw.subject, due_str),
-                                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-                            )),
-                        ];
+                        let mut header = vec![Span::styled(
+                            format!("  [{}] {}{}", hw.date, hw.subject, due_str),
+                            Style::default().fg(color).add_modifier(Modifier::BOLD),
+                        )];
+                        header.extend(is_new(hw).then(|| new_item_span(lang)));
+                        let mut lines = vec![Line::from(header)];
 
                         // Wrap the homework text
-                        for wrapped_line in wrap_text(&hw.text, text_width, "    ") {
+                        for wrapped_line in wrap_text_hanging(&hw.text, text_width, "    ", "      ") {
                             lines.push(Line::from(Span::styled(
                                 wrapped_line,
-                                Style::default().fg(Color::Green),
+                                Style::default().fg(color),
                             )));
                         }
                         lines.push(Line::from(""));
 
-                        items.push(ListItem::new(lines));
+                        items.extend(line_items(lines));
                     }
                     HomeworkItem::Divider => {
                         let divider = format!("  ─────────────── {} ───────────────", T::past_due(lang));
@@ -658,15 +1069,15 @@ fn draw_homework(frame: &mut Frame, app: &App, area: Rect) {
                             .map(|d| format!(" -> Due: {}", d))
                             .unwrap_or_default();
 
-                        let mut lines = vec![
-                            Line::from(Span::styled(
-                                format!("  [{}] {}{}", hw.date, hw.subject, due_str),
-                                Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
-                            )),
-                        ];
+                        let mut header = vec![Span::styled(
+                            format!("  [{}] {}{}", hw.date, hw.subject, due_str),
+                            Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
+                        )];
+                        header.extend(is_new(hw).then(|| new_item_span(lang)));
+                        let mut lines = vec![Line::from(header)];
 
                         // Wrap the homework text
-                        for wrapped_line in wrap_text(&hw.text, text_width, "    ") {
+                        for wrapped_line in wrap_text_hanging(&hw.text, text_width, "    ", "      ") {
                             lines.push(Line::from(Span::styled(
                                 wrapped_line,
                                 Style::default().fg(Color::DarkGray),
@@ -674,7 +1085,7 @@ fn draw_homework(frame: &mut Frame, app: &App, area: Rect) {
                         }
                         lines.push(Line::from(""));
 
-                        items.push(ListItem::new(lines));
+                        items.extend(line_items(lines));
                     }
                 }
             }
@@ -685,11 +1096,8 @@ fn draw_homework(frame: &mut Frame, app: &App, area: Rect) {
         vec![ListItem::new(format!("  {}", T::no_student(lang)))]
     };
 
-    let age = app.current_student()
-        .and_then(|d| d.homework_age.clone())
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let title = format!(" {} ({}) ", T::homework(lang), age);
+    let age = age_span(app, app.current_student().and_then(|d| d.homework_age), Some(app.cache_ttl));
+    let title = title_with_age(format!(" {} (", pane_title(T::homework(lang), app)), age, ") ");
 
     let is_focused = app.focus == Focus::Content;
     let border_style = if is_focused {
@@ -710,26 +1118,32 @@ fn draw_homework(frame: &mut Frame, app: &App, area: Rect) {
 fn draw_grades(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
     let content = if let Some(data) = app.current_student() {
-        if data.grades.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_grades(lang)))]
+        if app.grades_report {
+            draw_grades_report(app, data, area)
+        } else if data.grades.is_empty() {
+            vec![empty_pane(app, &data.grades_status, T::no_grades(lang))]
+        } else if app.grades_timeline {
+            draw_grades_timeline(app, data, area)
         } else {
             // Calculate scroll position with center-biased scrolling
             // Each grade entry takes ~5 lines
             let estimated_item_height = 5;
             let visible_items = (area.height as usize / estimated_item_height).max(1);
             let scroll = calculate_scroll(app.list_offset, visible_items, data.grades.len());
+            let new_keys = app.new_keys(Tab::Grades);
 
             data.grades
                 .iter()
                 .skip(scroll)
-                .take(area.height.saturating_sub(2) as usize / 5)
-                .map(|grade| {
-                    let mut lines = vec![
-                        Line::from(Span::styled(
-                            format!("  {}", grade.subject),
-                            Style::default().add_modifier(Modifier::BOLD),
-                        )),
-                    ];
+                .take((area.height.saturating_sub(2) as usize / 5).max(1))
+                .flat_map(|grade| {
+                    let mut header = vec![Span::styled(
+                        format!("  {}", grade.subject),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )];
+                    let has_new = tracking::grade_keys(std::slice::from_ref(grade)).iter().any(|key| new_keys.contains(key));
+                    header.extend(has_new.then(|| new_item_span(lang)));
+                    let mut lines = vec![Line::from(header)];
 
                     // Term 1: Show average first, then grades
                     if !grade.term1_grades.is_empty() {
@@ -742,6 +1156,7 @@ fn draw_grades(frame: &mut Frame, app: &App, area: Rect) {
                                 format!("{:.2}", a),
                                 Style::default().fg(average_color(a)).add_modifier(Modifier::BOLD),
                             ));
+                            spans.extend(weighted_average_span(grade, 1, a, lang));
                             spans.push(Span::raw(" <- "));
                         }
 
@@ -772,6 +1187,7 @@ fn draw_grades(frame: &mut Frame, app: &App, area: Rect) {
                                 format!("{:.2}", a),
                                 Style::default().fg(average_color(a)).add_modifier(Modifier::BOLD),
                             ));
+                            spans.extend(weighted_average_span(grade, 2, a, lang));
                             spans.push(Span::raw(" <- "));
                         }
 
@@ -800,7 +1216,7 @@ fn draw_grades(frame: &mut Frame, app: &App, area: Rect) {
 
                     lines.push(Line::from(""));
 
-                    ListItem::new(lines)
+                    line_items(lines)
                 })
                 .collect()
         }
@@ -808,11 +1224,14 @@ fn draw_grades(frame: &mut Frame, app: &App, area: Rect) {
         vec![ListItem::new(format!("  {}", T::no_student(lang)))]
     };
 
-    let age = app.current_student()
-        .and_then(|d| d.grades_age.clone())
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let title = format!(" {} ({}) ", T::grades(lang), age);
+    let age = age_span(app, app.current_student().and_then(|d| d.grades_age), Some(app.cache_ttl));
+    let title = if app.grades_report {
+        title_with_age(format!(" {} (", pane_title(&format!("{} - {}", T::grades(lang), T::grades_report_title(lang)), app)), age, ") ")
+    } else if app.grades_timeline {
+        title_with_age(format!(" {} (", pane_title(&format!("{} - {}", T::grades(lang), T::grades_timeline_title(lang)), app)), age, ") ")
+    } else {
+        title_with_age(format!(" {} (", pane_title(T::grades(lang), app)), age, ") ")
+    };
 
     let is_focused = app.focus == Focus::Content;
     let border_style = if is_focused {
@@ -830,19 +1249,130 @@ fn draw_grades(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
+/// Grades tab timeline: one line per grade, newest first
+fn draw_grades_timeline<'a>(app: &App, data: &'a StudentData, area: Rect) -> Vec<ListItem<'a>> {
+    let lang = app.lang;
+    let timeline = data.grades_timeline();
+    if timeline.is_empty() {
+        return vec![ListItem::new(format!("  {}", T::no_grades(lang)))];
+    }
+
+    let visible_items = (area.height.saturating_sub(2) as usize).max(1);
+    let scroll = calculate_scroll(app.list_offset, visible_items, timeline.len());
+    let value_width = timeline.iter().map(|g| g.value.chars().count()).max().unwrap_or(1);
+    // Marker, value column and the space after it
+    let text_width = (area.width.saturating_sub(2) as usize).saturating_sub(2 + value_width + 1);
+    let new_keys = app.new_keys(Tab::Grades);
+
+    timeline
+        .into_iter()
+        .enumerate()
+        .skip(scroll)
+        .map(|(idx, grade)| {
+            let is_selected = idx == app.list_offset;
+            let bg = if is_selected { Color::Rgb(40, 40, 50) } else { Color::Reset };
+            let marker_style = if is_selected { Style::default().fg(Color::Yellow).bg(bg) } else { Style::default() };
+            let term = if grade.term == 1 { T::term1(lang) } else { T::term2(lang) };
+            let new_item = new_keys.contains(&grade.key).then(|| new_item_span(lang));
+            let text_width = text_width.saturating_sub(new_item.as_ref().map_or(0, |s| s.width()));
+            let mut spans = vec![
+                Span::styled(if is_selected { "▸ " } else { "  " }, marker_style),
+                Span::styled(
+                    format!("{:<width$} ", grade.value, width = value_width),
+                    Style::default().fg(grade_color(grade.value)).bg(bg).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(fit_pair(grade.subject, Some(term), " · ", text_width), Style::default().bg(bg)),
+            ];
+            spans.extend(new_item);
+            ListItem::new(Line::from(spans))
+        })
+        .collect()
+}
+
+/// Grades tab table of average, grade count and absences per subject,
+/// riskiest first, under a header row
+fn draw_grades_report(app: &App, data: &StudentData, area: Rect) -> Vec<ListItem<'static>> {
+    let lang = app.lang;
+    let report = data.subject_report();
+    if report.is_empty() {
+        return vec![empty_pane(app, &data.grades_status, T::no_grades(lang))];
+    }
+
+    const COLUMN: usize = 8;
+    let subject_width = (area.width.saturating_sub(2) as usize).saturating_sub(2 + 4 * COLUMN).max(8);
+    let column = |text: &str| format!("{:>width$}", truncate(text, COLUMN - 1), width = COLUMN);
+    let header = Line::from(Span::styled(
+        format!(
+            "  {:<width$}{}{}{}{}",
+            truncate(T::report_subject(lang), subject_width),
+            column(T::report_average(lang)),
+            column(T::report_grades(lang)),
+            column(T::report_excused(lang)),
+            column(T::report_unexcused(lang)),
+            width = subject_width,
+        ),
+        Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
+    ));
+
+    let visible_items = (area.height.saturating_sub(3) as usize).max(1);
+    let scroll = calculate_scroll(app.list_offset, visible_items, report.len());
+    let mut items = vec![ListItem::new(header)];
+    items.extend(report.into_iter().enumerate().skip(scroll).map(|(idx, row)| {
+        let is_selected = idx == app.list_offset;
+        let bg = if is_selected { Color::Rgb(40, 40, 50) } else { Color::Reset };
+        let marker_style = if is_selected { Style::default().fg(Color::Yellow).bg(bg) } else { Style::default() };
+        let average = row.average.map_or_else(|| "-".to_string(), |a| format!("{:.2}", a));
+        let average_style = row.average.map_or(Style::default().fg(Color::DarkGray), |a| Style::default().fg(average_color(a)).add_modifier(Modifier::BOLD));
+        let count_style = |count: usize, color: Color| if count > 0 { Style::default().fg(color).bg(bg) } else { Style::default().fg(Color::DarkGray).bg(bg) };
+        ListItem::new(Line::from(vec![
+            Span::styled(if is_selected { "▸ " } else { "  " }, marker_style),
+            Span::styled(
+                format!("{:<width$}", truncate(&row.subject, subject_width), width = subject_width),
+                Style::default().bg(bg).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(column(&average), average_style.bg(bg)),
+            Span::styled(column(&row.grade_count.to_string()), Style::default().bg(bg)),
+            Span::styled(column(&row.excused.to_string()), count_style(row.excused, Color::Green)),
+            Span::styled(column(&row.unexcused.to_string()), count_style(row.unexcused, Color::Red)),
+        ]))
+    }));
+    items
+}
+
+/// " NEW" after a row that appeared since its tab was last viewed
+fn new_item_span(lang: Lang) -> Span<'static> {
+    Span::styled(format!(" {}", T::new_item(lang)), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+}
+
 fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
+
+    // If entering a date, split the area for the prompt
+    let (area, input_area) = if app.input_mode == InputMode::DateEntry {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    } else {
+        (area, None)
+    };
+    let inner_width = area.width.saturating_sub(2) as usize;
     let current_time = app.current_time;
     let current_minutes = current_time.0 as i32 * 60 + current_time.1 as i32;
     let is_today = app.is_schedule_today();
 
     let content = if let Some(data) = app.current_student() {
         if data.schedule.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_schedule(lang)))]
+            let empty = if app.schedule_day().is_some_and(is_weekend) { T::day_off(lang) } else { T::no_schedule(lang) };
+            vec![empty_pane(app, &data.schedule_status, empty)]
         } else {
             data.schedule
                 .iter()
-                .map(|hour| {
+                .flat_map(|hour| {
                     let (from_h, from_m) = parse_time(&hour.from_time);
                     let (to_h, to_m) = parse_time(&hour.to_time);
                     let from_mins = from_h * 60 + from_m;
@@ -870,16 +1400,20 @@ fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
 
                     let marker = if is_current { " <NOW" } else { "" };
 
+                    let prefix = format!("  {}. [{}] ", hour.hour_number, time);
+                    let room = inner_width.saturating_sub(prefix.chars().count() + marker.len());
                     let mut lines = vec![
                         Line::from(Span::styled(
-                            format!("  {}. [{}] {}{}", hour.hour_number, time, hour.subject, marker),
+                            format!("{}{}{}", prefix, truncate(&hour.subject, room), marker),
                             header_style,
                         )),
                     ];
 
                     if let Some(ref teacher) = hour.teacher {
+                        let label = format!("     {}: ", T::teacher(lang));
+                        let room = inner_width.saturating_sub(label.chars().count());
                         lines.push(Line::from(Span::styled(
-                            format!("     {}: {}", T::teacher(lang), teacher),
+                            format!("{}{}", label, truncate(teacher, room)),
                             detail_style,
                         )));
                     }
@@ -900,7 +1434,7 @@ fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
 
                     lines.push(Line::from(""));
 
-                    ListItem::new(lines)
+                    line_items(lines)
                 })
                 .collect()
         }
@@ -908,22 +1442,16 @@ fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
         vec![ListItem::new(format!("  {}", T::no_student(lang)))]
     };
 
-    let age = app.current_student()
-        .and_then(|d| d.schedule_age.clone())
-        .unwrap_or_else(|| "unknown".to_string());
+    let age = age_span(app, app.current_student().and_then(|d| d.schedule_age), app.schedule_ttl());
 
-    // Format date for display (YYYY-MM-DD -> DD.MM.YYYY)
-    let display_date = {
-        let parts: Vec<&str> = app.schedule_date.split('-').collect();
-        if parts.len() == 3 {
-            format!("{}.{}.{}", parts[2], parts[1], parts[0])
-        } else {
-            app.schedule_date.clone()
-        }
+    // Format date for display (YYYY-MM-DD -> weekday DD.MM)
+    let display_date = match app.schedule_day() {
+        Some(date) => format!("{} {:02}.{:02}", T::weekday(lang, date.weekday()), date.day(), date.month() as u8),
+        None => app.schedule_date.clone(),
     };
 
     let today_marker = if is_today {
-        match lang { crate::i18n::Lang::Bg => " (днес)", crate::i18n::Lang::En => " (today)" }
+        T::today_marker(lang)
     } else { "" };
 
     let time_str = if is_today {
@@ -932,12 +1460,13 @@ fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
         String::new()
     };
 
-    let nav_hint = match lang {
-        crate::i18n::Lang::Bg => " [p/n]-ден [t]-днес",
-        crate::i18n::Lang::En => " [p/n]-day [t]-today",
-    };
+    let nav_hint = T::schedule_nav_hint(lang);
 
-    let title = format!(" {} {}{} ({}){}{}  ", T::schedule(lang), display_date, today_marker, age, time_str, nav_hint);
+    let title = title_with_age(
+        format!(" {} {}{} (", pane_title(T::schedule(lang), app), display_date, today_marker),
+        age,
+        &format!("){}{}  ", time_str, nav_hint),
+    );
 
     let is_focused = app.focus == Focus::Content;
     let border_style = if is_focused {
@@ -953,6 +1482,27 @@ fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
             .title(title));
 
     frame.render_widget(list, area);
+
+    // Draw date entry prompt
+    if let Some(input_rect) = input_area {
+        let (input_title, input_color) = if app.date_entry_error {
+            (T::date_entry_invalid(lang), Color::Red)
+        } else {
+            (T::date_entry_title(lang), Color::Green)
+        };
+
+        let cursor_x = input_rect.x + 1 + app.input_cursor as u16;
+        let cursor_y = input_rect.y + 1;
+
+        let input = Paragraph::new(app.input_buffer.as_str())
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(input_color))
+                .title(input_title));
+
+        frame.render_widget(input, input_rect);
+        frame.set_cursor_position((cursor_x, cursor_y));
+    }
 }
 
 fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
@@ -960,7 +1510,7 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
 
     let content = if let Some(data) = app.current_student() {
         if data.absences.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_absences(lang)))]
+            vec![empty_pane(app, &data.absences_status, T::no_absences(lang))]
         } else {
             // Build all items first, then apply scroll to entire list
             let mut all_items: Vec<(Vec<Line>, bool)> = Vec::new(); // (lines, is_selectable)
@@ -973,7 +1523,7 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
             // Overall summary (not selectable)
             all_items.push((vec![Line::from(vec![
                 Span::styled(
-                    format!("  {}: ", match lang { crate::i18n::Lang::Bg => "Общо", crate::i18n::Lang::En => "Total" }),
+                    format!("  {}: ", T::total(lang)),
                     Style::default().add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
@@ -987,36 +1537,30 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
                 Span::raw(")"),
             ])], false));
 
-            all_items.push((vec![Line::from("")], false));
-
-            // Per-subject summary
-            let mut subject_counts: std::collections::HashMap<String, (usize, usize)> = std::collections::HashMap::new();
-            for absence in &data.absences {
-                let entry = subject_counts.entry(absence.subject.clone()).or_insert((0, 0));
-                if absence.is_excused {
-                    entry.0 += 1;
-                } else {
-                    entry.1 += 1;
-                }
+            // Unexcused absences that can only be excused for another day or two
+            let today = parse_day(&app.current_date);
+            let deadline_soon = |absence: &Absence| today.is_some_and(|today| absence.excuse_deadline_soon(today, app.absence_excuse_days));
+            let expiring = data.absences.iter().filter(|a| deadline_soon(a)).count();
+            if expiring > 0 {
+                all_items.push((vec![Line::from(Span::styled(
+                    format!("  {} {}", expiring, T::expiring_unexcused(lang)),
+                    Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+                ))], false));
             }
 
-            let mut subjects: Vec<_> = subject_counts.into_iter().collect();
-            subjects.sort_by(|a, b| {
-                let total_a = a.1.0 + a.1.1;
-                let total_b = b.1.0 + b.1.1;
-                total_b.cmp(&total_a).then_with(|| a.0.cmp(&b.0))
-            });
+            all_items.push((vec![Line::from("")], false));
 
-            for (subject, (excused, unexcused)) in &subjects {
-                let total_subj = excused + unexcused;
+            // Per-subject summary
+            let boundary = term_boundary(today.unwrap_or_else(|| util::now_local().date()));
+            for subject in absence_summary(&data.absences, boundary) {
                 all_items.push((vec![Line::from(vec![
                     Span::raw("  "),
-                    Span::styled(format!("{}: ", subject), Style::default().add_modifier(Modifier::BOLD)),
-                    Span::styled(format!("{} ", total_subj), Style::default()),
+                    Span::styled(format!("{}: ", subject.subject), Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(format!("{} ", subject.total()), Style::default()),
                     Span::raw("("),
-                    Span::styled(format!("{}", excused), Style::default().fg(Color::Green)),
+                    Span::styled(format!("{}", subject.total_excused()), Style::default().fg(Color::Green)),
                     Span::raw("/"),
-                    Span::styled(format!("{}", unexcused), Style::default().fg(Color::Red)),
+                    Span::styled(format!("{}", subject.total_unexcused()), Style::default().fg(Color::Red)),
                     Span::raw(")"),
                 ])], false));
             }
@@ -1030,9 +1574,7 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
 
             // Detailed list grouped by date - these are selectable
             let mut current_date = String::new();
-            let mut absence_index = 0usize;
-
-            for absence in &data.absences {
+            for (absence_index, absence) in data.absences.iter().enumerate() {
                 // Add date header if new date (not selectable)
                 if absence.date != current_date {
                     if !current_date.is_empty() {
@@ -1050,8 +1592,11 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
                 let bg = if is_selected { Color::Rgb(40, 40, 50) } else { Color::Reset };
                 let selected_marker = if is_selected { "▸ " } else { "  " };
 
+                let is_deadline_soon = deadline_soon(absence);
                 let status_style = if absence.is_excused {
                     Style::default().fg(Color::Green).bg(bg)
+                } else if is_deadline_soon {
+                    Style::default().fg(Color::LightRed).bg(bg).add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(Color::Red).bg(bg)
                 };
@@ -1079,6 +1624,13 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
                     ]),
                 ];
 
+                if let Some(deadline) = absence.excuse_deadline(app.absence_excuse_days).filter(|_| is_deadline_soon) {
+                    lines.push(Line::from(Span::styled(
+                        format!("      {} ({:02}.{:02})", T::excuse_deadline_soon(lang), deadline.day(), deadline.month() as u8),
+                        status_style,
+                    )));
+                }
+
                 // Show excuse reason if present
                 if let Some(ref reason) = absence.excuse_reason {
                     if !reason.is_empty() {
@@ -1090,7 +1642,6 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
                 }
 
                 all_items.push((lines, true));
-                absence_index += 1;
             }
 
             // Calculate scroll for entire list
@@ -1101,18 +1652,15 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
             // Convert to ListItems with scroll applied
             all_items.into_iter()
                 .skip(scroll)
-                .map(|(lines, _)| ListItem::new(lines))
+                .flat_map(|(lines, _)| line_items(lines))
                 .collect()
         }
     } else {
         vec![ListItem::new(format!("  {}", T::no_student(lang)))]
     };
 
-    let age = app.current_student()
-        .and_then(|d| d.absences_age.clone())
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let title = format!(" {} ({}) ", T::absences(lang), age);
+    let age = age_span(app, app.current_student().and_then(|d| d.absences_age), Some(app.cache_ttl));
+    let title = title_with_age(format!(" {} (", pane_title(T::absences(lang), app)), age, ") ");
 
     let is_focused = app.focus == Focus::Content;
     let border_style = if is_focused {
@@ -1133,9 +1681,23 @@ fn draw_absences(frame: &mut Frame, app: &App, area: Rect) {
 fn draw_feedbacks(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
 
+    // If entering a subject filter, split the area for the prompt
+    let (area, input_area) = if app.input_mode == InputMode::FeedbackSubject {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    } else {
+        (area, None)
+    };
+
     let content = if let Some(data) = app.current_student() {
         if data.feedbacks.is_empty() {
-            vec![ListItem::new(format!("  {}", T::no_feedbacks(lang)))]
+            vec![empty_pane(app, &data.feedbacks_status, T::no_feedbacks(lang))]
         } else {
             let mut items = Vec::new();
 
@@ -1145,7 +1707,7 @@ fn draw_feedbacks(frame: &mut Frame, app: &App, area: Rect) {
 
             items.push(ListItem::new(Line::from(vec![
                 Span::styled(
-                    format!("  {}: ", match lang { crate::i18n::Lang::Bg => "Общо", crate::i18n::Lang::En => "Total" }),
+                    format!("  {}: ", T::total(lang)),
                     Style::default().add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
@@ -1166,14 +1728,18 @@ fn draw_feedbacks(frame: &mut Frame, app: &App, area: Rect) {
             ))));
             items.push(ListItem::new(""));
 
-            // List feedbacks with center-biased scrolling
+            // List feedbacks passing the filters with center-biased scrolling
+            let feedbacks = app.visible_feedbacks();
+            if feedbacks.is_empty() {
+                items.push(ListItem::new(format!("  {}", T::no_matching_feedbacks(lang))));
+            }
             let estimated_item_height = 3;
             let visible_items = (area.height as usize / estimated_item_height).max(1);
-            let scroll = calculate_scroll(app.list_offset, visible_items, data.feedbacks.len());
+            let scroll = calculate_scroll(app.list_offset, visible_items, feedbacks.len());
 
-            for (idx, feedback) in data.feedbacks.iter().enumerate().skip(scroll) {
+            for (idx, feedback) in feedbacks.into_iter().enumerate().skip(scroll) {
                 let is_selected = idx == app.list_offset;
-                let emoji = feedback.emoji();
+                let emoji = if app.ascii { feedback.ascii_marker().to_string() } else { feedback.emoji() };
 
                 // Base style depends on positive/negative
                 let base_style = if feedback.is_positive {
@@ -1235,11 +1801,21 @@ fn draw_feedbacks(frame: &mut Frame, app: &App, area: Rect) {
         vec![ListItem::new(format!("  {}", T::no_student(lang)))]
     };
 
-    let age = app.current_student()
-        .and_then(|d| d.feedbacks_age.clone())
-        .unwrap_or_else(|| "unknown".to_string());
+    let age = age_span(app, app.current_student().and_then(|d| d.feedbacks_age), Some(app.cache_ttl));
 
-    let title = format!(" {} ({}) ", T::feedbacks(lang), age);
+    // Active filters, e.g. " Feedbacks [positive, Math] (5 min ago) "
+    let mut filters = Vec::new();
+    match app.feedback_filter {
+        FeedbackFilter::All => {}
+        FeedbackFilter::Positive => filters.push(T::positive(lang).to_string()),
+        FeedbackFilter::Negative => filters.push(T::negative(lang).to_string()),
+    }
+    filters.extend(app.feedback_subject.clone());
+    let title = if filters.is_empty() {
+        title_with_age(format!(" {} (", pane_title(T::feedbacks(lang), app)), age, ") ")
+    } else {
+        title_with_age(format!(" {} [{}] (", pane_title(T::feedbacks(lang), app), filters.join(", ")), age, ") ")
+    };
 
     let is_focused = app.focus == Focus::Content;
     let border_style = if is_focused {
@@ -1255,6 +1831,18 @@ fn draw_feedbacks(frame: &mut Frame, app: &App, area: Rect) {
             .title(title));
 
     frame.render_widget(list, area);
+
+    // Draw subject filter prompt
+    if let Some(input_rect) = input_area {
+        let input = Paragraph::new(app.input_buffer.as_str())
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green))
+                .title(T::subject_filter_title(lang)));
+
+        frame.render_widget(input, input_rect);
+        frame.set_cursor_position((input_rect.x + 1 + app.input_cursor as u16, input_rect.y + 1));
+    }
 }
 
 fn draw_messages(frame: &mut Frame, app: &App, area: Rect) {
@@ -1283,7 +1871,7 @@ fn draw_message_list(frame: &mut Frame, app: &App, area: Rect) {
             .iter()
             .enumerate()
             .skip(scroll)
-            .map(|(idx, msg)| {
+            .flat_map(|(idx, msg)| {
                 let is_selected = idx == app.list_offset;
 
                 // Base style depends on read/unread status
@@ -1311,8 +1899,11 @@ fn draw_message_list(frame: &mut Frame, app: &App, area: Rect) {
 
                 let mut lines = Vec::new();
 
-                // Subject line with unread marker
-                let subject_text = format!("{}{}{}", selected_marker, unread_marker, msg.subject);
+                // Subject line with unread marker, and a marker for a saved reply
+                let mut subject_text = format!("{}{}{}", selected_marker, unread_marker, msg.subject);
+                if app.drafts.get(Some(msg.id)).is_some() {
+                    subject_text = format!("{} {}", subject_text, T::draft_marker(lang));
+                }
                 for wrapped_line in wrap_text(&subject_text, text_width, "  ") {
                     lines.push(Line::from(Span::styled(wrapped_line, style)));
                 }
@@ -1332,7 +1923,7 @@ fn draw_message_list(frame: &mut Frame, app: &App, area: Rect) {
                     msg.last_sender,
                     msg.participant_count,
                     T::participants(lang),
-                    msg.display_time()
+                    msg.display_time(app.today(), lang)
                 );
                 lines.push(Line::from(Span::styled(
                     sender_info,
@@ -1341,24 +1932,25 @@ fn draw_message_list(frame: &mut Frame, app: &App, area: Rect) {
 
                 lines.push(Line::from(""));
 
-                ListItem::new(lines)
+                line_items(lines)
             })
             .collect()
     };
 
-    let age = app.messages_age
-        .clone()
-        .unwrap_or_else(|| "unknown".to_string());
+    let age = age_span(app, app.messages_age, Some(app.cache_ttl));
 
-    let unread_count = app.messages.iter().filter(|m| m.is_unread).count();
-    let hints = match lang {
-        crate::i18n::Lang::Bg => "[Enter]-отвори [c]-ново",
-        crate::i18n::Lang::En => "[Enter]-open [c]-new",
-    };
+    let unread_count = app.unread_in_folder();
+    let mut hints = T::messages_hint(lang).to_string();
+    if app.shows_folder_row() {
+        hints = format!("{} {}", hints, T::folder_hint(lang));
+    }
+    if app.drafts.get(None).is_some() {
+        hints = format!("{} {} {}", hints, T::draft_marker(lang), T::draft_resume_hint(lang));
+    }
     let title = if unread_count > 0 {
-        format!(" {} ({} {}) ({}) {} ", T::messages(lang), unread_count, T::unread(lang), age, hints)
+        title_with_age(format!(" {} ({} {}) (", T::messages(lang), unread_count, T::unread(lang)), age, &format!(") {} ", hints))
     } else {
-        format!(" {} ({}) {} ", T::messages(lang), age, hints)
+        title_with_age(format!(" {} (", T::messages(lang)), age, &format!(") {} ", hints))
     };
 
     let is_focused = app.focus == Focus::Content;
@@ -1368,13 +1960,57 @@ fn draw_message_list(frame: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
-    let list = List::new(content)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(border_style)
-            .title(title));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(title);
 
-    frame.render_widget(list, area);
+    if !app.shows_folder_row() {
+        frame.render_widget(List::new(content).block(block), area);
+        return;
+    }
+
+    // Folder selector on the first row inside the border, the threads below it
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
+    let mut folders = vec![Span::raw(" ")];
+    for (i, (folder, label)) in app.folder_choices().into_iter().enumerate() {
+        if i > 0 {
+            folders.push(Span::styled(app.folder_separator(), Style::default().fg(Color::DarkGray)));
+        }
+        let style = if folder == app.selected_folder {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        folders.push(Span::styled(label, style));
+    }
+    frame.render_widget(Paragraph::new(Line::from(folders)), rows[0]);
+    frame.render_widget(List::new(content), rows[1]);
+}
+
+/// First message to draw so the selected one is fully visible: the newest
+/// message sits at the bottom with older ones filling the space above it, the
+/// oldest at the top, and one in between gets the leftover space split around it
+fn thread_scroll(heights: &[usize], selected: usize, visible_height: usize) -> usize {
+    let Some(&selected_height) = heights.get(selected) else {
+        return 0;
+    };
+    let spare = visible_height.saturating_sub(selected_height);
+    let below: usize = heights[selected + 1..].iter().sum();
+    let mut room_above = spare - below.min(spare / 2);
+
+    let mut first = selected;
+    while first > 0 && heights[first - 1] <= room_above {
+        room_above -= heights[first - 1];
+        first -= 1;
+    }
+    first
 }
 
 fn draw_message_thread(frame: &mut Frame, app: &App, area: Rect) {
@@ -1402,20 +2038,41 @@ fn draw_message_thread(frame: &mut Frame, app: &App, area: Rect) {
         .map(|m| m.subject.clone())
         .unwrap_or_else(|| "Thread".to_string());
 
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(messages_area);
+
+    // Who is in the conversation, on a row under the subject
+    let participants = app.participant_labels();
+    let (participants_area, list_area) = if participants.is_empty() {
+        (None, inner)
+    } else {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+        (Some(rows[0]), rows[1])
+    };
+
     // Build message content
+    let today = app.today();
     let content: Vec<ListItem> = if app.thread_messages.is_empty() {
         vec![ListItem::new(format!("  {}", T::loading(lang)))]
     } else {
-        // Calculate scroll position with center-biased scrolling
-        let estimated_item_height = 4;
-        let visible_items = (messages_area.height as usize / estimated_item_height).max(1);
-        let scroll = calculate_scroll(app.thread_offset, visible_items, app.thread_messages.len());
+        // Sender line, body and a blank line per message
+        let bodies: Vec<Vec<String>> = app.thread_messages.iter()
+            .map(|msg| wrap_text(&msg.body, text_width, "    "))
+            .collect();
+        let heights: Vec<usize> = bodies.iter().map(|body| body.len() + 2).collect();
+        let scroll = thread_scroll(&heights, app.thread_offset, list_area.height as usize);
 
         app.thread_messages
             .iter()
+            .zip(bodies)
             .enumerate()
             .skip(scroll)
-            .map(|(idx, msg)| {
+            .flat_map(|(idx, (msg, body))| {
                 let is_selected = idx == app.thread_offset;
                 let mut lines = Vec::new();
 
@@ -1437,45 +2094,41 @@ fn draw_message_thread(frame: &mut Frame, app: &App, area: Rect) {
                         sender_style,
                     ),
                     Span::styled(
-                        msg.date.clone(),
+                        msg.display_time(today, lang),
                         Style::default().fg(Color::DarkGray).bg(bg),
                     ),
                 ]));
 
                 // Message body
                 let body_style = Style::default().bg(bg);
-                for wrapped_line in wrap_text(&msg.body, text_width, "    ") {
+                for wrapped_line in body {
                     lines.push(Line::from(Span::styled(wrapped_line, body_style)));
                 }
 
                 lines.push(Line::from(""));
 
-                ListItem::new(lines)
+                line_items(lines)
             })
             .collect()
     };
 
-    let reply_hint = match lang {
-        crate::i18n::Lang::Bg => "[r]-отговор [Esc]-назад",
-        crate::i18n::Lang::En => "[r]-reply [Esc]-back",
-    };
-
-    let title = format!(" {} {} ", subject, reply_hint);
+    let reply_hint = T::thread_hint(lang);
 
-    let list = List::new(content)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
-            .title(title));
+    let title = match app.drafts.get(app.selected_thread_id) {
+        Some(_) if app.input_mode != InputMode::Reply => format!(" {} {} {} ", subject, T::draft_marker(lang), reply_hint),
+        _ => format!(" {} {} ", subject, reply_hint),
+    };
 
-    frame.render_widget(list, messages_area);
+    frame.render_widget(block.title(title), messages_area);
+    if let Some(area) = participants_area {
+        let line = format!(" {}{}", T::thread_participants(lang), participants.join(", "));
+        frame.render_widget(Paragraph::new(line).style(Style::default().fg(Color::DarkGray)), area);
+    }
+    frame.render_widget(List::new(content), list_area);
 
     // Draw input area if in reply mode
     if let Some(input_rect) = input_area {
-        let input_title = match lang {
-            crate::i18n::Lang::Bg => " Отговор (Enter - изпрати, Esc - отказ) ",
-            crate::i18n::Lang::En => " Reply (Enter - send, Esc - cancel) ",
-        };
+        let input_title = T::reply_title(lang);
 
         // Show cursor position
         let cursor_x = input_rect.x + 1 + app.input_cursor as u16;
@@ -1508,19 +2161,14 @@ fn draw_compose(frame: &mut Frame, app: &App, area: Rect) {
 
     // Draw recipients list - hint changes based on mode
     let recipients_title = if app.input_mode == InputMode::Normal {
-        match lang {
-            crate::i18n::Lang::Bg => " Получатели (Space-избери, Tab-напред) ",
-            crate::i18n::Lang::En => " Recipients (Space-select, Tab-next) ",
-        }
+        T::recipients_title_active(lang)
     } else {
-        match lang {
-            crate::i18n::Lang::Bg => " Получатели (Shift+Tab-назад) ",
-            crate::i18n::Lang::En => " Recipients (Shift+Tab-back) ",
-        }
+        T::recipients_title(lang)
     };
 
     let recipient_items: Vec<ListItem> = if app.recipients.is_empty() {
-        vec![ListItem::new(format!("  {}", T::loading(lang)))]
+        let text = if app.loading { T::loading(lang) } else { T::recipients_retry(lang) };
+        vec![ListItem::new(format!("  {}", text))]
     } else {
         app.recipients
             .iter()
@@ -1576,15 +2224,9 @@ fn draw_compose(frame: &mut Frame, app: &App, area: Rect) {
 
     // Subject field
     let subject_title = if app.input_mode == InputMode::ComposeSubject {
-        match lang {
-            crate::i18n::Lang::Bg => " Тема (Tab-напред, Shift+Tab-назад) ",
-            crate::i18n::Lang::En => " Subject (Tab-next, Shift+Tab-back) ",
-        }
+        T::subject_title_active(lang)
     } else {
-        match lang {
-            crate::i18n::Lang::Bg => " Тема ",
-            crate::i18n::Lang::En => " Subject ",
-        }
+        T::subject_title(lang)
     };
 
     let subject_text = if app.input_mode == InputMode::ComposeSubject {
@@ -1611,15 +2253,9 @@ fn draw_compose(frame: &mut Frame, app: &App, area: Rect) {
 
     // Body field
     let body_title = if app.input_mode == InputMode::ComposeBody {
-        match lang {
-            crate::i18n::Lang::Bg => " Съобщение (Enter-изпрати, Shift+Tab-назад) ",
-            crate::i18n::Lang::En => " Message (Enter-send, Shift+Tab-back) ",
-        }
+        T::body_title_active(lang)
     } else {
-        match lang {
-            crate::i18n::Lang::Bg => " Съобщение ",
-            crate::i18n::Lang::En => " Message ",
-        }
+        T::body_title(lang)
     };
 
     let body_text = if app.input_mode == InputMode::ComposeBody {
@@ -1673,7 +2309,7 @@ fn draw_notifications(frame: &mut Frame, app: &App, area: Rect) {
             .iter()
             .enumerate()
             .skip(scroll)
-            .map(|(idx, notif)| {
+            .flat_map(|(idx, notif)| {
                 let is_selected = idx == app.list_offset;
 
                 // Base style depends on read/unread status
@@ -1703,15 +2339,25 @@ fn draw_notifications(frame: &mut Frame, app: &App, area: Rect) {
 
                 // Wrap title with selection marker
                 let title_text = format!("{}{}{}", selected_marker, read_marker, notif.title);
-                for wrapped_line in wrap_text(&title_text, text_width, "  ") {
+                for wrapped_line in wrap_text_hanging(&title_text, text_width, "  ", "    ") {
                     lines.push(Line::from(Span::styled(wrapped_line, style)));
                 }
 
-                // Wrap body if present
+                // Wrap body if present, collapsed to a few lines unless expanded
                 if let Some(ref body) = notif.body {
-                    for wrapped_line in wrap_text(body, text_width, "      ") {
+                    let mut body_lines = wrap_text_hanging(body, text_width, "      ", "        ");
+                    let collapsed = !app.expanded_notifications.contains(&idx)
+                        && body_lines.len() > COLLAPSED_BODY_LINES;
+                    if collapsed {
+                        body_lines.truncate(COLLAPSED_BODY_LINES);
+                    }
+                    for wrapped_line in body_lines {
                         lines.push(Line::from(Span::styled(wrapped_line, body_style)));
                     }
+                    if collapsed {
+                        let hint = truncate(&format!("      {}", T::notification_expand_hint(lang)), text_width);
+                        lines.push(Line::from(Span::styled(hint, body_style.fg(Color::DarkGray))));
+                    }
                 }
 
                 // Pupil name and date on same line
@@ -1727,24 +2373,19 @@ fn draw_notifications(frame: &mut Frame, app: &App, area: Rect) {
 
                 lines.push(Line::from(""));
 
-                ListItem::new(lines)
+                line_items(lines)
             })
             .collect()
     };
 
-    let age = app.notifications_age
-        .clone()
-        .unwrap_or_else(|| "unknown".to_string());
+    let age = age_span(app, app.notifications_age, Some(app.cache_ttl));
 
     let unread_count = app.notifications.iter().filter(|n| !n.is_read).count();
-    let enter_hint = match lang {
-        crate::i18n::Lang::Bg => "[Enter]-отвори",
-        crate::i18n::Lang::En => "[Enter]-open",
-    };
+    let enter_hint = T::notifications_hint(lang);
     let title = if unread_count > 0 {
-        format!(" {} ({} {}) ({}) {} ", T::notifications(lang), unread_count, T::unread(lang), age, enter_hint)
+        title_with_age(format!(" {} ({} {}) (", T::notifications(lang), unread_count, T::unread(lang)), age, &format!(") {} ", enter_hint))
     } else {
-        format!(" {} ({}) {} ", T::notifications(lang), age, enter_hint)
+        title_with_age(format!(" {} (", T::notifications(lang)), age, &format!(") {} ", enter_hint))
     };
 
     let is_focused = app.focus == Focus::Content;
@@ -1765,84 +2406,71 @@ fn draw_notifications(frame: &mut Frame, app: &App, area: Rect) {
 
 fn draw_settings(frame: &mut Frame, app: &App, area: Rect) {
     let lang = app.lang;
+    let key_style = Style::default().fg(Color::Yellow);
+    let value_style = Style::default().fg(Color::Cyan);
+    let on_off = |on: bool| if on { T::on(lang) } else { T::off(lang) };
+    // "  [K] label value" for rows with a key binding
+    let keyed = |entry: SettingsEntry, label: &str, value: Option<&str>| {
+        let mut spans = vec![
+            Span::styled(format!("  [{}] ", entry.key().unwrap_or(' ')), key_style),
+            Span::raw(label.to_string()),
+        ];
+        if let Some(value) = value {
+            spans.push(Span::styled(value.to_string(), value_style));
+        }
+        ListItem::new(Line::from(spans))
+    };
+    // Stand out while nothing else can be shown
+    let login_option = |entry: SettingsEntry, label: &str| {
+        let item = keyed(entry, label, None);
+        if app.needs_login { item.style(Style::default().add_modifier(Modifier::BOLD)) } else { item }
+    };
 
-    let mut items = vec![
-        ListItem::new(Line::from(vec![
-            Span::styled(
+    let items: Vec<ListItem> = app.settings_entries().into_iter()
+        .map(|entry| match entry {
+            SettingsEntry::AccountHeading => ListItem::new(Line::from(Span::styled(
                 format!("  {} ", T::account(lang)),
                 Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-            ),
-        ])),
-        ListItem::new(""),
-    ];
-
-    // Show current user or login options
-    if let Some(ref name) = app.user_name {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw(format!("  {}: ", T::logged_in_as(lang))),
-            Span::styled(name.clone(), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-        ])));
-        items.push(ListItem::new(""));
-        items.push(ListItem::new(Line::from(Span::styled(
-            format!("  [L] {}", T::logout(lang)),
-            Style::default().fg(Color::Yellow),
-        ))));
-    } else {
-        // Not logged in - show login options
-        items.push(ListItem::new(Line::from(Span::styled(
-            format!("  {}", T::login(lang)),
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-        ))));
-        items.push(ListItem::new(""));
-        items.push(ListItem::new(Line::from(vec![
-            Span::styled("  [1] ", Style::default().fg(Color::Yellow)),
-            Span::raw(T::login_password(lang)),
-        ])));
-        items.push(ListItem::new(Line::from(vec![
-            Span::styled("  [2] ", Style::default().fg(Color::Yellow)),
-            Span::raw(T::login_google(lang)),
-        ])));
-        items.push(ListItem::new(Line::from(vec![
-            Span::styled("  [3] ", Style::default().fg(Color::Yellow)),
-            Span::raw(T::import_token(lang)),
-        ])));
-        items.push(ListItem::new(Line::from(Span::styled(
-            format!("      {}", T::import_token_desc(lang)),
-            Style::default().fg(Color::DarkGray),
-        ))));
-    }
-
-    items.push(ListItem::new(""));
-    items.push(ListItem::new(Line::from(Span::raw("  ─────────────────────────────"))));
-    items.push(ListItem::new(""));
-
-    // Language toggle
-    items.push(ListItem::new(Line::from(vec![
-        Span::styled("  [G] ", Style::default().fg(Color::Yellow)),
-        Span::raw("Език / Language: "),
-        Span::styled(
-            match lang {
-                crate::i18n::Lang::Bg => "Български",
-                crate::i18n::Lang::En => "English",
-            },
-            Style::default().fg(Color::Cyan),
-        ),
-    ])));
-
-    items.push(ListItem::new(""));
-
-    // Auto-refresh interval
-    items.push(ListItem::new(Line::from(vec![
-        Span::styled("  [A] ", Style::default().fg(Color::Yellow)),
-        Span::raw(match lang {
-            crate::i18n::Lang::Bg => "Автоматично обновяване: ",
-            crate::i18n::Lang::En => "Auto-refresh: ",
-        }),
-        Span::styled(
-            app.auto_refresh_interval.label(lang),
-            Style::default().fg(Color::Cyan),
-        ),
-    ])));
+            ))),
+            SettingsEntry::LoggedInAs => ListItem::new(Line::from(vec![
+                Span::raw(format!("  {}: ", T::logged_in_as(lang))),
+                Span::styled(
+                    app.user_name.clone().unwrap_or_default(),
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+            ])),
+            SettingsEntry::Logout => ListItem::new(Line::from(Span::styled(
+                format!("  [L] {}", T::logout(lang)),
+                key_style,
+            ))),
+            SettingsEntry::LoginHeading => ListItem::new(Line::from(Span::styled(
+                format!("  {}", T::login(lang)),
+                key_style.add_modifier(Modifier::BOLD),
+            ))),
+            SettingsEntry::LoginPassword => login_option(entry, T::login_password(lang)),
+            SettingsEntry::LoginGoogle => login_option(entry, T::login_google(lang)),
+            SettingsEntry::ImportToken => login_option(entry, T::import_token(lang)),
+            SettingsEntry::ImportTokenDesc => ListItem::new(Line::from(Span::styled(
+                format!("      {}", T::import_token_desc(lang)),
+                Style::default().fg(Color::DarkGray),
+            ))),
+            SettingsEntry::Separator => ListItem::new(Line::from(Span::raw("  ─────────────────────────────"))),
+            SettingsEntry::Language => keyed(entry, T::language_label(), Some(T::language_name(lang))),
+            SettingsEntry::AutoRefresh => keyed(entry, T::auto_refresh_label(lang), Some(app.auto_refresh_interval.label(lang))),
+            SettingsEntry::ShowWeekends => keyed(entry, T::show_weekends(lang), Some(on_off(app.show_weekends))),
+            // First day of the week
+            SettingsEntry::WeekStart => keyed(entry, T::week_start_label(lang), Some(T::weekday(lang, app.week_start.weekday()))),
+            SettingsEntry::ThreadOrder => {
+                let order = if app.thread_newest_first { T::newest_first(lang) } else { T::oldest_first(lang) };
+                keyed(entry, T::thread_order_label(lang), Some(order))
+            }
+            // ASCII-only symbols for consoles without Unicode fonts
+            SettingsEntry::Ascii => keyed(entry, T::ascii_symbols(lang), Some(on_off(app.ascii))),
+            // '-' counts down
+            SettingsEntry::OverviewHomework => keyed(entry, T::overview_homework_count(lang), Some(&app.overview_homework_count.to_string())),
+            SettingsEntry::Blank => ListItem::new(""),
+        })
+        .collect();
 
     let title = format!(" {} ", T::settings(lang));
 
@@ -1859,8 +2487,8 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 
     // Show spinner when loading
     let status = if app.loading {
-        let spinner_idx = app.tick % SPINNER_FRAMES.len();
-        let spinner = SPINNER_FRAMES[spinner_idx];
+        let frames = if app.ascii { ASCII_SPINNER_FRAMES } else { SPINNER_FRAMES };
+        let spinner = frames[app.tick % frames.len()];
         let msg = app.status_message.as_deref().unwrap_or(T::loading(lang));
         format!("{} {}", spinner, msg)
     } else if let Some(ref msg) = app.status_message {
@@ -1869,10 +2497,19 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         "".to_string()
     };
 
-    let refresh_info = app.last_refresh
-        .as_ref()
-        .map(|t| format!("{} {}", T::last_refresh(lang), t))
-        .unwrap_or_default();
+    let (refresh_info, refresh_color) = if app.offline {
+        let info = match app.offline_data_age() {
+            Some(ts) => format!("{} — {} {}", T::offline(lang), T::offline_data_from(lang), format_age(ts, lang)),
+            None => T::offline(lang).to_string(),
+        };
+        (info, Color::Red)
+    } else {
+        let info = app.last_refresh
+            .as_ref()
+            .map(|t| format!("{} {}", T::last_refresh(lang), t))
+            .unwrap_or_default();
+        (info, Color::Green)
+    };
 
     let user_info = app.user_name
         .as_ref()
@@ -1889,6 +2526,11 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(Color::DarkGray),
         ),
         Span::raw(" "),
+        // Count typed for the next motion, like vim's showcmd
+        Span::styled(
+            app.pending_count().map(|count| format!("{} ", count)).unwrap_or_default(),
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ),
         Span::styled(
             status,
             Style::default().fg(Color::Yellow),
@@ -1899,7 +2541,7 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let right_content = Line::from(vec![
         Span::styled(
             refresh_info,
-            Style::default().fg(Color::Green),
+            Style::default().fg(refresh_color),
         ),
         Span::raw("  "),
         Span::styled(
@@ -1930,10 +2572,20 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(right_para, chunks[1]);
 }
 
+/// " (претеглен 4.80)" after the simple mean of a term, when the school
+/// weights grades and the two averages differ
+fn weighted_average_span(grade: &Grade, term: u8, simple: f64, lang: Lang) -> Option<Span<'static>> {
+    let weighted = grade.weighted_average(term).filter(|w| grade.has_weights(term) && (w - simple).abs() >= 0.005)?;
+    Some(Span::styled(
+        format!(" ({} {:.2})", T::weighted(lang), weighted),
+        Style::default().fg(average_color(weighted)),
+    ))
+}
+
 fn calculate_average(grades: &[String]) -> Option<f64> {
     let numeric: Vec<f64> = grades
         .iter()
-        .filter_map(|g| g.parse().ok())
+        .filter_map(|g| parse_bg_grade(g))
         .collect();
 
     if numeric.is_empty() {
@@ -1943,18 +2595,11 @@ fn calculate_average(grades: &[String]) -> Option<f64> {
     }
 }
 
-/// Get color for a grade value (Bulgarian grading: 2-6 scale)
+/// Get color for a grade value (Bulgarian grading: 2-6 scale, numbers or words)
 /// 6 = Excellent (green), 5 = Very Good (cyan), 4 = Good (yellow)
-/// 3 = Satisfactory (magenta), 2 = Poor (red)
+/// 3 = Satisfactory (magenta), 2 = Poor (red); anything else white
 fn grade_color(grade: &str) -> Color {
-    match grade.chars().next() {
-        Some('6') => Color::Green,
-        Some('5') => Color::Cyan,
-        Some('4') => Color::Yellow,
-        Some('3') => Color::Magenta,
-        Some('2') => Color::Red,
-        _ => Color::White,
-    }
+    parse_bg_grade(grade).map_or(Color::White, average_color)
 }
 
 /// Get color for an average grade value
@@ -1977,23 +2622,86 @@ fn parse_time(time_str: &str) -> (i32, i32) {
     }
 }
 
+/// Age of a pane's data: green within the cache TTL, yellow once expired,
+/// red when more than twice the TTL old
+fn age_span(app: &App, cached_at: Option<i64>, ttl: Option<i64>) -> Span<'static> {
+    match cached_at {
+        Some(ts) => {
+            let color = match app.freshness(ts, ttl) {
+                Freshness::Fresh => Color::Green,
+                Freshness::Expired => Color::Yellow,
+                Freshness::Stale => Color::Red,
+            };
+            Span::styled(format_age(ts, app.lang), Style::default().fg(color))
+        }
+        None => Span::raw(T::age_unknown(app.lang)),
+    }
+}
+
+/// Name of a per-student pane. Without the students pane (a single child, or
+/// a terminal too narrow for it) or with only the compact strip, nothing else
+/// on screen says whose data it is, so the student is named here.
+fn pane_title(base: &str, app: &App) -> String {
+    let pane_names_student = app.has_students_pane() && !app.students_pane_compact();
+    match app.current_student().filter(|_| !pane_names_student) {
+        Some(data) => match &data.student.class_name {
+            Some(class) => format!("{} — {} {}", base, data.student.name, class),
+            None => format!("{} — {}", base, data.student.name),
+        },
+        None => base.to_string(),
+    }
+}
+
+/// Pane title with a colored age in the middle
+fn title_with_age(before: String, age: Span<'static>, after: &str) -> Line<'static> {
+    Line::from(vec![Span::raw(before), age, Span::raw(after.to_string())])
+}
+
+/// Cut `s` to at most `max_len` characters, marking the cut with an ellipsis
+fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        return s.to_string();
+    }
+    match max_len {
+        0 => String::new(),
+        _ => s.chars().take(max_len - 1).chain(std::iter::once('…')).collect(),
+    }
+}
+
+/// Fit "primary<sep>secondary" into `width`, giving up the secondary part before the primary
+fn fit_pair(primary: &str, secondary: Option<&str>, sep: &str, width: usize) -> String {
+    const MIN_SECONDARY: usize = 4;
+    let used = primary.chars().count() + sep.chars().count();
+    match secondary {
+        Some(secondary) if used + MIN_SECONDARY.min(secondary.chars().count()) <= width => {
+            format!("{}{}{}", primary, sep, truncate(secondary, width - used))
+        }
+        _ => truncate(primary, width),
+    }
+}
+
 /// Wrap text to fit within a given width, returning multiple lines
 fn wrap_text(s: &str, width: usize, indent: &str) -> Vec<String> {
-    if width == 0 || s.is_empty() {
-        return vec![format!("{}{}", indent, s)];
-    }
+    wrap_text_hanging(s, width, indent, indent)
+}
 
-    let effective_width = width.saturating_sub(indent.chars().count());
-    if effective_width == 0 {
-        return vec![format!("{}{}", indent, s)];
+/// Wrap text with `first_indent` on the first line and `rest_indent` on the
+/// lines after it, so a body's continuation lines can sit deeper than its start
+fn wrap_text_hanging(s: &str, width: usize, first_indent: &str, rest_indent: &str) -> Vec<String> {
+    let first_width = width.saturating_sub(first_indent.chars().count());
+    let rest_width = width.saturating_sub(rest_indent.chars().count());
+    if width == 0 || s.is_empty() || first_width == 0 || rest_width == 0 {
+        return vec![format!("{}{}", first_indent, s)];
     }
 
     let mut lines = Vec::new();
     let mut current_line = String::new();
     let mut current_len = 0;
+    let indent = |lines: &Vec<String>| if lines.is_empty() { first_indent } else { rest_indent };
 
     for word in s.split_whitespace() {
         let word_len = word.chars().count();
+        let effective_width = if lines.is_empty() { first_width } else { rest_width };
 
         if current_len == 0 {
             // First word on line
@@ -2006,7 +2714,7 @@ fn wrap_text(s: &str, width: usize, indent: &str) -> Vec<String> {
             current_len += 1 + word_len;
         } else {
             // Word doesn't fit, start new line
-            lines.push(format!("{}{}", indent, current_line));
+            lines.push(format!("{}{}", indent(&lines), current_line));
             current_line = word.to_string();
             current_len = word_len;
         }
@@ -2014,12 +2722,843 @@ fn wrap_text(s: &str, width: usize, indent: &str) -> Vec<String> {
 
     // Don't forget the last line
     if !current_line.is_empty() {
-        lines.push(format!("{}{}", indent, current_line));
+        lines.push(format!("{}{}", indent(&lines), current_line));
     }
 
     if lines.is_empty() {
-        lines.push(indent.to_string());
+        lines.push(first_indent.to_string());
     }
 
     lines
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Absence, Draft, Event, Feedback, Grade, GradeEntry, Homework, Message, MessageFolder, MessageThread, Notification, Participant, ScheduleHour, Student, ThreadContents};
+    use crate::tui::app::{PendingDiscard, StudentData};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    /// App with fixed dates and no cache ages, so rendering is independent of the clock
+    fn fixture_app() -> App {
+        let mut app = App::new();
+        app.current_date = "2026-02-19".into();
+        app.schedule_date = "2026-02-19".into();
+        app.current_time = (9, 15);
+        app.user_name = Some("Иван Иванов".into());
+
+        let mut maria = StudentData::new(Student {
+            id: 1,
+            name: "Мария Иванова".into(),
+            class_name: Some("5А".into()),
+            school_name: Some("СУ \"Христо Ботев\"".into()),
+        });
+        maria.homework = vec![
+            Homework {
+                id: Some(1),
+                subject: "Български език и литература".into(),
+                text: "Прочетете приказката „Хитър Петър“ и напишете преразказ от една страница, като обърнете внимание на главните герои и тяхното поведение в различните епизоди.".into(),
+                date: "18.02.2026".into(),
+                due_date: Some("19.02.2026".into()),
+                date_sort: Some("2026-02-18".into()),
+                due_date_sort: Some("2026-02-19".into()),
+                links: Vec::new(),
+                raw_html: None,
+            },
+            Homework {
+                id: Some(2),
+                subject: "Математика".into(),
+                text: "Задачи 1-12 на стр. 45".into(),
+                date: "17.02.2026".into(),
+                due_date: Some("20.02.2026".into()),
+                date_sort: Some("2026-02-17".into()),
+                due_date_sort: Some("2026-02-20".into()),
+                links: Vec::new(),
+                raw_html: None,
+            },
+        ];
+        maria.grades = vec![
+            Grade {
+                subject: "Математика".into(),
+                term1_grades: vec!["6".into(), "5".into()],
+                term2_grades: vec!["6".into()],
+                term1_final: Some("6".into()),
+                term2_final: None,
+                annual: None,
+                entries: Vec::new(),
+            },
+            Grade {
+                subject: "Човекът и природата".into(),
+                term1_grades: vec!["4".into()],
+                term2_grades: Vec::new(),
+                term1_final: Some("4".into()),
+                term2_final: None,
+                annual: None,
+                entries: Vec::new(),
+            },
+        ];
+        maria.schedule = vec![
+            ScheduleHour {
+                hour_number: 1,
+                from_time: "08:00".into(),
+                to_time: "08:40".into(),
+                subject: "Математика".into(),
+                teacher: Some("Петрова".into()),
+                topic: Some("Дроби".into()),
+                homework: None,
+                room: Some("21".into()),
+            },
+            ScheduleHour {
+                hour_number: 2,
+                from_time: "08:50".into(),
+                to_time: "09:30".into(),
+                subject: "Български език и литература".into(),
+                teacher: None,
+                topic: None,
+                homework: Some("Преразказ".into()),
+                room: None,
+            },
+        ];
+        maria.absences = vec![Absence {
+            id: "1".into(),
+            date: "18.02.2026".into(),
+            date_sort: "2026-02-18".into(),
+            hour: 3,
+            subject: "Физическо възпитание и спорт".into(),
+            is_excused: false,
+            excuse_reason: None,
+            created_by: Some("Георгиева".into()),
+            created_at: None,
+        }];
+        maria.feedbacks = vec![Feedback {
+            id: 1,
+            badge_name: "Активно участие".into(),
+            badge_icon: None,
+            comment: Some("Много добра работа в час".into()),
+            is_positive: true,
+            date: "17.02.2026".into(),
+            teacher: "Петрова".into(),
+            subject: "Математика".into(),
+        }];
+
+        let petar = StudentData::new(Student { id: 2, name: "Петър Иванов".into(), class_name: None, school_name: None });
+        app.students = vec![maria, petar];
+
+        app.notifications = vec![Notification {
+            id: Some("n1".into()),
+            title: "Нова оценка по Математика".into(),
+            body: Some("Мария Иванова получи оценка Отличен 6".into()),
+            date: "17.02.2026".into(),
+            is_read: false,
+            notification_type: Some("new_grade".into()),
+            pupil_names: Some("Мария Иванова".into()),
+            target: Default::default(),
+        }];
+        app.messages = vec![MessageThread {
+            id: 10,
+            subject: "Родителска среща".into(),
+            last_message: "Уважаеми родители, срещата ще бъде в четвъртък от 18:00 часа в класната стая.".into(),
+            last_sender: "Петрова".into(),
+            participant_count: 25,
+            is_unread: true,
+            updated_at: "17.02.2026 12:00".into(),
+            creator: "Петрова".into(),
+        }];
+        app.thread_messages = vec![Message {
+            id: 100,
+            body: "Уважаеми родители, срещата ще бъде в четвъртък от 18:00 часа в класната стая.".into(),
+            sender_id: 5,
+            sender_name: "Петрова".into(),
+            date: "17.02.2026 12:00".into(),
+            is_system: false,
+        }];
+        app
+    }
+
+    fn render(app: &App, width: u16, height: u16) -> String {
+        crate::tui::snapshot::render(app, width, height, false)
+    }
+
+    /// Every tab with and without the overlays, in both message views
+    fn all_views() -> Vec<App> {
+        let mut views = Vec::new();
+        for &tab in Tab::all() {
+            for overlay in 0..3 {
+                let mut app = fixture_app();
+                app.current_tab = tab;
+                match overlay {
+                    1 => app.show_help = true,
+                    2 => app.error_message = Some("Грешка при връзка със сървъра: изтекло време за изчакване".into()),
+                    _ => {}
+                }
+                views.push(app);
+            }
+        }
+        let mut thread = fixture_app();
+        thread.current_tab = Tab::Messages;
+        thread.message_view = MessageView::Thread;
+        thread.selected_thread_id = Some(10);
+        views.push(thread);
+        let mut reply = fixture_app();
+        reply.current_tab = Tab::Messages;
+        reply.message_view = MessageView::Thread;
+        reply.selected_thread_id = Some(10);
+        reply.input_mode = InputMode::Reply;
+        reply.input_buffer = "Благодаря, ще присъствам.".into();
+        reply.input_cursor = reply.input_buffer.chars().count();
+        views.push(reply);
+        let mut compose = fixture_app();
+        compose.current_tab = Tab::Messages;
+        compose.message_view = MessageView::Compose;
+        compose.input_mode = InputMode::ComposeBody;
+        compose.compose_subject = "Отсъствие".into();
+        compose.input_buffer = "Детето е болно".into();
+        views.push(compose);
+        let mut discard = fixture_app();
+        discard.current_tab = Tab::Messages;
+        discard.message_view = MessageView::Compose;
+        discard.compose_subject = "Отсъствие".into();
+        discard.confirm_discard = Some(PendingDiscard::CloseEditor);
+        views.push(discard);
+        let mut drafts = fixture_app();
+        drafts.current_tab = Tab::Messages;
+        drafts.drafts.set(Draft { subject: "Отсъствие".into(), ..Default::default() });
+        drafts.drafts.set(Draft { thread_id: Some(10), body: "Благодаря".into(), ..Default::default() });
+        views.push(drafts);
+        let mut folders = fixture_app();
+        folders.current_tab = Tab::Messages;
+        folders.message_folders = vec![
+            MessageFolder { id: 4, name: "Входящи".into(), system_folder_slug: Some("inbox".into()), folder_total_count: 1, folder_unread_count: 1 },
+            MessageFolder { id: 5, name: "Изпратени".into(), system_folder_slug: Some("sent".into()), folder_total_count: 0, folder_unread_count: 0 },
+        ];
+        views.push(folders);
+        let mut date_entry = fixture_app();
+        date_entry.current_tab = Tab::Schedule;
+        date_entry.input_mode = InputMode::DateEntry;
+        date_entry.input_buffer = "20.02".into();
+        views.push(date_entry);
+        let mut feedback_filter = fixture_app();
+        feedback_filter.current_tab = Tab::Feedbacks;
+        feedback_filter.feedback_filter = FeedbackFilter::Negative;
+        feedback_filter.input_mode = InputMode::FeedbackSubject;
+        feedback_filter.input_buffer = "Мат".into();
+        feedback_filter.input_cursor = 3;
+        views.push(feedback_filter);
+        let mut switcher = fixture_app();
+        switcher.current_tab = Tab::Messages;
+        switcher.start_student_switch();
+        switcher.input_buffer = "Иван".into();
+        switcher.input_cursor = 4;
+        views.push(switcher);
+        let mut timeline = fixture_app();
+        timeline.current_tab = Tab::Grades;
+        timeline.grades_timeline = true;
+        views.push(timeline);
+        let mut report = fixture_app();
+        report.current_tab = Tab::Grades;
+        report.grades_report = true;
+        views.push(report);
+        let mut stats = fixture_app();
+        stats.show_cache_stats = true;
+        views.push(stats);
+        views
+    }
+
+    #[test]
+    fn test_draw_every_view_at_common_sizes() {
+        for (width, height) in [(80, 24), (40, 12)] {
+            for app in all_views() {
+                let screen = render(&app, width, height);
+                assert_eq!(screen.lines().count(), height as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ascii_mode_draws_only_ascii_and_letters() {
+        for mut app in all_views() {
+            app.ascii = true;
+            app.loading = true;
+            let screen = render(&app, 80, 24);
+            let stray: String = screen.chars().filter(|c| !c.is_ascii() && !c.is_alphabetic()).collect();
+            assert!(stray.is_empty(), "non-ASCII symbols {:?} in:\n{}", stray, screen);
+        }
+    }
+
+    #[test]
+    fn test_ascii_key_label() {
+        assert_eq!(ascii_key_label("←/h/[ →/l/]"), "Left/h/[ Right/l/]");
+        assert_eq!(ascii_key_label("⇧⌫"), "Shift+Bksp");
+        assert_eq!(ascii_key_label("Enter"), "Enter");
+    }
+
+    #[test]
+    fn test_overview_shows_this_weeks_absences_and_feedbacks() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        app.students[0].feedbacks_age = Some(1_771_500_000);
+        let screen = render(&app, 100, 24);
+        let week_line = screen.lines().find(|l| l.contains("This week:")).expect("weekly line");
+        let week = app.students[0].weekly_summary(&app.current_date);
+        assert!(week_line.contains(&format!("{} absences ({} unexcused)", week.absences, week.unexcused_absences)));
+        assert!(week_line.contains(&format!("{} positive · {} negative", week.positive_feedbacks, week.negative_feedbacks)));
+    }
+
+    #[test]
+    fn test_overview_grades_show_finals() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        app.students[0].grades[0].term2_final = Some("5".into());
+        let screen = render(&app, 100, 40);
+        assert!(screen.contains("Математика: Final 5 | 5.7 <- 6, 6, 5"), "{}", screen);
+        // Subjects without finals are unchanged
+        assert!(screen.contains("Човекът и природата: 4.0 <- 4"), "{}", screen);
+
+        // The annual grade wins over the term's, and shows without current grades
+        app.students[0].grades[0].annual = Some("Отличен 6".into());
+        app.students[0].grades[1].term1_grades.clear();
+        app.students[0].grades[1].annual = Some("4".into());
+        let screen = render(&app, 100, 40);
+        assert!(screen.contains("Математика: Annual Отличен 6 | 5.7 <- 6, 6, 5"), "{}", screen);
+        assert!(screen.contains("Човекът и природата: Annual 4 "), "{}", screen);
+    }
+
+    #[test]
+    fn test_title_age_colored_by_freshness() {
+        /// Color of the first character after the title's opening parenthesis
+        fn age_color(app: &App, title: &str) -> Color {
+            let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+            terminal.draw(|frame| draw(frame, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            let width = buffer.area.width as usize;
+            let cells = &buffer.content;
+            let start = (0..cells.len())
+                .find(|&i| i % width + title.chars().count() <= width
+                    && title.chars().enumerate().all(|(j, c)| cells[i + j].symbol() == c.to_string()))
+                .expect("title on screen");
+            cells[start + title.chars().count()].fg
+        }
+
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let mut app = fixture_app();
+        app.cache_ttl = 3600;
+        app.current_tab = Tab::Grades;
+        for (cached_at, color) in [(now - 60, Color::Green), (now - 5000, Color::Yellow), (now - 8000, Color::Red)] {
+            app.students[0].grades_age = Some(cached_at);
+            assert_eq!(age_color(&app, " Оценки ("), color);
+        }
+    }
+
+    #[test]
+    fn test_weighted_average_next_to_simple_mean() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Grades;
+        // Without weights only the simple mean shows
+        assert!(!render(&app, 100, 24).contains("претеглен"));
+
+        let math = &mut app.students[0].grades[0];
+        math.entries = vec![
+            GradeEntry { value: "6".into(), term: 1, id: Some(1), weight: Some(1.0) },
+            GradeEntry { value: "5".into(), term: 1, id: Some(2), weight: Some(3.0) },
+            GradeEntry { value: "6".into(), term: 2, id: Some(3), weight: Some(2.0) },
+        ];
+        let screen = render(&app, 100, 24);
+        assert!(screen.contains("Срок 1: 5.50 (претеглен 5.25) <- 6, 5"), "{}", screen);
+        // A single weighted grade gives the same average, shown once
+        assert!(screen.contains("Срок 2: 6.00 <- 6"), "{}", screen);
+    }
+
+    #[test]
+    fn test_new_items_badged_and_marked() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        app.current_tab = Tab::Homework;
+        app.track_new();
+        let screen = render(&app, 120, 30);
+        assert!(!screen.contains("NEW") && !screen.contains('•'), "{}", screen);
+
+        // A homework and a grade arrive with the next refresh
+        let mut homework = app.students[0].homework[1].clone();
+        homework.text = "Задачи 13-20 на стр. 46".into();
+        app.students[0].homework.push(homework);
+        app.students[0].grades[1].term1_grades.push("5".into());
+        app.track_new();
+
+        let screen = render(&app, 120, 30);
+        assert!(screen.contains("Homework •1") && screen.contains("Grades •1"), "{}", screen);
+        assert_eq!(screen.matches("NEW").count(), 1, "{}", screen);
+        assert!(screen.contains("-> Due: 20.02.2026 (tomorrow) NEW"), "{}", screen);
+
+        app.current_tab = Tab::Grades;
+        assert!(render(&app, 120, 30).contains("Човекът и природата NEW"));
+        app.grades_timeline = true;
+        let screen = render(&app, 120, 30);
+        assert!(screen.contains("Човекът и природата · Term 1 NEW"), "{}", screen);
+
+        app.ascii = true;
+        assert!(render(&app, 120, 30).contains("Grades *1"));
+    }
+
+    #[test]
+    fn test_grades_report_riskiest_first() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        app.current_tab = Tab::Grades;
+        app.toggle_grades_report();
+        let screen = render(&app, 120, 30);
+        assert!(screen.contains("Grades - absences and grades"), "{}", screen);
+
+        let row = |subject: &str| screen.lines().position(|l| l.contains(subject)).unwrap_or_else(|| panic!("no {} in:\n{}", subject, screen));
+        assert!(row("Subject") < row("Човекът и природата"));
+        // A low average outweighs one unexcused absence, which outweighs nothing
+        assert!(row("Човекът и природата") < row("Физическо възпитание и спорт"));
+        assert!(row("Физическо възпитание и спорт") < row("Математика"));
+        let line = screen.lines().find(|l| l.contains("Човекът и природата")).unwrap();
+        assert!(line.contains("4.00"), "{}", line);
+        assert_eq!(app.current_list_length(), 3);
+    }
+
+    #[test]
+    fn test_pending_homework_marked_by_urgency() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        app.current_tab = Tab::Homework;
+        let screen = render(&app, 120, 30);
+        assert!(screen.contains("-> Due: 19.02.2026 (today)"));
+        assert!(screen.contains("-> Due: 20.02.2026 (tomorrow)"));
+
+        // Once the school day is over, today's homework counts as past
+        app.current_time = (20, 0);
+        let screen = render(&app, 120, 30);
+        assert!(!screen.contains("(today)"));
+    }
+
+    #[test]
+    fn test_student_switcher_overlay() {
+        let mut app = fixture_app();
+        // The students pane is hidden here, so names can only come from the switcher
+        app.current_tab = Tab::Settings;
+        app.start_student_switch();
+        app.input_buffer = "пет".into();
+        app.input_cursor = 3;
+        let screen = render(&app, 80, 24);
+        assert!(screen.contains("> пет"));
+        assert!(screen.contains("> Петър Иванов"));
+        assert!(!screen.contains("Мария"));
+
+        app.input_buffer = "Георги".into();
+        assert!(render(&app, 80, 24).contains("Няма съвпадения"));
+    }
+
+    #[test]
+    fn test_settings_rows_follow_entries() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Settings;
+        for user_name in [Some("Иван Иванов".to_string()), None] {
+            app.user_name = user_name;
+            let screen = render(&app, 100, 34);
+            let rows: Vec<&str> = screen.lines().skip(4).collect();
+            for (i, entry) in app.settings_entries().into_iter().enumerate() {
+                if let Some(key) = entry.key() {
+                    assert!(rows[i].contains(&format!("[{}]", key)), "{:?} not on row {}: {}", entry, i, rows[i]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_absences_warn_about_excuse_deadline() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Absences;
+        assert!(!render(&app, 120, 30).contains("изтичащ срок"));
+
+        // Entered on the 13th, so the last day to excuse it is tomorrow
+        let absence = &mut app.students[0].absences[0];
+        absence.date = "12.02.2026".into();
+        absence.date_sort = "2026-02-12".into();
+        absence.created_at = Some("2026-02-13 11:00:00".into());
+        let screen = render(&app, 120, 30);
+        assert!(screen.contains("1 неизвинени с изтичащ срок"), "{}", screen);
+        assert!(screen.contains("Физическо възпитание и спорт - неизвинено"));
+        assert!(screen.contains("      излиза срокът за извиняване (20.02)"), "{}", screen);
+    }
+
+    #[test]
+    fn test_overview_counts_are_configurable() {
+        let mut app = fixture_app();
+        let screen = render(&app, 120, 40);
+        assert!(screen.contains("Задачи 1-12"));
+        assert!(screen.contains("Математика: 5.7 <- 6, 6, 5"));
+
+        app.overview_homework_count = 1;
+        app.overview_grades_count = Some(2);
+        let screen = render(&app, 120, 40);
+        assert!(screen.contains("Хитър Петър"));
+        assert!(!screen.contains("Задачи 1-12"));
+        assert!(screen.contains("Математика: 5.7 <- 6, 6, …"));
+        assert!(screen.contains("Човекът и природата: 4.0 <- 4 "));
+    }
+
+    #[test]
+    fn test_overview_lists_upcoming_exams() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        assert!(!render(&app, 120, 40).contains("Upcoming exams"));
+
+        app.students[0].events.push(Event {
+            id: None,
+            title: "Контролно по математика".into(),
+            description: None,
+            start_date: "2026-02-24 08:00:00".into(),
+            end_date: None,
+            event_type: None,
+            is_test: false,
+        });
+        app.students[0].homework[1].text = "Тест върху дробите".into();
+        let screen = render(&app, 120, 40);
+        assert!(screen.contains("Upcoming exams (2)"));
+        let first = screen.find("20.02 Математика: Тест върху дробите").expect("homework exam listed");
+        let second = screen.find("24.02 Контролно по математика").expect("event exam listed");
+        assert!(first < second);
+    }
+
+    #[test]
+    fn test_snapshot_single_student_names_panes_80x12() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Homework;
+        assert!(!render(&app, 80, 12).contains("— Мария"));
+
+        app.students.truncate(1);
+        assert_eq!(render(&app, 80, 12), concat!(
+            "┌ Школо ───────────────────────────────────────────────────────────────────────┐\n",
+            "│ Преглед │ Домашни │ Оценки │ Програма │ Отсъствия │ Отзиви │ Съобщения │ Изве│\n",
+            "└──────────────────────────────────────────────────────────────────────────────┘\n",
+            "┌ Домашни — Мария Иванова 5А (неизвестно) ─────────────────────────────────────┐\n",
+            "│  [18.02.2026] Български език и литература -> Due: 19.02.2026 (днес)          │\n",
+            "│    Прочетете приказката „Хитър Петър“ и напишете преразказ от една           │\n",
+            "│      страница, като обърнете внимание на главните герои и тяхното поведение  │\n",
+            "│      в различните епизоди.                                                   │\n",
+            "└──────────────────────────────────────────────────────────────────────────────┘\n",
+            "┌──────────────────────────────────────────────────────────────────────────────┐\n",
+            "│ [?] Помощ [R] Обнови [Q] Изход                                 [Иван Иванов] │\n",
+            "└──────────────────────────────────────────────────────────────────────────────┘\n",
+        ));
+
+        app.current_tab = Tab::Overview;
+        let screen = render(&app, 80, 24);
+        for pane in ["Днешна програма — Мария Иванова 5А", "Скорошни домашни — Мария Иванова 5А", "Обобщение оценки — Мария Иванова 5А"] {
+            assert!(screen.contains(pane), "{} missing", pane);
+        }
+    }
+
+    #[test]
+    fn test_students_pane_collapses_on_narrow_terminal() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Homework;
+        app.terminal_width = 60;
+        let screen = render(&app, 60, 12);
+        assert!(screen.contains("┌───┐┌ Домашни — Мария Иванова 5А"), "{}", screen);
+        assert!(screen.contains("│>1М││  [18.02.2026]"));
+        assert!(screen.contains("│ 2П││"));
+
+        app.terminal_width = 40;
+        let screen = render(&app, 40, 12);
+        assert!(screen.lines().nth(3).unwrap().starts_with("┌ Домашни — Мария Иванова 5А"), "{}", screen);
+
+        app.terminal_width = 100;
+        let screen = render(&app, 100, 12);
+        assert!(screen.contains("> МИ Мария Иванова 5А"));
+        assert!(!screen.contains("— Мария"));
+    }
+
+    #[test]
+    fn test_data_tabs_wait_for_login() {
+        let mut app = App::new();
+        app.require_login();
+        let screen = render(&app, 100, 20);
+        assert!(screen.contains("Импорт на токен"), "{}", screen);
+
+        for tab in [Tab::Overview, Tab::Grades, Tab::Messages, Tab::Notifications] {
+            app.current_tab = tab;
+            let screen = render(&app, 100, 20);
+            assert!(screen.contains("Трябва да влезете"), "{:?}\n{}", tab, screen);
+            assert!(screen.contains(&format!("┌ {} ", tab.name(app.lang))));
+        }
+    }
+
+    #[test]
+    fn test_students_pane_initials() {
+        assert_eq!(initials("Мария Иванова"), "МИ");
+        assert_eq!(initials("мария петрова иванова"), "МИ");
+        assert_eq!(initials("Петър"), "ПЕ");
+        assert_eq!(initials("  "), "");
+        assert_eq!(badge_color(7), badge_color(7));
+        assert_ne!(badge_color(7), badge_color(8));
+
+        let mut app = fixture_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let badge = &terminal.backend().buffer()[(3, 4)];
+        assert_eq!(badge.symbol(), "М");
+        assert_eq!(badge.bg, badge_color(app.students[0].student.id));
+
+        app.student_initials = false;
+        assert!(render(&app, 80, 24).contains("│> Мария Иванова 5А          │"));
+    }
+
+    #[test]
+    fn test_snapshot_overview_80x24() {
+        let app = fixture_app();
+        assert_eq!(render(&app, 80, 24), concat!(
+            "┌ Школо ───────────────────────────────────────────────────────────────────────┐\n",
+            "│ Преглед │ Домашни │ Оценки │ Програма │ Отсъствия │ Отзиви │ Съобщения │ Изве│\n",
+            "└──────────────────────────────────────────────────────────────────────────────┘\n",
+            "┌ Ученици ───────────────────┐ —\n",
+            "│> МИ Мария Иванова 5А       │\n",
+            "│  ПИ Петър Иванов           │┌ Днешна програма (2026-02-19) [09:15] ──────────┐\n",
+            "│                            ││  1. [08:00-08:40] Математика · Петрова         │\n",
+            "│                            ││  2. [08:50-09:30] Български език и литература <│\n",
+            "│                            ││                                                │\n",
+            "│                            │└────────────────────────────────────────────────┘\n",
+            "│                            │┌ Скорошни домашни ──────────────────────────────┐\n",
+            "│                            ││  [18.02.2026] Български език и литература -> 19│\n",
+            "│                            ││    Прочетете приказката „Хитър Петър“ и        │\n",
+            "│                            ││      напишете преразказ от една страница,      │\n",
+            "│                            ││      като обърнете внимание на главните герои  │\n",
+            "│                            ││      и тяхното поведение в различните          │\n",
+            "│                            │└────────────────────────────────────────────────┘\n",
+            "│                            │┌ Обобщение оценки ──────────────────────────────┐\n",
+            "│                            ││  Общо оценки: 4                                │\n",
+            "│                            ││  Математика: 5.7 <- 6, 6, 5                    │\n",
+            "└────────────────────────────┘└────────────────────────────────────────────────┘\n",
+            "┌──────────────────────────────────────────────────────────────────────────────┐\n",
+            "│ [?] Помощ [R] Обнови [Q] Изход                                 [Иван Иванов] │\n",
+            "└──────────────────────────────────────────────────────────────────────────────┘\n",
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_homework_40x12() {
+        // The first entry is taller than the pane; it must be clipped, not dropped
+        let mut app = fixture_app();
+        app.current_tab = Tab::Homework;
+        app.terminal_width = 40;
+        assert_eq!(render(&app, 40, 12), concat!(
+            "┌ Школо ───────────────────────────────┐\n",
+            "│ Преглед │ Домашни │ Оценки │ Програма│\n",
+            "└──────────────────────────────────────┘\n",
+            "┌ Домашни — Мария Иванова 5А (неизвестн┐\n",
+            "│  [18.02.2026] Български език и литера│\n",
+            "│    Прочетете приказката „Хитър       │\n",
+            "│      Петър“ и напишете преразказ от  │\n",
+            "│      една страница, като обърнете    │\n",
+            "└──────────────────────────────────────┘\n",
+            "┌──────────────────────────────────────┐\n",
+            "│ [?] Помощ [R] Обнови   [Иван Иванов] │\n",
+            "└──────────────────────────────────────┘\n",
+        ));
+    }
+
+    #[test]
+    fn test_drafts_marked_in_message_list() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Messages;
+        app.focus = Focus::Content;
+        assert!(!render(&app, 120, 24).contains("чернова"));
+
+        app.drafts.set(Draft { subject: "Отсъствие".into(), ..Default::default() });
+        app.drafts.set(Draft { thread_id: Some(10), body: "Благодаря".into(), ..Default::default() });
+        let screen = render(&app, 120, 24);
+        assert!(screen.contains("[c]-ново (чернова) [d]-продължи"), "{}", screen);
+        assert!(screen.contains("▸ [НОВО] Родителска среща (чернова)"), "{}", screen);
+    }
+
+    #[test]
+    fn test_folder_row_above_message_list() {
+        let mut app = all_views().into_iter().find(|app| !app.message_folders.is_empty()).unwrap();
+        app.focus = Focus::Content;
+        let screen = render(&app, 120, 24);
+        let lines: Vec<&str> = screen.lines().collect();
+        assert!(lines[3].contains("[c]-ново [f]-папка"), "{}", screen);
+        assert!(lines[4].contains("│ Входящи (1) │ Изпратени "), "{}", screen);
+        assert!(lines[5].contains("▸ [НОВО] Родителска среща"), "{}", screen);
+    }
+
+    #[test]
+    fn test_help_overlay_search() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Messages;
+        app.show_help = true;
+        let screen = render(&app, 100, 40);
+        assert!(screen.contains("/ - търсене"), "{}", screen);
+        assert!(screen.contains("Следваща папка"), "{}", screen);
+
+        app.help_filter = Some("ПАПКА".into());
+        let screen = render(&app, 100, 40);
+        assert!(screen.contains("f : Следваща папка"), "{}", screen);
+        assert!(screen.contains("/ ПАПКА_"), "{}", screen);
+        assert!(!screen.contains("Ново съобщение"), "{}", screen);
+
+        app.help_filter = Some("липсва".into());
+        assert!(render(&app, 100, 40).contains("Няма съвпадения"));
+    }
+
+    #[test]
+    fn test_thread_scroll_keeps_selected_visible() {
+        // Newest at the bottom with older messages filling the space above
+        assert_eq!(thread_scroll(&[3, 3, 3, 3, 3], 4, 10), 2);
+        assert_eq!(thread_scroll(&[3, 3, 3, 3, 3], 0, 10), 0);
+        // One in the middle gets half of the spare lines below it
+        assert_eq!(thread_scroll(&[3, 3, 3, 3, 3], 3, 10), 2);
+        // Everything fits
+        assert_eq!(thread_scroll(&[3, 3], 1, 10), 0);
+        // A tall message still starts at its sender line
+        assert_eq!(thread_scroll(&[3, 20, 3], 1, 10), 1);
+        assert_eq!(thread_scroll(&[], 0, 10), 0);
+    }
+
+    #[test]
+    fn test_long_thread_shows_latest_reply() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.selected_thread_id = Some(10);
+        let messages = (1..=12).map(|i| Message { id: i, body: format!("Отговор номер {}", i), ..app.thread_messages[0].clone() }).collect();
+        app.set_thread(ThreadContents { messages, ..Default::default() }, true);
+
+        let screen = render(&app, 60, 16);
+        assert!(screen.contains("Отговор номер 12"), "{}", screen);
+        assert!(!screen.contains("Отговор номер 1 "), "{}", screen);
+    }
+
+    #[test]
+    fn test_thread_lists_participants() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.selected_thread_id = Some(10);
+        app.user_id = Some(7);
+        app.thread_messages[0].date = "2026-02-19T12:32:00Z".into();
+        app.thread_participants = vec![
+            Participant { id: 5, name: "Елена Петрова".into(), role: "Класен ръководител".into() },
+            Participant { id: 6, name: "Стоян Стоянов".into(), role: "".into() },
+            Participant { id: 7, name: "Иван Иванов".into(), role: "Родител".into() },
+        ];
+
+        let screen = render(&app, 120, 16);
+        let lines: Vec<&str> = screen.lines().collect();
+        assert!(lines[4].contains("Участници: Елена Петрова (класен ръководител), Стоян Стоянов, вие"), "{}", screen);
+        // UTC from the API in Sofia time
+        assert!(lines[5].contains("▸ Петрова днес 14:32"), "{}", screen);
+    }
+
+    #[test]
+    fn test_snapshot_thread_40x12() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Messages;
+        app.message_view = MessageView::Thread;
+        app.selected_thread_id = Some(10);
+        assert_eq!(render(&app, 40, 12), concat!(
+            "┌ Школо ───────────────────────────────┐\n",
+            "│ Преглед │ Домашни │ Оценки │ Програма│\n",
+            "└──────────────────────────────────────┘\n",
+            "┌ Родителска среща [r]-отговор [Esc]-на┐\n",
+            "│▸ Петрова 17.02 12:00                 │\n",
+            "│    Уважаеми родители, срещата ще     │\n",
+            "│    бъде в четвъртък от 18:00 часа в  │\n",
+            "│    класната стая.                    │\n",
+            "└──────────────────────────────────────┘\n",
+            "┌──────────────────────────────────────┐\n",
+            "│ [?] Помощ [R] Обнови   [Иван Иванов] │\n",
+            "└──────────────────────────────────────┘\n",
+        ));
+    }
+
+    #[test]
+    fn test_word_and_decorated_grades_colored_and_averaged() {
+        assert_eq!(grade_color("Отличен"), Color::Green);
+        assert_eq!(grade_color("Мн. добър"), Color::Cyan);
+        assert_eq!(grade_color("5+"), Color::Cyan);
+        assert_eq!(grade_color("2+"), Color::Red);
+        assert_eq!(grade_color("отс."), Color::White);
+        assert_eq!(calculate_average(&["Отличен".into(), "Добър".into(), "5-".into()]), Some(5.0));
+        assert_eq!(calculate_average(&["отс.".into()]), None);
+    }
+
+    #[test]
+    fn test_truncate_tiny_widths() {
+        assert_eq!(truncate("Математика", 20), "Математика");
+        assert_eq!(truncate("Математика", 10), "Математика");
+        assert_eq!(truncate("Математика", 5), "Мате…");
+        assert_eq!(truncate("Математика", 1), "…");
+        assert_eq!(truncate("Математика", 0), "");
+        assert_eq!(truncate("", 0), "");
+    }
+
+    #[test]
+    fn test_wrap_text_hanging_indents() {
+        // Widths count characters, not bytes
+        assert_eq!(
+            wrap_text_hanging("Прочетете урок дванадесети от учебника", 20, "    ", "      "),
+            vec!["    Прочетете урок", "      дванадесети от", "      учебника"],
+        );
+        assert_eq!(
+            wrap_text("Прочетете урок дванадесети", 20, "  "),
+            vec!["  Прочетете урок", "  дванадесети"],
+        );
+        assert_eq!(wrap_text_hanging("Упр. 5", 20, "    ", "      "), vec!["    Упр. 5"]);
+        assert_eq!(wrap_text_hanging("", 20, "    ", "      "), vec!["    "]);
+        // No room after the deeper indent: keep the text on one line
+        assert_eq!(wrap_text_hanging("Упр. 5", 6, "  ", "      "), vec!["  Упр. 5"]);
+    }
+
+    #[test]
+    fn test_fit_pair_drops_secondary_first() {
+        let teacher = Some("Петрова");
+        assert_eq!(fit_pair("Математика", teacher, " · ", 30), "Математика · Петрова");
+        assert_eq!(fit_pair("Математика", teacher, " · ", 17), "Математика · Пет…");
+        // Not enough room for a useful bit of the teacher: show the subject alone
+        assert_eq!(fit_pair("Математика", teacher, " · ", 14), "Математика");
+        assert_eq!(fit_pair("Математика", teacher, " · ", 6), "Матем…");
+        assert_eq!(fit_pair("Математика", None, " · ", 0), "");
+        assert_eq!(fit_pair("Мария Иванова", Some("5А"), " ", 16), "Мария Иванова 5А");
+        assert_eq!(fit_pair("Мария Иванова", Some("5А"), " ", 15), "Мария Иванова");
+    }
+
+    #[test]
+    fn test_schedule_lines_fit_narrow_pane() {
+        let mut app = fixture_app();
+        app.current_tab = Tab::Schedule;
+        app.students.truncate(1);
+        app.students[0].schedule[0].teacher = Some("Петрова-Станчева-Димитрова".into());
+        let screen = render(&app, 30, 16);
+        assert!(screen.contains("2. [08:50-09:30] Бъл… <NOW│"), "{}", screen);
+        assert!(screen.contains("Учител: Петрова-Станче…│"), "{}", screen);
+    }
+
+    #[test]
+    fn test_notification_body_collapsed_until_expanded() {
+        let mut app = fixture_app();
+        app.lang = Lang::En;
+        app.current_tab = Tab::Notifications;
+        app.notifications[0].body = Some("one two three four five six seven eight nine ten eleven twelve thirteen".into());
+
+        let collapsed = render(&app, 40, 24);
+        assert!(collapsed.contains("… [Space]-expand"));
+        assert!(!collapsed.contains("thirteen"));
+
+        app.toggle_notification_expanded();
+        let expanded = render(&app, 40, 24);
+        assert!(!expanded.contains("[Space]-expand"));
+        assert!(expanded.contains("thirteen"));
+    }
+
+    #[test]
+    fn test_draw_survives_pathological_sizes() {
+        for (width, height) in [(10, 3), (1, 1), (0, 0), (200, 2), (3, 60)] {
+            for app in all_views() {
+                render(&app, width, height);
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use time::format_description::well_known::Rfc3339;
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
+
+/// Current time in the local timezone, falling back to UTC when the offset
+/// can't be determined (e.g. in a multi-threaded process on some platforms)
+pub fn now_local() -> OffsetDateTime {
+    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc())
+}
+
+/// Today's date as YYYY-MM-DD in local time
+pub fn today_local() -> String {
+    date_string(now_local())
+}
+
+/// Format the calendar date of `now` (in its own offset) as YYYY-MM-DD
+pub fn date_string(now: OffsetDateTime) -> String {
+    format!("{:04}-{:02}-{:02}", now.year(), now.month() as u8, now.day())
+}
+
+/// Parse the date part of "YYYY-MM-DD..." or "DD.MM.YYYY..." strings
+pub fn parse_day(s: &str) -> Option<Date> {
+    let head = s.get(..10)?;
+    Date::parse(head, time::macros::format_description!("[year]-[month]-[day]"))
+        .or_else(|_| Date::parse(head, time::macros::format_description!("[day].[month].[year]")))
+        .ok()
+}
+
+/// A whole YYYY-MM-DD or DD.MM.YYYY date, for command-line options
+pub fn parse_date_arg(s: &str) -> Result<Date, String> {
+    let s = s.trim();
+    parse_day(s)
+        .filter(|_| s.len() == 10)
+        .ok_or_else(|| format!("invalid date '{}': expected YYYY-MM-DD or DD.MM.YYYY", s))
+}
+
+/// Offset of Europe/Sofia at the instant `utc`: UTC+2, or UTC+3 in summer
+/// time, from 01:00 UTC on the last Sunday of March to the same on the last
+/// Sunday of October
+fn sofia_offset(utc: OffsetDateTime) -> UtcOffset {
+    let switch = |month| {
+        let last = Date::from_calendar_date(utc.year(), month, month.length(utc.year()))
+            .expect("last day of the month");
+        let sunday = last - Duration::days(last.weekday().number_days_from_sunday() as i64);
+        sunday.with_time(Time::from_hms(1, 0, 0).expect("01:00")).assume_utc()
+    };
+    let hours = if (switch(Month::March)..switch(Month::October)).contains(&utc) { 3 } else { 2 };
+    UtcOffset::from_hms(hours, 0, 0).expect("valid offset")
+}
+
+/// Sofia wall-clock time of an API timestamp. "YYYY-MM-DD HH:MM[:SS]" and
+/// "DD.MM.YYYY HH:MM" are already local; RFC 3339 ones with "Z" or an offset
+/// (some endpoints send UTC) are converted.
+pub fn parse_sofia_datetime(s: &str) -> Option<PrimitiveDateTime> {
+    let s = s.trim();
+    if let Ok(instant) = OffsetDateTime::parse(s, &Rfc3339) {
+        let local = instant.to_offset(sofia_offset(instant));
+        return Some(PrimitiveDateTime::new(local.date(), local.time()));
+    }
+
+    let date = parse_day(s)?;
+    let (hour, minute) = s.get(11..16)?.split_once(':')?;
+    let time = Time::from_hms(hour.parse().ok()?, minute.parse().ok()?, 0).ok()?;
+    Some(PrimitiveDateTime::new(date, time))
+}
+
+/// Minutes since midnight of "HH:MM"; unreadable parts count as 0
+pub fn minutes_of_day(time: &str) -> i32 {
+    let mut parts = time.split(':').map(|p| p.trim().parse::<i32>().unwrap_or(0));
+    parts.next().unwrap_or(0) * 60 + parts.next().unwrap_or(0)
+}
+
+/// `date` is today or one of the six days before it
+pub fn within_week(date: Date, today: Date) -> bool {
+    date <= today && (today - date).whole_days() < 7
+}
+
+/// Saturday or Sunday
+pub fn is_weekend(date: Date) -> bool {
+    matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday)
+}
+
+/// The day after `date` that has classes. Unless the school has weekend
+/// classes, Saturday and Sunday are skipped (Friday -> Monday).
+pub fn next_school_day(date: Date, show_weekends: bool) -> Date {
+    step_school_day(date, 1, show_weekends)
+}
+
+/// The day before `date` that has classes (Monday -> Friday without weekends)
+pub fn prev_school_day(date: Date, show_weekends: bool) -> Date {
+    step_school_day(date, -1, show_weekends)
+}
+
+fn step_school_day(date: Date, days: i64, show_weekends: bool) -> Date {
+    let mut target = date + Duration::days(days);
+    while !show_weekends && is_weekend(target) {
+        target += Duration::days(days);
+    }
+    target
+}
+
+/// First day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Monday,
+            WeekStart::Sunday => Weekday::Sunday,
+        }
+    }
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            WeekStart::Monday => "monday",
+            WeekStart::Sunday => "sunday",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [WeekStart::Monday, WeekStart::Sunday].into_iter().find(|w| w.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            WeekStart::Monday => WeekStart::Sunday,
+            WeekStart::Sunday => WeekStart::Monday,
+        }
+    }
+}
+
+/// First day of the week containing `date`
+pub fn week_start_date(date: Date, week_start: WeekStart) -> Date {
+    let days_since = (date.weekday().number_days_from_monday() + 7
+        - week_start.weekday().number_days_from_monday()) % 7;
+    date - Duration::days(days_since as i64)
+}
+
+/// First school day of the week `weeks` away from the one containing `date`
+pub fn school_week_start(date: Date, weeks: i64, week_start: WeekStart, show_weekends: bool) -> Date {
+    let start = week_start_date(date, week_start) + Duration::weeks(weeks);
+    if !show_weekends && is_weekend(start) {
+        next_school_day(start, false)
+    } else {
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime, offset};
+
+    #[test]
+    fn test_next_school_day() {
+        // Friday -> Monday without weekend classes
+        assert_eq!(next_school_day(date!(2026-02-20), false), date!(2026-02-23));
+        assert_eq!(prev_school_day(date!(2026-02-23), false), date!(2026-02-20));
+        // From a weekend day to the next school day
+        assert_eq!(next_school_day(date!(2026-02-21), false), date!(2026-02-23));
+
+        // Schools with Saturday classes step through every day
+        assert_eq!(next_school_day(date!(2026-02-20), true), date!(2026-02-21));
+        assert_eq!(prev_school_day(date!(2026-02-23), true), date!(2026-02-22));
+    }
+
+    #[test]
+    fn test_parse_date_arg() {
+        assert_eq!(parse_date_arg("2026-02-19"), Ok(date!(2026-02-19)));
+        assert_eq!(parse_date_arg("19.02.2026"), Ok(date!(2026-02-19)));
+        assert!(parse_date_arg("2026-02-30").is_err());
+        assert!(parse_date_arg("2026-02-19T08:00").is_err());
+        assert!(parse_date_arg("19.2.2026").unwrap_err().contains("YYYY-MM-DD or DD.MM.YYYY"));
+    }
+
+    #[test]
+    fn test_week_start_date() {
+        // Wednesday 18 Feb 2026
+        assert_eq!(week_start_date(date!(2026-02-18), WeekStart::Monday), date!(2026-02-16));
+        assert_eq!(week_start_date(date!(2026-02-18), WeekStart::Sunday), date!(2026-02-15));
+        // A Sunday starts its own week, or ends the Monday-based one
+        assert_eq!(week_start_date(date!(2026-02-22), WeekStart::Sunday), date!(2026-02-22));
+        assert_eq!(week_start_date(date!(2026-02-22), WeekStart::Monday), date!(2026-02-16));
+
+        // Sunday-based weeks still open on Monday without weekend classes
+        assert_eq!(school_week_start(date!(2026-02-18), 1, WeekStart::Sunday, false), date!(2026-02-23));
+        assert_eq!(school_week_start(date!(2026-02-18), 1, WeekStart::Sunday, true), date!(2026-02-22));
+        assert_eq!(school_week_start(date!(2026-02-18), -1, WeekStart::Monday, false), date!(2026-02-09));
+
+        assert_eq!(WeekStart::from_name("Sunday"), Some(WeekStart::Sunday));
+        assert_eq!(WeekStart::from_name("friday"), None);
+    }
+
+    #[test]
+    fn test_parse_sofia_datetime() {
+        assert_eq!(parse_sofia_datetime("2026-02-19 14:32:10"), Some(datetime!(2026-02-19 14:32)));
+        assert_eq!(parse_sofia_datetime("19.02.2026 08:00"), Some(datetime!(2026-02-19 08:00)));
+        // UTC from the API is shown in Sofia time, summer time included
+        assert_eq!(parse_sofia_datetime("2026-02-19T22:30:00.000000Z"), Some(datetime!(2026-02-20 00:30)));
+        assert_eq!(parse_sofia_datetime("2026-07-01T06:00:00Z"), Some(datetime!(2026-07-01 09:00)));
+        assert_eq!(parse_sofia_datetime("2026-07-01T09:00:00+03:00"), Some(datetime!(2026-07-01 09:00)));
+        assert_eq!(parse_sofia_datetime("2026-02-19"), None);
+        assert_eq!(parse_sofia_datetime("вчера"), None);
+
+        // Summer time starts and ends at 01:00 UTC on the last Sundays of March and October
+        assert_eq!(sofia_offset(datetime!(2026-03-29 00:59 UTC)), offset!(+2));
+        assert_eq!(sofia_offset(datetime!(2026-03-29 01:00 UTC)), offset!(+3));
+        assert_eq!(sofia_offset(datetime!(2026-10-25 00:59 UTC)), offset!(+3));
+        assert_eq!(sofia_offset(datetime!(2026-10-25 01:00 UTC)), offset!(+2));
+    }
+
+    #[test]
+    fn test_date_string_uses_local_offset_after_midnight() {
+        // 00:30 in Sofia (UTC+2) is still the previous day in UTC
+        let instant = datetime!(2026-02-19 22:30 UTC);
+        assert_eq!(date_string(instant), "2026-02-19");
+        assert_eq!(date_string(instant.to_offset(offset!(+2))), "2026-02-20");
+
+        // Summer time (UTC+3) moves the boundary an hour earlier
+        let instant = datetime!(2026-06-30 21:15 UTC);
+        assert_eq!(date_string(instant.to_offset(offset!(+3))), "2026-07-01");
+    }
+}